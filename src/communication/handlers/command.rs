@@ -1,16 +1,41 @@
-use std::io::{stdout, Write};
+use std::{
+    fs::write,
+    io::{stdout, Write},
+    time::Duration,
+};
 
-use crossterm::{event::KeyCode, Result};
+use crossterm::{
+    event::{KeyCode, KeyEvent},
+    Result,
+};
+use format_num::format_num;
+use regex::bytes::Regex;
 
 use super::handler::Handler;
 use crate::{
     communication::{
-        handlers::user_input::UserInputHandler,
-        input::{InputType, StreamType},
+        handlers::{parser::ParserState, user_input::UserInputHandler},
+        input::{CommandInput, InputType, StreamType},
+        pipe::pipe_messages,
         reader::MainWindow,
     },
-    ui::scroll::ScrollState,
-    util::{credits::gen, error::LogriaError},
+    constants::{
+        cli::{excludes::REPEAT_EXCLUDES, poll_rate::PIPE_TIMEOUT},
+        directories::filters,
+    },
+    extensions::{
+        extension::ExtensionMethods,
+        filter::{Filter, Filters},
+        session::Workspace,
+    },
+    ui::{
+        scroll::{PageSize, ScrollState},
+        selection,
+    },
+    util::{
+        aggregators::markdown::markdown_table, credits::gen, error::LogriaError,
+        timestamp::TimestampSearch,
+    },
 };
 
 pub struct CommandHandler {
@@ -23,6 +48,7 @@ impl CommandHandler {
         window.update_input_type(window.previous_input_type)?;
         window.write_status()?;
         window.config.delete_func = None;
+        window.config.marked_items.clear();
         window.set_cli_cursor(None)?;
         stdout().flush()?;
         Ok(())
@@ -42,6 +68,108 @@ impl CommandHandler {
         }
     }
 
+    fn resolve_line_number(&self, command: &str) -> std::result::Result<usize, LogriaError> {
+        let parts: Vec<&str> = command.split(' ').collect(); // ["g", "500", ...]
+        if parts.len() < 2 {
+            return Err(LogriaError::InvalidCommand(format!(
+                "No line number provided {:?}",
+                parts
+            )));
+        }
+        match parts[1].parse::<usize>() {
+            Ok(parsed) => Ok(parsed),
+            Err(why) => Err(LogriaError::InvalidCommand(format!("{:?}", why))),
+        }
+    }
+
+    /// Parse the maximum number of messages to keep in each buffer, or `None` for `off`
+    fn resolve_buffer_limit(
+        &self,
+        command: &str,
+    ) -> std::result::Result<Option<usize>, LogriaError> {
+        let parts: Vec<&str> = command.split(' ').collect(); // ["limit", "42", ...]
+        if parts.len() < 2 {
+            return Err(LogriaError::InvalidCommand(format!(
+                "No buffer limit provided {:?}",
+                parts
+            )));
+        }
+        if parts[1] == "off" {
+            return Ok(None);
+        }
+        match parts[1].parse::<usize>() {
+            Ok(0) => Err(LogriaError::InvalidCommand(String::from(
+                "Buffer limit must be at least 1",
+            ))),
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(why) => Err(LogriaError::InvalidCommand(format!("{:?}", why))),
+        }
+    }
+
+    /// Parse the number of rows to show above the match `n` and `N` move to, or `None` for `off`
+    fn resolve_match_context(
+        &self,
+        command: &str,
+    ) -> std::result::Result<Option<usize>, LogriaError> {
+        let parts: Vec<&str> = command.split(' ').collect(); // ["context", "3", ...]
+        if parts.len() < 2 {
+            return Err(LogriaError::InvalidCommand(format!(
+                "No match context provided {:?}",
+                parts
+            )));
+        }
+        if parts[1] == "off" {
+            return Ok(None);
+        }
+        match parts[1].parse::<usize>() {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(why) => Err(LogriaError::InvalidCommand(format!("{:?}", why))),
+        }
+    }
+
+    /// Resolve the directory for `:cd` relative to the one commands currently start in, or `None` to
+    /// go back to Logria's own directory
+    fn resolve_working_dir(
+        &self,
+        window: &MainWindow,
+        command: &str,
+    ) -> std::result::Result<Option<String>, LogriaError> {
+        let path = command["cd".len()..].trim();
+        if path.is_empty() {
+            return Ok(None);
+        }
+        let dir =
+            CommandInput::resolve_working_dir(window.config.stream_options.working_dir.as_deref())?
+                .join(path);
+        if !dir.is_dir() {
+            return Err(LogriaError::CannotRead(
+                path.to_owned(),
+                String::from("not a directory"),
+            ));
+        }
+        let dir = dir.canonicalize().unwrap_or(dir);
+        Ok(Some(dir.to_string_lossy().to_string()))
+    }
+
+    /// Parse `:env KEY=VALUE` to set a variable for new commands, or `:env KEY` to unset it
+    fn resolve_env(
+        &self,
+        command: &str,
+    ) -> std::result::Result<(String, Option<String>), LogriaError> {
+        let pair = command["env".len()..].trim();
+        let (key, value) = match pair.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_owned())),
+            None => (pair, None),
+        };
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(LogriaError::InvalidCommand(format!(
+                "{:?} is not a valid variable",
+                pair
+            )));
+        }
+        Ok((key.to_owned(), value))
+    }
+
     fn resolve_aggregation_count(&self, command: &str) -> std::result::Result<usize, LogriaError> {
         let parts: Vec<&str> = command.split(' ').collect(); // ["agg", "42", ...]
         if parts.len() < 2 {
@@ -109,7 +237,375 @@ impl CommandHandler {
         Ok(out_l)
     }
 
+    /// Save the active regex pattern and its toggles under a name
+    fn save_filter(&self, window: &mut MainWindow, name: &str) -> Result<()> {
+        let filter = match (&window.config.regex_pattern, &window.config.regex_filter) {
+            // Keep the typed pattern apart from the anchors and flags the toggles wrapped it in
+            (Some(pattern), Some(filter)) if filter.effective_pattern() == pattern.as_str() => {
+                filter.to_owned()
+            }
+            (Some(pattern), _) => Filter::new(pattern.as_str()),
+            (None, _) => {
+                window.write_to_command_line("No active regex to save as a filter.")?;
+                return Ok(());
+            }
+        };
+        let pattern = filter.pattern.to_owned();
+        Filters::verify_path();
+        let result = Filters::load(&filters()).and_then(|mut saved| {
+            saved.add(name, filter)?;
+            saved.save(&filters())
+        });
+        match result {
+            Ok(_) => {
+                window.write_to_command_line(&format!("Saved /{}/ as {}", pattern, name.trim()))
+            }
+            Err(why) => window.write_to_command_line(&why.to_string()),
+        }
+    }
+
+    /// Apply the regex pattern saved under a name
+    fn apply_filter(&self, window: &mut MainWindow, name: &str) -> Result<()> {
+        if !matches!(
+            window.previous_input_type,
+            InputType::Normal | InputType::Regex
+        ) {
+            return window
+                .write_to_command_line("Filters can only be applied in normal or regex mode.");
+        }
+        let filter = match Filters::load(&filters()) {
+            Ok(saved) => match saved.get(name) {
+                Some(filter) => filter.to_owned(),
+                None => {
+                    return window.write_to_command_line(&format!(
+                        "No saved filter named {:?}",
+                        name.trim()
+                    ));
+                }
+            },
+            Err(why) => return window.write_to_command_line(&why.to_string()),
+        };
+        let pattern = filter.effective_pattern();
+        match Regex::new(&pattern) {
+            Ok(regex) => {
+                // The regex handler restores the toggles from the filter when it adopts the pattern
+                window.set_regex(regex);
+                window.config.regex_filter = Some(filter);
+                window.previous_input_type = InputType::Regex;
+                window.reset_output()?;
+            }
+            Err(why) => {
+                window.write_to_command_line(&format!("Invalid regex: /{}/ ({})", pattern, why))?
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the open streams and view state as a session that restores both
+    fn save_workspace(&self, window: &mut MainWindow, name: &str) -> Result<()> {
+        let session = match &window.config.session {
+            Some(session) => session.clone(),
+            None => return window.write_to_command_line("No open streams to save."),
+        };
+        // Session names are file names, so they cannot contain path separators
+        let name = name.trim().replace('/', "_");
+        if name.is_empty() {
+            return window.write_to_command_line("Workspaces need a name, i.e. :workspace errors");
+        }
+        let workspace = Workspace {
+            split_view: window.config.split_view,
+            regex: window
+                .config
+                .regex_pattern
+                .as_ref()
+                .map(|pattern| pattern.as_str().to_owned()),
+        };
+        match session.with_workspace(workspace).save(&name) {
+            Ok(_) => window.write_to_command_line(&format!("Saved workspace {}", name)),
+            Err(why) => window.write_to_command_line(&why.to_string()),
+        }
+    }
+
+    /// Write the messages selected with `V` to a file, or every visible message if none are selected
+    fn save_messages(&self, window: &mut MainWindow, path: &str) -> Result<()> {
+        let path = path.trim();
+        let messages = match selection::range(window) {
+            Some((start, end)) => window.messages_in_range(start, end),
+            None => window.export_messages(),
+        };
+        let mut content = messages.join("\n");
+        content.push('\n');
+        match write(path, content) {
+            Ok(_) => {
+                selection::clear(window);
+                window.redraw()?;
+                window.write_to_command_line(&format!(
+                    "Saved {} messages to {}",
+                    messages.len(),
+                    path
+                ))
+            }
+            Err(why) => window.write_to_command_line(
+                &LogriaError::CannotWrite(path.to_owned(), why.to_string()).to_string(),
+            ),
+        }
+    }
+
+    /// Mark the end of the buffer and show only newer messages, optionally only those that match a regex
+    fn mark_since(&self, window: &mut MainWindow, pattern: &str) -> Result<()> {
+        if !matches!(
+            window.previous_input_type,
+            InputType::Normal | InputType::Regex
+        ) || matches!(window.config.stream_type, StreamType::Auxiliary)
+        {
+            return window.write_to_command_line("Marks can only be set on a live buffer.");
+        }
+        let pattern = pattern.trim();
+        match Regex::new(pattern) {
+            Ok(regex) => {
+                // Matches are rebuilt by the regex handler on the next tick
+                window.config.watermark = Some(window.messages().len());
+                window.config.regex_pattern = Some(regex);
+                window.config.matched_rows.clear();
+                window.config.last_index_regexed = 0;
+                window.config.highlight_match = !pattern.is_empty();
+                window.config.current_status = Some(if pattern.is_empty() {
+                    String::from("New messages since mark")
+                } else {
+                    format!("New messages since mark with pattern /{}/", pattern)
+                });
+                window.config.scroll_state = ScrollState::Bottom;
+                window.config.did_switch = true;
+                window.previous_input_type = InputType::Regex;
+                window.reset_output()?;
+            }
+            Err(why) => {
+                window.write_to_command_line(&format!("Invalid regex: /{}/ ({})", pattern, why))?
+            }
+        }
+        Ok(())
+    }
+
+    /// Scroll to the first message at or after a timestamp, optionally given as `<timestamp> as <format>`
+    ///
+    /// Without a format, the timestamp is read with the active parser's date formats, if any.
+    fn jump_to_timestamp(&self, window: &mut MainWindow, target: &str) -> Result<()> {
+        if window.previous_input_type == InputType::Startup || window.config.aggregation_enabled {
+            return window.write_to_command_line("Timestamps can only be found in the messages.");
+        }
+        let (target, format) = match target.split_once(" as ") {
+            Some((target, format)) => (target, Some(format.trim())),
+            None => (target, None),
+        };
+        match TimestampSearch::new(target, format, &window.config.timestamp_formats) {
+            Ok(search) => {
+                if window.scroll_to_timestamp(&search) {
+                    window.redraw()
+                } else {
+                    window.write_to_command_line("No message after that time.")
+                }
+            }
+            Err(why) => window.write_to_command_line(&why.to_string()),
+        }
+    }
+
+    /// Write the aggregation panel to a file as a Markdown table
+    fn export_aggregation(&self, window: &mut MainWindow, path: &str) -> Result<()> {
+        let path = path.trim();
+        if !window.config.aggregation_enabled
+            || window.config.parser_state != ParserState::Full
+            || window.config.auxiliary_messages.is_empty()
+        {
+            return window.write_to_command_line("No aggregation to export.");
+        }
+        match write(path, markdown_table(&window.config.auxiliary_messages)) {
+            Ok(_) => window.write_to_command_line(&format!("Aggregation exported to {}", path)),
+            Err(why) => window.write_to_command_line(
+                &LogriaError::CannotWrite(path.to_owned(), why.to_string()).to_string(),
+            ),
+        }
+    }
+
+    /// Keep the live buffer to compare a later run against
+    fn mark_run(&self, window: &mut MainWindow) -> Result<()> {
+        if !matches!(
+            window.previous_input_type,
+            InputType::Normal | InputType::Regex
+        ) || matches!(window.config.stream_type, StreamType::Auxiliary)
+        {
+            return window.write_to_command_line("Only a live buffer can be kept for diffing.");
+        }
+        let kept = window.mark_run();
+        window.write_to_command_line(&format!(
+            "Kept {} messages; use :diff to mark what changes.",
+            kept
+        ))
+    }
+
+    /// Toggle marking messages that are new since the kept run
+    fn toggle_diff(&self, window: &mut MainWindow) -> Result<()> {
+        if let Some(diff) = window.config.run_diff.as_mut() {
+            diff.active = !diff.active;
+        }
+        let status = match &window.config.run_diff {
+            Some(diff) => {
+                if diff.active {
+                    let added = diff.added(window.messages()).len();
+                    let removed = diff.removed(window.messages()).len();
+                    format!("Diff: {} added, {} removed messages.", added, removed)
+                } else {
+                    String::from("Diff overlay off.")
+                }
+            }
+            None => String::from("Keep a run to compare against with :diff mark."),
+        };
+        window.reset_output()?;
+        window.redraw()?;
+        window.write_to_command_line(&status)
+    }
+
+    /// Show the messages from the kept run that are missing from the live buffer
+    fn show_removed(&self, window: &mut MainWindow) -> Result<()> {
+        let removed = match &window.config.run_diff {
+            Some(diff) => diff.removed(window.messages()),
+            None => {
+                return window
+                    .write_to_command_line("Keep a run to compare against with :diff mark.")
+            }
+        };
+        if !matches!(window.previous_input_type, InputType::Normal)
+            || matches!(window.config.stream_type, StreamType::Auxiliary)
+        {
+            window.write_to_command_line("Removed messages are only available in normal mode.")
+        } else if removed.is_empty() {
+            window.write_to_command_line("No messages were removed.")
+        } else {
+            window.config.previous_stream_type = window.config.stream_type;
+            window.config.stream_type = StreamType::Auxiliary;
+            window.config.auxiliary_messages = removed;
+            window.config.scroll_state = ScrollState::Top;
+            window.reset_output()?;
+            window.redraw()?;
+            window.write_to_command_line("Removed messages; press esc to return.")
+        }
+    }
+
+    /// Run the current buffer, respecting an active regex, through a command and show its output
+    fn pipe_buffer(&self, window: &mut MainWindow, pipe_command: &str) -> Result<()> {
+        if !matches!(
+            window.previous_input_type,
+            InputType::Normal | InputType::Regex
+        ) {
+            return window
+                .write_to_command_line("Buffers can only be piped in normal or regex mode.");
+        }
+        if pipe_command.trim().is_empty() {
+            return window.write_to_command_line("Enter a command to pipe the buffer through.");
+        }
+        let buffer = window.messages_in_range(0, window.number_of_messages());
+        match pipe_messages(
+            pipe_command,
+            &buffer,
+            &window.config.stream_options,
+            Duration::from_millis(PIPE_TIMEOUT),
+        ) {
+            Ok(output) => {
+                // Piping the output of a previous pipe keeps the stream to return to
+                if !matches!(window.config.stream_type, StreamType::Auxiliary) {
+                    window.config.previous_stream_type = window.config.stream_type;
+                }
+                window.config.stream_type = StreamType::Auxiliary;
+                window.config.auxiliary_messages = output;
+                // The output is a new buffer, so matches from the piped buffer no longer apply
+                window.config.regex_pattern = None;
+                window.config.matched_rows.clear();
+                window.config.last_index_regexed = 0;
+                window.config.highlight_match = false;
+                if window.previous_input_type == InputType::Regex {
+                    window.previous_input_type = InputType::Normal;
+                    window.config.current_status = None;
+                }
+                window.config.scroll_state = ScrollState::Top;
+                window.reset_output()?;
+                window.redraw()?;
+                window.write_to_command_line(&format!(
+                    "Piped {} messages through {}; press esc to return.",
+                    buffer.len(),
+                    pipe_command.trim()
+                ))
+            }
+            Err(why) => window.write_to_command_line(&why.to_string()),
+        }
+    }
+
+    /// Show the stream named `stderr`, `stdout`, or `aux`
+    fn show_stream(&self, window: &mut MainWindow, name: &str) -> Result<()> {
+        let stream_type = match name.trim() {
+            "stderr" => StreamType::StdErr,
+            "stdout" => StreamType::StdOut,
+            "aux" => StreamType::Auxiliary,
+            other => {
+                return window.write_to_command_line(&format!(
+                    "Unknown stream {:?}; use stderr, stdout, or aux.",
+                    other
+                ))
+            }
+        };
+        if !matches!(window.previous_input_type, InputType::Normal) {
+            return window.write_to_command_line("Streams can only be changed in normal mode.");
+        }
+        if window.config.stream_type == stream_type {
+            return window.write_to_command_line(&format!("Already showing {}.", name.trim()));
+        }
+        window.switch_stream(stream_type);
+        window.config.scroll_state = ScrollState::Bottom;
+        window.reset_output()?;
+        window.redraw()?;
+        window.write_to_command_line(&format!("Showing {}.", name.trim()))
+    }
+
+    /// Hide or show the messages from the stream at the index given to `:mute` or `:unmute`
+    fn mute_stream(&self, window: &mut MainWindow, index: &str, muted: bool) -> Result<()> {
+        let index = match index.trim().parse::<usize>() {
+            Ok(index) if index < window.config.streams.len() => index,
+            _ => {
+                return window.write_to_command_line(&format!(
+                    "No stream {:?}; use :streams to list them.",
+                    index.trim()
+                ))
+            }
+        };
+        let name = window.config.streams[index].process_name.to_owned();
+        if !window.set_muted(index, muted) {
+            return window.write_to_command_line(&format!(
+                "{} is already {}.",
+                name,
+                if muted { "muted" } else { "shown" }
+            ));
+        }
+        window.config.scroll_state = ScrollState::Bottom;
+        window.reset_output()?;
+        window.redraw()?;
+        window.write_to_command_line(&format!(
+            "{} {}.",
+            if muted { "Muted" } else { "Unmuted" },
+            name
+        ))
+    }
+
     fn process_command(&mut self, window: &mut MainWindow, command: &str) -> Result<()> {
+        window.trace("command", command);
+        // Run the previous command in the history again, skipping repeats so this cannot repeat itself
+        if REPEAT_EXCLUDES.contains(&command) {
+            return match self.input_handler.last_entry_except(&REPEAT_EXCLUDES) {
+                Some(last) => self.process_command(window, &last),
+                None => {
+                    window.write_to_command_line("No command to repeat.")?;
+                    self.return_to_prev_state(window)
+                }
+            };
+        }
+
         if command == "q" {
             window.quit()?;
         }
@@ -149,10 +645,386 @@ impl CommandHandler {
                 window.write_to_command_line("History tape disabled!")?;
             }
         }
+        // Toggle rendering messages exactly as they were received
+        else if command == "raw" {
+            window.config.raw_messages = !window.config.raw_messages;
+            window.redraw()?;
+            if window.config.raw_messages {
+                window.write_to_command_line("Raw message display enabled!")?;
+            } else {
+                window.write_to_command_line("Raw message display disabled!")?;
+            }
+        }
+        // Pin the first message above the scroll region
+        else if command == "header" {
+            window.config.sticky_header = !window.config.sticky_header;
+            window.reset_output()?;
+            window.redraw()?;
+            if window.config.sticky_header {
+                window.write_to_command_line("Sticky header enabled!")?;
+            } else {
+                window.write_to_command_line("Sticky header disabled!")?;
+            }
+        }
+        // Mark where new messages start after scrolling away from the bottom
+        else if command == "divider" {
+            window.config.new_message_divider = !window.config.new_message_divider;
+            window.reset_output()?;
+            window.redraw()?;
+            if window.config.new_message_divider {
+                window.write_to_command_line("New message divider enabled!")?;
+            } else {
+                window.write_to_command_line("New message divider disabled!")?;
+            }
+        }
+        // Color what changed between consecutive messages
+        else if command == "changes" {
+            window.config.highlight_changes = !window.config.highlight_changes;
+            window.redraw()?;
+            if window.config.highlight_changes {
+                window.write_to_command_line("Change highlighting enabled!")?;
+            } else {
+                window.write_to_command_line("Change highlighting disabled!")?;
+            }
+        }
+        // Show where the bottom of the view is in the buffer
+        else if command == "position" {
+            window.config.show_position = !window.config.show_position;
+            window.redraw()?;
+            if window.config.show_position {
+                window.write_to_command_line("Position display enabled!")?;
+            } else {
+                window.write_to_command_line("Position display disabled!")?;
+            }
+        }
+        // Toggle showing the index of each message
+        else if command == "numbers" {
+            window.config.show_line_numbers = !window.config.show_line_numbers;
+            window.redraw()?;
+            if window.config.show_line_numbers {
+                window.write_to_command_line("Line numbers enabled!")?;
+            } else {
+                window.write_to_command_line("Line numbers disabled!")?;
+            }
+        }
+        // Toggle labeling each message with the stream it came from
+        else if command == "source" {
+            window.config.show_source = !window.config.show_source;
+            window.redraw()?;
+            if window.config.show_source {
+                window.write_to_command_line("Source labels enabled!")?;
+            } else {
+                window.write_to_command_line("Source labels disabled!")?;
+            }
+        }
+        // Switch between hard wrap, word wrap, and no wrap
+        else if command == "wrap" {
+            window.config.wrap_mode = window.config.wrap_mode.next();
+            window.reset_output()?;
+            window.redraw()?;
+            window
+                .write_to_command_line(&format!("Wrap mode: {}", window.config.wrap_mode.name()))?;
+        }
+        // List saved regex filters
+        else if command == "filters" {
+            match Filters::load(&filters()) {
+                Ok(saved) if saved.patterns.is_empty() => {
+                    window.write_to_command_line("No saved filters.")?
+                }
+                Ok(saved) => window.write_to_command_line(&format!(
+                    "Saved filters: {}",
+                    saved.list().join(", ")
+                ))?,
+                Err(why) => window.write_to_command_line(&why.to_string())?,
+            }
+        }
+        // Save the active regex as a named filter
+        else if let Some(name) = command.strip_prefix("filter save ") {
+            self.save_filter(window, name)?;
+        }
+        // Apply a saved regex filter
+        else if let Some(name) = command.strip_prefix("filter ") {
+            self.apply_filter(window, name)?;
+        }
+        // Empty the auxiliary buffer without touching the streams
+        else if command == "clear-aux" {
+            match window.previous_input_type {
+                InputType::Parser | InputType::Startup => {
+                    window.write_to_command_line(
+                        "Cannot clear the auxiliary buffer while it is in use.",
+                    )?;
+                }
+                _ => {
+                    if let StreamType::Auxiliary = window.config.stream_type {
+                        window.config.stream_type = window.config.previous_stream_type;
+                        window.config.scroll_state = ScrollState::Bottom;
+                    }
+                    window.clear_auxiliary();
+                    window.reset_output()?;
+                    window.redraw()?;
+                    window.write_to_command_line("Auxiliary buffer cleared.")?;
+                }
+            }
+        }
+        // Read file streams again from the start, i.e. after they were rotated
+        else if command == "reload" {
+            match window.reload_files() {
+                Ok(0) => window.write_to_command_line("There are no files to reload.")?,
+                Ok(count) => {
+                    window.reset_output()?;
+                    window.redraw()?;
+                    window.write_to_command_line(&format!("Reloaded {} file(s).", count))?;
+                }
+                Err(why) => window.write_to_command_line(&why.to_string())?,
+            }
+        }
+        // Show how much data the buffers are holding
+        else if command == "mem" {
+            if let InputType::Normal = window.previous_input_type {
+                let diagnostics = window.diagnostics();
+                window.config.previous_stream_type = window.config.stream_type;
+                window.config.stream_type = StreamType::Auxiliary;
+                window.config.auxiliary_messages = diagnostics;
+                window.config.scroll_state = ScrollState::Top;
+                window.reset_output()?;
+                window.redraw()?;
+                window.write_to_command_line("Buffer diagnostics; press esc to return.")?;
+            } else {
+                window.write_to_command_line("Diagnostics are only available in normal mode.")?;
+            }
+        }
+        // Show the whole status when it does not fit in the command line
+        else if command == "status full" {
+            if !window.open_status_overlay()? {
+                window.write_to_command_line("There is no status to show.")?;
+            }
+        }
+        // Show the modes and filters that shape what is on screen
+        else if command == "status" {
+            let report = window.status_report();
+            if let InputType::Normal = window.previous_input_type {
+                window.config.previous_stream_type = window.config.stream_type;
+                window.config.stream_type = StreamType::Auxiliary;
+                window.config.auxiliary_messages = report;
+                window.config.scroll_state = ScrollState::Top;
+                window.reset_output()?;
+                window.redraw()?;
+                window.write_to_command_line("Current status; press esc to return.")?;
+            } else {
+                // Other modes may be using the auxiliary buffer, so summarize on the command line
+                let summary: Vec<&str> = report[1..].iter().map(|line| line.trim()).collect();
+                window.write_to_command_line(&summary.join(", "))?;
+            }
+        }
+        // Export the aggregation panel for pasting into tickets
+        else if let Some(path) = command.strip_prefix("agg-export-md ") {
+            self.export_aggregation(window, path)?;
+        }
+        // Freeze the messages on screen to compare against live messages
+        else if command == "snapshot" {
+            if !matches!(
+                window.previous_input_type,
+                InputType::Normal | InputType::Regex
+            ) || matches!(window.config.stream_type, StreamType::Auxiliary)
+            {
+                window.write_to_command_line("Snapshots can only be taken of a live buffer.")?;
+            } else {
+                window.take_snapshot();
+                window.write_to_command_line("Snapshot taken; press f to view it.")?;
+            }
+        }
+        // Compare the live buffer against a previous run
+        else if command == "diff mark" {
+            self.mark_run(window)?;
+        } else if command == "diff removed" {
+            self.show_removed(window)?;
+        } else if command == "diff" {
+            self.toggle_diff(window)?;
+        }
+        // Save the streams and view as a workspace
+        else if let Some(name) = command.strip_prefix("workspace ") {
+            self.save_workspace(window, name)?;
+        }
+        // Set how far pg_up and pg_down scroll
+        else if let Some(size) = command.strip_prefix("page ") {
+            match PageSize::parse(size) {
+                Ok(page_size) => {
+                    window.config.page_size = page_size;
+                    window.write_to_command_line(&match page_size {
+                        PageSize::Lines(lines) => format!("Paging by {} lines", lines),
+                        PageSize::Fraction(fraction) => {
+                            format!("Paging by {} of the window", fraction)
+                        }
+                    })?;
+                }
+                Err(why) => window.write_to_command_line(&why.to_string())?,
+            }
+        }
+        // Show a specific stream
+        else if let Some(name) = command.strip_prefix("stream ") {
+            self.show_stream(window, name)?;
+        }
+        // Run the buffer through an external command
+        else if let Some(pipe_command) = command.strip_prefix("pipe ") {
+            self.pipe_buffer(window, pipe_command)?;
+        }
+        // Write the selected messages to a file
+        else if let Some(path) = command.strip_prefix("save ") {
+            self.save_messages(window, path)?;
+        }
+        // Only show messages received from now on
+        else if command == "since" {
+            self.mark_since(window, "")?;
+        } else if let Some(pattern) = command.strip_prefix("since ") {
+            self.mark_since(window, pattern)?;
+        }
+        // Scroll to the first message at or after a timestamp
+        else if let Some(target) = command.strip_prefix("at ") {
+            self.jump_to_timestamp(window, target)?;
+        }
+        // Scroll to a message by its index in the buffer, stopping at the last message
+        else if command.starts_with("g ") {
+            match self.resolve_line_number(command) {
+                Ok(index) => {
+                    window.scroll_to_message(index);
+                    window.redraw()?;
+                }
+                Err(why) => {
+                    window.write_to_command_line(&format!(
+                        "Failed to parse line number: {:?}",
+                        why
+                    ))?;
+                }
+            }
+        }
+        // Set the directory new command streams start in
+        else if command == "cd" || command.starts_with("cd ") {
+            match self.resolve_working_dir(window, command) {
+                Ok(dir) => {
+                    window.config.stream_options.working_dir = dir;
+                    match &window.config.stream_options.working_dir {
+                        Some(dir) => window.write_to_command_line(&format!(
+                            "New commands will start in {}",
+                            dir
+                        ))?,
+                        None => window.write_to_command_line(
+                            "New commands will start in Logria's directory",
+                        )?,
+                    }
+                }
+                Err(why) => window.write_to_command_line(&why.to_string())?,
+            }
+        }
+        // List the variables set for new command streams
+        else if command == "env" {
+            if window.config.stream_options.env.is_empty() {
+                window.write_to_command_line("No environment variables set.")?;
+            } else {
+                let vars: Vec<String> = window
+                    .config
+                    .stream_options
+                    .env
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                window.write_to_command_line(&format!("Environment: {}", vars.join(", ")))?;
+            }
+        }
+        // Set or unset a variable for new command streams
+        else if command.starts_with("env ") {
+            match self.resolve_env(command) {
+                Ok((key, Some(value))) => {
+                    window.write_to_command_line(&format!(
+                        "New commands will run with {}={}",
+                        key, value
+                    ))?;
+                    window.config.stream_options.env.insert(key, value);
+                }
+                Ok((key, None)) => {
+                    window.config.stream_options.env.remove(&key);
+                    window.write_to_command_line(&format!("Unset {} for new commands", key))?;
+                }
+                Err(why) => window.write_to_command_line(&why.to_string())?,
+            }
+        }
+        // List the streams with the indices used to mute them
+        else if command == "streams" {
+            let streams = window.describe_streams();
+            if streams.is_empty() {
+                window.write_to_command_line("No streams.")?;
+            } else {
+                window.write_to_command_line(&format!("Streams: {}", streams.join(", ")))?;
+            }
+        }
+        // Hide or show the messages from a stream
+        else if let Some(index) = command.strip_prefix("mute ") {
+            self.mute_stream(window, index, true)?;
+        } else if let Some(index) = command.strip_prefix("unmute ") {
+            self.mute_stream(window, index, false)?;
+        }
+        // Toggle whether `n` and `N` wrap around past the ends of the matches
+        else if command == "matchwrap" {
+            window.config.wrap_matches = !window.config.wrap_matches;
+            if window.config.wrap_matches {
+                window.write_to_command_line("Match wrap-around enabled!")?;
+            } else {
+                window.write_to_command_line("Match wrap-around disabled!")?;
+            }
+        }
+        // Set where `n` and `N` place the match in the window
+        else if command.starts_with("context") {
+            match self.resolve_match_context(command) {
+                Ok(context) => {
+                    window.config.match_context = context;
+                    match context {
+                        Some(context) => window.write_to_command_line(&format!(
+                            "Showing matches {} rows from the top",
+                            context
+                        ))?,
+                        None => window.write_to_command_line("Showing matches at the bottom")?,
+                    }
+                }
+                Err(why) => {
+                    window.write_to_command_line(&format!(
+                        "Failed to parse match context: {:?}",
+                        why
+                    ))?;
+                }
+            }
+        }
+        // Cap the number of messages kept from each stream
+        else if command.starts_with("limit") {
+            match self.resolve_buffer_limit(command) {
+                Ok(limit) => {
+                    window.config.max_buffer_lines = limit;
+                    window.enforce_buffer_limits();
+                    window.redraw()?;
+                    match limit {
+                        Some(limit) => window.write_to_command_line(&format!(
+                            "Keeping the last {} messages of each stream",
+                            format_num!(",d", limit as f64)
+                        ))?,
+                        None => window.write_to_command_line("Keeping all messages")?,
+                    }
+                }
+                Err(why) => {
+                    window.write_to_command_line(&format!(
+                        "Failed to parse buffer limit: {:?}",
+                        why
+                    ))?;
+                }
+            }
+        }
         // Remove saved sessions from the main screen
         else if command.starts_with('r') {
             if let StreamType::Auxiliary = window.config.stream_type {
-                if let Ok(items) = self.resolve_delete_command(command) {
+                // Without items, remove the ones marked in the list
+                let items = if command.trim() == "r" && !window.config.marked_items.is_empty() {
+                    Ok(std::mem::take(&mut window.config.marked_items))
+                } else {
+                    self.resolve_delete_command(command)
+                };
+                if let Ok(items) = items {
                     if let Some(del) = window.config.delete_func {
                         match del(&items) {
                             Ok(_) => {}
@@ -194,7 +1066,7 @@ impl CommandHandler {
             match self.resolve_aggregation_count(command) {
                 Ok(val) => {
                     window.config.num_to_aggregate = val;
-                    // TODO: This wont cause the screen to re-render until there is a new message to get parsed
+                    window.config.rerender_aggregation = true;
                 }
                 Err(why) => {
                     window.write_to_command_line(&format!(
@@ -218,7 +1090,9 @@ impl Handler for CommandHandler {
         }
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()> {
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
         match key {
             // Execute the command
             KeyCode::Enter => {
@@ -230,7 +1104,7 @@ impl Handler for CommandHandler {
             }
             // Go back to the previous state
             KeyCode::Esc => self.return_to_prev_state(window)?,
-            key => self.input_handler.receive_input(window, key)?,
+            _ => self.input_handler.receive_input(window, event)?,
         }
         Ok(())
     }
@@ -264,6 +1138,159 @@ mod poll_rate_tests {
     }
 }
 
+#[cfg(test)]
+mod buffer_limit_tests {
+    use super::CommandHandler;
+    use crate::communication::{handlers::handler::Handler, input::InputType, reader::MainWindow};
+
+    #[test]
+    fn test_can_resolve_buffer_limit() {
+        let handler = CommandHandler::new();
+        assert_eq!(
+            handler.resolve_buffer_limit("limit 500").unwrap(),
+            Some(500)
+        );
+    }
+
+    #[test]
+    fn test_can_resolve_buffer_limit_off() {
+        let handler = CommandHandler::new();
+        assert_eq!(handler.resolve_buffer_limit("limit off").unwrap(), None);
+    }
+
+    #[test]
+    fn test_cannot_resolve_bad_buffer_limit() {
+        let handler = CommandHandler::new();
+        assert!(handler.resolve_buffer_limit("limit").is_err());
+        assert!(handler.resolve_buffer_limit("limit abc").is_err());
+        assert!(handler.resolve_buffer_limit("limit 0").is_err());
+    }
+
+    #[test]
+    fn test_limit_truncates_buffer() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "limit 10").unwrap();
+
+        assert_eq!(logria.config.max_buffer_lines, Some(10));
+        assert_eq!(logria.messages().len(), 10);
+        assert_eq!(logria.messages()[0], "90");
+    }
+
+    #[test]
+    fn test_can_resolve_match_context() {
+        let handler = CommandHandler::new();
+        assert_eq!(handler.resolve_match_context("context 3").unwrap(), Some(3));
+        assert_eq!(handler.resolve_match_context("context 0").unwrap(), Some(0));
+        assert_eq!(handler.resolve_match_context("context off").unwrap(), None);
+    }
+
+    #[test]
+    fn test_cannot_resolve_bad_match_context() {
+        let handler = CommandHandler::new();
+        assert!(handler.resolve_match_context("context").is_err());
+        assert!(handler.resolve_match_context("context abc").is_err());
+    }
+
+    #[test]
+    fn test_can_toggle_match_wrap() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        let mut handler = CommandHandler::new();
+        assert!(!logria.config.wrap_matches);
+        handler.process_command(&mut logria, "matchwrap").unwrap();
+        assert!(logria.config.wrap_matches);
+
+        logria.input_type = InputType::Command;
+        handler.process_command(&mut logria, "matchwrap").unwrap();
+        assert!(!logria.config.wrap_matches);
+    }
+
+    #[test]
+    fn test_can_set_match_context() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "context 3").unwrap();
+        assert_eq!(logria.config.match_context, Some(3));
+
+        logria.input_type = InputType::Command;
+        handler.process_command(&mut logria, "context off").unwrap();
+        assert_eq!(logria.config.match_context, None);
+    }
+}
+
+#[cfg(test)]
+mod line_number_tests {
+    use super::CommandHandler;
+    use crate::{
+        communication::{handlers::handler::Handler, input::InputType, reader::MainWindow},
+        ui::scroll::ScrollState,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn test_can_resolve_line_number() {
+        let handler = CommandHandler::new();
+        assert_eq!(handler.resolve_line_number("g 500").unwrap(), 500);
+    }
+
+    #[test]
+    fn test_cannot_resolve_bad_line_number() {
+        let handler = CommandHandler::new();
+        assert!(handler.resolve_line_number("g abc").is_err());
+        assert!(handler.resolve_line_number("g -1").is_err());
+    }
+
+    #[test]
+    fn test_cannot_resolve_missing_line_number() {
+        let handler = CommandHandler::new();
+        assert!(handler.resolve_line_number("g").is_err());
+    }
+
+    #[test]
+    fn test_can_go_to_line() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "g 20").unwrap();
+
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.determine_render_position(), (20, 27));
+    }
+
+    #[test]
+    fn test_go_to_line_past_end() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Centered;
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "g 500").unwrap();
+
+        // Clamped to the last message rather than an error
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.config.current_end, 100);
+        assert_eq!(logria.determine_render_position(), (93, 100));
+    }
+
+    #[test]
+    fn test_go_to_bad_line_keeps_position() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "g abc").unwrap();
+
+        assert!(matches!(logria.config.scroll_state, ScrollState::Bottom));
+    }
+}
+
 #[cfg(test)]
 mod remove_tests {
     use super::CommandHandler;
@@ -367,3 +1394,546 @@ mod remove_tests {
         assert_eq!(resolved.len(), 0);
     }
 }
+
+#[cfg(test)]
+mod trace_tests {
+    use std::sync::mpsc::channel;
+
+    use super::CommandHandler;
+    use crate::{
+        communication::{
+            handlers::{handler::Handler, user_input::UserInputHandler},
+            input::InputType,
+            reader::MainWindow,
+        },
+        util::trace::Tracer,
+    };
+
+    #[test]
+    fn test_trace_command() {
+        let (sender, receiver) = channel();
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.tracer = Some(Tracer::with_sender(sender));
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "poll 50").unwrap();
+
+        let events: Vec<String> = receiver
+            .try_iter()
+            .map(|line| line.split_once(' ').unwrap().1.to_string())
+            .collect();
+        assert_eq!(events[0], "command poll 50");
+        assert!(events.contains(&String::from("mode Command -> Normal")));
+        assert_eq!(logria.config.poll_rate, 50);
+    }
+
+    #[test]
+    fn test_trace_repeat() {
+        let (sender, receiver) = channel();
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.tracer = Some(Tracer::with_sender(sender));
+        logria.previous_input_type = InputType::Normal;
+
+        let mut handler = CommandHandler {
+            input_handler: UserInputHandler::_new_dummy(&["poll 50", "!!"]),
+        };
+        handler.process_command(&mut logria, "poll 50").unwrap();
+        handler.process_command(&mut logria, "!!").unwrap();
+
+        let commands: Vec<String> = receiver
+            .try_iter()
+            .filter_map(|line| {
+                line.split_once(" command ")
+                    .map(|(_, command)| command.to_owned())
+            })
+            .collect();
+        assert_eq!(commands, vec!["poll 50", "!!", "poll 50"]);
+    }
+}
+
+#[cfg(test)]
+mod repeat_tests {
+    use super::CommandHandler;
+    use crate::communication::{
+        handlers::user_input::UserInputHandler, input::InputType, reader::MainWindow,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria
+    }
+
+    fn handler(history: &[&str]) -> CommandHandler {
+        CommandHandler {
+            input_handler: UserInputHandler::_new_dummy(history),
+        }
+    }
+
+    #[test]
+    fn test_repeat_last_command() {
+        let mut logria = window();
+        let mut handler = handler(&["poll 75", "repeat"]);
+        handler.process_command(&mut logria, "repeat").unwrap();
+        assert_eq!(logria.config.poll_rate, 75);
+    }
+
+    #[test]
+    fn test_repeat_does_not_repeat_itself() {
+        let mut logria = window();
+        let mut handler = handler(&["poll 75", "!!", "repeat"]);
+        handler.process_command(&mut logria, "repeat").unwrap();
+        assert_eq!(logria.config.poll_rate, 75);
+    }
+
+    #[test]
+    fn test_repeat_without_command() {
+        let mut logria = window();
+        let mut handler = handler(&["!!"]);
+        handler.process_command(&mut logria, "!!").unwrap();
+        assert!(logria.config.smart_poll_rate);
+        assert_eq!(logria.input_type, InputType::Normal);
+    }
+}
+
+#[cfg(test)]
+mod pipe_tests {
+    use regex::bytes::Regex;
+
+    use super::CommandHandler;
+    use crate::communication::{
+        handlers::handler::Handler,
+        input::{InputType, StreamType},
+        reader::MainWindow,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn test_pipe_buffer() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "pipe tail -n 3")
+            .unwrap();
+        assert!(matches!(logria.config.stream_type, StreamType::Auxiliary));
+        assert!(matches!(
+            logria.config.previous_stream_type,
+            StreamType::StdErr
+        ));
+        assert_eq!(logria.config.auxiliary_messages, vec!["97", "98", "99"]);
+    }
+
+    #[test]
+    fn test_pipe_filtered_buffer() {
+        let mut logria = window();
+        logria.previous_input_type = InputType::Regex;
+        logria.config.regex_pattern = Some(Regex::new("^9").unwrap());
+        logria.config.matched_rows = vec![9, 90, 91];
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "pipe sort -r")
+            .unwrap();
+        assert_eq!(logria.config.auxiliary_messages, vec!["91", "90", "9"]);
+        assert!(logria.config.regex_pattern.is_none());
+        assert!(logria.config.matched_rows.is_empty());
+        // Leaving the piped output does not return to the cleared regex
+        assert_eq!(logria.input_type, InputType::Normal);
+    }
+
+    #[test]
+    fn test_pipe_piped_buffer() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "pipe tail -n 3")
+            .unwrap();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        handler
+            .process_command(&mut logria, "pipe head -n 1")
+            .unwrap();
+        assert_eq!(logria.config.auxiliary_messages, vec!["97"]);
+        assert!(matches!(
+            logria.config.previous_stream_type,
+            StreamType::StdErr
+        ));
+    }
+
+    #[test]
+    fn test_pipe_missing_command() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "pipe logria_missing_command")
+            .unwrap();
+        assert!(matches!(logria.config.stream_type, StreamType::StdErr));
+        assert!(logria.config.auxiliary_messages.is_empty());
+    }
+
+    #[test]
+    fn test_pipe_outside_normal_mode() {
+        let mut logria = window();
+        logria.previous_input_type = InputType::Parser;
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "pipe cat").unwrap();
+        assert!(matches!(logria.config.stream_type, StreamType::StdErr));
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use super::CommandHandler;
+    use crate::communication::{
+        handlers::handler::Handler,
+        input::{InputType, StreamType},
+        reader::MainWindow,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn test_show_stream() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "stream stdout")
+            .unwrap();
+        assert_eq!(logria.config.stream_type, StreamType::StdOut);
+        assert_eq!(logria.config.previous_stream_type, StreamType::StdErr);
+    }
+
+    #[test]
+    fn test_show_aux_stream() {
+        let mut logria = window();
+        logria.config.frozen_at = Some(10);
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "stream aux").unwrap();
+        assert_eq!(logria.config.stream_type, StreamType::Auxiliary);
+        assert_eq!(logria.config.previous_stream_type, StreamType::StdErr);
+        assert!(logria.config.frozen_at.is_none());
+    }
+
+    #[test]
+    fn test_show_current_stream() {
+        let mut logria = window();
+        logria.config.previous_stream_type = StreamType::StdOut;
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "stream stderr")
+            .unwrap();
+        assert_eq!(logria.config.stream_type, StreamType::StdErr);
+        assert_eq!(logria.config.previous_stream_type, StreamType::StdOut);
+    }
+
+    #[test]
+    fn test_show_invalid_stream() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "stream stdin")
+            .unwrap();
+        assert_eq!(logria.config.stream_type, StreamType::StdErr);
+    }
+
+    #[test]
+    fn test_show_stream_outside_normal_mode() {
+        let mut logria = window();
+        logria.previous_input_type = InputType::Regex;
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "stream stdout")
+            .unwrap();
+        assert_eq!(logria.config.stream_type, StreamType::StdErr);
+    }
+}
+
+#[cfg(test)]
+mod save_tests {
+    use std::{
+        env::temp_dir,
+        fs::{read_to_string, remove_file},
+    };
+
+    use super::CommandHandler;
+    use crate::communication::{handlers::handler::Handler, input::InputType, reader::MainWindow};
+
+    fn save(logria: &mut MainWindow, name: &str) -> String {
+        let path = temp_dir().join(name);
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(logria, &format!("save {}", path.to_string_lossy()))
+            .unwrap();
+        let content = read_to_string(&path).unwrap();
+        remove_file(&path).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_save_buffer() {
+        let mut logria = MainWindow::_new_dummy();
+        let content = save(&mut logria, "logria_save_buffer");
+        assert_eq!(content.lines().count(), 100);
+        assert!(content.starts_with("0\n1\n"));
+    }
+
+    #[test]
+    fn test_save_selection() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.selection_start = Some(12);
+        logria.config.selection_end = Some(10);
+        let content = save(&mut logria, "logria_save_selection");
+        assert_eq!(content, "10\n11\n12\n");
+        assert!(logria.config.selection_start.is_none());
+    }
+}
+
+#[cfg(test)]
+mod workspace_tests {
+    use regex::bytes::Regex;
+
+    use super::CommandHandler;
+    use crate::{
+        communication::{handlers::handler::Handler, input::InputType, reader::MainWindow},
+        extensions::session::{Session, SessionType, Workspace},
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Regex;
+        logria
+    }
+
+    #[test]
+    fn test_save_workspace() {
+        let mut logria = window();
+        logria.config.session = Some(Session::new(
+            &[String::from("README.md")],
+            SessionType::File,
+        ));
+        logria.config.split_view = true;
+        logria.config.regex_pattern = Some(Regex::new("ERROR").unwrap());
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "workspace ls -la command workspace")
+            .unwrap();
+
+        let saved = Session::find("ls -la command workspace").unwrap();
+        assert_eq!(saved.commands, vec![String::from("README.md")]);
+        assert_eq!(
+            saved.workspace,
+            Some(Workspace {
+                split_view: true,
+                regex: Some(String::from("ERROR")),
+            })
+        );
+    }
+
+    #[test]
+    fn test_cannot_save_workspace_without_streams() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler
+            .process_command(&mut logria, "workspace zzz missing workspace")
+            .unwrap();
+        assert!(Session::find("zzz missing workspace").is_err());
+    }
+}
+
+#[cfg(test)]
+mod working_dir_tests {
+    use std::path::Path;
+
+    use super::CommandHandler;
+    use crate::communication::{handlers::handler::Handler, input::InputType, reader::MainWindow};
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn test_cd_sets_working_dir() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "cd src").unwrap();
+
+        let expected = Path::new("src").canonicalize().unwrap();
+        assert_eq!(
+            logria.config.stream_options.working_dir,
+            Some(expected.to_string_lossy().to_string())
+        );
+    }
+
+    #[test]
+    fn test_cd_is_relative_to_working_dir() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "cd src").unwrap();
+        handler.process_command(&mut logria, "cd util").unwrap();
+
+        let expected = Path::new("src/util").canonicalize().unwrap();
+        assert_eq!(
+            logria.config.stream_options.working_dir,
+            Some(expected.to_string_lossy().to_string())
+        );
+    }
+
+    #[test]
+    fn test_cd_rejects_missing_dir() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "cd src").unwrap();
+        handler
+            .process_command(&mut logria, "cd zzz missing dir")
+            .unwrap();
+
+        assert!(logria
+            .config
+            .stream_options
+            .working_dir
+            .unwrap()
+            .ends_with("src"));
+    }
+
+    #[test]
+    fn test_cd_without_path_resets_working_dir() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "cd src").unwrap();
+        handler.process_command(&mut logria, "cd").unwrap();
+
+        assert!(logria.config.stream_options.working_dir.is_none());
+    }
+}
+
+#[cfg(test)]
+mod env_tests {
+    use super::CommandHandler;
+    use crate::communication::{handlers::handler::Handler, input::InputType, reader::MainWindow};
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn test_can_resolve_env() {
+        let handler = CommandHandler::new();
+        assert_eq!(
+            handler.resolve_env("env TZ=UTC").unwrap(),
+            (String::from("TZ"), Some(String::from("UTC")))
+        );
+    }
+
+    #[test]
+    fn test_can_resolve_env_value_with_equals() {
+        let handler = CommandHandler::new();
+        assert_eq!(
+            handler.resolve_env("env RUST_LOG=logria=debug").unwrap(),
+            (String::from("RUST_LOG"), Some(String::from("logria=debug")))
+        );
+    }
+
+    #[test]
+    fn test_can_resolve_env_unset() {
+        let handler = CommandHandler::new();
+        assert_eq!(
+            handler.resolve_env("env TZ").unwrap(),
+            (String::from("TZ"), None)
+        );
+    }
+
+    #[test]
+    fn test_cannot_resolve_bad_env() {
+        let handler = CommandHandler::new();
+        assert!(handler.resolve_env("env =UTC").is_err());
+        assert!(handler.resolve_env("env T Z=UTC").is_err());
+    }
+
+    #[test]
+    fn test_env_sets_and_unsets_variables() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "env TZ=UTC").unwrap();
+        handler
+            .process_command(&mut logria, "env RUST_LOG=debug")
+            .unwrap();
+        handler.process_command(&mut logria, "env TZ").unwrap();
+
+        assert_eq!(logria.config.stream_options.env.len(), 1);
+        assert_eq!(logria.config.stream_options.env["RUST_LOG"], "debug");
+    }
+}
+
+#[cfg(test)]
+mod mute_tests {
+    use super::CommandHandler;
+    use crate::{
+        communication::{
+            handlers::handler::Handler,
+            input::{InputStream, InputType},
+            reader::MainWindow,
+        },
+        util::error::LogriaError,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Normal;
+        logria.set_streams(vec![
+            InputStream::failed("a", "a", LogriaError::InvalidCommand(String::from("a"))),
+            InputStream::failed("b", "b", LogriaError::InvalidCommand(String::from("b"))),
+        ]);
+        logria
+    }
+
+    #[test]
+    fn test_mute_and_unmute_stream() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "mute 1").unwrap();
+        assert!(logria.config.muted_streams.contains(&1));
+
+        handler.process_command(&mut logria, "unmute 1").unwrap();
+        assert!(logria.config.muted_streams.is_empty());
+    }
+
+    #[test]
+    fn test_cannot_mute_missing_stream() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "mute 2").unwrap();
+        handler.process_command(&mut logria, "mute x").unwrap();
+        assert!(logria.config.muted_streams.is_empty());
+    }
+
+    #[test]
+    fn test_list_streams() {
+        let mut logria = window();
+        let mut handler = CommandHandler::new();
+        handler.process_command(&mut logria, "streams").unwrap();
+        assert!(logria.config.muted_streams.is_empty());
+    }
+}
@@ -1,9 +1,10 @@
 use crossterm::Result;
-use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
 
 use crate::communication::reader::MainWindow;
 
 pub trait Handler {
     fn new() -> Self;
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()>;
+    /// Handle a key press; a bare `KeyCode` is a press without modifiers
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()>;
 }
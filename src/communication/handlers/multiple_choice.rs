@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 use crossterm::Result;
 
 use crate::{
@@ -9,38 +9,175 @@ use crate::{
         reader::MainWindow,
     },
     ui::scroll,
+    util::sanitizers::selection::{parse_selection, parse_selections},
 };
 
+/// Whether the text selects items by index, i.e. `1` or `1,3-4`, instead of filtering them
+fn is_selection(text: &str) -> bool {
+    text.chars()
+        .all(|c| c.is_ascii_digit() || c == ',' || c == '-' || c.is_whitespace())
+}
+
 pub struct MultipleChoiceHandler {
     choices_map: HashMap<usize, String>,
+    /// The text rendered and filtered for each choice, if it differs from the choice itself
+    labels: HashMap<usize, String>,
     input_handler: UserInputHandler,
     pub result: Option<usize>,
+    /// Every item in the most recent valid choice, when several items may be chosen at once
+    pub results: Vec<usize>,
+    multiple: bool,
+    /// Items marked with `tab`, i.e. to remove several at once
+    toggled: HashSet<usize>,
+    toggling: bool,
 }
 
 impl MultipleChoiceHandler {
     /// Set internal choices map
     pub fn set_choices(&mut self, choices: &[String]) {
+        // Marked items only make sense for the list they were marked in
+        let changed = choices.len() != self.choices_map.len()
+            || choices
+                .iter()
+                .enumerate()
+                .any(|(index, choice)| self.choices_map.get(&index) != Some(choice));
+        if changed {
+            self.toggled.clear();
+        }
         self.choices_map.clear();
+        self.labels.clear();
         choices.iter().enumerate().for_each(|(index, choice)| {
             self.choices_map.insert(index, choice.to_owned());
         })
     }
 
-    /// Build body text for a set of choices
-    pub fn get_body_text(&self) -> Vec<String> {
-        let mut body_text: Vec<String> = vec![];
-        (0..self.choices_map.len()).for_each(|key| {
-            body_text.push(format!("{}: {}", key, self.choices_map.get(&key).unwrap()))
+    /// Set whether `tab` can mark items, i.e. to remove several at once
+    pub fn allow_toggling(&mut self, toggling: bool) {
+        self.toggling = toggling;
+        if !toggling {
+            self.toggled.clear();
+        }
+    }
+
+    /// Mark or unmark each item in a selection, i.e. `1,3` or `1-3`, or the only item matching a filter
+    pub fn toggle_choices(&mut self, window: &mut MainWindow, choice: &str) -> Result<()> {
+        let items = if is_selection(choice) {
+            parse_selections(choice).unwrap_or_default()
+        } else {
+            match self.matching_choices(choice)[..] {
+                [index] => vec![index],
+                _ => vec![],
+            }
+        };
+        if items.is_empty() || !items.iter().all(|item| self.choices_map.contains_key(item)) {
+            window.write_to_command_line(&format!("Invalid item: {}", choice))?;
+            return Ok(());
+        }
+        items.into_iter().for_each(|item| {
+            if !self.toggled.remove(&item) {
+                self.toggled.insert(item);
+            }
         });
-        body_text
+        Ok(())
+    }
+
+    /// Get every marked item, in the order they are listed
+    pub fn get_choices(&self) -> Vec<usize> {
+        let mut choices: Vec<usize> = self.toggled.iter().copied().collect();
+        choices.sort_unstable();
+        choices
+    }
+
+    /// Set the text shown for each choice, i.e. a file name instead of its full path
+    pub fn set_labels(&mut self, labels: &[String]) {
+        self.labels.clear();
+        labels.iter().enumerate().for_each(|(index, label)| {
+            self.labels.insert(index, label.to_owned());
+        })
+    }
+
+    /// Get the text shown for a choice
+    fn label(&self, index: usize) -> &str {
+        self.labels
+            .get(&index)
+            .or_else(|| self.choices_map.get(&index))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// Get the indices of the choices whose text contains the filter, ignoring case
+    fn matching_choices(&self, filter: &str) -> Vec<usize> {
+        let filter = filter.trim().to_lowercase();
+        (0..self.choices_map.len())
+            .filter(|&index| self.label(index).to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    /// Get the typed filter, if the user has typed text that is not an index selection
+    fn filter(&self) -> Option<String> {
+        let content = self.input_handler.get_content();
+        if is_selection(&content) {
+            None
+        } else {
+            Some(content)
+        }
+    }
+
+    /// Render the choices that match the current filter
+    fn render_choices(&self, window: &mut MainWindow) {
+        window.config.auxiliary_messages.clear();
+        window
+            .config
+            .auxiliary_messages
+            .extend(self.get_body_text());
+    }
+
+    /// Set whether a choice can select several items, i.e. `1,3` or `1-3`
+    pub fn allow_multiple(&mut self, multiple: bool) {
+        self.multiple = multiple;
+    }
+
+    /// Build body text for a set of choices, keeping only those that match the typed filter
+    pub fn get_body_text(&self) -> Vec<String> {
+        let keys = match self.filter() {
+            Some(filter) => self.matching_choices(&filter),
+            None => (0..self.choices_map.len()).collect(),
+        };
+        keys.iter()
+            .map(|key| match self.toggled.contains(key) {
+                true => format!("{}: * {}", key, self.label(*key)),
+                false => format!("{}: {}", key, self.label(*key)),
+            })
+            .collect()
     }
 
     /// Determine if the choice is valid
     pub fn validate_choice(&mut self, window: &mut MainWindow, choice: &str) -> Result<()> {
-        match choice.parse::<usize>() {
-            Ok(res) => {
-                if self.choices_map.contains_key(&res) {
-                    self.result = Some(res.to_owned());
+        // Text that is not an index selects the only choice that contains it
+        if !is_selection(choice) {
+            match self.matching_choices(choice)[..] {
+                [index] => {
+                    self.result = Some(index);
+                    self.results = vec![index];
+                }
+                [] => window.write_to_command_line(&format!("No match: {}", choice))?,
+                _ => window
+                    .write_to_command_line(&format!("Several items match: {}", choice.trim()))?,
+            }
+            return Ok(());
+        }
+
+        let selection = if self.multiple {
+            parse_selections(choice)
+        } else {
+            parse_selection(choice).map(|res| vec![res])
+        };
+        match selection {
+            Ok(items) => {
+                if !items.is_empty() && items.iter().all(|item| self.choices_map.contains_key(item))
+                {
+                    self.result = Some(items[0]);
+                    self.results = items;
                 } else {
                     window.write_to_command_line(&format!("Invalid item: {}", choice))?;
                 }
@@ -58,6 +195,7 @@ impl MultipleChoiceHandler {
         match self.result {
             Some(index) => {
                 self.result = None;
+                self.results.clear();
                 self.choices_map.get(&index)
             }
             None => None,
@@ -69,12 +207,19 @@ impl Handler for MultipleChoiceHandler {
     fn new() -> MultipleChoiceHandler {
         MultipleChoiceHandler {
             choices_map: HashMap::new(),
+            labels: HashMap::new(),
             input_handler: UserInputHandler::new(),
             result: None,
+            results: vec![],
+            multiple: false,
+            toggled: HashSet::new(),
+            toggling: false,
         }
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()> {
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
         match key {
             // Scroll
             KeyCode::Down => scroll::down(window),
@@ -93,12 +238,24 @@ impl Handler for MultipleChoiceHandler {
                     Err(why) => panic!("Unable to gather text: {:?}", why),
                 };
                 self.validate_choice(window, &choice)?;
+                // The filter was cleared with the command line, so show every choice again
+                self.render_choices(window);
                 // Send 2 new refresh ticks from the main app loop when this method returns
                 window.config.did_switch = true;
             }
 
-            // User text input
-            key => self.input_handler.receive_input(window, key)?,
+            // Mark or unmark the typed items
+            KeyCode::Tab if self.toggling => {
+                let choice = self.input_handler.gather(window)?;
+                self.toggle_choices(window, &choice)?;
+                self.render_choices(window);
+            }
+
+            // User text input, which filters the choices unless it selects an index
+            _ => {
+                self.input_handler.receive_input(window, event)?;
+                self.render_choices(window);
+            }
         }
         window.redraw()?;
         Ok(())
@@ -109,6 +266,8 @@ impl Handler for MultipleChoiceHandler {
 mod kc_tests {
     use std::collections::HashMap;
 
+    use crossterm::event::KeyCode;
+
     use super::MultipleChoiceHandler;
     use crate::communication::{handlers::handler::Handler, reader::MainWindow};
 
@@ -159,6 +318,85 @@ mod kc_tests {
         assert_eq!(Some(1), mc.result);
     }
 
+    #[test]
+    fn can_validate_choice_padded() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, " 02 ").unwrap();
+
+        assert_eq!(Some(2), mc.result);
+    }
+
+    #[test]
+    fn can_validate_choice_zeros() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, "00").unwrap();
+
+        assert_eq!(Some(0), mc.result);
+    }
+
+    #[test]
+    fn can_validate_multiple_choices() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        mc.allow_multiple(true);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, "2,0-1").unwrap();
+
+        assert_eq!(Some(2), mc.result);
+        assert_eq!(vec![2, 0, 1], mc.results);
+    }
+
+    #[test]
+    fn cannot_validate_multiple_choices_with_invalid_item() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        mc.allow_multiple(true);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, "1,3").unwrap();
+
+        assert!(mc.result.is_none());
+        assert!(mc.results.is_empty());
+    }
+
+    #[test]
+    fn cannot_validate_multiple_choices_by_default() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, "0,1").unwrap();
+
+        assert!(mc.result.is_none());
+    }
+
     #[test]
     fn can_get_choice() {
         // Setup Logria
@@ -173,4 +411,220 @@ mod kc_tests {
 
         assert_eq!("b", mc.get_choice().unwrap());
     }
+
+    #[test]
+    fn can_filter_body_text() {
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&[
+            "Apache".to_string(),
+            "nginx".to_string(),
+            "Hapi".to_string(),
+        ]);
+        mc.input_handler
+            .receive_input(&mut MainWindow::_new_dummy(), KeyCode::Char('A'))
+            .unwrap();
+        mc.input_handler
+            .receive_input(&mut MainWindow::_new_dummy(), KeyCode::Char('p'))
+            .unwrap();
+
+        // Generate expected result
+        let expected = vec!["0: Apache", "2: Hapi"];
+
+        assert_eq!(mc.get_body_text(), expected);
+    }
+
+    #[test]
+    fn numbers_do_not_filter_body_text() {
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a1".to_string(), "b".to_string(), "c".to_string()]);
+        mc.input_handler
+            .receive_input(&mut MainWindow::_new_dummy(), KeyCode::Char('1'))
+            .unwrap();
+
+        // Generate expected result
+        let expected = vec!["0: a1", "1: b", "2: c"];
+
+        assert_eq!(mc.get_body_text(), expected);
+    }
+
+    #[test]
+    fn can_filter_labels() {
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["/parsers/Apache".to_string(), "/parsers/nginx".to_string()]);
+        mc.set_labels(&["Apache".to_string(), "nginx".to_string()]);
+
+        assert_eq!(mc.matching_choices("parsers"), Vec::<usize>::new());
+        assert_eq!(mc.matching_choices("NGINX"), vec![1]);
+    }
+
+    #[test]
+    fn can_filter_to_one_match_and_select_it() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&[
+            "Apache".to_string(),
+            "nginx".to_string(),
+            "Hapi".to_string(),
+        ]);
+        "gin".chars().for_each(|c| {
+            mc.receive_input(&mut logria, KeyCode::Char(c)).unwrap();
+        });
+        assert_eq!(logria.config.auxiliary_messages, vec!["1: nginx"]);
+
+        mc.receive_input(&mut logria, KeyCode::Enter).unwrap();
+
+        assert_eq!(Some(1), mc.result);
+        assert_eq!(vec![1], mc.results);
+        assert_eq!("nginx", mc.get_choice().unwrap());
+        assert_eq!(logria.config.auxiliary_messages.len(), 3);
+    }
+
+    #[test]
+    fn cannot_validate_ambiguous_filter() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&[
+            "Apache".to_string(),
+            "nginx".to_string(),
+            "Hapi".to_string(),
+        ]);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, "ap").unwrap();
+
+        assert!(mc.result.is_none());
+    }
+
+    #[test]
+    fn cannot_validate_filter_without_match() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&[
+            "Apache".to_string(),
+            "nginx".to_string(),
+            "Hapi".to_string(),
+        ]);
+
+        // Generate expected result
+        mc.validate_choice(&mut logria, "iis").unwrap();
+
+        assert!(mc.result.is_none());
+    }
+
+    #[test]
+    fn can_toggle_choices() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        mc.allow_toggling(true);
+        mc.toggle_choices(&mut logria, "2").unwrap();
+        mc.toggle_choices(&mut logria, "0-1").unwrap();
+
+        assert_eq!(mc.get_body_text(), vec!["0: * a", "1: * b", "2: * c"]);
+        assert_eq!(mc.get_choices(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn can_toggle_choice_off() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        mc.allow_toggling(true);
+        mc.toggle_choices(&mut logria, "0,2").unwrap();
+        mc.toggle_choices(&mut logria, "0").unwrap();
+
+        assert_eq!(mc.get_body_text(), vec!["0: a", "1: b", "2: * c"]);
+        assert_eq!(mc.get_choices(), vec![2]);
+    }
+
+    #[test]
+    fn toggles_survive_get_choices() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        mc.allow_toggling(true);
+        mc.toggle_choices(&mut logria, "1").unwrap();
+
+        assert_eq!(mc.get_choices(), vec![1]);
+        assert_eq!(mc.get_choices(), vec![1]);
+        assert_eq!(mc.get_body_text(), vec!["0: a", "1: * b", "2: c"]);
+    }
+
+    #[test]
+    fn cannot_toggle_invalid_choice() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string(), "c".to_string()]);
+        mc.allow_toggling(true);
+        mc.toggle_choices(&mut logria, "1,3").unwrap();
+
+        assert!(mc.get_choices().is_empty());
+    }
+
+    #[test]
+    fn can_toggle_with_tab() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&[
+            "Apache".to_string(),
+            "nginx".to_string(),
+            "Hapi".to_string(),
+        ]);
+        mc.allow_toggling(true);
+        "gin".chars().for_each(|c| {
+            mc.receive_input(&mut logria, KeyCode::Char(c)).unwrap();
+        });
+        mc.receive_input(&mut logria, KeyCode::Tab).unwrap();
+        mc.receive_input(&mut logria, KeyCode::Char('2')).unwrap();
+        mc.receive_input(&mut logria, KeyCode::Tab).unwrap();
+
+        assert!(mc.result.is_none());
+        assert_eq!(logria.config.auxiliary_messages[1], "1: * nginx");
+        assert_eq!(mc.get_choices(), vec![1, 2]);
+    }
+
+    #[test]
+    fn toggles_survive_same_choices() {
+        // Setup Logria
+        let mut logria = MainWindow::_new_dummy();
+
+        // Setup handler
+        let mut mc = MultipleChoiceHandler::new();
+        mc.set_choices(&["a".to_string(), "b".to_string()]);
+        mc.allow_toggling(true);
+        mc.toggle_choices(&mut logria, "1").unwrap();
+        mc.set_choices(&["a".to_string(), "b".to_string()]);
+        assert_eq!(mc.get_choices(), vec![1]);
+
+        mc.toggle_choices(&mut logria, "1").unwrap();
+        mc.set_choices(&["a".to_string(), "c".to_string()]);
+        assert!(mc.get_choices().is_empty());
+    }
 }
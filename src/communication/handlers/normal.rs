@@ -1,6 +1,10 @@
 use std::io::stdout;
 
-use crossterm::{cursor, event::KeyCode, queue, Result};
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    queue, Result,
+};
 
 use super::handler::Handler;
 use crate::{
@@ -8,7 +12,7 @@ use crate::{
         input::{InputType, StreamType},
         reader::MainWindow,
     },
-    ui::scroll,
+    ui::{scroll, selection, snapshot::Snapshot},
 };
 
 pub struct NormalHandler {}
@@ -38,13 +42,12 @@ impl NormalHandler {
     }
 
     fn swap_streams(&self, window: &mut MainWindow) -> Result<()> {
-        window.config.previous_stream_type = window.config.stream_type;
-        window.config.stream_type = match window.config.stream_type {
+        window.switch_stream(match window.config.stream_type {
             StreamType::StdOut => StreamType::StdErr,
             StreamType::StdErr => StreamType::StdOut,
             // Do not swap from auxiliary stream
             StreamType::Auxiliary => StreamType::Auxiliary,
-        };
+        });
         window.update_input_type(InputType::Normal)?;
         window.set_cli_cursor(None)?;
         window.reset_command_line()?;
@@ -52,6 +55,124 @@ impl NormalHandler {
         window.redraw()?;
         Ok(())
     }
+
+    /// Toggle rendering stderr and stdout in separate panes
+    fn toggle_split(&self, window: &mut MainWindow) -> Result<()> {
+        window.config.split_view = !window.config.split_view;
+        window.reset_output()?;
+        if window.config.split_view {
+            window.write_to_command_line("Split view: stderr above, stdout below")?;
+        } else {
+            window.reset_command_line()?;
+        }
+        Ok(())
+    }
+
+    /// Toggle showing the newest messages above the command line while scrolled elsewhere
+    fn toggle_tail(&self, window: &mut MainWindow) -> Result<()> {
+        window.config.tail_view = !window.config.tail_view;
+        window.reset_output()?;
+        if window.config.tail_view {
+            window.write_to_command_line("Tail view: newest messages shown at the bottom")?;
+        } else {
+            window.reset_command_line()?;
+        }
+        Ok(())
+    }
+
+    /// Stop showing new messages until `c` is pressed again, while still receiving them
+    fn toggle_freeze(&self, window: &mut MainWindow) -> Result<()> {
+        if matches!(window.config.stream_type, StreamType::Auxiliary) {
+            return window.write_to_command_line("Only a live buffer can be frozen.");
+        }
+        match window.toggle_freeze() {
+            Some(received) => {
+                window.config.current_status = None;
+                window.reset_output()?;
+                window.write_to_command_line(&format!(
+                    "Resumed; {} messages arrived while frozen.",
+                    received
+                ))
+            }
+            None => {
+                let status = format!(
+                    "Frozen at {} messages; press c to resume.",
+                    window.number_of_messages()
+                );
+                window.config.current_status = Some(status.to_owned());
+                window.reset_output()?;
+                window.write_to_command_line(&status)
+            }
+        }
+    }
+
+    /// Start selecting a range of messages to copy or save
+    fn start_selection(&self, window: &mut MainWindow) -> Result<()> {
+        if matches!(window.config.stream_type, StreamType::Auxiliary) {
+            return window.write_to_command_line("Selections can only be made in a live buffer.");
+        }
+        selection::start(window);
+        if selection::is_active(window) {
+            window.write_to_command_line(
+                "Selecting; ↑/↓ to extend, y to copy to the snapshot, :save to write, esc to cancel",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Copy the selected messages into the snapshot so they can be viewed with `f`
+    fn copy_selection(&self, window: &mut MainWindow) -> Result<()> {
+        if let Some((start, end)) = selection::range(window) {
+            let messages = window.messages_in_range(start, end);
+            let count = messages.len();
+            window.config.snapshot = Some(Snapshot::new(messages));
+            selection::clear(window);
+            window.write_to_command_line(&format!(
+                "Copied {} messages to the snapshot; press f to view them.",
+                count
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Switch between the snapshot taken with `:snapshot` and the live buffer
+    fn toggle_snapshot(&self, window: &mut MainWindow) -> Result<()> {
+        if !window.toggle_snapshot() {
+            return match window.config.snapshot {
+                Some(_) => window.write_to_command_line("Close this view to see the snapshot."),
+                None => {
+                    window.write_to_command_line("No snapshot to view; take one with :snapshot")
+                }
+            };
+        }
+        window.reset_output()?;
+        if window.viewing_snapshot() {
+            window
+                .write_to_command_line("Viewing snapshot; press f to return to live messages.")?;
+        } else {
+            window.reset_command_line()?;
+        }
+        Ok(())
+    }
+
+    /// Return from an auxiliary view, i.e. diagnostics, to the stream the user was reading
+    fn close_auxiliary(&self, window: &mut MainWindow) -> Result<()> {
+        if selection::is_active(window) {
+            selection::clear(window);
+            return window.reset_command_line();
+        }
+        if window.viewing_snapshot() {
+            return self.toggle_snapshot(window);
+        }
+        if let StreamType::Auxiliary = window.config.stream_type {
+            window.config.stream_type = window.config.previous_stream_type;
+            window.clear_auxiliary();
+            window.config.scroll_state = scroll::ScrollState::Bottom;
+            window.reset_command_line()?;
+            window.reset_output()?;
+        }
+        Ok(())
+    }
 }
 
 impl Handler for NormalHandler {
@@ -59,8 +180,16 @@ impl Handler for NormalHandler {
         NormalHandler {}
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()> {
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
+        let control = event.modifiers.contains(KeyModifiers::CONTROL);
         match key {
+            // Extend the selection
+            KeyCode::Down if selection::is_active(window) => selection::down(window),
+            KeyCode::Up if selection::is_active(window) => selection::up(window),
+            KeyCode::Char('y') => self.copy_selection(window)?,
+
             // Scroll
             KeyCode::Down => scroll::down(window),
             KeyCode::Up => scroll::up(window),
@@ -70,15 +199,143 @@ impl Handler for NormalHandler {
             KeyCode::End => scroll::bottom(window),
             KeyCode::PageUp => scroll::pg_up(window),
             KeyCode::PageDown => scroll::pg_down(window),
+            KeyCode::Char('u') if control => scroll::half_pg_up(window),
+            KeyCode::Char('d') if control => scroll::half_pg_down(window),
+            KeyCode::Char('m') => scroll::center(window),
 
             // Modes
             KeyCode::Char(':') => window.set_command_mode(None)?,
             KeyCode::Char('/') => self.set_regex_mode(window)?,
             KeyCode::Char('p') => self.set_parser_mode(window)?,
             KeyCode::Char('s') => self.swap_streams(window)?,
+            KeyCode::Char('w') => self.toggle_split(window)?,
+            KeyCode::Char('t') => self.toggle_tail(window)?,
+            KeyCode::Char('c') => self.toggle_freeze(window)?,
+            KeyCode::Char('f') => self.toggle_snapshot(window)?,
+            KeyCode::Char('V') => self.start_selection(window)?,
+            KeyCode::Esc => self.close_auxiliary(window)?,
             _ => {}
         }
         window.redraw()?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod auxiliary_tests {
+    use crossterm::event::KeyCode;
+
+    use super::NormalHandler;
+    use crate::{
+        communication::{
+            handlers::{handler::Handler, parser::ParserState},
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        util::credits::gen,
+    };
+
+    #[test]
+    fn test_leaving_auxiliary_clears_buffer() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = NormalHandler::new();
+        logria.input_type = InputType::Normal;
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.generate_auxiliary_messages = Some(gen);
+        logria.config.auxiliary_messages = gen();
+
+        handler.receive_input(&mut logria, KeyCode::Esc).unwrap();
+
+        assert!(logria.config.auxiliary_messages.is_empty());
+        assert!(logria.config.generate_auxiliary_messages.is_none());
+        assert!(matches!(logria.config.stream_type, StreamType::StdOut));
+    }
+
+    #[test]
+    fn test_clear_keeps_parser_output() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.parser_state = ParserState::Full;
+        logria.config.auxiliary_messages = vec![String::from("parsed")];
+
+        assert!(!logria.clear_auxiliary());
+        assert_eq!(logria.config.auxiliary_messages, vec!["parsed"]);
+    }
+}
+
+#[cfg(test)]
+mod half_page_tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::NormalHandler;
+    use crate::{
+        communication::{handlers::handler::Handler, input::InputType, reader::MainWindow},
+        ui::scroll::ScrollState,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.scroll_state = ScrollState::Bottom;
+        logria.determine_render_position();
+        logria
+    }
+
+    #[test]
+    fn test_control_u_scrolls_half_page_up() {
+        let mut logria = window();
+        let mut handler = NormalHandler::new();
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL),
+            )
+            .unwrap();
+        assert_eq!(logria.determine_render_position(), (90, 97));
+    }
+
+    #[test]
+    fn test_control_d_scrolls_half_page_down() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        logria.determine_render_position();
+        let mut handler = NormalHandler::new();
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            )
+            .unwrap();
+        assert_eq!(logria.determine_render_position(), (3, 10));
+    }
+
+    #[test]
+    fn test_d_and_control_d_are_distinct() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        logria.determine_render_position();
+        let mut handler = NormalHandler::new();
+
+        handler
+            .receive_input(&mut logria, KeyCode::Char('d'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Top));
+
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            )
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+    }
+
+    #[test]
+    fn test_u_without_control_does_not_scroll() {
+        let mut logria = window();
+        let mut handler = NormalHandler::new();
+        handler
+            .receive_input(&mut logria, KeyCode::Char('u'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Bottom));
+    }
+}
@@ -1,7 +1,9 @@
 use std::path::Path;
 
-use crossterm::{event::KeyCode, Result};
-use regex::Regex;
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    Result,
+};
 
 use crate::{
     communication::{
@@ -11,14 +13,28 @@ use crate::{
         input::{InputType::Normal, StreamType},
         reader::MainWindow,
     },
-    extensions::{
-        extension::ExtensionMethods,
-        parser::{Parser, PatternType},
-    },
+    extensions::{extension::ExtensionMethods, parser::Parser},
     ui::scroll,
     util::error::LogriaError,
 };
 
+/// List the indices of the parsed parts being rendered, i.e. `1, 3`
+pub fn format_indices(indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|index| index.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Describe the parsed parts being rendered for the status line, i.e. `field 1` or `fields 1, 3`
+fn fields_status(indices: &[usize]) -> String {
+    match indices {
+        [index] => format!("field {}", index),
+        indices => format!("fields {}", format_indices(indices)),
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ParserState {
     Disabled,
@@ -46,9 +62,16 @@ impl ParserHandler {
         body_text
     }
 
+    /// Match the choices to the saved parsers, dropping marks once the list changes
+    fn refresh_parsers(&mut self) {
+        self.mc_handler.set_choices(&Parser::list_full());
+        self.mc_handler.set_labels(&Parser::list_clean());
+    }
+
     fn select_parser(&mut self, window: &mut MainWindow) -> Result<()> {
-        let parsers = Parser::list_full();
-        self.mc_handler.set_choices(&parsers);
+        self.refresh_parsers();
+        self.mc_handler.allow_toggling(true);
+        self.mc_handler.allow_multiple(false);
         window.render_auxiliary_text()?;
         Ok(())
     }
@@ -59,6 +82,9 @@ impl ParserHandler {
             match parser.get_example() {
                 Ok(examples) => {
                     self.mc_handler.set_choices(&examples);
+                    // Several parts can be rendered together, i.e. `1,3`
+                    self.mc_handler.allow_multiple(true);
+                    self.mc_handler.allow_toggling(false);
                 }
                 Err(why) => {
                     window.write_to_command_line(&why.to_string())?;
@@ -74,40 +100,20 @@ impl ParserHandler {
         Ok(())
     }
 
-    /// Parse a message with the current parser rules
-    fn parse(
-        &mut self,
-        index: usize,
-        message: &str,
-    ) -> std::result::Result<Option<String>, LogriaError> {
-        match self.parser.as_ref().unwrap().pattern_type {
-            PatternType::Regex => match self.parser.as_ref().unwrap().get_regex() {
-                Ok(pattern) => Ok(self.regex_handle(message, index, pattern)),
-                Err(why) => Err(why),
-            },
-            PatternType::Split => {
-                Ok(self.split_handle(message, index, &self.parser.as_ref().unwrap().pattern))
-            }
-        }
-    }
-
-    /// Parse a message with regex logic
-    fn regex_handle(&self, message: &str, index: usize, pattern: Regex) -> Option<String> {
-        // We add 1 here because the zeroth index of a Capture is the original message
-        match pattern.captures(message) {
-            Some(caps) => caps
-                .get(index.checked_add(1).unwrap_or(index))
-                .map(|s| s.as_str().to_owned()),
-            None => None,
+    /// Parse a batch of messages with the current parser rules
+    fn apply(
+        &self,
+        messages: &[String],
+        indices: &[usize],
+    ) -> std::result::Result<Vec<Option<String>>, LogriaError> {
+        match &self.parser {
+            Some(parser) => parser.apply(messages, indices),
+            None => Err(LogriaError::InvalidParserState(
+                "no parser selected!".to_string(),
+            )),
         }
     }
 
-    /// Parse a message with split logic
-    fn split_handle(&self, message: &str, index: usize, pattern: &str) -> Option<String> {
-        let result: Vec<&str> = message.split_terminator(pattern).collect();
-        result.get(index).map(|part| String::from(*part))
-    }
-
     /// Handle aggregation logic for a single message
     fn aggregate_handle(
         &mut self,
@@ -116,76 +122,67 @@ impl ParserHandler {
         render: bool,
     ) -> std::result::Result<Vec<String>, LogriaError> {
         match &mut self.parser {
-            Some(parser) => {
-                // Split message into a Vec<&str> of its parts
-                let message_parts: std::result::Result<Vec<&str>, LogriaError> = match parser
-                    .pattern_type
-                {
-                    PatternType::Regex => match parser.get_regex() {
-                        Ok(pattern) => {
-                            if let Some(captures) = pattern.captures(message) {
-                                Ok(captures
-                                    .iter()
-                                    .skip(1)
-                                    .flatten()
-                                    .map(|f| f.as_str())
-                                    .collect())
-                            } else {
-                                Err(LogriaError::CannotParseMessage(
-                                    "regex did not match message!".to_string(),
-                                ))
-                            }
-                        }
-                        Err(why) => Err(why),
-                    },
-                    PatternType::Split => Ok(message.split_terminator(&parser.pattern).collect()),
-                };
-
-                match message_parts {
-                    Ok(message_parts) => {
-                        // If we got this far, allocate the return value
-                        let mut aggregated_data = vec![];
-                        for (idx, part) in message_parts.iter().enumerate() {
-                            if let Some(item) = parser.order.get(idx).cloned() {
-                                if let Some(aggregator) = parser.aggregator_map.get_mut(&item) {
-                                    aggregator.update(part)?;
-                                    if render {
-                                        // Name of aggregated part
-                                        aggregated_data.push(item);
-                                        // Messages generated for that aggregator
-                                        aggregated_data.extend(aggregator.messages(num_to_get));
-                                    }
-                                } else {
-                                    return Err(LogriaError::InvalidParserState(format!(
-                                        "aggregator missing for {}!",
-                                        item
-                                    )));
-                                }
-                            } else {
-                                return Err(LogriaError::CannotParseMessage(
-                                    "number of aggregation methods not equal to number of matches!"
-                                        .to_string(),
-                                ));
-                            }
-                        }
-                        Ok(aggregated_data)
-                    }
-                    Err(why) => Err(why),
-                }
-            }
+            Some(parser) => parser.aggregate(message, num_to_get, render),
             None => Err(LogriaError::InvalidParserState(
                 "no parser selected!".to_string(),
             )),
         }
     }
 
+    /// Use the parser a session was opened with, if one is waiting
+    fn adopt_attached_parser(&mut self, window: &mut MainWindow) {
+        if let Some((name, parser)) = window.config.attached_parser.take() {
+            window.config.timestamp_formats = parser.date_formats();
+            self.parser = Some(parser);
+            self.redraw = true;
+            self.status = format!(
+                "Parsing with {}, {}",
+                name,
+                fields_status(&window.config.parser_indices)
+            );
+            window.config.current_status = Some(self.status.to_owned());
+        }
+    }
+
+    /// Return to parser selection when a parser file cannot be loaded, i.e. if it was deleted
+    fn handle_load_failure(&mut self, window: &mut MainWindow, why: LogriaError) -> Result<()> {
+        self.parser = None;
+        window.config.timestamp_formats.clear();
+        self.status.clear();
+        window.config.parser_state = ParserState::NeedsParser;
+        window.config.parser_indices.clear();
+        window.config.stream_type = StreamType::Auxiliary;
+        window.config.generate_auxiliary_messages = Some(ParserHandler::parser_messages_handle);
+
+        // Refresh the choices so a missing file is no longer listed
+        self.select_parser(window)?;
+        window.write_to_command_line(&why.to_string())?;
+        Ok(())
+    }
+
+    /// Rebuild the aggregation panel from the existing aggregators without processing any messages
+    fn rerender_aggregation(&self, window: &mut MainWindow) {
+        if !window.config.rerender_aggregation {
+            return;
+        }
+        window.config.rerender_aggregation = false;
+        if let Some(parser) = &self.parser {
+            // An empty panel means nothing has been aggregated yet, so there is nothing to update
+            if window.config.aggregation_enabled && !window.config.auxiliary_messages.is_empty() {
+                window.config.auxiliary_messages =
+                    parser.render_aggregation(&window.config.num_to_aggregate);
+            }
+        }
+    }
+
     /// Reset parser
     fn reset(&mut self, window: &mut MainWindow) {
         // Parser still active, but not set up
         window.config.parser_state = ParserState::NeedsParser;
         window.config.auxiliary_messages.clear();
         self.parser = None;
-        window.config.parser_index = 0;
+        window.config.timestamp_formats.clear();
+        window.config.parser_indices.clear();
         window.config.did_switch = true;
     }
 }
@@ -207,6 +204,7 @@ impl ProcessorMethods for ParserHandler {
     /// Clear the parsed messages from the message buffer
     fn clear_matches(&mut self, window: &mut MainWindow) -> Result<()> {
         self.parser = None;
+        window.config.timestamp_formats.clear();
         window.config.auxiliary_messages.clear();
         window.config.last_index_processed = 0;
         window.config.aggregation_enabled = false;
@@ -217,8 +215,12 @@ impl ProcessorMethods for ParserHandler {
 
     /// Parse messages, loading the buffer of parsed messages in the main window
     fn process_matches(&mut self, window: &mut MainWindow) -> Result<()> {
+        self.adopt_attached_parser(window);
+
         // Only process if the parser is set up properly
         if let ParserState::Full = window.config.parser_state {
+            self.rerender_aggregation(window);
+
             // TODO: Possibly async? Possibly loading indicator for large jobs?
             if self.parser.is_some() {
                 // Start from where we left off to the most recent message
@@ -227,6 +229,23 @@ impl ProcessorMethods for ParserHandler {
                     window.previous_messages().len(),
                 );
 
+                // Without aggregation, parse the new messages as a single batch
+                if !window.config.aggregation_enabled {
+                    let parsed = match window.previous_messages().get(buf_range.0..buf_range.1) {
+                        Some(messages) => self.apply(messages, &window.config.parser_indices),
+                        None => Ok(vec![]),
+                    };
+                    if let Ok(parsed) = parsed {
+                        window
+                            .config
+                            .auxiliary_messages
+                            .extend(parsed.into_iter().flatten());
+                    }
+                    // Update the last spot so we know where to start next time
+                    window.config.last_index_processed = buf_range.1;
+                    return Ok(());
+                }
+
                 // Iterate "forever", skipping to the start and taking up till end-start
                 // TODO: Something to indicate progress
                 let last = buf_range.1.checked_sub(1).unwrap_or(buf_range.0);
@@ -234,31 +253,24 @@ impl ProcessorMethods for ParserHandler {
                     .skip(buf_range.0)
                     .take(buf_range.1.checked_sub(buf_range.0).unwrap_or(buf_range.0))
                 {
-                    if window.config.aggregation_enabled {
-                        match self.aggregate_handle(
-                            &window.previous_messages()[index],
-                            &window.config.num_to_aggregate,
-                            index == last,
-                        ) {
-                            Ok(aggregated_messages) => {
-                                if !aggregated_messages.is_empty() {
-                                    window.config.auxiliary_messages.clear();
-                                    window.config.auxiliary_messages.extend(aggregated_messages);
-                                }
+                    match self.aggregate_handle(
+                        &window.previous_messages()[index],
+                        &window.config.num_to_aggregate,
+                        index == last,
+                    ) {
+                        Ok(aggregated_messages) => {
+                            if !aggregated_messages.is_empty() {
+                                window.config.auxiliary_messages.clear();
+                                window.config.auxiliary_messages.extend(aggregated_messages);
                             }
-                            Err(why) => {
-                                // If the message failed parsing, it might just be a different format, so we ignore it
-                                // If the parser is in an invalid state, alert the user
-                                if let LogriaError::CannotParseMessage(error) = why {
-                                    window.write_to_command_line(&error)?;
-                                }
+                        }
+                        Err(why) => {
+                            // If the message failed parsing, it might just be a different format, so we ignore it
+                            // If the parser is in an invalid state, alert the user
+                            if let LogriaError::CannotParseMessage(error) = why {
+                                window.write_to_command_line(&error)?;
                             }
                         }
-                    } else if let Ok(Some(message)) = self.parse(
-                        window.config.parser_index,
-                        &window.previous_messages()[index],
-                    ) {
-                        window.config.auxiliary_messages.push(message);
                     }
                     // Update the last spot so we know where to start next time
                     window.config.last_index_processed = index + 1;
@@ -279,9 +291,23 @@ impl Handler for ParserHandler {
         }
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> crossterm::Result<()> {
+    fn receive_input(
+        &mut self,
+        window: &mut MainWindow,
+        key: impl Into<KeyEvent>,
+    ) -> crossterm::Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
+        let control = event.modifiers.contains(KeyModifiers::CONTROL);
+        self.adopt_attached_parser(window);
+
         // Enable command mode for parsers
         if key == KeyCode::Char(':') {
+            // Parsers marked in the list are removed by `: r`
+            if let ParserState::NeedsParser = window.config.parser_state {
+                self.refresh_parsers();
+            }
+            window.config.marked_items = self.mc_handler.get_choices();
             window.set_command_mode(Some(Parser::del))?;
             // Early escape to not send a `:` char to the rest of this method
             return Ok(());
@@ -304,6 +330,7 @@ impl Handler for ParserHandler {
                             parser.setup();
 
                             // Set the new parser and parser state
+                            window.config.timestamp_formats = parser.date_formats();
                             self.parser = Some(parser);
 
                             window.config.parser_state = ParserState::NeedsIndex;
@@ -317,9 +344,7 @@ impl Handler for ParserHandler {
                             // Update the auxillary messages for the second setup step
                             self.select_index(window)?;
                         }
-                        Err(why) => {
-                            window.write_to_command_line(&why.to_string())?;
-                        }
+                        Err(why) => self.handle_load_failure(window, why)?,
                     },
                     None => {
                         if self.redraw {
@@ -333,21 +358,24 @@ impl Handler for ParserHandler {
                         }
                         window.render_auxiliary_text()?;
                         self.select_parser(window)?;
-                        self.mc_handler.receive_input(window, key)?;
+                        self.mc_handler.receive_input(window, event)?;
                     }
                 }
             }
             ParserState::NeedsIndex => {
                 match self.mc_handler.result {
-                    Some(item) => {
+                    Some(_) => {
                         // Tell the parser to redraw on the next tick
                         self.redraw = true;
 
+                        // Every chosen index, since several parts can be rendered together
+                        let items = std::mem::take(&mut self.mc_handler.results);
+
                         // get_choice() clears the item from the mc handler)
                         self.mc_handler.get_choice();
 
-                        // Set the new parser index and parser state
-                        window.config.parser_index = item;
+                        // Set the new parser indices and parser state
+                        window.config.parser_indices = items;
                         window.config.parser_state = ParserState::Full;
 
                         // Clear auxillary messages for next use
@@ -357,7 +385,10 @@ impl Handler for ParserHandler {
                         self.process_matches(window)?;
 
                         // Update the status string
-                        self.status.push_str(&format!(", field {}", item));
+                        self.status.push_str(&format!(
+                            ", {}",
+                            fields_status(&window.config.parser_indices)
+                        ));
 
                         // Clear the screen for new messages
                         window.reset_output()?;
@@ -367,7 +398,7 @@ impl Handler for ParserHandler {
                         window.write_status()?;
                     }
                     None => {
-                        self.mc_handler.receive_input(window, key)?;
+                        self.mc_handler.receive_input(window, event)?;
                     }
                 }
             }
@@ -383,6 +414,8 @@ impl Handler for ParserHandler {
                     KeyCode::End => scroll::bottom(window),
                     KeyCode::PageUp => scroll::pg_up(window),
                     KeyCode::PageDown => scroll::pg_down(window),
+                    KeyCode::Char('u') if control => scroll::half_pg_up(window),
+                    KeyCode::Char('d') if control => scroll::half_pg_down(window),
 
                     // Build new parser
                     KeyCode::Char('p') => {
@@ -395,7 +428,7 @@ impl Handler for ParserHandler {
                         if !window.config.aggregation_enabled {
                             let new_status = self.status.to_owned();
                             window.config.current_status = Some(new_status.replace(
-                                &format!("field {}", window.config.parser_index),
+                                &fields_status(&window.config.parser_indices),
                                 "aggregation mode",
                             ));
                             window.write_status()?;
@@ -452,11 +485,47 @@ mod parse_tests {
         );
         handler.parser = Some(parser);
 
-        let parsed_message = handler.parse(0, "I - Am - A - Test").unwrap().unwrap();
+        let parsed_message = handler
+            .apply(&[String::from("I - Am - A - Test")], &[0])
+            .unwrap()
+            .remove(0)
+            .unwrap();
 
         assert_eq!(parsed_message, String::from("I"))
     }
 
+    #[test]
+    fn test_adopts_attached_parser() {
+        let mut handler = ParserHandler::new();
+        let mut logria = MainWindow::_new_dummy();
+
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Count);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("1"),
+            vec![String::from("1")],
+            map,
+        );
+        parser.setup();
+        logria.config.attached_parser = Some((String::from("Test"), parser));
+        logria.config.parser_state = ParserState::Full;
+        logria.config.parser_indices = vec![0];
+        logria.config.previous_stream_type = StreamType::StdErr;
+        logria.config.stream_type = StreamType::Auxiliary;
+
+        handler.process_matches(&mut logria).unwrap();
+
+        assert!(handler.parser.is_some());
+        assert!(logria.config.attached_parser.is_none());
+        assert_eq!(logria.config.auxiliary_messages.len(), 100);
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Parsing with Test, field 0"))
+        );
+    }
+
     #[test]
     fn test_does_regex() {
         // Create handler
@@ -475,8 +544,9 @@ mod parse_tests {
         handler.parser = Some(parser);
 
         let parsed_message = handler
-            .parse(0, "Log message part 65 test")
+            .apply(&[String::from("Log message part 65 test")], &[0])
             .unwrap()
+            .remove(0)
             .unwrap();
 
         assert_eq!(parsed_message, String::from("65"))
@@ -513,7 +583,7 @@ mod parse_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -571,7 +641,7 @@ mod parse_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -602,16 +672,18 @@ mod parse_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date("[year]-[month]-[day]".to_string()),
+            AggregationMethod::Date("[year]-[month]-[day]".to_string().into()),
         );
         map.insert(
             String::from("Time"),
-            AggregationMethod::Time("[hour]:[minute]:[second]".to_string()),
+            AggregationMethod::Time("[hour]:[minute]:[second]".to_string().into()),
         );
         map.insert(
             String::from("DateTime"),
             AggregationMethod::DateTime(
-                "[year]-[month]-[day] [hour]:[minute]:[second]".to_string(),
+                "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    .to_string()
+                    .into(),
             ),
         );
         let mut parser = Parser::new(
@@ -632,7 +704,7 @@ mod parse_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -646,16 +718,19 @@ mod parse_tests {
                 "    Count: 4",
                 "    Earliest: 2021-03-10",
                 "    Latest: 2021-03-15",
+                "    Span: 5 days",
                 "Time",
                 "    Rate: 4 per minute",
                 "    Count: 4",
                 "    Earliest: 8:10:26.0",
                 "    Latest: 8:10:56.0",
+                "    Span: 30 seconds",
                 "DateTime",
                 "    Rate: 2 per hour",
                 "    Count: 4",
                 "    Earliest: 2021-03-19 8:10:26.0",
-                "    Latest: 2021-03-19 10:30:26.0"
+                "    Latest: 2021-03-19 10:30:26.0",
+                "    Span: 2 hours"
             ]
         );
     }
@@ -698,7 +773,7 @@ mod regex_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 0;
+        logria.config.parser_indices = vec![0];
         logria.config.previous_stream_type = StreamType::StdErr;
 
         handler.process_matches(&mut logria).unwrap();
@@ -729,7 +804,7 @@ mod regex_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
 
         handler.process_matches(&mut logria).unwrap();
@@ -761,7 +836,7 @@ mod regex_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
 
         handler.process_matches(&mut logria).unwrap();
@@ -803,7 +878,7 @@ mod regex_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -835,6 +910,7 @@ mod regex_tests {
 #[cfg(test)]
 mod split_tests {
     use super::ParserHandler;
+    use crossterm::event::KeyCode;
     use std::collections::HashMap;
 
     use crate::{
@@ -867,7 +943,7 @@ mod split_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 0;
+        logria.config.parser_indices = vec![0];
         logria.config.previous_stream_type = StreamType::StdErr;
 
         handler.process_matches(&mut logria).unwrap();
@@ -901,7 +977,7 @@ mod split_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
 
         handler.process_matches(&mut logria).unwrap();
@@ -912,6 +988,88 @@ mod split_tests {
         assert_eq!(logria.config.auxiliary_messages.len(), 10)
     }
 
+    /// A split parser for the messages in `MainWindow::_new_dummy_parse()`
+    fn hyphen_parser() -> Parser {
+        let mut map = HashMap::new();
+        map.insert(String::from("full"), AggregationMethod::Count);
+        map.insert(String::from("minus_1"), AggregationMethod::Count);
+        map.insert(String::from("minus_2"), AggregationMethod::Count);
+        map.insert(String::from("minus_3"), AggregationMethod::Count);
+        Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("10 - 9 - 8 - 7"),
+            vec![
+                String::from("full"),
+                String::from("minus_1"),
+                String::from("minus_2"),
+                String::from("minus_3"),
+            ],
+            map,
+        )
+    }
+
+    #[test]
+    fn test_can_setup_with_session_two_indices() {
+        let mut logria = MainWindow::_new_dummy_parse();
+        let mut handler = ParserHandler::new();
+
+        // Update window config
+        handler.parser = Some(hyphen_parser());
+        logria.config.parser_state = ParserState::Full;
+        logria.input_type = InputType::Parser;
+        logria.config.parser_indices = vec![1, 3];
+        logria.config.previous_stream_type = StreamType::StdErr;
+
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(
+            logria.config.auxiliary_messages[0..3],
+            vec!["9 | 7", "10 | 8", "11 | 9"]
+        );
+        assert_eq!(logria.config.auxiliary_messages.len(), 100);
+    }
+
+    #[test]
+    fn test_can_setup_with_session_reversed_indices() {
+        let mut logria = MainWindow::_new_dummy_parse();
+        let mut handler = ParserHandler::new();
+
+        // Update window config
+        handler.parser = Some(hyphen_parser());
+        logria.config.parser_state = ParserState::Full;
+        logria.input_type = InputType::Parser;
+        logria.config.parser_indices = vec![2, 0];
+        logria.config.previous_stream_type = StreamType::StdErr;
+
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.auxiliary_messages[0], "8 | 10");
+    }
+
+    #[test]
+    fn test_can_select_two_indices() {
+        let mut logria = MainWindow::_new_dummy_parse();
+        let mut handler = ParserHandler::new();
+        handler.parser = Some(hyphen_parser());
+        handler.status.push_str("Parsing with hyphens");
+        logria.input_type = InputType::Parser;
+        logria.config.parser_state = ParserState::NeedsIndex;
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.previous_stream_type = StreamType::StdErr;
+
+        // Choose two of the parts of the example
+        handler.select_index(&mut logria).unwrap();
+        handler
+            .mc_handler
+            .validate_choice(&mut logria, "1,3")
+            .unwrap();
+        handler.receive_input(&mut logria, KeyCode::Enter).unwrap();
+
+        assert_eq!(logria.config.parser_state, ParserState::Full);
+        assert_eq!(logria.config.parser_indices, vec![1, 3]);
+        assert_eq!(handler.status, "Parsing with hyphens, fields 1, 3");
+        assert_eq!(logria.config.auxiliary_messages[0], "9 | 7");
+    }
+
     #[test]
     fn test_can_setup_with_session_aggregated() {
         let mut logria = MainWindow::_new_dummy_parse();
@@ -943,7 +1101,7 @@ mod split_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -972,6 +1130,69 @@ mod split_tests {
     }
 }
 
+#[cfg(test)]
+mod json_tests {
+    use super::ParserHandler;
+    use std::collections::HashMap;
+
+    use crate::{
+        communication::{
+            handlers::{handler::Handler, parser::ParserState, processor::ProcessorMethods},
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        extensions::parser::{Parser, PatternType},
+        util::aggregators::aggregator::AggregationMethod,
+    };
+
+    fn json_window() -> (MainWindow, ParserHandler) {
+        let mut logria = MainWindow::_new_dummy_parse_json();
+
+        let mut map = HashMap::new();
+        map.insert(String::from("Level"), AggregationMethod::Count);
+        map.insert(String::from("Status"), AggregationMethod::Sum);
+        let mut parser = Parser::new(
+            String::from("level, response.status"),
+            PatternType::Json,
+            String::from(r#"{"level": "info", "response": {"status": 200}}"#),
+            vec![String::from("Level"), String::from("Status")],
+            map,
+        );
+        parser.setup();
+
+        let mut handler = ParserHandler::new();
+        handler.parser = Some(parser);
+        logria.config.parser_state = ParserState::Full;
+        logria.input_type = InputType::Parser;
+        logria.config.previous_stream_type = StreamType::StdErr;
+        (logria, handler)
+    }
+
+    #[test]
+    fn test_can_parse_nested_field() {
+        let (mut logria, mut handler) = json_window();
+        logria.config.parser_indices = vec![1];
+
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.auxiliary_messages, vec!["200", "500"]);
+    }
+
+    #[test]
+    fn test_can_aggregate_around_unparsable_messages() {
+        let (mut logria, mut handler) = json_window();
+        logria.config.aggregation_enabled = true;
+        logria.config.num_to_aggregate = 1;
+
+        handler.process_matches(&mut logria).unwrap();
+        // The messages that are not JSON or are missing a field are skipped
+        assert_eq!(logria.config.last_index_processed, 4);
+        assert_eq!(
+            handler.parser.unwrap().render_aggregation(&1)[2..],
+            vec!["Status", "    Total: 700"]
+        );
+    }
+}
+
 #[cfg(test)]
 mod failure_tests {
     use super::ParserHandler;
@@ -1018,7 +1239,7 @@ mod failure_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -1057,7 +1278,7 @@ mod failure_tests {
         handler.parser = Some(parser);
         logria.config.parser_state = ParserState::Full;
         logria.input_type = InputType::Parser;
-        logria.config.parser_index = 1;
+        logria.config.parser_indices = vec![1];
         logria.config.previous_stream_type = StreamType::StdErr;
         logria.config.aggregation_enabled = true;
 
@@ -1065,3 +1286,150 @@ mod failure_tests {
         assert_eq!(logria.config.auxiliary_messages, Vec::<String>::new());
     }
 }
+
+#[cfg(test)]
+mod load_failure_tests {
+    use std::env::temp_dir;
+
+    use crossterm::event::KeyCode;
+
+    use super::ParserHandler;
+    use crate::communication::{
+        handlers::{handler::Handler, parser::ParserState},
+        input::{InputType, StreamType},
+        reader::MainWindow,
+    };
+
+    fn missing_parser() -> String {
+        temp_dir()
+            .join("logria_missing_parser")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn returns_to_selection_when_parser_is_missing() {
+        let mut logria = MainWindow::_new_dummy_parse();
+        let mut handler = ParserHandler::new();
+        logria.input_type = InputType::Parser;
+        logria.config.parser_state = ParserState::NeedsParser;
+
+        handler.mc_handler.set_choices(&[missing_parser()]);
+        handler.mc_handler.result = Some(0);
+        handler.receive_input(&mut logria, KeyCode::Enter).unwrap();
+
+        assert!(handler.parser.is_none());
+        assert!(handler.status.is_empty());
+        assert_eq!(logria.config.parser_state, ParserState::NeedsParser);
+        assert!(matches!(logria.config.stream_type, StreamType::Auxiliary));
+        assert!(logria.config.generate_auxiliary_messages.is_some());
+    }
+
+    #[test]
+    fn clears_previous_parser_when_parser_is_missing() {
+        let mut logria = MainWindow::_new_dummy_parse();
+        let mut handler = ParserHandler::new();
+        logria.input_type = InputType::Parser;
+        logria.config.parser_state = ParserState::Disabled;
+        logria.config.parser_indices = vec![3];
+        handler.status.push_str("Parsing with deleted, field 3");
+
+        handler.mc_handler.set_choices(&[missing_parser()]);
+        handler.mc_handler.result = Some(0);
+        handler.receive_input(&mut logria, KeyCode::Enter).unwrap();
+
+        assert!(handler.status.is_empty());
+        assert!(logria.config.parser_indices.is_empty());
+        assert_eq!(logria.config.parser_state, ParserState::NeedsParser);
+        assert!(handler.mc_handler.result.is_none());
+    }
+}
+
+#[cfg(test)]
+mod rerender_tests {
+    use super::ParserHandler;
+    use std::collections::HashMap;
+
+    use crate::{
+        communication::{
+            handlers::{handler::Handler, parser::ParserState, processor::ProcessorMethods},
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        extensions::parser::{Parser, PatternType},
+        util::aggregators::aggregator::AggregationMethod,
+    };
+
+    fn aggregating_window() -> (MainWindow, ParserHandler) {
+        let mut logria = MainWindow::_new_dummy_parse();
+        let mut handler = ParserHandler::new();
+
+        // Create Parser
+        let mut map = HashMap::new();
+        map.insert(String::from("full"), AggregationMethod::Count);
+        map.insert(String::from("rest"), AggregationMethod::None);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("1"),
+            vec![
+                String::from("full"),
+                String::from("rest"),
+                String::from("rest"),
+                String::from("rest"),
+            ],
+            map,
+        );
+        parser.setup();
+        handler.parser = Some(parser);
+
+        // Update window config
+        logria.config.parser_state = ParserState::Full;
+        logria.input_type = InputType::Parser;
+        logria.config.previous_stream_type = StreamType::StdErr;
+        logria.config.aggregation_enabled = true;
+        logria.config.num_to_aggregate = 5;
+        handler.process_matches(&mut logria).unwrap();
+        (logria, handler)
+    }
+
+    #[test]
+    fn test_rerender_updates_count() {
+        let (mut logria, mut handler) = aggregating_window();
+        let before = logria.config.auxiliary_messages.clone();
+
+        logria.config.num_to_aggregate = 2;
+        logria.config.rerender_aggregation = true;
+        handler.process_matches(&mut logria).unwrap();
+
+        let after = &logria.config.auxiliary_messages;
+        assert_eq!(before.len() - after.len(), 3);
+        assert_eq!(after[..3], before[..3]);
+        assert_eq!(after[3], "rest");
+        assert!(!logria.config.rerender_aggregation);
+    }
+
+    #[test]
+    fn test_no_rerender_without_flag() {
+        let (mut logria, mut handler) = aggregating_window();
+        let before = logria.config.auxiliary_messages.clone();
+
+        logria.config.num_to_aggregate = 2;
+        handler.process_matches(&mut logria).unwrap();
+
+        assert_eq!(logria.config.auxiliary_messages, before);
+    }
+
+    #[test]
+    fn test_no_rerender_without_aggregation() {
+        let (mut logria, mut handler) = aggregating_window();
+        logria.config.aggregation_enabled = false;
+        logria.config.auxiliary_messages.clear();
+
+        logria.config.rerender_aggregation = true;
+        handler.process_matches(&mut logria).unwrap();
+
+        assert!(logria.config.auxiliary_messages.is_empty());
+        assert!(!logria.config.rerender_aggregation);
+    }
+}
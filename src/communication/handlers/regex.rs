@@ -1,18 +1,40 @@
-use crossterm::{event::KeyCode, Result};
+use std::cmp::{max, min};
+
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    Result,
+};
+use format_num::format_num;
 use regex::bytes::Regex;
 
 use super::{handler::Handler, processor::ProcessorMethods};
 use crate::{
     communication::{
-        handlers::user_input::UserInputHandler, input::InputType::Normal, reader::MainWindow,
+        handlers::user_input::UserInputHandler,
+        input::InputType::{self, Normal},
+        reader::MainWindow,
     },
     constants::cli::{cli_chars::NORMAL_CHAR, patterns::ANSI_COLOR_PATTERN},
-    ui::scroll,
+    extensions::filter::Filter,
+    ui::scroll::{self, ScrollState},
+    util::sanitizers::ansi::CleanText,
 };
 
 pub struct RegexHandler {
     color_pattern: Regex,
     current_pattern: Option<Regex>,
+    /// The pattern as the user typed it, before anchors are added
+    pattern: Option<String>,
+    /// Whether matches must start at the start of the message
+    anchor_start: bool,
+    /// Whether matches must end at the end of the message
+    anchor_end: bool,
+    /// Whether to show the messages that do not match the pattern instead of those that do
+    invert: bool,
+    /// Whether matches ignore case, as if the pattern started with `(?i)`
+    case_insensitive: bool,
+    /// The position of the match `n` or `N` last moved to and the `current_end` that shows it
+    last_jump: Option<(usize, usize)>,
     input_handler: UserInputHandler,
 }
 
@@ -20,11 +42,9 @@ impl RegexHandler {
     /// Test a message to see if it matches the pattern while also escaping the color code
     fn test(&self, message: &str) -> bool {
         // TODO: Possibly without the extra allocation here?
-        let clean_message = self
-            .color_pattern
-            .replace_all(message.as_bytes(), "".as_bytes());
+        let clean_message = CleanText::new(&self.color_pattern, message.as_bytes());
         match &self.current_pattern {
-            Some(pattern) => pattern.is_match(&clean_message),
+            Some(pattern) => pattern.is_match(&clean_message.text),
             None => panic!("Match called with no pattern!"),
         }
     }
@@ -36,13 +56,15 @@ impl RegexHandler {
             Err(why) => panic!("Unable to gather text: {:?}", why),
         };
 
-        self.current_pattern = match Regex::new(&pattern) {
+        self.current_pattern = match Regex::new(&self.effective_pattern(&pattern)) {
             Ok(regex) => {
-                window.config.current_status = Some(format!("Regex with pattern /{}/", pattern));
+                window.config.current_status = Some(self.status(window, &pattern));
                 window.write_status()?;
 
                 // Update the main window's regex
                 window.config.regex_pattern = Some(regex.to_owned());
+                window.config.regex_filter = Some(self.filter(&pattern));
+                self.pattern = Some(pattern);
                 Some(regex)
             }
             Err(e) => {
@@ -54,26 +76,254 @@ impl RegexHandler {
         window.config.highlight_match = true;
         Ok(())
     }
+
+    /// The user's pattern with the active toggles
+    fn filter(&self, pattern: &str) -> Filter {
+        Filter {
+            pattern: pattern.to_owned(),
+            anchor_start: self.anchor_start,
+            anchor_end: self.anchor_end,
+            invert: self.invert,
+            case_insensitive: self.case_insensitive,
+        }
+    }
+
+    /// The pattern to compile, wrapping the user's pattern in the active anchors and case flag
+    fn effective_pattern(&self, pattern: &str) -> String {
+        self.filter(pattern).effective_pattern()
+    }
+
+    /// Describe the active pattern and anchors for the status bar, i.e. `Regex with pattern ^/pat/`
+    ///
+    /// A `:since` mark kept from an earlier search is noted, since it hides the older messages.
+    fn status(&self, window: &MainWindow, pattern: &str) -> String {
+        format!(
+            "Regex {}with pattern {}/{}/{}{}{}",
+            if self.invert { "EXCLUDE " } else { "" },
+            if self.anchor_start { "^" } else { "" },
+            pattern,
+            if self.anchor_end { "$" } else { "" },
+            if self.case_insensitive {
+                " ignoring case"
+            } else {
+                ""
+            },
+            if window.config.watermark.is_some() {
+                " since mark"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Add the number of matched messages to the status, i.e. `Regex with pattern /pat/ — 42 matches`
+    fn write_match_count(&self, window: &mut MainWindow) -> Result<()> {
+        let pattern = match (&self.pattern, &self.current_pattern) {
+            (Some(pattern), _) => pattern.to_owned(),
+            (None, Some(current)) => current.as_str().to_owned(),
+            (None, None) => return Ok(()),
+        };
+        let count = window.config.matched_rows.len();
+        let status = format!(
+            "{} — {} {}",
+            self.status(window, &pattern),
+            format_num!(",d", count as f64),
+            if count == 1 { "match" } else { "matches" }
+        );
+        if window.config.current_status.as_ref() != Some(&status) {
+            window.config.current_status = Some(status);
+            // Other modes use the command line for their own input
+            if window.input_type == InputType::Regex {
+                window.write_status()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Position of the current match in the filtered view, counted as the number of matches up to and including it
+    ///
+    /// Returns `None` when the view is centered, since `current_end` is then the index of the match itself.
+    fn match_position(&self, window: &MainWindow, matches: usize) -> Option<usize> {
+        match window.config.scroll_state {
+            ScrollState::Top => Some(0),
+            ScrollState::Bottom => Some(matches),
+            ScrollState::Free => match self.last_jump {
+                // The match may not be at the bottom of the window if it is shown with context
+                Some((position, end)) if end == window.config.current_end => Some(position),
+                _ => Some(window.config.current_end),
+            },
+            ScrollState::Centered => None,
+        }
+    }
+
+    /// Get the `current_end` that shows the match at `position` with `context` rows above it
+    ///
+    /// Without context, or when the window has no room for it, the match is shown at the bottom.
+    fn match_view_end(
+        position: usize,
+        matches: usize,
+        last_row: usize,
+        context: Option<usize>,
+    ) -> usize {
+        match context {
+            Some(context) => max(
+                position,
+                min(matches, position - 1 + last_row.saturating_sub(context)),
+            ),
+            None => position,
+        }
+    }
+
+    /// Get the position of the match after or before `position`, counted from the first match
+    ///
+    /// Past the last or first match, wraps around to the other end if `wrap` is set, else stays at the end.
+    fn step_match(position: usize, matches: usize, forward: bool, wrap: bool) -> usize {
+        match (forward, wrap) {
+            (true, true) if position >= matches => 1,
+            (true, false) if position >= matches => matches,
+            (true, _) => position + 1,
+            (false, true) if position <= 1 => matches,
+            (false, false) if position <= 1 => 1,
+            (false, _) => position - 1,
+        }
+    }
+
+    /// Scroll the view to the match at `position`, counted from the first match
+    fn show_match(&mut self, window: &mut MainWindow, position: usize, matches: usize) {
+        let end = RegexHandler::match_view_end(
+            position,
+            matches,
+            window.config.last_row as usize,
+            window.config.match_context,
+        );
+        window.config.scroll_state = ScrollState::Free;
+        window.config.current_end = end;
+        self.last_jump = Some((position, end));
+    }
+
+    /// Move the view to the match after or before the current one
+    fn step_through_matches(&mut self, window: &mut MainWindow, forward: bool) {
+        let matches = window.number_of_messages();
+        if matches == 0 {
+            return;
+        }
+        let wrap = window.config.wrap_matches;
+        match self.match_position(window, matches) {
+            Some(position) => {
+                let position = RegexHandler::step_match(position, matches, forward, wrap);
+                self.show_match(window, position, matches);
+            }
+            // The centered message is the match itself, so step its index
+            None => {
+                let position =
+                    RegexHandler::step_match(window.config.current_end + 1, matches, forward, wrap);
+                window.config.current_end = position - 1;
+            }
+        }
+    }
+
+    /// Move the view to the match after the current one
+    fn next_match(&mut self, window: &mut MainWindow) {
+        self.step_through_matches(window, true);
+    }
+
+    /// Move the view to the match before the current one
+    fn previous_match(&mut self, window: &mut MainWindow) {
+        self.step_through_matches(window, false);
+    }
+
+    /// Forget the pattern along with its anchors, exclude mode, and case
+    fn reset_pattern(&mut self) {
+        self.current_pattern = None;
+        self.pattern = None;
+        self.anchor_start = false;
+        self.anchor_end = false;
+        self.invert = false;
+        self.case_insensitive = false;
+        self.last_jump = None;
+    }
+
+    /// Recompile the active pattern after an anchor, exclude mode, or case is toggled and rebuild the matches
+    fn rebuild_matches(&mut self, window: &mut MainWindow) -> Result<()> {
+        let pattern = match (&self.pattern, &self.current_pattern) {
+            (Some(pattern), _) => pattern.to_owned(),
+            (None, Some(current)) => current.as_str().to_owned(),
+            (None, None) => return Ok(()),
+        };
+        match Regex::new(&self.effective_pattern(&pattern)) {
+            Ok(regex) => {
+                self.current_pattern = Some(regex.to_owned());
+                self.pattern = Some(pattern.to_owned());
+                self.last_jump = None;
+                window.config.regex_pattern = Some(regex);
+                window.config.regex_filter = Some(self.filter(&pattern));
+                window.config.matched_rows.clear();
+                window.config.last_index_regexed = 0;
+                window.config.current_status = Some(self.status(window, &pattern));
+                window.reset_output()?;
+                self.process_matches(window)?;
+                window.write_status()?;
+            }
+            Err(why) => {
+                window.write_to_command_line(&format!("Invalid regex: /{}/ ({})", pattern, why))?
+            }
+        }
+        Ok(())
+    }
 }
 
 impl ProcessorMethods for RegexHandler {
     /// Process matches, loading the buffer of indexes to matched messages in the main buffer
     fn process_matches(&mut self, window: &mut MainWindow) -> Result<()> {
+        match &window.config.regex_pattern {
+            // Adopt a pattern applied outside of this handler, i.e. from a saved filter
+            Some(pattern)
+                if self.current_pattern.as_ref().map(Regex::as_str) != Some(pattern.as_str()) =>
+            {
+                self.reset_pattern();
+                self.current_pattern = Some(pattern.to_owned());
+                match &window.config.regex_filter {
+                    // A saved filter is compiled with its toggles applied, so restore them with the typed pattern
+                    Some(filter) if filter.effective_pattern() == pattern.as_str() => {
+                        self.pattern = Some(filter.pattern.to_owned());
+                        self.anchor_start = filter.anchor_start;
+                        self.anchor_end = filter.anchor_end;
+                        self.invert = filter.invert;
+                        self.case_insensitive = filter.case_insensitive;
+                    }
+                    _ => self.pattern = Some(pattern.as_str().to_owned()),
+                }
+            }
+            // Drop a pattern this handler applied that was cleared outside of it, i.e. by piping the matches
+            None if self.pattern.is_some() => self.reset_pattern(),
+            _ => {}
+        }
+
         // TODO: Possibly async? Possibly loading indicator for large jobs?
         if self.current_pattern.is_some() {
-            // Start from where we left off to the most recent message
-            let buf_range = (window.config.last_index_regexed, window.messages().len());
+            // Start from where we left off to the most recent message, skipping messages before the watermark
+            let buf_range = (
+                max(
+                    window.config.last_index_regexed,
+                    window.config.watermark.unwrap_or(0),
+                ),
+                window.messages().len(),
+            );
 
             // Iterate "forever", skipping to the start and taking up till end-start
             // TODO: Something to indicate progress
             for index in (0..).skip(buf_range.0).take(buf_range.1 - buf_range.0) {
-                if self.test(&window.messages()[index]) {
+                // In exclude mode, keep the messages that do not match instead; muted streams are never matched
+                if !window.is_muted(index) && self.test(&window.messages()[index]) != self.invert {
                     window.config.matched_rows.push(index);
                 }
 
                 // Update the last spot so we know where to start next time
                 window.config.last_index_regexed = index + 1;
             }
+
+            // Matches accrue as messages arrive, so keep the count current
+            self.write_match_count(window)?;
         }
         Ok(())
     }
@@ -81,6 +331,7 @@ impl ProcessorMethods for RegexHandler {
     /// Return the app to a normal input state
     fn return_to_normal(&mut self, window: &mut MainWindow) -> Result<()> {
         self.clear_matches(window)?;
+        window.config.watermark = None;
         window.config.current_status = None;
         window.update_input_type(Normal)?;
         window.set_cli_cursor(None)?;
@@ -91,8 +342,9 @@ impl ProcessorMethods for RegexHandler {
 
     /// Clear the matched messages from the message buffer
     fn clear_matches(&mut self, window: &mut MainWindow) -> Result<()> {
-        self.current_pattern = None;
+        self.reset_pattern();
         window.config.regex_pattern = None;
+        window.config.regex_filter = None;
         window.config.matched_rows.clear();
         window.config.last_index_regexed = 0;
         window.config.highlight_match = false;
@@ -106,11 +358,20 @@ impl Handler for RegexHandler {
         RegexHandler {
             color_pattern: Regex::new(ANSI_COLOR_PATTERN).unwrap(),
             current_pattern: None,
+            pattern: None,
+            anchor_start: false,
+            anchor_end: false,
+            invert: false,
+            case_insensitive: false,
+            last_jump: None,
             input_handler: UserInputHandler::new(),
         }
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()> {
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
+        let control = event.modifiers.contains(KeyModifiers::CONTROL);
         match &self.current_pattern {
             Some(_) => match key {
                 // Scroll
@@ -122,6 +383,12 @@ impl Handler for RegexHandler {
                 KeyCode::End => scroll::bottom(window),
                 KeyCode::PageUp => scroll::pg_up(window),
                 KeyCode::PageDown => scroll::pg_down(window),
+                KeyCode::Char('u') if control => scroll::half_pg_up(window),
+                KeyCode::Char('d') if control => scroll::half_pg_down(window),
+
+                // Step through the matches
+                KeyCode::Char('n') => self.next_match(window),
+                KeyCode::Char('N') => self.previous_match(window),
 
                 // Build new regex
                 KeyCode::Char('/') => {
@@ -136,6 +403,28 @@ impl Handler for RegexHandler {
                     window.redraw()?;
                 }
 
+                // Toggle anchoring matches to the start or end of the message
+                KeyCode::Char('^') => {
+                    self.anchor_start = !self.anchor_start;
+                    self.rebuild_matches(window)?;
+                }
+                KeyCode::Char('$') => {
+                    self.anchor_end = !self.anchor_end;
+                    self.rebuild_matches(window)?;
+                }
+
+                // Toggle showing the messages that do not match
+                KeyCode::Char('v') => {
+                    self.invert = !self.invert;
+                    self.rebuild_matches(window)?;
+                }
+
+                // Toggle ignoring case
+                KeyCode::Tab => {
+                    self.case_insensitive = !self.case_insensitive;
+                    self.rebuild_matches(window)?;
+                }
+
                 // Enter command mode
                 KeyCode::Char(':') => window.set_command_mode(None)?,
 
@@ -153,7 +442,9 @@ impl Handler for RegexHandler {
                     window.redraw()?;
                 }
                 KeyCode::Esc => self.return_to_normal(window)?,
-                key => self.input_handler.receive_input(window, key)?,
+                // Toggle ignoring case for the pattern being typed
+                KeyCode::Tab => self.case_insensitive = !self.case_insensitive,
+                _ => self.input_handler.receive_input(window, event)?,
             },
         }
         window.redraw()?;
@@ -166,10 +457,14 @@ mod tests {
     use crossterm::event::KeyCode;
     use regex::bytes::Regex;
 
-    use crate::communication::{
-        handlers::{handler::Handler, processor::ProcessorMethods},
-        input::InputType,
-        reader::MainWindow,
+    use crate::{
+        communication::{
+            handlers::{handler::Handler, processor::ProcessorMethods},
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        extensions::filter::Filter,
+        ui::scroll::ScrollState,
     };
 
     #[test]
@@ -190,6 +485,231 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_can_filter_inverted() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+
+        // Set state to regex mode
+        logria.input_type = InputType::Regex;
+
+        // Set regex pattern
+        let pattern = "0";
+        handler.current_pattern = Some(Regex::new(pattern).unwrap());
+        handler.invert = true;
+        handler.process_matches(&mut logria).unwrap();
+
+        // Every message that `test_can_filter` does not match
+        let expected: Vec<usize> = (0..100).filter(|index| index % 10 != 0).collect();
+        assert_eq!(expected, logria.config.matched_rows);
+    }
+
+    #[test]
+    fn test_can_toggle_invert() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        handler.current_pattern = Some(Regex::new("0").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("0").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.matched_rows.len(), 10);
+
+        // Toggling rescans every message for the complement of the matches
+        handler
+            .receive_input(&mut logria, KeyCode::Char('v'))
+            .unwrap();
+        assert_eq!(logria.config.matched_rows.len(), 90);
+        assert!(!logria.config.matched_rows.contains(&10));
+        assert_eq!(logria.config.last_index_regexed, 100);
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex EXCLUDE with pattern /0/ — 90 matches"))
+        );
+
+        // Toggling again restores the matches
+        handler
+            .receive_input(&mut logria, KeyCode::Char('v'))
+            .unwrap();
+        assert_eq!(
+            logria.config.matched_rows,
+            vec![0, 10, 20, 30, 40, 50, 60, 70, 80, 90]
+        );
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex with pattern /0/ — 10 matches"))
+        );
+    }
+
+    #[test]
+    fn test_can_filter_ignoring_case() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.auxiliary_messages = vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("A"),
+            String::from("ba"),
+        ];
+
+        handler.current_pattern = Some(Regex::new("A").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("A").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.matched_rows, vec![2]);
+
+        // Toggling rescans every message, matching the lowercase rows too
+        handler.receive_input(&mut logria, KeyCode::Tab).unwrap();
+        assert_eq!(handler.current_pattern.as_ref().unwrap().as_str(), "(?i)A");
+        assert_eq!(logria.config.matched_rows, vec![0, 2, 3]);
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from(
+                "Regex with pattern /A/ ignoring case — 3 matches"
+            ))
+        );
+
+        // Toggling again only matches the uppercase row
+        handler.receive_input(&mut logria, KeyCode::Tab).unwrap();
+        assert_eq!(logria.config.matched_rows, vec![2]);
+    }
+
+    #[test]
+    fn test_can_toggle_case_before_pattern() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        // No pattern is active yet, so the flag applies to the pattern being typed
+        handler.receive_input(&mut logria, KeyCode::Tab).unwrap();
+        assert!(handler.case_insensitive);
+        assert!(handler.current_pattern.is_none());
+        assert_eq!(handler.effective_pattern("A"), "(?i)A");
+
+        handler.anchor_start = true;
+        assert_eq!(handler.effective_pattern("A"), "(?i)^(?:A)");
+    }
+
+    #[test]
+    fn test_drops_pattern_cleared_outside() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        handler.current_pattern = Some(Regex::new("(?i)0").unwrap());
+        handler.pattern = Some(String::from("0"));
+        handler.case_insensitive = true;
+        handler.process_matches(&mut logria).unwrap();
+
+        assert!(handler.current_pattern.is_none());
+        assert!(handler.pattern.is_none());
+        assert!(!handler.case_insensitive);
+        assert!(logria.config.matched_rows.is_empty());
+    }
+
+    #[test]
+    fn test_adopts_filter_toggles() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        let filter = Filter {
+            anchor_start: true,
+            invert: true,
+            ..Filter::new("1")
+        };
+        logria.set_regex(Regex::new(&filter.effective_pattern()).unwrap());
+        logria.config.regex_filter = Some(filter.clone());
+        handler.process_matches(&mut logria).unwrap();
+
+        assert_eq!(handler.pattern, Some(String::from("1")));
+        assert!(handler.anchor_start);
+        assert!(handler.invert);
+        assert!(!handler.anchor_end);
+        assert_eq!(logria.config.matched_rows.len(), 89);
+        assert_eq!(handler.filter("1"), filter);
+    }
+
+    #[test]
+    fn test_ignores_filter_for_other_pattern() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        logria.set_regex(Regex::new("2").unwrap());
+        logria.config.regex_filter = Some(Filter {
+            invert: true,
+            ..Filter::new("1")
+        });
+        handler.process_matches(&mut logria).unwrap();
+
+        assert_eq!(handler.pattern, Some(String::from("2")));
+        assert!(!handler.invert);
+    }
+
+    #[test]
+    fn test_shows_match_count() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        handler.current_pattern = Some(Regex::new("0").unwrap());
+        handler.pattern = Some(String::from("0"));
+        logria.config.regex_pattern = Some(Regex::new("0").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex with pattern /0/ — 10 matches"))
+        );
+    }
+
+    #[test]
+    fn test_shows_kept_since_mark() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.watermark = Some(95);
+
+        handler.current_pattern = Some(Regex::new("9").unwrap());
+        handler.pattern = Some(String::from("9"));
+        logria.config.regex_pattern = Some(Regex::new("9").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from(
+                "Regex with pattern /9/ since mark — 5 matches"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_updates_match_count_for_new_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.auxiliary_messages = vec![String::from("error"), String::from("ok")];
+
+        handler.current_pattern = Some(Regex::new("error").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex with pattern /error/ — 1 match"))
+        );
+
+        // Only the new messages are scanned, but the count includes every match
+        logria
+            .config
+            .auxiliary_messages
+            .extend((0..1500).map(|_| String::from("error")));
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex with pattern /error/ — 1,501 matches"))
+        );
+    }
+
     #[test]
     fn test_can_filter_no_matches() {
         let mut logria = MainWindow::_new_dummy();
@@ -291,4 +811,368 @@ mod tests {
             logria.number_of_messages()
         );
     }
+
+    #[test]
+    fn test_can_anchor_start() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        handler.current_pattern = Some(Regex::new("1").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("1").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+
+        handler
+            .receive_input(&mut logria, KeyCode::Char('^'))
+            .unwrap();
+        assert_eq!(handler.current_pattern.as_ref().unwrap().as_str(), "^(?:1)");
+        assert_eq!(
+            logria.config.regex_pattern.as_ref().unwrap().as_str(),
+            "^(?:1)"
+        );
+        assert_eq!(
+            logria.config.matched_rows,
+            vec![1, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]
+        );
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex with pattern ^/1/ — 11 matches"))
+        );
+    }
+
+    #[test]
+    fn test_can_anchor_both() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        handler.current_pattern = Some(Regex::new("1|2").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("1|2").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+
+        handler
+            .receive_input(&mut logria, KeyCode::Char('^'))
+            .unwrap();
+        handler
+            .receive_input(&mut logria, KeyCode::Char('$'))
+            .unwrap();
+        assert_eq!(
+            handler.current_pattern.as_ref().unwrap().as_str(),
+            "^(?:1|2)$"
+        );
+        assert_eq!(logria.config.matched_rows, vec![1, 2]);
+        assert_eq!(
+            logria.config.current_status,
+            Some(String::from("Regex with pattern ^/1|2/$ — 2 matches"))
+        );
+
+        // Toggling again removes the anchor
+        handler
+            .receive_input(&mut logria, KeyCode::Char('^'))
+            .unwrap();
+        assert_eq!(
+            handler.current_pattern.as_ref().unwrap().as_str(),
+            "(?:1|2)$"
+        );
+    }
+
+    #[test]
+    fn test_clear_resets_anchors() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+
+        handler.current_pattern = Some(Regex::new("1").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("1").unwrap());
+        handler
+            .receive_input(&mut logria, KeyCode::Char('$'))
+            .unwrap();
+        handler
+            .receive_input(&mut logria, KeyCode::Char('v'))
+            .unwrap();
+        handler.receive_input(&mut logria, KeyCode::Tab).unwrap();
+        handler.clear_matches(&mut logria).unwrap();
+
+        assert!(!handler.anchor_start);
+        assert!(!handler.anchor_end);
+        assert!(!handler.invert);
+        assert!(!handler.case_insensitive);
+        assert!(handler.pattern.is_none());
+        assert_eq!(handler.effective_pattern("1"), "1");
+    }
+
+    #[test]
+    fn test_can_filter_since_watermark() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.watermark = Some(100);
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.auxiliary_messages = (0..100).map(|x| x.to_string()).collect();
+        logria.config.auxiliary_messages.extend([
+            String::from("error 10"),
+            String::from("ok"),
+            String::from("error 20"),
+        ]);
+
+        handler.current_pattern = Some(Regex::new("0").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.matched_rows, vec![100, 102]);
+    }
+
+    #[test]
+    fn test_can_filter_since_watermark_without_pattern() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.watermark = Some(100);
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.auxiliary_messages = (0..100).map(|x| x.to_string()).collect();
+        logria
+            .config
+            .auxiliary_messages
+            .extend([String::from("a"), String::from("b")]);
+
+        handler.current_pattern = Some(Regex::new("").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.matched_rows, vec![100, 101]);
+    }
+
+    #[test]
+    fn test_can_filter_since_watermark_after_reset() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.watermark = Some(95);
+
+        handler.current_pattern = Some(Regex::new("9").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.matched_rows, vec![95, 96, 97, 98, 99]);
+
+        // Rebuilding the matches, i.e. after toggling an anchor, still skips old messages
+        logria.config.matched_rows.clear();
+        logria.config.last_index_regexed = 0;
+        handler.process_matches(&mut logria).unwrap();
+        assert_eq!(logria.config.matched_rows, vec![95, 96, 97, 98, 99]);
+    }
+
+    #[test]
+    fn test_return_normal_clears_watermark() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        logria.config.watermark = Some(50);
+
+        handler.current_pattern = Some(Regex::new("0").unwrap());
+        handler.return_to_normal(&mut logria).unwrap();
+        assert!(logria.config.watermark.is_none());
+    }
+
+    fn match_window() -> (MainWindow, super::RegexHandler) {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        handler.current_pattern = Some(Regex::new("0").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("0").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        logria.config.scroll_state = ScrollState::Top;
+        (logria, handler)
+    }
+
+    #[test]
+    fn test_next_match_from_top() {
+        let (mut logria, mut handler) = match_window();
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.config.current_end, 1);
+        assert_eq!(logria.config.matched_rows[logria.config.current_end - 1], 0);
+    }
+
+    #[test]
+    fn test_next_match_steps_forward() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.scroll_state = ScrollState::Free;
+        logria.config.current_end = 4;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 5);
+    }
+
+    #[test]
+    fn test_next_match_wraps_to_first() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.wrap_matches = true;
+        logria.config.scroll_state = ScrollState::Bottom;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 1);
+    }
+
+    #[test]
+    fn test_previous_match_wraps_to_last() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.wrap_matches = true;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.config.current_end, 10);
+        assert_eq!(
+            logria.config.matched_rows[logria.config.current_end - 1],
+            90
+        );
+    }
+
+    #[test]
+    fn test_next_match_stops_at_last() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.scroll_state = ScrollState::Bottom;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.config.current_end, 10);
+    }
+
+    #[test]
+    fn test_previous_match_stops_at_first() {
+        let (mut logria, mut handler) = match_window();
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.config.current_end, 1);
+    }
+
+    #[test]
+    fn test_can_step_match() {
+        assert_eq!(super::RegexHandler::step_match(4, 10, true, false), 5);
+        assert_eq!(super::RegexHandler::step_match(4, 10, false, false), 3);
+        assert_eq!(super::RegexHandler::step_match(4, 10, true, true), 5);
+        assert_eq!(super::RegexHandler::step_match(4, 10, false, true), 3);
+    }
+
+    #[test]
+    fn test_can_step_match_clamped() {
+        assert_eq!(super::RegexHandler::step_match(10, 10, true, false), 10);
+        assert_eq!(super::RegexHandler::step_match(1, 10, false, false), 1);
+        assert_eq!(super::RegexHandler::step_match(0, 10, false, false), 1);
+    }
+
+    #[test]
+    fn test_can_step_match_wrapped() {
+        assert_eq!(super::RegexHandler::step_match(10, 10, true, true), 1);
+        assert_eq!(super::RegexHandler::step_match(1, 10, false, true), 10);
+        assert_eq!(super::RegexHandler::step_match(0, 10, false, true), 10);
+    }
+
+    #[test]
+    fn test_previous_match_steps_back() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.scroll_state = ScrollState::Free;
+        logria.config.current_end = 4;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 3);
+    }
+
+    #[test]
+    fn test_step_through_centered_matches_clamped() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.scroll_state = ScrollState::Centered;
+        logria.config.current_end = 9;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Centered));
+        assert_eq!(logria.config.current_end, 9);
+        logria.config.current_end = 0;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 0);
+    }
+
+    #[test]
+    fn test_step_through_centered_matches() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.wrap_matches = true;
+        logria.config.scroll_state = ScrollState::Centered;
+        logria.config.current_end = 9;
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Centered));
+        assert_eq!(logria.config.current_end, 0);
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 9);
+    }
+
+    #[test]
+    fn test_match_view_end_without_context() {
+        assert_eq!(super::RegexHandler::match_view_end(5, 10, 4, None), 5);
+    }
+
+    #[test]
+    fn test_match_view_end_with_context() {
+        // Ending the view at 6 shows matches 3 through 6, so match 4 is one row down
+        assert_eq!(super::RegexHandler::match_view_end(4, 10, 4, Some(1)), 6);
+        assert_eq!(super::RegexHandler::match_view_end(4, 10, 4, Some(0)), 7);
+    }
+
+    #[test]
+    fn test_match_view_end_with_context_near_end() {
+        assert_eq!(super::RegexHandler::match_view_end(9, 10, 4, Some(1)), 10);
+    }
+
+    #[test]
+    fn test_match_view_end_with_context_taller_than_window() {
+        assert_eq!(super::RegexHandler::match_view_end(4, 10, 4, Some(8)), 4);
+    }
+
+    #[test]
+    fn test_step_through_matches_with_context() {
+        let (mut logria, mut handler) = match_window();
+        logria.config.last_row = 4;
+        logria.config.match_context = Some(1);
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 3);
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 4);
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert_eq!(logria.config.current_end, 3);
+    }
+
+    #[test]
+    fn test_step_through_no_matches() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = super::RegexHandler::new();
+        logria.input_type = InputType::Regex;
+        handler.current_pattern = Some(Regex::new("x").unwrap());
+        logria.config.regex_pattern = Some(Regex::new("x").unwrap());
+        handler.process_matches(&mut logria).unwrap();
+        logria.config.scroll_state = ScrollState::Top;
+
+        handler
+            .receive_input(&mut logria, KeyCode::Char('n'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Top));
+        handler
+            .receive_input(&mut logria, KeyCode::Char('N'))
+            .unwrap();
+        assert!(matches!(logria.config.scroll_state, ScrollState::Top));
+    }
 }
@@ -1,8 +1,13 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
-use crossterm::{event::KeyCode, Result};
+use crossterm::{
+    event::{KeyCode, KeyEvent},
+    Result,
+};
 
-use super::{handler::Handler, user_input::UserInputHandler};
+use super::{
+    handler::Handler, multiple_choice::MultipleChoiceHandler, user_input::UserInputHandler,
+};
 use crate::{
     communication::{
         input::{
@@ -10,29 +15,62 @@ use crate::{
         },
         reader::MainWindow,
     },
-    constants::cli::messages::START_MESSAGE,
-    extensions::{extension::ExtensionMethods, session::Session},
+    constants::directories::text as text_file,
+    extensions::{
+        extension::ExtensionMethods,
+        session::{Session, SessionType},
+        text::TextOverrides,
+    },
     ui::scroll,
+    util::sanitizers::selection::parse_selection,
 };
 
 pub struct StartupHandler {
     input_handler: UserInputHandler,
     session_data: HashMap<usize, String>,
+    /// Tracks the sessions marked for removal
+    mc_handler: MultipleChoiceHandler,
 }
 
 impl StartupHandler {
     /// Generate the startup message with available session configurations
     pub fn get_startup_text() -> Vec<String> {
-        let mut text: Vec<String> = Vec::new();
         let sessions = Session::list_clean();
-        START_MESSAGE.iter().for_each(|&s| text.push(s.to_string()));
-        sessions.iter().enumerate().for_each(|(i, s)| {
-            let value = s.to_string();
-            text.push(format!("{}: {}", i, value));
-        });
+        StartupHandler::startup_text(
+            sessions
+                .iter()
+                .enumerate()
+                .map(|(i, s)| format!("{}: {}", i, s))
+                .collect(),
+        )
+    }
+
+    /// Generate the startup message above a list of sessions
+    fn startup_text(sessions: Vec<String>) -> Vec<String> {
+        // Invalid text files are reported when the app starts, so fall back to the built in text here
+        let mut text = TextOverrides::load(&text_file())
+            .unwrap_or_default()
+            .startup();
+        text.extend(sessions);
         text
     }
 
+    /// Match the choices to the saved sessions, dropping marks once the list changes
+    fn refresh_choices(&mut self) {
+        self.mc_handler.set_choices(&Session::list_full());
+        self.mc_handler.set_labels(&Session::list_clean());
+    }
+
+    /// Mark or unmark the typed sessions for removal
+    fn toggle_sessions(&mut self, window: &mut MainWindow) -> Result<()> {
+        let selection = self.input_handler.gather(window)?;
+        self.refresh_choices();
+        self.mc_handler.toggle_choices(window, &selection)?;
+        window.config.auxiliary_messages =
+            StartupHandler::startup_text(self.mc_handler.get_body_text());
+        Ok(())
+    }
+
     /// Load the session_data hashmap internally
     fn initialize(&mut self) {
         let sessions = Session::list_full();
@@ -43,7 +81,7 @@ impl StartupHandler {
     }
 
     fn process_command(&mut self, window: &mut MainWindow, command: &str) -> Result<()> {
-        let selection = command.parse::<usize>();
+        let selection = parse_selection(command);
         match selection {
             Ok(item) => {
                 match self.session_data.get(&item) {
@@ -51,17 +89,36 @@ impl StartupHandler {
                         let session = Session::load(file_path);
                         match session {
                             // Successfully start the app
-                            Ok(session) => {
-                                window.config.streams = match build_streams_from_session(session) {
+                            Ok(mut session) => {
+                                let workspace = session.workspace.take();
+                                let attached = session.parser.take();
+                                let opened = session.clone();
+                                // Reopened by `--resume`
+                                let name = Path::new(file_path).file_name().unwrap();
+                                if let Err(why) = Session::record_last(name.to_str().unwrap()) {
+                                    window.write_to_command_line(&why.to_string())?;
+                                }
+                                let streams = match build_streams_from_session(
+                                    session,
+                                    &window.config.stream_options,
+                                ) {
                                     Ok(streams) => streams,
                                     Err(why) => {
                                         window.write_to_command_line(&why.to_string())?;
                                         return Ok(());
                                     }
                                 };
+                                window.set_streams(streams);
+                                window.config.session = Some(opened);
                                 window.config.stream_type = StdErr;
                                 window.update_input_type(InputType::Normal)?;
-                                window.config.generate_auxiliary_messages = None;
+                                window.clear_auxiliary();
+                                if let Some(workspace) = workspace {
+                                    window.apply_workspace(&workspace)?;
+                                }
+                                if let Some(attached) = attached {
+                                    window.attach_parser(&attached)?;
+                                }
                                 window.config.message_speed_tracker.reset();
                                 window.reset_output()?;
                                 window.redraw()?;
@@ -81,16 +138,28 @@ impl StartupHandler {
                 Ok(())
             }
             Err(_) => {
-                window.config.streams = match build_streams_from_input(&[command.to_owned()], true)
-                {
+                let streams = match build_streams_from_input(
+                    &[command.to_owned()],
+                    true,
+                    &window.config.stream_options,
+                ) {
                     Ok(streams) => streams,
                     Err(why) => {
                         window.write_to_command_line(&why.to_string())?;
-                        build_streams_from_input(&[command.to_owned()], false).unwrap()
+                        build_streams_from_input(
+                            &[command.to_owned()],
+                            false,
+                            &window.config.stream_options,
+                        )
+                        .unwrap()
                     }
                 };
+                window.set_streams(streams);
+                window.config.session =
+                    Some(Session::new(&[command.to_owned()], SessionType::Mixed));
                 window.config.stream_type = StdErr;
                 window.update_input_type(InputType::Normal)?;
+                window.clear_auxiliary();
                 window.reset_output()?;
                 window.redraw()?;
                 Ok(())
@@ -101,13 +170,18 @@ impl StartupHandler {
 
 impl Handler for StartupHandler {
     fn new() -> StartupHandler {
+        let mut mc_handler = MultipleChoiceHandler::new();
+        mc_handler.allow_toggling(true);
         StartupHandler {
             input_handler: UserInputHandler::new(),
             session_data: HashMap::new(),
+            mc_handler,
         }
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()> {
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
         match key {
             // Scroll
             KeyCode::Down => scroll::down(window),
@@ -119,8 +193,16 @@ impl Handler for StartupHandler {
             KeyCode::PageUp => scroll::pg_up(window),
             KeyCode::PageDown => scroll::pg_down(window),
 
-            // Mode change for remove or config commands
-            KeyCode::Char(':') => window.set_command_mode(Some(Session::del))?,
+            // Mode change for remove or config commands, where `: r` removes the marked sessions
+            KeyCode::Char(':') => {
+                // Marks made before a removal no longer match the sessions they were made for
+                self.refresh_choices();
+                window.config.marked_items = self.mc_handler.get_choices();
+                window.set_command_mode(Some(Session::del))?;
+            }
+
+            // Mark the typed sessions for removal
+            KeyCode::Tab => self.toggle_sessions(window)?,
 
             // Handle user input selection
             KeyCode::Enter => {
@@ -136,7 +218,7 @@ impl Handler for StartupHandler {
             }
 
             // User input
-            key => self.input_handler.receive_input(window, key)?,
+            _ => self.input_handler.receive_input(window, event)?,
         }
         window.redraw()?;
         Ok(())
@@ -154,9 +236,15 @@ mod startup_tests {
         constants::cli::messages::START_MESSAGE,
         extensions::{
             extension::ExtensionMethods,
-            session::{Session, SessionType::Command},
+            parser::{Parser, PatternType},
+            session::{
+                Session, SessionParser,
+                SessionType::{Command, File},
+            },
         },
+        util::aggregators::aggregator::AggregationMethod,
     };
+    use std::collections::HashMap;
 
     use super::StartupHandler;
 
@@ -192,6 +280,70 @@ mod startup_tests {
         assert!(matches!(window.config.stream_type, StreamType::StdErr));
     }
 
+    /// Open a saved session by name, returning the window it opened in
+    fn open_session(session: Session, name: &str) -> MainWindow {
+        session.save(name).unwrap();
+        let mut window = MainWindow::_new_dummy();
+        let mut handler = StartupHandler::new();
+        handler.initialize();
+        let index = handler
+            .session_data
+            .iter()
+            .find(|(_, path)| path.ends_with(&format!("/{}", name)))
+            .map(|(index, _)| *index)
+            .unwrap();
+        handler
+            .process_command(&mut window, &index.to_string())
+            .unwrap();
+        window
+    }
+
+    #[test]
+    fn can_load_session_with_parser() {
+        Parser::verify_path();
+        let mut map = HashMap::new();
+        map.insert(String::from("Word"), AggregationMethod::Count);
+        Parser::new(
+            String::from(" "),
+            PatternType::Split,
+            String::from("Logria"),
+            vec![String::from("Word")],
+            map,
+        )
+        .save("Logria Session Test")
+        .unwrap();
+
+        let mut session = Session::new(&[String::from("README.md")], File);
+        session.parser = Some(SessionParser {
+            name: String::from("Logria Session Test"),
+            index: 0,
+        });
+        let window = open_session(session, "README.md parser");
+
+        assert!(matches!(window.input_type, InputType::Parser));
+        assert!(matches!(window.config.stream_type, StreamType::Auxiliary));
+        assert!(matches!(
+            window.config.previous_stream_type,
+            StreamType::StdErr
+        ));
+        assert!(window.config.attached_parser.is_some());
+        assert!(window.config.session.unwrap().parser.is_none());
+    }
+
+    #[test]
+    fn can_load_session_with_missing_parser() {
+        let mut session = Session::new(&[String::from("README.md")], File);
+        session.parser = Some(SessionParser {
+            name: String::from("zzz missing parser"),
+            index: 0,
+        });
+        let window = open_session(session, "README.md missing parser");
+
+        assert!(matches!(window.input_type, InputType::Normal));
+        assert!(matches!(window.config.stream_type, StreamType::StdErr));
+        assert!(window.config.attached_parser.is_none());
+    }
+
     #[test]
     fn doesnt_crash_bad_index() {
         // Setup dummy window
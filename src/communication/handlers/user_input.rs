@@ -3,13 +3,43 @@ use std::{
     io::{stdout, Write},
 };
 
-use crossterm::{cursor, event::KeyCode, queue, style, terminal::size, Result};
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    queue, style,
+    terminal::size,
+    Result,
+};
 
 use crate::{
     communication::{handlers::handler::Handler, reader::MainWindow},
     util::history::Tape,
 };
 
+/// Index of the start of the word before `index`, skipping any whitespace directly before it
+fn previous_word_boundary(content: &[char], index: usize) -> usize {
+    let mut index = min(index, content.len());
+    while index > 0 && content[index - 1].is_whitespace() {
+        index -= 1;
+    }
+    while index > 0 && !content[index - 1].is_whitespace() {
+        index -= 1;
+    }
+    index
+}
+
+/// Index just past the end of the word after `index`, skipping any whitespace directly after it
+fn next_word_boundary(content: &[char], index: usize) -> usize {
+    let mut index = min(index, content.len());
+    while index < content.len() && content[index].is_whitespace() {
+        index += 1;
+    }
+    while index < content.len() && !content[index].is_whitespace() {
+        index += 1;
+    }
+    index
+}
+
 // Used in Command and Regex handler to capture user typing
 pub struct UserInputHandler {
     x: u16,
@@ -20,6 +50,17 @@ pub struct UserInputHandler {
 }
 
 impl UserInputHandler {
+    /// Construct a handler whose history tape holds only `items`, for testing
+    pub fn _new_dummy(items: &[&str]) -> UserInputHandler {
+        UserInputHandler {
+            x: 0,
+            y: 0,
+            last_write: 1,
+            content: vec![],
+            history: Tape::_new_dummy(items),
+        }
+    }
+
     /// Get the useable area of the textbox container
     fn update_dimensions(&mut self) {
         let (w, h) = size().unwrap_or((0, 0));
@@ -28,14 +69,14 @@ impl UserInputHandler {
     }
 
     fn y(&self) -> u16 {
-        self.y - 2
+        self.y.saturating_sub(2)
     }
 
     fn x(&self) -> u16 {
-        self.x - 3
+        self.x.saturating_sub(3)
     }
 
-    fn get_content(&self) -> String {
+    pub fn get_content(&self) -> String {
         self.content.iter().collect()
     }
 
@@ -118,6 +159,35 @@ impl UserInputHandler {
         Ok(())
     }
 
+    /// Move the cursor to the start of the content
+    fn move_home(&mut self) -> Result<()> {
+        self.last_write = 1;
+        queue!(stdout(), cursor::MoveTo(self.last_write, self.y()))?;
+        Ok(())
+    }
+
+    /// Move the cursor past the end of the content, staying inside the command box
+    fn move_end(&mut self) -> Result<()> {
+        self.last_write = min(self.content.len() as u16 + 1, max(1, self.x()));
+        queue!(stdout(), cursor::MoveTo(self.last_write, self.y()))?;
+        Ok(())
+    }
+
+    /// Move the cursor to the start of the previous word
+    fn move_word_left(&mut self) -> Result<()> {
+        self.last_write =
+            previous_word_boundary(&self.content, self.position_as_index()) as u16 + 1;
+        queue!(stdout(), cursor::MoveTo(self.last_write, self.y()))?;
+        Ok(())
+    }
+
+    /// Move the cursor to the end of the next word
+    fn move_word_right(&mut self) -> Result<()> {
+        self.last_write = next_word_boundary(&self.content, self.position_as_index()) as u16 + 1;
+        queue!(stdout(), cursor::MoveTo(self.last_write, self.y()))?;
+        Ok(())
+    }
+
     /// Get the next item in the history tape if it exists
     fn tape_forward(&mut self, window: &mut MainWindow) -> Result<()> {
         let content = self.history.scroll_forward();
@@ -145,6 +215,11 @@ impl UserInputHandler {
         Ok(())
     }
 
+    /// Get the most recent history entry that is not one of `skip`
+    pub fn last_entry_except(&self, skip: &[&str]) -> Option<String> {
+        self.history.last_item_except(skip)
+    }
+
     /// Get the contents of the command line as a String
     pub fn gather(&mut self, window: &mut MainWindow) -> Result<String> {
         // Copy the result to a new place so we can clear out the existing one and reuse the struct
@@ -183,21 +258,32 @@ impl Handler for UserInputHandler {
         handler
     }
 
-    fn receive_input(&mut self, window: &mut MainWindow, key: KeyCode) -> Result<()> {
+    fn receive_input(&mut self, window: &mut MainWindow, key: impl Into<KeyEvent>) -> Result<()> {
+        let event: KeyEvent = key.into();
+        let key = event.code;
+        let word = event
+            .modifiers
+            .intersects(KeyModifiers::ALT | KeyModifiers::CONTROL);
         queue!(stdout(), cursor::Show)?;
         match key {
             // Remove data
             KeyCode::Delete => self.delete(window)?,
             KeyCode::Backspace => self.backspace(window)?,
 
-            // Move cursor
-            // TODO: Possibly opt+left to skip words/symbols
+            // Move cursor, by a word if alt or ctrl is held
+            KeyCode::Left if word => self.move_word_left()?,
+            KeyCode::Right if word => self.move_word_right()?,
             KeyCode::Left => self.move_left()?,
             KeyCode::Right => self.move_right()?,
+            KeyCode::Home => self.move_home()?,
+            KeyCode::End => self.move_end()?,
 
             KeyCode::Up => self.tape_back(window)?,
             KeyCode::Down => self.tape_forward(window)?,
 
+            // Control combinations are key bindings, not text, but AltGr on Windows sends control and alt
+            KeyCode::Char(_) if event.modifiers == KeyModifiers::CONTROL => {}
+
             // Insert char
             command => self.insert_char(window, command)?,
         }
@@ -205,3 +291,209 @@ impl Handler for UserInputHandler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod modifier_tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::UserInputHandler;
+    use crate::communication::{handlers::handler::Handler, reader::MainWindow};
+
+    #[test]
+    fn test_types_plain_char() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = UserInputHandler::new();
+        handler
+            .receive_input(&mut logria, KeyCode::Char('d'))
+            .unwrap();
+        assert_eq!(handler.get_content(), "d");
+    }
+
+    #[test]
+    fn test_does_not_type_control_char() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = UserInputHandler::new();
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            )
+            .unwrap();
+        assert_eq!(handler.get_content(), "");
+    }
+
+    #[test]
+    fn test_types_alt_gr_char() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = UserInputHandler::new();
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(
+                    KeyCode::Char('@'),
+                    KeyModifiers::CONTROL | KeyModifiers::ALT,
+                ),
+            )
+            .unwrap();
+        assert_eq!(handler.get_content(), "@");
+    }
+
+    #[test]
+    fn test_types_shifted_char() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = UserInputHandler::new();
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT),
+            )
+            .unwrap();
+        assert_eq!(handler.get_content(), "D");
+    }
+}
+
+#[cfg(test)]
+mod word_tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{next_word_boundary, previous_word_boundary, UserInputHandler};
+    use crate::communication::{handlers::handler::Handler, reader::MainWindow};
+
+    fn content() -> Vec<char> {
+        "agg  42 --fast".chars().collect()
+    }
+
+    #[test]
+    fn test_previous_word_from_end() {
+        assert_eq!(previous_word_boundary(&content(), 14), 8);
+    }
+
+    #[test]
+    fn test_previous_word_from_inside_word() {
+        assert_eq!(previous_word_boundary(&content(), 6), 5);
+    }
+
+    #[test]
+    fn test_previous_word_skips_whitespace() {
+        assert_eq!(previous_word_boundary(&content(), 5), 0);
+    }
+
+    #[test]
+    fn test_previous_word_at_start() {
+        assert_eq!(previous_word_boundary(&content(), 0), 0);
+    }
+
+    #[test]
+    fn test_next_word_from_start() {
+        assert_eq!(next_word_boundary(&content(), 0), 3);
+    }
+
+    #[test]
+    fn test_next_word_skips_whitespace() {
+        assert_eq!(next_word_boundary(&content(), 3), 7);
+    }
+
+    #[test]
+    fn test_next_word_at_end() {
+        assert_eq!(next_word_boundary(&content(), 14), 14);
+    }
+
+    #[test]
+    fn test_word_boundaries_of_empty_content() {
+        assert_eq!(previous_word_boundary(&[], 0), 0);
+        assert_eq!(next_word_boundary(&[], 0), 0);
+    }
+
+    #[test]
+    fn test_alt_arrows_move_by_word() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = UserInputHandler::new();
+        handler.content = content();
+        handler.last_write = 15;
+
+        handler
+            .receive_input(&mut logria, KeyEvent::new(KeyCode::Left, KeyModifiers::ALT))
+            .unwrap();
+        assert_eq!(handler.last_write, 9);
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Left, KeyModifiers::CONTROL),
+            )
+            .unwrap();
+        assert_eq!(handler.last_write, 6);
+        handler
+            .receive_input(
+                &mut logria,
+                KeyEvent::new(KeyCode::Right, KeyModifiers::ALT),
+            )
+            .unwrap();
+        assert_eq!(handler.last_write, 8);
+    }
+
+    #[test]
+    fn test_plain_arrows_move_by_char() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = UserInputHandler::new();
+        handler.content = content();
+        handler.last_write = 15;
+
+        handler.receive_input(&mut logria, KeyCode::Left).unwrap();
+        assert_eq!(handler.last_write, 14);
+    }
+}
+
+#[cfg(test)]
+mod home_end_tests {
+    use crossterm::event::KeyCode;
+
+    use super::UserInputHandler;
+    use crate::communication::{handlers::handler::Handler, reader::MainWindow};
+
+    fn handler() -> UserInputHandler {
+        let mut handler = UserInputHandler::new();
+        handler.x = 100;
+        handler.content = "agg 42".chars().collect();
+        handler.last_write = 4;
+        handler
+    }
+
+    #[test]
+    fn test_home_moves_to_start() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = handler();
+
+        handler.receive_input(&mut logria, KeyCode::Home).unwrap();
+        assert_eq!(handler.last_write, 1);
+    }
+
+    #[test]
+    fn test_end_moves_past_content() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = handler();
+
+        handler.receive_input(&mut logria, KeyCode::End).unwrap();
+        assert_eq!(handler.last_write, 7);
+    }
+
+    #[test]
+    fn test_end_of_empty_content() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = handler();
+        handler.content.clear();
+        handler.last_write = 1;
+
+        handler.receive_input(&mut logria, KeyCode::End).unwrap();
+        assert_eq!(handler.last_write, 1);
+    }
+
+    #[test]
+    fn test_end_stays_inside_command_box() {
+        let mut logria = MainWindow::_new_dummy();
+        let mut handler = handler();
+        handler.x = 6;
+
+        handler.receive_input(&mut logria, KeyCode::End).unwrap();
+        assert_eq!(handler.last_write, 3);
+    }
+}
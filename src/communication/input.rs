@@ -1,35 +1,42 @@
 use is_executable::is_executable;
 
 use crate::{
+    constants::cli::poll_rate::{DELETED_CHECKS, FOLLOW, SAMPLES},
     extensions::{
         extension::ExtensionMethods,
         session::{Session, SessionType},
     },
     util::{
         error::LogriaError,
+        gzip::GzipLines,
         poll::{ms_per_message, RollingMean},
+        sanitizers::{binary::looks_binary, control::escape_control_chars},
     },
 };
 
 use std::{
-    collections::HashSet,
+    cmp::min,
+    collections::{BTreeMap, HashSet, VecDeque},
     env::current_dir,
     error::Error,
-    fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
+    fs::{metadata, File, Metadata},
+    io::{stdin, BufRead, BufReader, ErrorKind, Seek},
+    path::{Path, PathBuf},
     process::Stdio,
     result::Result,
     sync::{
-        mpsc::{channel, Receiver},
+        mpsc::{channel, Receiver, Sender},
         Arc, Mutex,
     },
     thread, time,
 };
 
+#[cfg(unix)]
+use std::os::unix::{fs::FileTypeExt, net::UnixStream};
+
 use tokio::{
-    io::{AsyncBufReadExt, BufReader as TokioBufReader},
-    process::Command,
+    io::{AsyncBufReadExt, AsyncReadExt, BufReader as TokioBufReader, Lines},
+    process::{ChildStdout, Command},
     runtime::Runtime,
 };
 
@@ -38,11 +45,79 @@ pub struct InputStream {
     pub stdout: Receiver<String>,
     pub stderr: Receiver<String>,
     pub process_name: String,
+    /// The path, command, or address the stream reads from
+    pub source: String,
     pub process: Result<std::thread::JoinHandle<()>, std::io::Error>,
     pub should_die: Arc<Mutex<bool>>,
     pub _type: String,
 }
 
+impl InputStream {
+    /// Create a stream that reports why it could not be built on its stderr channel
+    pub fn failed(name: &str, source: &str, why: LogriaError) -> InputStream {
+        let (_, out_rx) = channel();
+        let (err_tx, err_rx) = channel();
+        err_tx.send(why.to_string()).ok();
+        InputStream {
+            stdout: out_rx,
+            stderr: err_rx,
+            process_name: name.to_owned(),
+            source: source.to_owned(),
+            process: thread::Builder::new()
+                .name(format!("FailedInput: {}", name))
+                .spawn(|| {}),
+            should_die: Arc::new(Mutex::new(true)),
+            _type: String::from("FailedInput"),
+        }
+    }
+}
+
+/// Settings shared by the streams started from user input or a session
+#[derive(Debug, Clone)]
+pub struct StreamOptions {
+    /// Number of recent poll rates averaged by smart polling
+    pub poll_samples: usize,
+    /// Whether command output is decompressed as a gzip stream
+    pub gunzip: bool,
+    /// Whether file inputs keep reading lines appended after the end of the file
+    pub follow: bool,
+    /// What followed file inputs do when their file is deleted
+    pub deleted_file: DeletedFilePolicy,
+    /// Directory new command streams start in, set with `:cd`; Logria's own directory if unset
+    pub working_dir: Option<String>,
+    /// Environment variables set for new command streams with `:env`
+    pub env: BTreeMap<String, String>,
+}
+
+impl StreamOptions {
+    pub fn new(poll_samples: usize) -> StreamOptions {
+        StreamOptions {
+            poll_samples,
+            gunzip: false,
+            follow: false,
+            deleted_file: DeletedFilePolicy::default(),
+            working_dir: None,
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        StreamOptions::new(SAMPLES)
+    }
+}
+
+/// What a followed file input does when its file is deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeletedFilePolicy {
+    /// Wait for a file to be created at the same path and read it from the start, i.e. for an atomic replace
+    #[default]
+    Reopen,
+    /// Report that the file was deleted on `stderr` and stop reading
+    Stop,
+}
+
 pub trait Input {
     fn build(name: String, command: String) -> Result<InputStream, LogriaError>;
 }
@@ -51,11 +126,32 @@ pub trait Input {
 pub struct FileInput {}
 
 impl Input for FileInput {
+    /// Create a file input that reads the file once
+    fn build(name: String, command: String) -> Result<InputStream, LogriaError> {
+        FileInput::build_with_options(name, command, &StreamOptions::default())
+    }
+}
+
+impl FileInput {
     /// Create a file input
     /// poll_rate is unused since the file will be read all at once
-    fn build(name: String, command: String) -> Result<InputStream, LogriaError> {
+    ///
+    /// If the start of the file looks binary, a warning is sent to `stderr` and control characters in
+    /// each line are escaped so they cannot corrupt the terminal. Binary files are not followed.
+    ///
+    /// If `options.follow` is set, lines appended after the end of the file are read as they arrive,
+    /// like `tail -f`.
+    pub fn build_with_options(
+        name: String,
+        command: String,
+        options: &StreamOptions,
+    ) -> Result<InputStream, LogriaError> {
+        let source = command.clone();
+        let follow = options.follow;
+        let deleted_file = options.deleted_file;
+
         // Setup multiprocessing queues
-        let (_, err_rx) = channel();
+        let (err_tx, err_rx) = channel();
         let (out_tx, out_rx) = channel();
 
         // Try and open a handle to the file
@@ -73,12 +169,47 @@ impl Input for FileInput {
             Ok(file) => file,
         };
 
+        // Provide check for termination outside of the thread
+        let should_die = Arc::new(Mutex::new(false));
+        let die = should_die.clone();
+
         // Start process
         let process = thread::Builder::new()
             .name(format!("FileInput: {}", name))
             .spawn(move || {
                 // Create a buffer and read from it
-                let reader = BufReader::new(file);
+                let mut reader = BufReader::new(file);
+                let binary = reader.fill_buf().is_ok_and(looks_binary);
+                if binary {
+                    err_tx
+                        .send(format!(
+                            "{} looks like a binary file, showing it with control characters escaped",
+                            command
+                        ))
+                        .ok();
+                    if follow {
+                        err_tx
+                            .send(format!(
+                                "{} cannot be followed as a binary file, showing its current contents",
+                                command
+                            ))
+                            .ok();
+                    }
+                    for line in reader.split(b'\n').map_while(Result::ok) {
+                        if out_tx
+                            .send(escape_control_chars(&String::from_utf8_lossy(&line)))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    return;
+                }
+                // Lines are read one at a time, so one still being written is not sent early
+                if follow {
+                    FileInput::follow(reader, &command, deleted_file, &out_tx, &err_tx, &die);
+                    return;
+                }
                 for line in reader.lines() {
                     if line.is_ok() {
                         out_tx
@@ -95,11 +226,266 @@ impl Input for FileInput {
             stdout: out_rx,
             stderr: err_rx,
             process_name: name,
+            source,
             process,
-            should_die: Arc::new(Mutex::new(false)),
+            should_die,
             _type: String::from("FileInput"),
         })
     }
+
+    /// Send each line of a file, then the lines appended to it, until the stream is stopped
+    ///
+    /// If the file shrinks, i.e. because it was truncated, or a different file is moved to the path, i.e.
+    /// because it was rotated, the file at the path is read from the start. While nothing is at the path,
+    /// the open file is still read until `deleted_file` decides what to do.
+    fn follow(
+        mut reader: BufReader<File>,
+        path: &str,
+        deleted_file: DeletedFilePolicy,
+        out_tx: &Sender<String>,
+        err_tx: &Sender<String>,
+        die: &Arc<Mutex<bool>>,
+    ) {
+        let mut opened = reader
+            .get_ref()
+            .metadata()
+            .ok()
+            .and_then(|file| file_id(&file));
+        // Number of checks in a row that found nothing at the path
+        let mut missing_checks = 0;
+        // A line is only sent once its newline is written
+        let mut pending = String::new();
+        while !*die.lock().unwrap() {
+            match reader.read_line(&mut pending) {
+                Ok(0) | Err(_) => {
+                    let position = reader.stream_position().unwrap_or_default();
+                    let state = FollowedPath::check(path, opened);
+                    missing_checks = match state {
+                        FollowedPath::Missing => missing_checks + 1,
+                        _ => 0,
+                    };
+                    match state.action(position, missing_checks, deleted_file) {
+                        FollowAction::Reopen => {
+                            if let Ok(file) = File::open(path) {
+                                opened = file.metadata().ok().and_then(|file| file_id(&file));
+                                reader = BufReader::new(file);
+                                pending.clear();
+                                continue;
+                            }
+                        }
+                        FollowAction::Stop => {
+                            err_tx.send(format!("{} was deleted", path)).ok();
+                            return;
+                        }
+                        FollowAction::Wait => {}
+                    }
+                    thread::sleep(time::Duration::from_millis(FOLLOW));
+                }
+                Ok(_) => {
+                    if pending.ends_with('\n') {
+                        let line = pending.trim_end_matches(['\n', '\r']).to_owned();
+                        pending.clear();
+                        if out_tx.send(line).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Identify the file behind a path or handle, so a different file moved to the same path is noticed
+#[cfg(unix)]
+fn file_id(file: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((file.dev(), file.ino()))
+}
+
+/// Identify the file behind a path or handle, so a different file moved to the same path is noticed
+#[cfg(not(unix))]
+fn file_id(file: &Metadata) -> Option<(u64, u64)> {
+    let created = file.created().ok()?.duration_since(time::UNIX_EPOCH).ok()?;
+    Some((created.as_secs(), u64::from(created.subsec_nanos())))
+}
+
+/// What the path of a followed file points to once the open file has been read to the end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FollowedPath {
+    /// The open file, which is now this long
+    Same(u64),
+    /// A different file, i.e. the open file was rotated or replaced
+    Replaced,
+    /// Nothing, i.e. the open file was deleted or is being replaced
+    Missing,
+}
+
+impl FollowedPath {
+    /// Compare the file at `path` with the open file, identified by `opened`
+    fn check(path: &str, opened: Option<(u64, u64)>) -> FollowedPath {
+        match metadata(path) {
+            Err(_) => FollowedPath::Missing,
+            Ok(file) => match (file_id(&file), opened) {
+                (Some(current), Some(opened)) if current != opened => FollowedPath::Replaced,
+                _ => FollowedPath::Same(file.len()),
+            },
+        }
+    }
+
+    /// Decide what to do given how much of the open file was read and how many checks in a row found
+    /// nothing at the path
+    ///
+    /// A path is only considered deleted once it has been missing for `DELETED_CHECKS` checks, since
+    /// replacing a file can remove it briefly.
+    fn action(
+        &self,
+        position: u64,
+        missing_checks: u32,
+        deleted_file: DeletedFilePolicy,
+    ) -> FollowAction {
+        match (self, deleted_file) {
+            (FollowedPath::Same(length), _) if *length < position => FollowAction::Reopen,
+            (FollowedPath::Same(_), _) => FollowAction::Wait,
+            (FollowedPath::Replaced, _) => FollowAction::Reopen,
+            (FollowedPath::Missing, DeletedFilePolicy::Stop)
+                if missing_checks >= DELETED_CHECKS =>
+            {
+                FollowAction::Stop
+            }
+            (FollowedPath::Missing, _) => FollowAction::Wait,
+        }
+    }
+}
+
+/// What a followed file's reader does after checking its path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FollowAction {
+    /// Keep reading the open file as lines are appended
+    Wait,
+    /// Read the file at the path from the start
+    Reopen,
+    /// Stop reading, since the file was deleted
+    Stop,
+}
+
+/// Reads messages piped to Logria, i.e. `tail -f log.txt | logria --pipe`
+#[derive(Debug)]
+pub struct StdinInput {}
+
+impl StdinInput {
+    /// Create an input that sends each line of `reader` to `stdout` until it ends
+    pub fn build_from_reader<R: BufRead + Send + 'static>(
+        name: String,
+        reader: R,
+    ) -> Result<InputStream, LogriaError> {
+        // Setup multiprocessing queues
+        let (_, err_rx) = channel();
+        let (out_tx, out_rx) = channel();
+
+        // Provide check for termination outside of the thread
+        let should_die = Arc::new(Mutex::new(false));
+        let die = should_die.clone();
+
+        let process = thread::Builder::new()
+            .name(format!("StdinInput: {}", name))
+            .spawn(move || {
+                for line in reader.split(b'\n').map_while(Result::ok) {
+                    if *die.lock().unwrap() {
+                        return;
+                    }
+                    let message = String::from_utf8_lossy(&line)
+                        .trim_end_matches('\r')
+                        .to_owned();
+                    if out_tx.send(message).is_err() {
+                        return;
+                    }
+                }
+            });
+
+        Ok(InputStream {
+            stdout: out_rx,
+            stderr: err_rx,
+            process_name: name,
+            source: String::from("stdin"),
+            process,
+            should_die,
+            _type: String::from("StdinInput"),
+        })
+    }
+}
+
+impl Input for StdinInput {
+    /// Create an input from Logria's own stdin
+    /// command is unused since there is only one stdin to read
+    fn build(name: String, _: String) -> Result<InputStream, LogriaError> {
+        StdinInput::build_from_reader(name, BufReader::new(stdin()))
+    }
+}
+
+/// Lines read from a command's stdout, decompressed first if the output is gzipped
+enum CommandOutput {
+    Plain(Lines<TokioBufReader<ChildStdout>>),
+    Gzip {
+        stdout: ChildStdout,
+        lines: GzipLines,
+        pending: VecDeque<String>,
+        done: bool,
+    },
+}
+
+impl CommandOutput {
+    fn new(stdout: ChildStdout, gunzip: bool) -> CommandOutput {
+        if gunzip {
+            CommandOutput::Gzip {
+                stdout,
+                lines: GzipLines::new(),
+                pending: VecDeque::new(),
+                done: false,
+            }
+        } else {
+            CommandOutput::Plain(TokioBufReader::new(stdout).lines())
+        }
+    }
+
+    /// Get the next line of output, or `None` once the output ends
+    ///
+    /// Compressed output is read with `read()`, which is cancel safe, so this can be used in `tokio::select!`
+    async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        match self {
+            CommandOutput::Plain(lines) => lines.next_line().await,
+            CommandOutput::Gzip {
+                stdout,
+                lines,
+                pending,
+                done,
+            } => {
+                let mut buffer = [0; 8192];
+                loop {
+                    if let Some(line) = pending.pop_front() {
+                        return Ok(Some(line));
+                    }
+                    if *done {
+                        return Ok(None);
+                    }
+                    let decoded = match stdout.read(&mut buffer).await? {
+                        0 => {
+                            *done = true;
+                            lines.finish()
+                        }
+                        read => lines.feed(&buffer[..read]),
+                    };
+                    match decoded {
+                        Ok(decoded) => pending.extend(decoded),
+                        Err(why) => {
+                            // Corrupt data cannot be recovered, so report it and stop reading
+                            *done = true;
+                            pending.push_back(format!("Unable to decompress output: {}", why));
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -107,14 +493,113 @@ pub struct CommandInput {}
 
 impl CommandInput {
     /// Parse a command string to a list of parts for `subprocess`
-    fn parse_command(command: &str) -> Vec<&str> {
-        command.split(' ').collect()
+    ///
+    /// Like a shell, parts are separated by whitespace, except inside single or double quotes or
+    /// after a backslash, so `grep "foo bar" file` has 3 parts. Inside double quotes, a backslash
+    /// only escapes `"` and `\`; inside single quotes, nothing is escaped.
+    pub fn parse_command(command: &str) -> Vec<String> {
+        let mut parts = vec![];
+        let mut part = String::new();
+        // Quotes can make an empty part, i.e. `echo ""`, so track whether a part was started
+        let mut in_part = false;
+        let mut chars = command.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    in_part = true;
+                    part.extend(chars.by_ref().take_while(|&c| c != '\''));
+                }
+                '"' => {
+                    in_part = true;
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '"' => break,
+                            '\\' => match chars.next() {
+                                Some(escaped @ ('"' | '\\')) => part.push(escaped),
+                                Some(other) => {
+                                    part.push('\\');
+                                    part.push(other);
+                                }
+                                None => part.push('\\'),
+                            },
+                            _ => part.push(c),
+                        }
+                    }
+                }
+                '\\' => {
+                    in_part = true;
+                    part.push(chars.next().unwrap_or('\\'));
+                }
+                c if c.is_whitespace() => {
+                    if in_part {
+                        parts.push(std::mem::take(&mut part));
+                        in_part = false;
+                    }
+                }
+                _ => {
+                    in_part = true;
+                    part.push(c);
+                }
+            }
+        }
+        if in_part {
+            parts.push(part);
+        }
+        parts
     }
-}
 
-impl Input for CommandInput {
-    /// Create a command input
-    fn build(name: String, command: String) -> Result<InputStream, LogriaError> {
+    /// Get the directory commands start in: `working_dir` if it is set, otherwise Logria's own
+    pub fn resolve_working_dir(working_dir: Option<&str>) -> Result<PathBuf, LogriaError> {
+        match working_dir {
+            Some(dir) if Path::new(dir).is_dir() => Ok(PathBuf::from(dir)),
+            Some(dir) => Err(LogriaError::CannotRead(
+                dir.to_owned(),
+                String::from("not a directory"),
+            )),
+            None => current_dir()
+                .map_err(|why| LogriaError::CannotRead(String::from("."), why.to_string())),
+        }
+    }
+
+    /// Build the process for a command split into its parts, started in `working_dir` with the
+    /// variables in `env` added to Logria's environment
+    fn build_command(
+        parts: &[String],
+        working_dir: &Path,
+        env: &BTreeMap<String, String>,
+    ) -> Command {
+        let mut command = Command::new(&parts[0]);
+        command
+            .args(&parts[1..])
+            .current_dir(working_dir)
+            .envs(env)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null());
+        command
+    }
+
+    /// Create a command input whose poll rate averages the last `options.poll_samples` reads
+    ///
+    /// If `options.gunzip` is set, stdout is decompressed as a gzip stream before it is split into
+    /// lines. The command starts in `options.working_dir`, or Logria's own directory if it is not
+    /// set, with the variables in `options.env` set.
+    pub fn build_with_options(
+        name: String,
+        command: String,
+        options: &StreamOptions,
+    ) -> Result<InputStream, LogriaError> {
+        let source = command.clone();
+        let working_dir = CommandInput::resolve_working_dir(options.working_dir.as_deref())?;
+        let command_to_run = CommandInput::parse_command(&command);
+        if command_to_run.is_empty() {
+            return Err(LogriaError::InvalidCommand(String::from(
+                "No command provided",
+            )));
+        }
+        let env = options.env.clone();
+        let gunzip = options.gunzip;
+
         // Setup multiprocessing queues
         let (err_tx, err_rx) = channel();
         let (out_tx, out_rx) = channel();
@@ -124,29 +609,27 @@ impl Input for CommandInput {
         let die = should_die.clone();
 
         // Handle poll rate
-        let mut poll_rate = RollingMean::new(5);
+        let mut poll_rate = RollingMean::new(options.poll_samples);
+
+        // Start the process before the reader thread, so a command that cannot run is an error here
+        let runtime = Runtime::new()
+            .map_err(|why| LogriaError::CannotRead(command.to_owned(), why.to_string()))?;
+        let mut proc_read = {
+            let _guard = runtime.enter();
+            CommandInput::build_command(&command_to_run, &working_dir, &env)
+                .spawn()
+                .map_err(|why| {
+                    LogriaError::CannotRead(command_to_run[0].to_owned(), why.to_string())
+                })?
+        };
 
         // Start reading from the queues
         let process = thread::Builder::new()
             .name(format!("CommandInput: {}", name))
             .spawn(move || {
-                let runtime = Runtime::new().unwrap();
                 runtime.block_on(async {
-                    let command_to_run = CommandInput::parse_command(&command);
-                    let mut proc_read = match Command::new(command_to_run[0])
-                        .args(&command_to_run[1..])
-                        .current_dir(current_dir().unwrap())
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .stdin(Stdio::null())
-                        .spawn()
-                    {
-                        Ok(connected) => connected,
-                        Err(why) => panic!("Unable to connect to process: {}", why),
-                    };
-
                     // Create buffers from stderr and stdout handles
-                    let mut stdout = TokioBufReader::new(proc_read.stdout.take().unwrap()).lines();
+                    let mut stdout = CommandOutput::new(proc_read.stdout.take().unwrap(), gunzip);
                     let mut stderr = TokioBufReader::new(proc_read.stderr.take().unwrap()).lines();
 
                     loop {
@@ -187,6 +670,7 @@ impl Input for CommandInput {
             stdout: out_rx,
             stderr: err_rx,
             process_name: name,
+            source,
             process,
             should_die,
             _type: String::from("CommandInput"),
@@ -194,8 +678,259 @@ impl Input for CommandInput {
     }
 }
 
+impl Input for CommandInput {
+    /// Create a command input with the default number of poll rate samples
+    fn build(name: String, command: String) -> Result<InputStream, LogriaError> {
+        CommandInput::build_with_options(name, command, &StreamOptions::default())
+    }
+}
+
+#[derive(Debug)]
+pub struct SseInput {}
+
+impl SseInput {
+    /// Longest time to wait between reconnection attempts
+    const MAX_BACKOFF: time::Duration = time::Duration::from_secs(30);
+    /// How long a read or a wait to reconnect lasts before checking whether the stream should stop
+    const READ_TIMEOUT: time::Duration = time::Duration::from_millis(500);
+
+    /// Get the payload of a `data:` field from a line of an event stream
+    fn parse_event_line(line: &str) -> Option<&str> {
+        line.strip_prefix("data:")
+            .map(|data| data.strip_prefix(' ').unwrap_or(data))
+    }
+
+    /// Add a line of an event stream to the data of the event being read
+    ///
+    /// A blank line ends the event, returning its `data:` lines joined by newlines, if it had any.
+    fn read_event_line(line: &str, data: &mut Vec<String>) -> Option<String> {
+        if line.is_empty() {
+            return (!data.is_empty()).then(|| std::mem::take(data).join("\n"));
+        }
+        if let Some(payload) = SseInput::parse_event_line(line) {
+            data.push(payload.to_owned());
+        }
+        None
+    }
+
+    /// Wait for `duration`, returning `false` early if the stream should stop
+    fn wait(duration: time::Duration, die: &Mutex<bool>) -> bool {
+        let deadline = time::Instant::now() + duration;
+        while !*die.lock().unwrap() {
+            let now = time::Instant::now();
+            if now >= deadline {
+                return true;
+            }
+            thread::sleep(min(deadline - now, SseInput::READ_TIMEOUT));
+        }
+        false
+    }
+
+    /// Double the time to wait before reconnecting, up to `MAX_BACKOFF`
+    fn next_backoff(backoff: time::Duration) -> time::Duration {
+        min(backoff * 2, SseInput::MAX_BACKOFF)
+    }
+}
+
+impl Input for SseInput {
+    /// Create an input that reads the `data:` lines of a Server-Sent Events endpoint
+    fn build(name: String, command: String) -> Result<InputStream, LogriaError> {
+        let source = command.clone();
+
+        // Setup multiprocessing queues
+        let (err_tx, err_rx) = channel();
+        let (out_tx, out_rx) = channel();
+
+        // Provide check for termination outside of the thread
+        let should_die = Arc::new(Mutex::new(false));
+        let die = should_die.clone();
+
+        let process = thread::Builder::new()
+            .name(format!("SseInput: {}", name))
+            .spawn(move || {
+                // Reads time out so an idle stream still checks whether it should stop
+                let agent = ureq::AgentBuilder::new()
+                    .timeout_read(SseInput::READ_TIMEOUT)
+                    .build();
+                let mut backoff = time::Duration::from_secs(1);
+                loop {
+                    let reason = match agent
+                        .get(&command)
+                        .set("Accept", "text/event-stream")
+                        .call()
+                    {
+                        Ok(response) => {
+                            // Connected, so the next disconnect starts the backoff over
+                            backoff = time::Duration::from_secs(1);
+                            let mut reader = BufReader::new(response.into_reader());
+                            // Bytes read before a timeout are kept, so a line split across reads is not lost
+                            let mut line: Vec<u8> = vec![];
+                            let mut data: Vec<String> = vec![];
+                            loop {
+                                if *die.lock().unwrap() {
+                                    return;
+                                }
+                                match reader.read_until(b'\n', &mut line) {
+                                    Ok(0) => break String::from("stream ended"),
+                                    Ok(_) => {
+                                        let text = String::from_utf8_lossy(&line);
+                                        let event = SseInput::read_event_line(
+                                            text.trim_end_matches(['\n', '\r']),
+                                            &mut data,
+                                        );
+                                        line.clear();
+                                        if let Some(event) = event {
+                                            if out_tx.send(event).is_err() {
+                                                return;
+                                            }
+                                        }
+                                    }
+                                    Err(why)
+                                        if matches!(
+                                            why.kind(),
+                                            ErrorKind::WouldBlock | ErrorKind::TimedOut
+                                        ) => {}
+                                    Err(why) => break why.to_string(),
+                                }
+                            }
+                        }
+                        Err(why) => why.to_string(),
+                    };
+
+                    if *die.lock().unwrap() {
+                        return;
+                    }
+                    let status = format!(
+                        "Disconnected from {}: {}; reconnecting in {}s",
+                        command,
+                        reason,
+                        backoff.as_secs()
+                    );
+                    if err_tx.send(status).is_err() || !SseInput::wait(backoff, &die) {
+                        return;
+                    }
+                    backoff = SseInput::next_backoff(backoff);
+                }
+            });
+
+        Ok(InputStream {
+            stdout: out_rx,
+            stderr: err_rx,
+            process_name: name,
+            source,
+            process,
+            should_die,
+            _type: String::from("SseInput"),
+        })
+    }
+}
+
+/// Reads newline delimited messages from a Unix domain socket, i.e. one a daemon exposes for its logs
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct UnixSocketInput {}
+
+#[cfg(unix)]
+impl UnixSocketInput {
+    /// How long a read waits before checking whether the stream should stop
+    const READ_TIMEOUT: time::Duration = time::Duration::from_millis(500);
+}
+
+#[cfg(unix)]
+impl Input for UnixSocketInput {
+    /// Create an input connected to the socket at the path `command`
+    ///
+    /// When the socket disconnects, the reason is sent to `stderr` and the stream ends.
+    fn build(name: String, command: String) -> Result<InputStream, LogriaError> {
+        let source = command.clone();
+
+        // Setup multiprocessing queues
+        let (err_tx, err_rx) = channel();
+        let (out_tx, out_rx) = channel();
+
+        // Connect up front so a missing or refused socket is reported like a missing file
+        let socket = UnixStream::connect(&command)
+            .and_then(|socket| {
+                socket.set_read_timeout(Some(UnixSocketInput::READ_TIMEOUT))?;
+                Ok(socket)
+            })
+            .map_err(|why| LogriaError::CannotRead(command.to_owned(), why.to_string()))?;
+
+        // Provide check for termination outside of the thread
+        let should_die = Arc::new(Mutex::new(false));
+        let die = should_die.clone();
+
+        let process = thread::Builder::new()
+            .name(format!("UnixSocketInput: {}", name))
+            .spawn(move || {
+                let mut reader = BufReader::new(socket);
+                // Bytes read before a timeout are kept, so a line split across reads is not lost
+                let mut line: Vec<u8> = vec![];
+                let reason = loop {
+                    if *die.lock().unwrap() {
+                        return;
+                    }
+                    match reader.read_until(b'\n', &mut line) {
+                        Ok(0) => break String::from("connection closed"),
+                        Ok(_) => {
+                            let message = String::from_utf8_lossy(&line)
+                                .trim_end_matches(['\n', '\r'])
+                                .to_owned();
+                            line.clear();
+                            if out_tx.send(message).is_err() {
+                                return;
+                            }
+                        }
+                        Err(why)
+                            if matches!(
+                                why.kind(),
+                                ErrorKind::WouldBlock | ErrorKind::TimedOut
+                            ) => {}
+                        Err(why) => break why.to_string(),
+                    }
+                };
+                let _ = err_tx.send(format!("Disconnected from {}: {}", command, reason));
+            });
+
+        Ok(InputStream {
+            stdout: out_rx,
+            stderr: err_rx,
+            process_name: name,
+            source,
+            process,
+            should_die,
+            _type: String::from("UnixSocketInput"),
+        })
+    }
+}
+
+/// Determine if a path is a Unix domain socket
+#[cfg(unix)]
+fn is_socket(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|metadata| metadata.file_type().is_socket())
+}
+
+#[cfg(not(unix))]
+fn is_socket(_: &Path) -> bool {
+    false
+}
+
+/// Determine if a command is a URL to read an event stream from
+fn is_url(command: &str) -> bool {
+    let command = command.trim();
+    (command.starts_with("http://") || command.starts_with("https://"))
+        && !command.contains(char::is_whitespace)
+}
+
 fn determine_stream_type(command: &str) -> SessionType {
+    if is_url(command) {
+        return SessionType::Url;
+    }
     let path = Path::new(command);
+    if is_socket(path) {
+        return SessionType::UnixSocket;
+    }
     match path.exists() {
         true => match is_executable(path) {
             true => SessionType::Command,
@@ -205,52 +940,101 @@ fn determine_stream_type(command: &str) -> SessionType {
     }
 }
 
+/// Determine the name to save a session under from its first command
+///
+/// Session names are file names, so the path separators in a URL are replaced.
+fn session_name(command: &str) -> String {
+    match is_url(command) {
+        true => command.replace('/', "_"),
+        false => command.to_owned(),
+    }
+}
+
+/// Remove repeated commands or paths so the same input is not read twice
+///
+/// Files are compared by their canonical path, so `README.md` and `./README.md` are duplicates.
+fn dedupe_commands(commands: &[String]) -> Vec<String> {
+    let mut seen: HashSet<String> = HashSet::new();
+    commands
+        .iter()
+        .filter(|command| {
+            let key = match determine_stream_type(command) {
+                SessionType::File => match Path::new(command).canonicalize() {
+                    Ok(path) => path.to_string_lossy().to_string(),
+                    Err(_) => command.to_string(),
+                },
+                _ => command.trim().to_string(),
+            };
+            seen.insert(key)
+        })
+        .cloned()
+        .collect()
+}
+
 /// Build app streams from user input, i.e. command text or a filepath
 pub fn build_streams_from_input(
     commands: &[String],
     save: bool,
+    options: &StreamOptions,
 ) -> Result<Vec<InputStream>, LogriaError> {
+    let commands = &dedupe_commands(commands);
     let mut streams: Vec<InputStream> = vec![];
     let mut stream_types: HashSet<SessionType> = HashSet::new();
     for command in commands {
         // Determine if command is a file, create FileInput if it is, CommandInput if not
-        match determine_stream_type(command) {
+        let stream = match determine_stream_type(command) {
             SessionType::Command => {
-                // None indicates default poll rate
-                match CommandInput::build(command.to_owned(), command.to_owned()) {
-                    Ok(stream) => streams.push(stream),
-                    Err(why) => return Err(why),
-                };
                 stream_types.insert(SessionType::Command);
+                CommandInput::build_with_options(command.to_owned(), command.to_owned(), options)
             }
             SessionType::File => {
+                stream_types.insert(SessionType::File);
                 // None indicates default poll rate
                 let path = Path::new(command);
                 let name = path.file_name().unwrap().to_str().unwrap().to_string();
-                match FileInput::build(name, command.to_owned()) {
-                    Ok(stream) => streams.push(stream),
-                    Err(why) => return Err(why),
-                };
-                stream_types.insert(SessionType::File);
+                FileInput::build_with_options(name, command.to_owned(), options)
             }
-            _ => {}
-        }
-    }
-    if save {
+            SessionType::Url => {
+                stream_types.insert(SessionType::Url);
+                let url = command.trim();
+                SseInput::build(url.to_owned(), url.to_owned())
+            }
+            #[cfg(unix)]
+            SessionType::UnixSocket => {
+                stream_types.insert(SessionType::UnixSocket);
+                UnixSocketInput::build(command.to_owned(), command.to_owned())
+            }
+            _ => continue,
+        };
+        // A stream that fails to start reports why instead of aborting the rest
+        streams.push(stream.unwrap_or_else(|why| InputStream::failed(command, command, why)));
+    }
+    // A session that does not start cleanly is not worth reopening
+    let failed = streams.iter().any(|stream| stream._type == "FailedInput");
+    if save && !failed {
         let stream_type = match stream_types.len() {
             1 => {
                 if stream_types.contains(&SessionType::File) {
                     SessionType::File
                 } else if stream_types.contains(&SessionType::Command) {
                     SessionType::Command
+                } else if stream_types.contains(&SessionType::Url) {
+                    SessionType::Url
+                } else if stream_types.contains(&SessionType::UnixSocket) {
+                    SessionType::UnixSocket
                 } else {
                     SessionType::Mixed
                 }
             }
             _ => SessionType::Mixed,
         };
-        return match Session::new(commands, stream_type).save(&commands[0]) {
-            Ok(_) => Ok(streams),
+        let session_name = session_name(&commands[0]);
+        return match Session::new(commands, stream_type).save(&session_name) {
+            Ok(_) => {
+                // The marker is only used by `--resume`, so failing to write it does not stop the streams
+                Session::record_last(&session_name).ok();
+                Ok(streams)
+            }
             Err(why) => Err(why),
         };
     }
@@ -258,14 +1042,23 @@ pub fn build_streams_from_input(
 }
 
 /// Build app streams from a session struct
-pub fn build_streams_from_session(session: Session) -> Result<Vec<InputStream>, LogriaError> {
+///
+/// Like `build_streams_from_input`, a stream that fails to start reports why instead of aborting the rest.
+pub fn build_streams_from_session(
+    session: Session,
+    options: &StreamOptions,
+) -> Result<Vec<InputStream>, LogriaError> {
     match session.stream_type {
         SessionType::Command => {
             let mut streams: Vec<InputStream> = vec![];
             for command in session.commands {
-                match CommandInput::build(command.to_owned(), command.to_owned()) {
+                match CommandInput::build_with_options(
+                    command.to_owned(),
+                    command.to_owned(),
+                    options,
+                ) {
                     Ok(stream) => streams.push(stream),
-                    Err(why) => return Err(why),
+                    Err(why) => streams.push(InputStream::failed(&command, &command, why)),
                 };
             }
             Ok(streams)
@@ -273,14 +1066,41 @@ pub fn build_streams_from_session(session: Session) -> Result<Vec<InputStream>,
         SessionType::File => {
             let mut streams: Vec<InputStream> = vec![];
             for command in session.commands {
-                match FileInput::build(command.to_owned(), command.to_owned()) {
+                match FileInput::build_with_options(command.to_owned(), command.to_owned(), options)
+                {
+                    Ok(stream) => streams.push(stream),
+                    Err(why) => streams.push(InputStream::failed(&command, &command, why)),
+                };
+            }
+            Ok(streams)
+        }
+        SessionType::Url => {
+            let mut streams: Vec<InputStream> = vec![];
+            for command in session.commands {
+                match SseInput::build(command.to_owned(), command.to_owned()) {
                     Ok(stream) => streams.push(stream),
-                    Err(why) => return Err(why),
+                    Err(why) => streams.push(InputStream::failed(&command, &command, why)),
                 };
             }
             Ok(streams)
         }
-        SessionType::Mixed => build_streams_from_input(&session.commands, false),
+        #[cfg(unix)]
+        SessionType::UnixSocket => {
+            let mut streams: Vec<InputStream> = vec![];
+            for command in session.commands {
+                match UnixSocketInput::build(command.to_owned(), command.to_owned()) {
+                    Ok(stream) => streams.push(stream),
+                    Err(why) => streams.push(InputStream::failed(&command, &command, why)),
+                };
+            }
+            Ok(streams)
+        }
+        #[cfg(not(unix))]
+        SessionType::UnixSocket => Err(LogriaError::CannotRead(
+            session.commands.join(", "),
+            String::from("Unix sockets are not supported on this platform"),
+        )),
+        SessionType::Mixed => build_streams_from_input(&session.commands, false, options),
     }
 }
 
@@ -293,16 +1113,137 @@ pub enum InputType {
     Startup,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StreamType {
     StdErr,
     StdOut,
     Auxiliary,
 }
 
+#[cfg(test)]
+mod parse_command_tests {
+    use crate::communication::input::CommandInput;
+
+    #[test]
+    fn can_parse_command_simple() {
+        assert_eq!(CommandInput::parse_command("ls"), vec!["ls"]);
+    }
+
+    #[test]
+    fn can_parse_command_simple_args() {
+        assert_eq!(
+            CommandInput::parse_command("ls -l -a"),
+            vec!["ls", "-l", "-a"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_repeated_spaces() {
+        assert_eq!(
+            CommandInput::parse_command("  tail   -f log.txt "),
+            vec!["tail", "-f", "log.txt"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_double_quotes() {
+        assert_eq!(
+            CommandInput::parse_command("grep \"foo bar\" file"),
+            vec!["grep", "foo bar", "file"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_single_quotes() {
+        assert_eq!(
+            CommandInput::parse_command("awk '{ print $1 }' fake.txt"),
+            vec!["awk", "{ print $1 }", "fake.txt"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_single_quotes_no_escapes() {
+        assert_eq!(
+            CommandInput::parse_command("echo 'a\\b'"),
+            vec!["echo", "a\\b"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_escaped_quotes() {
+        assert_eq!(
+            CommandInput::parse_command("echo \"say \\\"hi\\\"\" \\'"),
+            vec!["echo", "say \"hi\"", "'"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_escaped_space() {
+        assert_eq!(
+            CommandInput::parse_command("cat my\\ file.txt"),
+            vec!["cat", "my file.txt"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_backslash_in_double_quotes() {
+        assert_eq!(
+            CommandInput::parse_command("grep \"\\d+\" file"),
+            vec!["grep", "\\d+", "file"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_joined_quotes() {
+        assert_eq!(
+            CommandInput::parse_command("echo a\"b c\"d"),
+            vec!["echo", "ab cd"]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_empty_tokens() {
+        assert_eq!(
+            CommandInput::parse_command("printf '' \"\""),
+            vec!["printf", "", ""]
+        );
+    }
+
+    #[test]
+    fn can_parse_command_empty() {
+        assert!(CommandInput::parse_command("").is_empty());
+        assert!(CommandInput::parse_command("   ").is_empty());
+    }
+
+    #[test]
+    fn can_parse_command_unterminated_quote() {
+        assert_eq!(
+            CommandInput::parse_command("echo \"foo bar"),
+            vec!["echo", "foo bar"]
+        );
+    }
+}
+
 #[cfg(test)]
 mod session_type_tests {
-    use crate::{communication::input::determine_stream_type, extensions::session::SessionType};
+    use crate::{
+        communication::input::{determine_stream_type, session_name},
+        extensions::session::SessionType,
+    };
+
+    #[test]
+    fn can_name_url_session() {
+        assert_eq!(
+            session_name("https://example.com/events"),
+            "https:__example.com_events"
+        );
+    }
+
+    #[test]
+    fn can_name_command_session() {
+        assert_eq!(session_name("tail -f logs/app.log"), "tail -f logs/app.log");
+        assert_eq!(session_name("ls"), "ls");
+    }
 
     #[test]
     fn can_build_command_simple() {
@@ -349,33 +1290,187 @@ mod session_type_tests {
     fn can_build_file_simple() {
         assert_eq!(determine_stream_type("/"), SessionType::File);
     }
+
+    #[test]
+    fn can_build_url_http() {
+        assert_eq!(
+            determine_stream_type("http://localhost:8080/logs"),
+            SessionType::Url
+        );
+    }
+
+    #[test]
+    fn can_build_url_https() {
+        assert_eq!(
+            determine_stream_type("https://example.com/events?level=debug"),
+            SessionType::Url
+        );
+    }
+
+    #[test]
+    fn can_build_url_surrounding_whitespace() {
+        assert_eq!(
+            determine_stream_type(" https://example.com/events "),
+            SessionType::Url
+        );
+    }
+
+    #[test]
+    fn can_build_command_with_url_arg() {
+        assert_eq!(
+            determine_stream_type("curl https://example.com/events"),
+            SessionType::Command
+        );
+    }
+
+    #[test]
+    fn can_build_command_url_with_args() {
+        assert_eq!(
+            determine_stream_type("https://example.com/events --flag"),
+            SessionType::Command
+        );
+    }
+
+    #[test]
+    fn can_build_command_other_scheme() {
+        assert_eq!(
+            determine_stream_type("ftp://example.com/logs"),
+            SessionType::Command
+        );
+    }
+}
+
+#[cfg(test)]
+mod sse_tests {
+    use std::{
+        io::Write,
+        net::TcpListener,
+        sync::Mutex,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use crate::communication::input::{Input, SseInput};
+
+    #[test]
+    fn can_parse_data_line() {
+        assert_eq!(SseInput::parse_event_line("data: message"), Some("message"));
+    }
+
+    #[test]
+    fn can_parse_data_line_no_space() {
+        assert_eq!(SseInput::parse_event_line("data:message"), Some("message"));
+    }
+
+    #[test]
+    fn can_skip_other_fields() {
+        assert_eq!(SseInput::parse_event_line("event: log"), None);
+        assert_eq!(SseInput::parse_event_line(": keep-alive"), None);
+        assert_eq!(SseInput::parse_event_line(""), None);
+    }
+
+    #[test]
+    fn can_join_data_lines_of_event() {
+        let mut data = vec![];
+        assert_eq!(SseInput::read_event_line("event: log", &mut data), None);
+        assert_eq!(SseInput::read_event_line("data: first", &mut data), None);
+        assert_eq!(SseInput::read_event_line("data: second", &mut data), None);
+        assert_eq!(
+            SseInput::read_event_line("", &mut data),
+            Some(String::from("first\nsecond"))
+        );
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn can_skip_event_without_data() {
+        let mut data = vec![];
+        assert_eq!(SseInput::read_event_line(": keep-alive", &mut data), None);
+        assert_eq!(SseInput::read_event_line("", &mut data), None);
+    }
+
+    #[test]
+    fn can_stop_waiting() {
+        let start = Instant::now();
+        assert!(!SseInput::wait(Duration::from_secs(30), &Mutex::new(true)));
+        assert!(SseInput::wait(
+            Duration::from_millis(10),
+            &Mutex::new(false)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn can_stop_idle_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n")
+                .unwrap();
+            socket.write_all(b"data: first\ndata: second\n\n").unwrap();
+            // Keep the connection open without sending anything else
+            thread::sleep(Duration::from_secs(30));
+        });
+
+        let stream = SseInput::build(url.to_owned(), url).unwrap();
+        assert_eq!(
+            stream.stdout.recv_timeout(Duration::from_secs(5)).unwrap(),
+            "first\nsecond"
+        );
+        *stream.should_die.lock().unwrap() = true;
+        let process = stream.process.unwrap();
+        let start = Instant::now();
+        while !process.is_finished() && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(process.is_finished());
+    }
+
+    #[test]
+    fn can_back_off() {
+        assert_eq!(
+            SseInput::next_backoff(Duration::from_secs(1)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            SseInput::next_backoff(Duration::from_secs(20)),
+            Duration::from_secs(30)
+        );
+    }
 }
 
 #[cfg(test)]
 mod stream_tests {
     use crate::{
-        communication::input::{build_streams_from_input, build_streams_from_session},
+        communication::input::{
+            build_streams_from_input, build_streams_from_session, StreamOptions,
+        },
         extensions::session::{Session, SessionType},
     };
 
     #[test]
     fn test_build_file_stream() {
         let commands = vec![String::from("README.md")];
-        let streams = build_streams_from_input(&commands, false).unwrap();
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "FileInput");
     }
 
     #[test]
     fn test_build_command_stream() {
         let commands = vec![String::from("ls -la ~")];
-        let streams = build_streams_from_input(&commands, false).unwrap();
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "CommandInput");
     }
 
     #[test]
     fn test_build_command_and_file_streams() {
         let commands = vec![String::from("ls -la ~"), String::from("README.md")];
-        let streams = build_streams_from_input(&commands, false).unwrap();
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "CommandInput");
         assert_eq!(streams[1]._type, "FileInput");
     }
@@ -383,15 +1478,57 @@ mod stream_tests {
     #[test]
     fn test_build_multiple_command_streams() {
         let commands = vec![String::from("ls -la ~"), String::from("ls /")];
-        let streams = build_streams_from_input(&commands, false).unwrap();
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "CommandInput");
         assert_eq!(streams[1]._type, "CommandInput");
     }
 
+    #[test]
+    fn test_build_duplicate_file_streams() {
+        let commands = vec![String::from("README.md"), String::from("README.md")];
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
+        assert_eq!(streams.len(), 1);
+    }
+
+    #[test]
+    fn test_build_duplicate_file_streams_relative() {
+        let commands = vec![String::from("README.md"), String::from("./README.md")];
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
+        assert_eq!(streams.len(), 1);
+    }
+
+    #[test]
+    fn test_build_duplicate_command_streams() {
+        let commands = vec![String::from("ls /"), String::from("ls / ")];
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
+        assert_eq!(streams.len(), 1);
+    }
+
+    #[test]
+    fn test_build_duplicate_streams_keeps_distinct() {
+        let commands = vec![
+            String::from("README.md"),
+            String::from("ls /"),
+            String::from("README.md"),
+            String::from("Cargo.toml"),
+        ];
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
+        assert_eq!(streams.len(), 3);
+        assert_eq!(streams[0]._type, "FileInput");
+        assert_eq!(streams[1]._type, "CommandInput");
+        assert_eq!(streams[2]._type, "FileInput");
+    }
+
     #[test]
     fn test_build_multiple_file_streams() {
         let commands = vec![String::from("README.md"), String::from("Cargo.toml")];
-        let streams = build_streams_from_input(&commands, false).unwrap();
+        let streams =
+            build_streams_from_input(&commands, false, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "FileInput");
         assert_eq!(streams[1]._type, "FileInput");
     }
@@ -399,14 +1536,14 @@ mod stream_tests {
     #[test]
     fn test_build_file_stream_from_session() {
         let session = Session::new(&[String::from("README.md")], SessionType::File);
-        let streams = build_streams_from_session(session).unwrap();
+        let streams = build_streams_from_session(session, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "FileInput");
     }
 
     #[test]
     fn test_build_command_stream_from_session() {
         let session = Session::new(&[String::from("ls -l")], SessionType::Command);
-        let streams = build_streams_from_session(session).unwrap();
+        let streams = build_streams_from_session(session, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "CommandInput");
     }
 
@@ -416,8 +1553,599 @@ mod stream_tests {
             &[String::from("ls -l"), String::from("README.md")],
             SessionType::Mixed,
         );
-        let streams = build_streams_from_session(session).unwrap();
+        let streams = build_streams_from_session(session, &StreamOptions::default()).unwrap();
         assert_eq!(streams[0]._type, "CommandInput");
         assert_eq!(streams[1]._type, "FileInput");
     }
 }
+
+#[cfg(test)]
+mod spawn_error_tests {
+    use std::time::Duration;
+
+    use crate::{
+        communication::input::{
+            build_streams_from_input, build_streams_from_session, CommandInput, StreamOptions,
+        },
+        extensions::session::{Session, SessionType},
+    };
+
+    #[test]
+    fn missing_binary_reports_error() {
+        let stream = CommandInput::build_with_options(
+            String::from("missing"),
+            String::from("zzz_logria_missing_binary --flag"),
+            &StreamOptions::default(),
+        );
+        match stream {
+            Err(why) => assert!(why.to_string().contains("zzz_logria_missing_binary")),
+            Ok(_) => panic!("missing binary started"),
+        }
+    }
+
+    #[test]
+    fn missing_binary_is_not_saved() {
+        let commands = vec![String::from("zzz_logria_missing_binary_session")];
+        let streams = build_streams_from_input(&commands, true, &StreamOptions::default()).unwrap();
+        assert_eq!(streams[0]._type, "FailedInput");
+        assert!(Session::find("zzz_logria_missing_binary_session").is_err());
+    }
+
+    #[test]
+    fn failed_command_does_not_abort_batch() {
+        let commands = vec![String::from("ls -la"), String::from("README.md")];
+        let options = StreamOptions {
+            working_dir: Some(String::from("zzz missing dir")),
+            ..StreamOptions::default()
+        };
+        let streams = build_streams_from_input(&commands, false, &options).unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0]._type, "FailedInput");
+        assert_eq!(streams[0].source, "ls -la");
+        assert!(streams[0]
+            .stderr
+            .recv_timeout(Duration::from_secs(1))
+            .unwrap()
+            .contains("zzz missing dir"));
+        assert_eq!(streams[1]._type, "FileInput");
+    }
+
+    #[test]
+    fn failed_command_is_not_saved() {
+        let commands = vec![String::from("zzz_logria_unsaved_session")];
+        let options = StreamOptions {
+            working_dir: Some(String::from("zzz missing dir")),
+            ..StreamOptions::default()
+        };
+        let streams = build_streams_from_input(&commands, true, &options).unwrap();
+        assert_eq!(streams[0]._type, "FailedInput");
+        assert!(Session::find("zzz_logria_unsaved_session").is_err());
+    }
+
+    #[test]
+    fn failed_file_does_not_abort_session() {
+        let session = Session::new(
+            &[
+                String::from("zzz_logria_missing_file.log"),
+                String::from("README.md"),
+            ],
+            SessionType::File,
+        );
+        let streams = build_streams_from_session(session, &StreamOptions::default()).unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0]._type, "FailedInput");
+        assert_eq!(streams[0].source, "zzz_logria_missing_file.log");
+        assert_eq!(streams[1]._type, "FileInput");
+    }
+}
+
+#[cfg(test)]
+mod file_tests {
+    use std::{
+        env::temp_dir,
+        fs::{remove_file, write},
+    };
+
+    use crate::communication::input::{FileInput, StreamOptions};
+
+    fn read_file(name: &str, content: &[u8]) -> (Vec<String>, Vec<String>) {
+        read_file_with_options(name, content, &StreamOptions::default())
+    }
+
+    fn read_file_with_options(
+        name: &str,
+        content: &[u8],
+        options: &StreamOptions,
+    ) -> (Vec<String>, Vec<String>) {
+        let path = temp_dir().join(name);
+        write(&path, content).unwrap();
+        let command = path.to_string_lossy().to_string();
+        let stream = FileInput::build_with_options(name.to_owned(), command, options).unwrap();
+        stream.process.unwrap().join().unwrap();
+        remove_file(&path).unwrap();
+        (
+            stream.stdout.try_iter().collect(),
+            stream.stderr.try_iter().collect(),
+        )
+    }
+
+    #[test]
+    fn can_read_text_file() {
+        let (stdout, stderr) = read_file("logria_file_text", b"first\nsecond\n");
+        assert_eq!(stdout, vec!["first", "second"]);
+        assert!(stderr.is_empty());
+    }
+
+    #[test]
+    fn can_read_binary_file() {
+        let (stdout, stderr) = read_file("logria_file_binary", b"\x7fELF\x00\x01\n\x1b\x02");
+        assert_eq!(stdout, vec!["\\u{7f}ELF\\u{0}\\u{1}", "\\u{1b}\\u{2}"]);
+        assert_eq!(stderr.len(), 1);
+        assert!(stderr[0]
+            .ends_with("looks like a binary file, showing it with control characters escaped"));
+    }
+
+    #[test]
+    fn reports_binary_file_is_not_followed() {
+        let options = StreamOptions {
+            follow: true,
+            ..StreamOptions::default()
+        };
+        let (stdout, stderr) =
+            read_file_with_options("logria_file_binary_follow", b"\x7fELF\x00\x01\n", &options);
+        assert_eq!(stdout, vec!["\\u{7f}ELF\\u{0}\\u{1}"]);
+        assert_eq!(stderr.len(), 2);
+        assert!(stderr[1]
+            .ends_with("cannot be followed as a binary file, showing its current contents"));
+    }
+}
+
+#[cfg(test)]
+mod working_dir_tests {
+    use std::{collections::BTreeMap, env::current_dir, ffi::OsStr, path::Path};
+
+    use crate::communication::input::{CommandInput, StreamOptions};
+
+    #[test]
+    fn defaults_to_current_dir() {
+        assert_eq!(
+            CommandInput::resolve_working_dir(None).unwrap(),
+            current_dir().unwrap()
+        );
+    }
+
+    #[test]
+    fn can_resolve_working_dir() {
+        assert_eq!(
+            CommandInput::resolve_working_dir(Some("src")).unwrap(),
+            Path::new("src")
+        );
+    }
+
+    #[test]
+    fn cannot_resolve_missing_working_dir() {
+        assert!(CommandInput::resolve_working_dir(Some("zzz missing dir")).is_err());
+        assert!(CommandInput::resolve_working_dir(Some("README.md")).is_err());
+    }
+
+    #[test]
+    fn command_starts_in_working_dir() {
+        let command = CommandInput::build_command(
+            &[String::from("ls"), String::from("-la")],
+            Path::new("src"),
+            &BTreeMap::new(),
+        );
+        assert_eq!(command.as_std().get_current_dir(), Some(Path::new("src")));
+        assert_eq!(command.as_std().get_program(), "ls");
+    }
+
+    #[test]
+    fn command_runs_with_env() {
+        let mut env = BTreeMap::new();
+        env.insert(String::from("TZ"), String::from("UTC"));
+        let command = CommandInput::build_command(&[String::from("date")], Path::new("."), &env);
+        let envs: Vec<_> = command.as_std().get_envs().collect();
+        assert_eq!(envs, vec![(OsStr::new("TZ"), Some(OsStr::new("UTC")))]);
+    }
+
+    #[test]
+    fn command_without_env_inherits_environment() {
+        let command =
+            CommandInput::build_command(&[String::from("date")], Path::new("."), &BTreeMap::new());
+        assert_eq!(command.as_std().get_envs().count(), 0);
+    }
+
+    #[test]
+    fn cannot_build_command_in_missing_dir() {
+        let options = StreamOptions {
+            working_dir: Some(String::from("zzz missing dir")),
+            ..StreamOptions::new(5)
+        };
+        let stream =
+            CommandInput::build_with_options(String::from("ls"), String::from("ls"), &options);
+        assert!(stream.is_err());
+    }
+}
+
+#[cfg(test)]
+mod follow_tests {
+    use std::{
+        env::temp_dir,
+        fs::{remove_file, rename, write, OpenOptions},
+        io::Write,
+        sync::mpsc::Receiver,
+        time::Duration,
+    };
+
+    use crate::{
+        communication::input::{
+            DeletedFilePolicy, FileInput, FollowAction, FollowedPath, Input, InputStream,
+            StreamOptions,
+        },
+        constants::cli::poll_rate::DELETED_CHECKS,
+    };
+
+    /// Start following the file at `path`
+    fn follow(path: &str, deleted_file: DeletedFilePolicy) -> InputStream {
+        let options = StreamOptions {
+            follow: true,
+            deleted_file,
+            ..StreamOptions::default()
+        };
+        FileInput::build_with_options(String::from("log"), path.to_owned(), &options).unwrap()
+    }
+
+    /// Wait for the next line a stream sends
+    fn next_line(receiver: &Receiver<String>) -> String {
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    fn append(path: &str, content: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn can_follow_appended_lines() {
+        let path = temp_dir().join("logria_follow_append");
+        let path = path.to_str().unwrap();
+        write(path, "first\n").unwrap();
+        let stream = follow(path, DeletedFilePolicy::Reopen);
+        assert_eq!(next_line(&stream.stdout), "first");
+
+        append(path, "second\nthird\n");
+        assert_eq!(next_line(&stream.stdout), "second");
+        assert_eq!(next_line(&stream.stdout), "third");
+
+        *stream.should_die.lock().unwrap() = true;
+        stream.process.unwrap().join().unwrap();
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn waits_for_the_end_of_a_line() {
+        let path = temp_dir().join("logria_follow_partial");
+        let path = path.to_str().unwrap();
+        write(path, "").unwrap();
+        let stream = follow(path, DeletedFilePolicy::Reopen);
+
+        append(path, "half");
+        assert!(stream
+            .stdout
+            .recv_timeout(Duration::from_millis(300))
+            .is_err());
+        append(path, " line\n");
+        assert_eq!(next_line(&stream.stdout), "half line");
+
+        *stream.should_die.lock().unwrap() = true;
+        stream.process.unwrap().join().unwrap();
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn can_follow_truncated_file() {
+        let path = temp_dir().join("logria_follow_truncate");
+        let path = path.to_str().unwrap();
+        write(path, "a long first line\n").unwrap();
+        let stream = follow(path, DeletedFilePolicy::Reopen);
+        assert_eq!(next_line(&stream.stdout), "a long first line");
+
+        write(path, "new\n").unwrap();
+        assert_eq!(next_line(&stream.stdout), "new");
+
+        *stream.should_die.lock().unwrap() = true;
+        stream.process.unwrap().join().unwrap();
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn can_follow_rotated_file() {
+        let path = temp_dir().join("logria_follow_rotate");
+        let path = path.to_str().unwrap();
+        let rotated = format!("{}.1", path);
+        write(path, "before rotation\n").unwrap();
+        let stream = follow(path, DeletedFilePolicy::Reopen);
+        assert_eq!(next_line(&stream.stdout), "before rotation");
+
+        // The new file is longer than what was read, so only its identity shows it is new
+        rename(path, &rotated).unwrap();
+        write(path, "after rotation, a longer line\n").unwrap();
+        assert_eq!(next_line(&stream.stdout), "after rotation, a longer line");
+
+        *stream.should_die.lock().unwrap() = true;
+        stream.process.unwrap().join().unwrap();
+        remove_file(path).unwrap();
+        remove_file(rotated).unwrap();
+    }
+
+    #[test]
+    fn can_follow_recreated_file() {
+        let path = temp_dir().join("logria_follow_recreate");
+        let path = path.to_str().unwrap();
+        write(path, "first\n").unwrap();
+        let stream = follow(path, DeletedFilePolicy::Reopen);
+        assert_eq!(next_line(&stream.stdout), "first");
+
+        remove_file(path).unwrap();
+        assert!(stream
+            .stdout
+            .recv_timeout(Duration::from_millis(300))
+            .is_err());
+        write(path, "recreated\n").unwrap();
+        assert_eq!(next_line(&stream.stdout), "recreated");
+
+        *stream.should_die.lock().unwrap() = true;
+        stream.process.unwrap().join().unwrap();
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stops_following_deleted_file() {
+        let path = temp_dir().join("logria_follow_delete_stop");
+        let path = path.to_str().unwrap();
+        write(path, "first\n").unwrap();
+        let stream = follow(path, DeletedFilePolicy::Stop);
+        assert_eq!(next_line(&stream.stdout), "first");
+
+        remove_file(path).unwrap();
+        assert_eq!(next_line(&stream.stderr), format!("{} was deleted", path));
+        stream.process.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn reopens_truncated_or_replaced_path() {
+        for deleted_file in [DeletedFilePolicy::Reopen, DeletedFilePolicy::Stop] {
+            assert_eq!(
+                FollowedPath::Same(10).action(10, 0, deleted_file),
+                FollowAction::Wait
+            );
+            assert_eq!(
+                FollowedPath::Same(4).action(10, 0, deleted_file),
+                FollowAction::Reopen
+            );
+            assert_eq!(
+                FollowedPath::Replaced.action(10, 0, deleted_file),
+                FollowAction::Reopen
+            );
+        }
+    }
+
+    #[test]
+    fn waits_for_deleted_path_to_return() {
+        let reopen = DeletedFilePolicy::Reopen;
+        assert_eq!(
+            FollowedPath::Missing.action(10, 1, reopen),
+            FollowAction::Wait
+        );
+        assert_eq!(
+            FollowedPath::Missing.action(10, DELETED_CHECKS * 10, reopen),
+            FollowAction::Wait
+        );
+        // The file created at the path is a different file
+        assert_eq!(
+            FollowedPath::Replaced.action(10, 0, reopen),
+            FollowAction::Reopen
+        );
+    }
+
+    #[test]
+    fn stops_once_path_stays_deleted() {
+        let stop = DeletedFilePolicy::Stop;
+        assert_eq!(
+            FollowedPath::Missing.action(10, 1, stop),
+            FollowAction::Wait
+        );
+        assert_eq!(
+            FollowedPath::Missing.action(10, DELETED_CHECKS - 1, stop),
+            FollowAction::Wait
+        );
+        assert_eq!(
+            FollowedPath::Missing.action(10, DELETED_CHECKS, stop),
+            FollowAction::Stop
+        );
+    }
+
+    #[test]
+    fn checks_missing_path() {
+        assert_eq!(
+            FollowedPath::check("zzz_logria_missing_followed_file", None),
+            FollowedPath::Missing
+        );
+    }
+
+    #[test]
+    fn stops_at_end_without_follow() {
+        let path = temp_dir().join("logria_follow_off");
+        let path = path.to_str().unwrap();
+        write(path, "first\n").unwrap();
+        let stream = FileInput::build(String::from("log"), path.to_owned()).unwrap();
+        stream.process.unwrap().join().unwrap();
+        append(path, "second\n");
+
+        assert_eq!(stream.stdout.try_iter().collect::<Vec<_>>(), vec!["first"]);
+        remove_file(path).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod stdin_tests {
+    use std::io::Cursor;
+
+    use crate::communication::input::StdinInput;
+
+    #[test]
+    fn can_build_stdin_stream() {
+        let stream =
+            StdinInput::build_from_reader(String::from("stdin"), Cursor::new(b"".to_vec()))
+                .unwrap();
+        assert_eq!(stream.process_name, "stdin");
+        assert_eq!(stream._type, "StdinInput");
+    }
+
+    #[test]
+    fn can_read_piped_lines() {
+        let stream = StdinInput::build_from_reader(
+            String::from("stdin"),
+            Cursor::new(b"first\r\nsecond\n\xffthird".to_vec()),
+        )
+        .unwrap();
+        stream.process.unwrap().join().unwrap();
+        assert_eq!(
+            stream.stdout.try_iter().collect::<Vec<String>>(),
+            vec!["first", "second", "\u{fffd}third"]
+        );
+        assert!(stream.stderr.try_iter().next().is_none());
+    }
+}
+
+#[cfg(all(test, unix))]
+mod unix_socket_tests {
+    use std::{
+        env::temp_dir, fs::remove_file, io::Write, os::unix::net::UnixListener, path::PathBuf,
+        thread,
+    };
+
+    use crate::{
+        communication::input::{determine_stream_type, Input, UnixSocketInput},
+        extensions::session::SessionType,
+    };
+
+    fn socket_path(name: &str) -> PathBuf {
+        let path = temp_dir().join(name);
+        let _ = remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn can_detect_socket() {
+        let path = socket_path("logria_socket_detect");
+        let _listener = UnixListener::bind(&path).unwrap();
+        assert_eq!(
+            determine_stream_type(&path.to_string_lossy()),
+            SessionType::UnixSocket
+        );
+        remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn can_detect_removed_socket() {
+        let path = socket_path("logria_socket_removed");
+        drop(UnixListener::bind(&path).unwrap());
+        remove_file(&path).unwrap();
+        assert_eq!(
+            determine_stream_type(&path.to_string_lossy()),
+            SessionType::Command
+        );
+    }
+
+    #[test]
+    fn can_read_socket() {
+        let path = socket_path("logria_socket_read");
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = thread::spawn(move || {
+            let (mut client, _) = listener.accept().unwrap();
+            client.write_all(b"first\nsecond\r\nlast").unwrap();
+        });
+
+        let command = path.to_string_lossy().to_string();
+        let stream = UnixSocketInput::build(command.to_owned(), command.to_owned()).unwrap();
+        server.join().unwrap();
+        stream.process.unwrap().join().unwrap();
+        remove_file(&path).unwrap();
+
+        assert_eq!(
+            stream.stdout.try_iter().collect::<Vec<String>>(),
+            vec!["first", "second", "last"]
+        );
+        assert_eq!(
+            stream.stderr.try_iter().collect::<Vec<String>>(),
+            vec![format!("Disconnected from {}: connection closed", command)]
+        );
+    }
+
+    #[test]
+    fn cannot_connect_missing_socket() {
+        let path = socket_path("logria_socket_missing");
+        let command = path.to_string_lossy().to_string();
+        assert!(UnixSocketInput::build(command.to_owned(), command).is_err());
+    }
+}
+
+#[cfg(test)]
+mod command_output_tests {
+    use std::{
+        env::temp_dir,
+        fs::{remove_file, write},
+        io::Write,
+        process::Stdio,
+    };
+
+    use flate2::{write::GzEncoder, Compression};
+    use tokio::{process::Command, runtime::Runtime};
+
+    use crate::communication::input::CommandOutput;
+
+    fn read_output(name: &str, content: &[u8], gunzip: bool) -> Vec<String> {
+        let path = temp_dir().join(name);
+        write(&path, content).unwrap();
+        let runtime = Runtime::new().unwrap();
+        let lines = runtime.block_on(async {
+            let mut child = Command::new("cat")
+                .arg(&path)
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            let mut output = CommandOutput::new(child.stdout.take().unwrap(), gunzip);
+            let mut lines = vec![];
+            while let Some(line) = output.next_line().await.unwrap() {
+                lines.push(line);
+            }
+            child.wait().await.unwrap();
+            lines
+        });
+        remove_file(&path).unwrap();
+        lines
+    }
+
+    #[test]
+    fn can_read_plain_output() {
+        let lines = read_output("logria_command_plain", b"first\nsecond\n", false);
+        assert_eq!(lines, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn can_read_gzip_output() {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(b"first\nsecond\n").unwrap();
+        let content = encoder.finish().unwrap();
+        let lines = read_output("logria_command_gzip", &content, true);
+        assert_eq!(lines, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn cannot_read_invalid_gzip_output() {
+        let lines = read_output("logria_command_invalid_gzip", b"first\n", true);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("Unable to decompress output"));
+    }
+}
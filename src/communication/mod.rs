@@ -1,3 +1,4 @@
 pub mod input;
+pub mod pipe;
 pub mod reader;
 pub mod handlers;
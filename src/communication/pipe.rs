@@ -0,0 +1,193 @@
+use std::{
+    io::{Read, Result as IoResult, Write},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    communication::input::{CommandInput, StreamOptions},
+    util::error::LogriaError,
+};
+
+/// Write each message to `writer` on its own line
+pub fn write_messages(writer: &mut impl Write, messages: &[String]) -> IoResult<()> {
+    for message in messages {
+        writer.write_all(message.as_bytes())?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Read everything from `reader` on another thread, so a full pipe cannot stall the process writing to it
+fn read_all(mut reader: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = vec![];
+        let _ = reader.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+/// Run `command` with `messages` as its stdin and collect the lines it writes to stdout
+///
+/// The command starts in the working directory and with the variables from `options`, like commands
+/// opened as streams. Commands that do not exit within `timeout`, i.e. `tail -f`, are stopped.
+pub fn pipe_messages(
+    command: &str,
+    messages: &[String],
+    options: &StreamOptions,
+    timeout: Duration,
+) -> Result<Vec<String>, LogriaError> {
+    let command = command.trim();
+    let error = |why: String| LogriaError::CannotPipe(command.to_owned(), why);
+    let command_to_run = CommandInput::parse_command(command);
+    if command_to_run.is_empty() {
+        return Err(error(String::from("no command provided")));
+    }
+    let working_dir = CommandInput::resolve_working_dir(options.working_dir.as_deref())
+        .map_err(|why| error(why.to_string()))?;
+    let mut child = Command::new(&command_to_run[0])
+        .args(&command_to_run[1..])
+        .current_dir(working_dir)
+        .envs(&options.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|why| error(why.to_string()))?;
+
+    // Feed stdin from another thread so a command that writes as it reads cannot fill its stdout and stall
+    let mut stdin = child.stdin.take().unwrap();
+    let input = messages.to_vec();
+    let writer = thread::spawn(move || write_messages(&mut stdin, &input));
+    let stdout = read_all(child.stdout.take().unwrap());
+    let stderr = read_all(child.stderr.take().unwrap());
+
+    // Wait for the command to exit, stopping it once it runs too long
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(|why| error(why.to_string()))? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(error(format!(
+                    "stopped after {} seconds without exiting",
+                    timeout.as_secs_f32()
+                )));
+            }
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    // Commands like `head` stop reading early, so a broken pipe is not an error
+    let _ = writer.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let reason = stderr
+            .lines()
+            .next()
+            .map_or_else(|| status.to_string(), str::to_owned);
+        return Err(error(reason));
+    }
+    Ok(String::from_utf8_lossy(&stdout)
+        .lines()
+        .map(str::to_owned)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, env::temp_dir, time::Duration};
+
+    use super::{pipe_messages, write_messages};
+    use crate::{communication::input::StreamOptions, util::error::LogriaError};
+
+    fn messages(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn pipe(command: &str, messages: &[String]) -> Result<Vec<String>, LogriaError> {
+        pipe_messages(
+            command,
+            messages,
+            &StreamOptions::default(),
+            Duration::from_secs(5),
+        )
+    }
+
+    #[test]
+    fn can_write_messages() {
+        let mut stdin = vec![];
+        write_messages(&mut stdin, &messages(&["b", "a", "c"])).unwrap();
+        assert_eq!(stdin, b"b\na\nc\n");
+    }
+
+    #[test]
+    fn can_write_no_messages() {
+        let mut stdin = vec![];
+        write_messages(&mut stdin, &[]).unwrap();
+        assert!(stdin.is_empty());
+    }
+
+    #[test]
+    fn can_pipe_through_sort() {
+        let result = pipe("sort", &messages(&["b", "a", "c", "a"])).unwrap();
+        assert_eq!(result, vec!["a", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn can_pipe_with_args() {
+        let result = pipe("head -n 2", &messages(&["1", "2", "3"])).unwrap();
+        assert_eq!(result, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn can_pipe_many_messages() {
+        let input: Vec<String> = (0..100_000).map(|x| x.to_string()).collect();
+        let result = pipe("cat", &input).unwrap();
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn cannot_pipe_missing_command() {
+        assert!(pipe("logria_missing_command", &messages(&["a"])).is_err());
+    }
+
+    #[test]
+    fn cannot_pipe_failed_command() {
+        let why = pipe("ls logria_missing_file", &[]).unwrap_err();
+        assert!(why
+            .to_string()
+            .starts_with("Couldn't pipe through \"ls logria_missing_file\""));
+    }
+
+    #[test]
+    fn stops_command_that_does_not_exit() {
+        let why = pipe_messages(
+            "sleep 10",
+            &[],
+            &StreamOptions::default(),
+            Duration::from_millis(100),
+        )
+        .unwrap_err();
+        assert!(why.to_string().contains("without exiting"));
+    }
+
+    #[test]
+    fn can_pipe_in_working_dir_with_env() {
+        let dir = temp_dir().canonicalize().unwrap();
+        let options = StreamOptions {
+            working_dir: Some(dir.to_string_lossy().to_string()),
+            env: BTreeMap::from([(String::from("LOGRIA_PIPE"), String::from("set"))]),
+            ..StreamOptions::default()
+        };
+        let timeout = Duration::from_secs(5);
+        let result = pipe_messages("pwd", &[], &options, timeout).unwrap();
+        assert_eq!(result, vec![dir.to_string_lossy()]);
+        let result = pipe_messages("printenv LOGRIA_PIPE", &[], &options, timeout).unwrap();
+        assert_eq!(result, vec!["set"]);
+    }
+}
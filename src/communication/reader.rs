@@ -1,6 +1,9 @@
 use std::{
-    cmp::max,
-    io::{stdout, Write},
+    borrow::Cow,
+    cmp::{max, min},
+    collections::BTreeSet,
+    io::{stdin, stdout, Stdout, Write},
+    mem::{replace, size_of},
     panic,
     time::{Duration, Instant},
 };
@@ -10,8 +13,10 @@ use crossterm::{
     event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers},
     execute, queue, style,
     terminal::{disable_raw_mode, size, Clear, ClearType},
+    tty::IsTty,
     Result,
 };
+use format_num::format_num;
 use regex::bytes::Regex;
 
 use crate::{
@@ -21,25 +26,57 @@ use crate::{
             handler::Handler,
             multiple_choice::MultipleChoiceHandler,
             normal::NormalHandler,
-            parser::{ParserHandler, ParserState},
+            parser::{format_indices, ParserHandler, ParserState},
             processor::ProcessorMethods,
             regex::RegexHandler,
             startup::StartupHandler,
         },
-        input::{build_streams_from_input, InputStream, InputType, StreamType},
+        input::{
+            build_streams_from_input, build_streams_from_session, FileInput, Input, InputStream,
+            InputType, StdinInput, StreamOptions, StreamType,
+        },
     },
     constants::cli::{
         cli_chars, colors,
-        messages::{NO_MESSAGE_IN_BUFFER_NORMAL, NO_MESSAGE_IN_BUFFER_PARSER, PIPE_INPUT_ERROR},
-        poll_rate::DEFAULT,
+        messages::{PIPE_INPUT_ERROR, TERMINAL_TOO_SMALL_ERROR},
+        poll_rate::{DEFAULT, SAMPLES},
+    },
+    constants::directories::patterns,
+    extensions::{
+        extension::ExtensionMethods,
+        filter::Filter,
+        parser::Parser,
+        session::{Session, SessionParser, SessionType, Workspace},
+        text::TextOverrides,
     },
     ui::{
-        interface::{build, valid_tty},
-        scroll::ScrollState,
+        changes::highlight_changes,
+        diff::RunDiff,
+        interface::{build, valid_size, valid_tty},
+        overlay::StatusOverlay,
+        position::position_text,
+        scroll::{PageSize, ScrollState},
+        selection,
+        snapshot::Snapshot,
+        source::{source_label, source_width},
+        split::{render_range, split_panes, Pane},
+        tail::{tail_range, TAIL_ROWS},
+        wrap::{hard_wrap, truncate, truncate_with_ellipsis, word_wrap, WrapMode},
     },
     util::{
+        aggregators::date::DateParserType,
+        error::LogriaError,
+        inactivity::InactivityTimer,
+        metrics::{unix_timestamp, MetricsSnapshot, MetricsWriter},
         poll::{ms_per_message, RollingMean},
-        sanitizers::length::LengthFinder,
+        sanitizers::{
+            ansi::{CleanText, ControlSequences},
+            control::escape_control_chars,
+            elide::elide,
+            length::LengthFinder,
+        },
+        timestamp::TimestampSearch,
+        trace::Tracer,
         types::Del,
     },
 };
@@ -53,12 +90,30 @@ pub struct LogriaConfig {
     pub last_row: u16,
     /// Current last row we have rendered
     pub current_end: usize,
+    /// Number of messages in the buffer when the user scrolled away from the bottom
+    pub seen_up_to: Option<usize>,
+    /// Index where the keyboard selection started
+    pub selection_start: Option<usize>,
+    /// Index of the message the keyboard selection was moved to
+    pub selection_end: Option<usize>,
+    /// Length of the buffer when `:since` was run; the regex only matches messages received after it
+    pub watermark: Option<usize>,
 
     // Message buffers
     /// Messages read from standard error
     stderr_messages: Vec<String>,
     /// Messages read from standard output
     stdout_messages: Vec<String>,
+    /// Index of the stream each message in the stderr buffer came from
+    stderr_sources: Vec<usize>,
+    /// Index of the stream each message in the stdout buffer came from
+    stdout_sources: Vec<usize>,
+    /// Indices of the streams whose messages are hidden with `:mute`
+    pub muted_streams: BTreeSet<usize>,
+    /// List of index of messages from streams that are not muted when any stream is muted
+    pub unmuted_rows: Vec<usize>,
+    /// The last index the mute filter saw
+    pub last_index_unmuted: usize,
     /// The stream type Logria is currently displaying
     pub stream_type: StreamType,
     /// The previous stream the user was looking at
@@ -69,6 +124,8 @@ pub struct LogriaConfig {
     // Regex settings
     /// Current regex pattern
     pub regex_pattern: Option<Regex>,
+    /// The typed pattern and toggles `regex_pattern` was compiled from, so `:filter save` can keep them apart
+    pub regex_filter: Option<Filter>,
     /// List of index of matches when regex filtering is active
     pub matched_rows: Vec<usize>,
     /// The last index the filtering function saw
@@ -77,34 +134,98 @@ pub struct LogriaConfig {
     color_replace_regex: Regex,
     /// Determines whether we highlight the matched text to the user
     pub highlight_match: bool,
+    /// Render messages exactly as received instead of trimming them
+    pub raw_messages: bool,
+    /// Remove ANSI color codes from messages before rendering
+    pub strip_colors: bool,
+    /// Pin the first message of the buffer above the scroll region
+    pub sticky_header: bool,
+    /// Render stderr and stdout in separate panes
+    pub split_view: bool,
+    /// Show the newest messages below the scroll region
+    pub tail_view: bool,
+    /// Show the position of the bottom of the view on the command line's border
+    pub show_position: bool,
+    /// Show the index of each message in a gutter to the left of the output window
+    pub show_line_numbers: bool,
+    /// Label each message with the name of the stream it came from
+    pub show_source: bool,
+    /// Color the part of each message that changed since the message above it
+    pub highlight_changes: bool,
+    /// Render a divider between messages that arrived before and after the user scrolled away
+    pub new_message_divider: bool,
+    /// How to render messages wider than the window
+    pub wrap_mode: WrapMode,
+    /// Messages frozen with `:snapshot`
+    pub snapshot: Option<Snapshot>,
+    /// Number of messages shown while the display is frozen with `c`; newer messages are still received
+    pub frozen_at: Option<usize>,
+    /// Messages kept with `:diff mark` to compare the live buffer against
+    pub run_diff: Option<RunDiff>,
+    /// The full status text, shown over the output window until a key is pressed
+    pub status_overlay: Option<StatusOverlay>,
 
     // Parser settings
-    /// Index for the parser to look at
-    pub parser_index: usize,
+    /// Indices of the parsed parts to render, in the order they were chosen
+    pub parser_indices: Vec<usize>,
     /// The state of the current parser
     pub parser_state: ParserState,
     /// Whether we are aggregating log data or not
     pub aggregation_enabled: bool,
     /// The last index the parsing function saw
     pub last_index_processed: usize,
+    /// Parser loaded by a session, along with its name, waiting for the parser handler to use it
+    pub attached_parser: Option<(String, Parser)>,
+    /// Formats of the active parser's date fields, used to find timestamps with `:at`
+    pub timestamp_formats: Vec<(String, DateParserType)>,
     /// The number of items to get when aggregating a Counter
     pub num_to_aggregate: usize,
+    /// Whether the aggregation panel should be rebuilt from the current aggregators, i.e. after `:agg`
+    pub rerender_aggregation: bool,
 
     // App state
     /// How long a loop of the main app takes
     loop_time: Instant,
     /// The rate at which we check for new messages
     pub poll_rate: u64,
+    /// Settings for the streams the app starts
+    pub stream_options: StreamOptions,
+    /// Whether messages piped to stdin are read as a stream
+    pub pipe: bool,
+    /// Messages longer than this many characters are cut before they are stored
+    pub max_line_length: Option<usize>,
+    /// Oldest messages are dropped once a stream's buffer holds more than this many messages
+    pub max_buffer_lines: Option<usize>,
+    /// Saved session opened in place of the startup screen, if any
+    pub startup_session: Option<Session>,
+    /// Session the current streams were opened from, saved with `:workspace`
+    pub session: Option<Session>,
     /// A deque based moving average tracker
     pub message_speed_tracker: RollingMean,
     /// Whether we reduce the poll rate to the message receive speed
     pub smart_poll_rate: bool,
     /// Whether the app records user input to a history tape
     pub use_history: bool,
+    /// Writes periodic metrics to a file, if enabled
+    pub metrics: Option<MetricsWriter>,
+    /// Records Logria's own actions to a file, if enabled
+    pub tracer: Option<Tracer>,
+    /// Exits the app after a period without messages or input, if enabled
+    pub inactivity: Option<InactivityTimer>,
+    /// Resume following new messages after the user stops scrolling for a while
+    pub resume_follow: Option<InactivityTimer>,
+    /// Replacements for built in UI text
+    pub text: TextOverrides,
 
     // Render data
     /// The current scroll mode
     pub scroll_state: ScrollState,
+    /// How far `pg_up` and `pg_down` scroll
+    pub page_size: PageSize,
+    /// Rows between the top of the window and the match `n` and `N` move to, or `None` to show it at the bottom
+    pub match_context: Option<usize>,
+    /// Whether `n` and `N` wrap around past the last and first match instead of stopping there
+    pub wrap_matches: bool,
     /// Can be a vector of FileInputs, CommandInputs, etc
     pub streams: Vec<InputStream>,
     /// Tuple of previous render boundaries, i.e. the (start, end) range of buffer that is rendered
@@ -115,6 +236,8 @@ pub struct LogriaConfig {
     pub did_switch: bool,
     /// Pointer to function used to delete items for the `: r` command
     pub delete_func: Del,
+    /// Items marked in a list, removed by `: r` when it is given no items
+    pub marked_items: Vec<usize>,
     /// Current status of the app  if there is one, i.e. if regex or parsers are active
     pub current_status: Option<String>,
     /// Function that can generate messages for display
@@ -128,12 +251,13 @@ pub struct MainWindow {
     // pub output: Stdout,
     pub mc_handler: MultipleChoiceHandler,
     length_finder: LengthFinder,
+    control_sequences: ControlSequences,
 }
 
 impl MainWindow {
     /// Construct sample window for testing simple actions
     pub fn _new_dummy() -> MainWindow {
-        let mut app = MainWindow::new(true, true);
+        let mut app = MainWindow::new(true, true, SAMPLES);
 
         // Set fake dimensions
         app.config.height = 10;
@@ -152,7 +276,7 @@ impl MainWindow {
 
     /// Construct sample window for testing parsers
     pub fn _new_dummy_parse() -> MainWindow {
-        let mut app = MainWindow::new(true, true);
+        let mut app = MainWindow::new(true, true, SAMPLES);
 
         // Set fake dimensions
         app.config.height = 10;
@@ -173,7 +297,7 @@ impl MainWindow {
 
     /// Construct sample window for testing date parsers
     pub fn _new_dummy_parse_date() -> MainWindow {
-        let mut app = MainWindow::new(true, true);
+        let mut app = MainWindow::new(true, true, SAMPLES);
 
         // Set fake dimensions
         app.config.height = 10;
@@ -195,64 +319,253 @@ impl MainWindow {
         app
     }
 
-    pub fn new(history: bool, smart_poll_rate: bool) -> MainWindow {
+    /// Construct sample window for testing JSON parsers
+    pub fn _new_dummy_parse_json() -> MainWindow {
+        let mut app = MainWindow::new(true, true, SAMPLES);
+
+        // Set fake dimensions
+        app.config.height = 10;
+        app.config.width = 100;
+        app.config.stream_type = StreamType::StdErr;
+        app.config.previous_stream_type = StreamType::StdOut;
+
+        // Set fake previous render
+        app.config.last_row = app.config.height - 3; // simulate the last row we can render to
+
+        // Set fake messages, including one that is not JSON and one missing a field
+        app.config.stderr_messages = vec![
+            r#"{"level": "info", "response": {"status": 200}}"#.to_string(),
+            "not json".to_string(),
+            r#"{"level": "error", "response": {"status": 500}}"#.to_string(),
+            r#"{"level": "info"}"#.to_string(),
+        ];
+
+        app
+    }
+
+    pub fn new(history: bool, smart_poll_rate: bool, poll_samples: usize) -> MainWindow {
         // Build streams here
         MainWindow {
             input_type: InputType::Startup,
             previous_input_type: InputType::Startup,
             length_finder: LengthFinder::new(),
+            control_sequences: ControlSequences::new(),
             mc_handler: MultipleChoiceHandler::new(),
             config: LogriaConfig {
                 poll_rate: DEFAULT,
                 smart_poll_rate,
                 use_history: history,
+                metrics: None,
+                tracer: None,
+                inactivity: None,
+                resume_follow: None,
+                text: TextOverrides::default(),
                 height: 0,
                 width: 0,
                 loop_time: Instant::now(),
                 previous_render: (0, 0),
                 stderr_messages: vec![],
                 stdout_messages: vec![],
+                stderr_sources: vec![],
+                stdout_sources: vec![],
+                muted_streams: BTreeSet::new(),
+                unmuted_rows: vec![],
+                last_index_unmuted: 0,
                 auxiliary_messages: vec![],
                 stream_type: StreamType::Auxiliary,
                 previous_stream_type: StreamType::Auxiliary,
                 regex_pattern: None,
+                regex_filter: None,
                 matched_rows: vec![],
                 last_index_regexed: 0,
                 color_replace_regex: Regex::new(
                     crate::constants::cli::patterns::ANSI_COLOR_PATTERN,
                 )
                 .unwrap(),
-                parser_index: 0,
+                parser_indices: vec![],
+                rerender_aggregation: false,
                 parser_state: ParserState::Disabled,
                 aggregation_enabled: false,
                 num_to_aggregate: 5,
                 last_index_processed: 0,
+                attached_parser: None,
+                timestamp_formats: vec![],
                 highlight_match: false,
+                raw_messages: false,
+                strip_colors: false,
+                sticky_header: false,
+                split_view: false,
+                tail_view: false,
+                show_position: false,
+                show_line_numbers: false,
+                show_source: false,
+                highlight_changes: false,
+                frozen_at: None,
+                new_message_divider: false,
+                wrap_mode: WrapMode::Hard,
+                snapshot: None,
+                run_diff: None,
+                status_overlay: None,
                 last_row: 0,
                 scroll_state: ScrollState::Bottom,
+                page_size: PageSize::default(),
+                match_context: None,
+                wrap_matches: false,
                 current_end: 0,
+                seen_up_to: None,
+                watermark: None,
+                selection_start: None,
+                selection_end: None,
                 streams: vec![],
                 did_switch: false,
                 was_empty: false,
                 delete_func: None,
+                marked_items: vec![],
                 generate_auxiliary_messages: None,
                 current_status: None,
-                message_speed_tracker: RollingMean::new(5),
+                stream_options: StreamOptions::new(poll_samples),
+                pipe: false,
+                max_line_length: None,
+                max_buffer_lines: None,
+                startup_session: None,
+                session: None,
+                message_speed_tracker: RollingMean::new(poll_samples),
             },
         }
     }
 
-    /// Get the number of messages in the current message buffer
+    /// Disable history, smart polling, and colors, overriding any other options
+    ///
+    /// Used to check whether odd behavior is caused by one of those features.
+    pub fn enable_safe_mode(&mut self) {
+        self.config.use_history = false;
+        self.config.smart_poll_rate = false;
+        self.config.strip_colors = true;
+        self.update_poll_rate(DEFAULT);
+    }
+
+    /// Copy the messages between `start` and `end` in the current buffer, respecting an active regex
+    pub fn messages_in_range(&self, start: usize, end: usize) -> Vec<String> {
+        (start..min(end, self.number_of_messages()))
+            .map(|index| self.get_message_at_index(index).to_owned())
+            .collect()
+    }
+
+    /// Get every message in the current view without color codes, i.e. to write to a file
+    ///
+    /// A parser's output is used while one is active, otherwise only regex matches if there is a regex
+    pub fn export_messages(&self) -> Vec<String> {
+        let messages: Vec<&String> = if self.config.parser_state == ParserState::Full {
+            self.config.auxiliary_messages.iter().collect()
+        } else if self.config.regex_pattern.is_some() {
+            self.config
+                .matched_rows
+                .iter()
+                .map(|&row| &self.messages()[row])
+                .collect()
+        } else if self.muting_active() {
+            self.config
+                .unmuted_rows
+                .iter()
+                .map(|&row| &self.messages()[row])
+                .collect()
+        } else {
+            self.messages().iter().collect()
+        };
+        messages
+            .into_iter()
+            .map(|message| {
+                let clean = CleanText::new(&self.config.color_replace_regex, message.as_bytes());
+                String::from_utf8_lossy(&clean.text).into_owned()
+            })
+            .collect()
+    }
+
+    /// Freeze the messages currently on screen so they can be studied without live updates
+    pub fn take_snapshot(&mut self) {
+        let (start, end) = self.determine_render_position();
+        self.config.snapshot = Some(Snapshot::new(self.messages_in_range(start, end)));
+    }
+
+    /// Keep the live buffer so messages from a later run can be compared against it
+    ///
+    /// Returns the number of messages kept.
+    pub fn mark_run(&mut self) -> usize {
+        let previous = self.messages().clone();
+        let kept = previous.len();
+        self.config.run_diff = Some(RunDiff::new(previous));
+        kept
+    }
+
+    /// Get the diff to mark rendered messages with, if the diff overlay is on for a live buffer
+    fn active_diff(&self) -> Option<&RunDiff> {
+        if matches!(self.config.stream_type, StreamType::Auxiliary) {
+            return None;
+        }
+        self.config.run_diff.as_ref().filter(|diff| diff.active)
+    }
+
+    /// Whether the snapshot is being shown in place of the live buffer
+    pub fn viewing_snapshot(&self) -> bool {
+        matches!(self.config.stream_type, StreamType::Auxiliary)
+            && self
+                .config
+                .snapshot
+                .as_ref()
+                .is_some_and(|snapshot| snapshot.active)
+    }
+
+    /// Switch between the snapshot and the live buffer
+    ///
+    /// Returns `false` if there is no snapshot or another auxiliary view is open.
+    pub fn toggle_snapshot(&mut self) -> bool {
+        let viewing = self.viewing_snapshot();
+        let snapshot = match self.config.snapshot.as_mut() {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+        if viewing {
+            snapshot.active = false;
+            snapshot.scroll_state = replace(&mut self.config.scroll_state, ScrollState::Bottom);
+            snapshot.current_end = self.config.current_end;
+            self.config.stream_type = self.config.previous_stream_type;
+            self.config.auxiliary_messages.clear();
+        } else {
+            if matches!(self.config.stream_type, StreamType::Auxiliary) {
+                return false;
+            }
+            snapshot.active = true;
+            self.config.previous_stream_type = self.config.stream_type;
+            self.config.stream_type = StreamType::Auxiliary;
+            self.config.auxiliary_messages = snapshot.messages.clone();
+            self.config.scroll_state = replace(&mut snapshot.scroll_state, ScrollState::Top);
+            self.config.current_end = snapshot.current_end;
+        }
+        true
+    }
+
+    /// Get the number of messages in the current message buffer, stopping where the display was frozen
     pub fn number_of_messages(&self) -> usize {
+        let received = self.number_of_received_messages();
+        match self.config.frozen_at {
+            Some(frozen_at) if !matches!(self.config.stream_type, StreamType::Auxiliary) => {
+                min(received, frozen_at)
+            }
+            _ => received,
+        }
+    }
+
+    /// Get the number of messages in the current message buffer, including any received while frozen
+    fn number_of_received_messages(&self) -> usize {
         // if there is a regex active, use that, otherwise handle normally
         if self.config.regex_pattern.is_some() {
             return self.config.matched_rows.len();
         }
         match self.input_type {
-            InputType::Normal | InputType::Command | InputType::Startup => self.messages().len(),
+            InputType::Normal | InputType::Command | InputType::Startup => self.visible_len(),
             InputType::Regex => {
                 if self.config.regex_pattern.is_none() {
-                    self.messages().len()
+                    self.visible_len()
                 } else {
                     self.config.matched_rows.len()
                 }
@@ -261,41 +574,136 @@ impl MainWindow {
                 if self.config.parser_state == ParserState::Full {
                     self.config.auxiliary_messages.len()
                 } else {
-                    self.messages().len()
+                    self.visible_len()
                 }
             }
         }
     }
 
+    /// Number of rows reserved for the sticky header, 1 if it is active and 0 otherwise
+    ///
+    /// The header is only pinned for unfiltered stdout/stderr buffers, since the
+    /// first message of a filtered or auxiliary buffer is not a header row.
+    pub fn header_offset(&self) -> usize {
+        if self.config.sticky_header
+            && self.config.regex_pattern.is_none()
+            && matches!(self.input_type, InputType::Normal | InputType::Command)
+            && !matches!(self.config.stream_type, StreamType::Auxiliary)
+            && !self.messages().is_empty()
+        {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Number of rows reserved for the tail strip, including its divider, or 0 if it is hidden
+    ///
+    /// The strip is hidden for auxiliary buffers and when the window is too short to fit it.
+    pub fn tail_offset(&self) -> usize {
+        let rows = TAIL_ROWS + 1;
+        if self.config.tail_view
+            && !self.split_active()
+            && matches!(
+                self.input_type,
+                InputType::Normal | InputType::Command | InputType::Regex
+            )
+            && !matches!(self.config.stream_type, StreamType::Auxiliary)
+            && (self.config.last_row as usize) > rows + self.header_offset()
+        {
+            rows
+        } else {
+            0
+        }
+    }
+
+    /// Whether stderr and stdout should be rendered in separate panes
+    ///
+    /// Split view only applies to unfiltered buffers, as regex and parser state
+    /// are tied to the buffer in the current stream.
+    pub fn split_active(&self) -> bool {
+        self.config.split_view
+            && self.config.regex_pattern.is_none()
+            && self.config.muted_streams.is_empty()
+            && matches!(self.input_type, InputType::Normal | InputType::Command)
+            && !matches!(self.config.stream_type, StreamType::Auxiliary)
+            && self.config.frozen_at.is_none()
+    }
+
+    /// Freeze the display at the messages received so far, or resume following new messages
+    ///
+    /// Returns the number of messages received while frozen when resuming, or `None` when freezing.
+    pub fn toggle_freeze(&mut self) -> Option<usize> {
+        match self.config.frozen_at.take() {
+            Some(frozen_at) => Some(self.number_of_received_messages().saturating_sub(frozen_at)),
+            None => {
+                self.config.frozen_at = Some(self.number_of_received_messages());
+                None
+            }
+        }
+    }
+
+    /// Record where new messages start when the user scrolls away from the bottom
+    ///
+    /// Once the user is back at the bottom, the marker is cleared when there are no new messages or the first new
+    /// message is no longer on screen.
+    pub fn update_seen_up_to(&mut self, start: usize, end: usize) {
+        if !self.config.new_message_divider {
+            self.config.seen_up_to = None;
+            return;
+        }
+        let num_messages = self.number_of_messages();
+        self.config.seen_up_to = match (&self.config.scroll_state, self.config.seen_up_to) {
+            (ScrollState::Bottom, Some(seen)) if seen <= start || seen >= end => None,
+            (_, Some(seen)) if seen > num_messages => None,
+            (ScrollState::Bottom, seen) => seen,
+            (_, None) => Some(num_messages),
+            (_, seen) => seen,
+        };
+    }
+
+    /// Get the index of the first new message if the divider above it falls between `start` and `end`
+    pub fn new_message_divider(&self, start: usize, end: usize) -> Option<usize> {
+        self.config
+            .seen_up_to
+            .filter(|&seen| self.config.new_message_divider && seen > start && seen < end)
+    }
+
     /// Determine the start and end indexes we need to render in the window
     pub fn determine_render_position(&mut self) -> (usize, usize) {
         let mut end: usize = 0;
         let mut rows: usize = 0;
         let message_pointer_length = self.number_of_messages();
 
+        // If the header is pinned or the tail is shown, they are excluded from the scroll region
+        let header = self.header_offset();
+        let last_row = (self.config.last_row as usize)
+            .saturating_sub(header)
+            .saturating_sub(self.tail_offset());
+
         // Handle empty message queue
         if message_pointer_length == 0 {
             return (0, 0);
         }
 
         // Early escape: render all if we have fewer messages than rows
-        if message_pointer_length <= self.config.last_row as usize {
-            return (0, message_pointer_length);
+        if message_pointer_length - header <= last_row {
+            return (header, message_pointer_length);
         }
 
         // Otherwise, determine how much we can render
         match self.config.scroll_state {
             ScrollState::Top => {
-                let mut current_index: usize = 0;
+                let mut current_index: usize = header;
                 loop {
                     let message: &str = match self.input_type {
                         InputType::Normal | InputType::Command | InputType::Startup => {
-                            &self.messages()[current_index]
+                            self.visible_message(current_index)
                         }
                         InputType::Regex => {
                             // If we have not activated regex or parser yet, render normal messages
                             if self.config.regex_pattern.is_none() {
-                                &self.messages()[current_index]
+                                self.visible_message(current_index)
                             } else {
                                 &self.messages()[self.config.matched_rows[current_index]]
                             }
@@ -304,17 +712,10 @@ impl MainWindow {
                     };
 
                     // Determine if we can fit the next message
-                    let message_length = self.length_finder.get_real_length(message);
-                    rows += max(
-                        1,
-                        (message_length + (self.config.width as usize - 2))
-                            / self.config.width as usize,
-                    );
+                    rows += self.scroll_rows(message);
 
                     // If we can fit, increment the last row number
-                    if rows <= self.config.last_row as usize
-                        && current_index < message_pointer_length - 1
-                    {
+                    if rows <= last_row && current_index < message_pointer_length - 1 {
                         current_index += 1;
                         continue;
                     }
@@ -323,13 +724,13 @@ impl MainWindow {
                     break;
                 }
                 self.config.current_end = current_index; // Save this row so we know where we are
-                return (0, current_index);
+                return (header, current_index);
             }
             ScrollState::Free => {
-                if message_pointer_length < self.config.last_row as usize {
+                if message_pointer_length < last_row {
                     // If have fewer messages than lines, just render it all
                     end = message_pointer_length - 1;
-                } else if (self.config.current_end < self.config.last_row as usize)
+                } else if (self.config.current_end < last_row)
                     | (self.config.current_end < message_pointer_length)
                 {
                     // If the last row we rendered comes before the last row we can render,
@@ -348,15 +749,130 @@ impl MainWindow {
             ScrollState::Bottom => {
                 end = message_pointer_length;
             }
+            ScrollState::Centered => {
+                // The centered message is saved in `current_end` so it stays put as messages arrive
+                let center = self
+                    .config
+                    .current_end
+                    .clamp(header, message_pointer_length - 1);
+                self.config.current_end = center;
+                let (mut start, mut end) = (center, center + 1);
+                let rows = self.scroll_rows(self.get_message_at_index(center));
+
+                // Add messages to whichever side uses fewer rows, filling the other side once one is out of room
+                let (mut rows_above, mut rows_below) = (0, 0);
+                let (mut above_done, mut below_done) =
+                    (start == header, end == message_pointer_length);
+                while !(above_done && below_done) {
+                    let take_above = !above_done && (below_done || rows_above <= rows_below);
+                    let index = if take_above { start - 1 } else { end };
+                    let needed = self.scroll_rows(self.get_message_at_index(index));
+                    if rows + rows_above + rows_below + needed > last_row {
+                        if take_above {
+                            above_done = true;
+                        } else {
+                            below_done = true;
+                        }
+                    } else if take_above {
+                        rows_above += needed;
+                        start -= 1;
+                        above_done = start == header;
+                    } else {
+                        rows_below += needed;
+                        end += 1;
+                        below_done = end == message_pointer_length;
+                    }
+                }
+                return (start, end);
+            }
         }
         self.config.current_end = end; // Save this row so we know where we are
-        let mut start: usize = 0; // default start
-        if end > self.config.last_row as usize {
-            start = (end.checked_sub(self.config.last_row as usize)).unwrap_or(start);
+        let mut start: usize = header; // default start
+        if end > last_row {
+            start = max(end.checked_sub(last_row).unwrap_or(start), header);
         }
         (start, end)
     }
 
+    /// Scroll so the first message at or after a timestamp is at the top of the window
+    ///
+    /// Returns `false` without scrolling if no message has a timestamp at or after the bound.
+    pub fn scroll_to_timestamp(&mut self, search: &TimestampSearch) -> bool {
+        let found = if self.parsed_view() {
+            // Parsed messages are only kept for the view, so they are searched directly
+            search.first_match(self.config.auxiliary_messages.iter().map(|m| m.as_str()))
+        } else {
+            search
+                .first_match(self.messages().iter().map(|m| m.as_str()))
+                .map(|index| self.view_index(index))
+        };
+        match found {
+            Some(index) if index < self.view_len() => {
+                self.scroll_to_message(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the view shows parsed messages instead of the message buffer, including behind the command line
+    fn parsed_view(&self) -> bool {
+        let input_type = match self.input_type {
+            InputType::Command => self.previous_input_type,
+            input_type => input_type,
+        };
+        input_type == InputType::Parser && self.config.parser_state == ParserState::Full
+    }
+
+    /// Get the number of messages in the view
+    fn view_len(&self) -> usize {
+        if self.parsed_view() {
+            return self.config.auxiliary_messages.len();
+        }
+        self.number_of_messages()
+    }
+
+    /// Get the message at `index` in the view
+    fn view_message(&self, index: usize) -> &str {
+        if self.parsed_view() {
+            return &self.config.auxiliary_messages[index];
+        }
+        self.get_message_at_index(index)
+    }
+
+    /// Get the position in the view of the first shown message at or after `index` in the buffer
+    fn view_index(&self, index: usize) -> usize {
+        if self.config.regex_pattern.is_some() {
+            return self.config.matched_rows.partition_point(|&row| row < index);
+        }
+        if self.muting_active() {
+            return self.config.unmuted_rows.partition_point(|&row| row < index);
+        }
+        index
+    }
+
+    /// Scroll so the message at `index` is at the top of the window, or the last message if `index` is past the end
+    pub fn scroll_to_message(&mut self, index: usize) {
+        let number_of_messages = self.view_len();
+        let last_row = (self.config.last_row as usize)
+            .saturating_sub(self.header_offset())
+            .saturating_sub(self.tail_offset())
+            .max(1);
+        self.config.scroll_state = ScrollState::Free;
+
+        // Messages render upwards from `current_end`, so take as many as fit below `index`, since long messages wrap
+        let mut end = min(index, number_of_messages);
+        let mut rows = 0;
+        while end < number_of_messages {
+            rows += self.scroll_rows(self.view_message(end));
+            if rows > last_row {
+                break;
+            }
+            end += 1;
+        }
+        self.config.current_end = max(end, min(index + 1, number_of_messages));
+    }
+
     /// Get the message at a specific index in the current buffer
     fn get_message_at_index(&self, index: usize) -> &str {
         // if there is a regex active, use that, otherwise handle normally
@@ -364,26 +880,265 @@ impl MainWindow {
             return &self.messages()[self.config.matched_rows[index]];
         }
         match self.input_type {
-            InputType::Normal | InputType::Command | InputType::Startup => &self.messages()[index],
+            InputType::Normal | InputType::Command | InputType::Startup => {
+                self.visible_message(index)
+            }
             InputType::Regex => {
                 if self.config.regex_pattern.is_none() {
-                    &self.messages()[index]
+                    self.visible_message(index)
                 } else {
                     &self.messages()[self.config.matched_rows[index]]
                 }
             }
-            InputType::Parser => &self.messages()[index],
+            InputType::Parser => self.visible_message(index),
+        }
+    }
+
+    /// Get the number of messages in the current buffer that are not from a muted stream
+    fn visible_len(&self) -> usize {
+        if self.muting_active() {
+            return self.config.unmuted_rows.len();
+        }
+        self.messages().len()
+    }
+
+    /// Get the message at `index` among the messages in the current buffer that are not from a muted stream
+    fn visible_message(&self, index: usize) -> &str {
+        if self.muting_active() {
+            return &self.messages()[self.config.unmuted_rows[index]];
+        }
+        &self.messages()[index]
+    }
+
+    /// Prepare a message for rendering
+    ///
+    /// By default, trailing whitespace is trimmed. In raw mode the message is left
+    /// intact, but control characters are escaped so they cannot corrupt the terminal.
+    fn prepare_message<'a>(&self, message: &'a str) -> Cow<'a, str> {
+        if self.config.strip_colors {
+            let clean = CleanText::new(&self.config.color_replace_regex, message.as_bytes());
+            let message = String::from_utf8_lossy(&clean.text);
+            return if self.config.raw_messages {
+                Cow::Owned(escape_control_chars(&message))
+            } else {
+                Cow::Owned(message.trim_end().to_owned())
+            };
+        }
+        if self.config.raw_messages {
+            Cow::Owned(escape_control_chars(message))
+        } else {
+            Cow::Borrowed(message.trim_end())
+        }
+    }
+
+    /// The number shown in the gutter for the message at `index` in the current view
+    ///
+    /// With a regex active or a stream muted, this is the index of the message in the unfiltered buffer.
+    fn line_number(&self, index: usize) -> usize {
+        if self.config.regex_pattern.is_some() {
+            return self.config.matched_rows[index];
+        }
+        if self.muting_active() {
+            return self.config.unmuted_rows[index];
+        }
+        index
+    }
+
+    /// Width of the line number gutter, including the space after the numbers, or 0 if it is hidden
+    ///
+    /// The gutter fits the largest line number in the view, so it does not change width while scrolling.
+    fn gutter_width(&self) -> usize {
+        if !self.config.show_line_numbers
+            || self.split_active()
+            || self.input_type == InputType::Startup
+        {
+            return 0;
+        }
+        let largest = match self.number_of_messages().checked_sub(1) {
+            Some(index) => self.line_number(index),
+            None => 0,
+        };
+        largest.to_string().len() + 1
+    }
+
+    /// Width of the source labels, including the space after them, or 0 if they are hidden
+    ///
+    /// The labels fit the longest stream name, so they do not change width while scrolling.
+    fn source_width(&self) -> usize {
+        if !self.config.show_source
+            || self.split_active()
+            || self.input_type == InputType::Startup
+            || matches!(self.config.stream_type, StreamType::Auxiliary)
+        {
+            return 0;
+        }
+        source_width(
+            self.config
+                .streams
+                .iter()
+                .map(|stream| stream.process_name.as_str()),
+        )
+    }
+
+    /// Name of the stream the message at `index` in the current view came from, if it is known
+    fn source_name(&self, index: usize) -> Option<&str> {
+        let source = *self
+            .sources(self.config.stream_type)
+            .get(self.line_number(index))?;
+        self.config
+            .streams
+            .get(source)
+            .map(|stream| stream.process_name.as_str())
+    }
+
+    /// Text written before a row of the message at `index`
+    ///
+    /// The first row gets the line number and source label, rows the message wraps onto are blank so they line up.
+    fn line_prefix(&self, index: usize, first_row: bool) -> String {
+        let gutter = self.gutter_width();
+        let source = self.source_width();
+        if !first_row {
+            return " ".repeat(gutter + source);
+        }
+        let number = match gutter {
+            0 => String::new(),
+            _ => format!("{:>1$} ", self.line_number(index), gutter - 1),
+        };
+        let label = match source {
+            0 => String::new(),
+            _ => source_label(self.source_name(index), source),
+        };
+        number + &label
+    }
+
+    /// Number of columns messages are wrapped at, leaving room for the line number gutter and source labels
+    fn text_width(&self) -> usize {
+        max(
+            1,
+            (self.config.width as usize)
+                .saturating_sub(self.gutter_width())
+                .saturating_sub(self.source_width()),
+        )
+    }
+
+    /// Number of rows a message is counted as when deciding how many messages fit in the scroll region
+    fn scroll_rows(&self, message: &str) -> usize {
+        match self.config.wrap_mode {
+            WrapMode::Hard => {
+                let width = self.text_width();
+                let message_length = self.length_finder.get_real_length(message);
+                max(1, (message_length + width.saturating_sub(2)) / width)
+            }
+            _ => self.message_rows(message),
+        }
+    }
+
+    /// Get the number of rows a message takes to render in the current wrap mode
+    fn message_rows(&self, message: &str) -> usize {
+        let width = self.text_width();
+        match self.config.wrap_mode {
+            WrapMode::Hard => max(
+                1,
+                self.length_finder.get_real_length(message).div_ceil(width),
+            ),
+            WrapMode::Word => word_wrap(&self.config.color_replace_regex, message, width).len(),
+            WrapMode::Off => 1,
+        }
+    }
+
+    /// Write a message to the window starting at `row` in the current wrap mode
+    ///
+    /// Each row the message uses is padded to the width of the window.
+    fn queue_message(&self, stdout: &mut Stdout, row: u16, message: &str) -> Result<()> {
+        let width = self.config.width as usize;
+        match self.config.wrap_mode {
+            WrapMode::Hard => {
+                // See `render_text_in_output` docs for note on why we need this padding
+                let message_length = self.length_finder.get_real_length(message);
+                let message_rows = max(1, message_length.div_ceil(width));
+                let padding = " ".repeat((width * message_rows) - message_length);
+                queue!(
+                    stdout,
+                    cursor::MoveTo(0, row),
+                    style::Print(message),
+                    style::Print(padding)
+                )?;
+            }
+            WrapMode::Word => {
+                let lines = word_wrap(&self.config.color_replace_regex, message, width);
+                for (offset, line) in lines.iter().enumerate() {
+                    let line_length = self.length_finder.get_real_length(line);
+                    queue!(
+                        stdout,
+                        cursor::MoveTo(0, row + offset as u16),
+                        style::Print(line),
+                        style::Print(" ".repeat(width.saturating_sub(line_length)))
+                    )?;
+                }
+            }
+            WrapMode::Off => {
+                let line = truncate_with_ellipsis(&self.config.color_replace_regex, message, width);
+                let line_length = self.length_finder.get_real_length(&line);
+                queue!(
+                    stdout,
+                    cursor::MoveTo(0, row),
+                    style::Print(line),
+                    style::Print(" ".repeat(width.saturating_sub(line_length)))
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the message at `index` to the window starting at `row` after its line number and source label
+    ///
+    /// Rows the message wraps onto are indented past the prefix so they line up with the first row.
+    fn queue_prefixed_message(
+        &self,
+        stdout: &mut Stdout,
+        row: u16,
+        message: &str,
+        index: usize,
+    ) -> Result<()> {
+        let width = self.text_width();
+        let lines = match self.config.wrap_mode {
+            WrapMode::Hard => hard_wrap(&self.config.color_replace_regex, message, width),
+            WrapMode::Word => word_wrap(&self.config.color_replace_regex, message, width),
+            WrapMode::Off => vec![truncate_with_ellipsis(
+                &self.config.color_replace_regex,
+                message,
+                width,
+            )],
+        };
+        for (offset, line) in lines.iter().enumerate() {
+            let prefix = self.line_prefix(index, offset == 0);
+            let line_length = self.length_finder.get_real_length(line);
+            queue!(
+                stdout,
+                cursor::MoveTo(0, row + offset as u16),
+                style::Print(prefix),
+                style::Print(line),
+                style::Print(" ".repeat(width.saturating_sub(line_length)))
+            )?;
         }
+        Ok(())
+    }
+
+    /// Whether rendered messages should have their regex matches highlighted
+    ///
+    /// The highlight is a color, so it is left out when colors are stripped, i.e. in safe mode.
+    fn should_highlight(&self) -> bool {
+        self.config.highlight_match
+            && self.config.regex_pattern.is_some()
+            && !self.config.strip_colors
     }
 
     /// Highlight the regex matched text with an ASCII escape code
     fn highlight_match(&self, message: &str) -> String {
-        // Regex out any existing color codes
+        // Match against the message without color codes, then map the matches back to the original
         // We use a bytes regex because we cannot compile the pattern using normal regex
-        let clean_message = self
-            .config
-            .color_replace_regex
-            .replace_all(message.as_bytes(), "".as_bytes());
+        let original = message.as_bytes();
+        let clean_message = CleanText::new(&self.config.color_replace_regex, original);
 
         // Store some vectors of char bytes so we don't have to cast to a string every loop
         let mut new_msg: Vec<u8> = vec![];
@@ -395,19 +1150,21 @@ impl MainWindow {
             .regex_pattern
             .as_ref()
             .unwrap()
-            .find_iter(&clean_message)
+            .find_iter(&clean_message.text)
         {
-            new_msg.extend(clean_message[last_end..capture.start()].to_vec());
+            let start = clean_message.original_start(capture.start());
+            let end = clean_message.original_end(capture.end()).max(start);
+            new_msg.extend(original[last_end..start].to_vec());
             // Add start color string
             new_msg.extend(colors::HIGHLIGHT_COLOR.as_bytes().to_vec());
-            new_msg.extend(clean_message[capture.start()..capture.end()].to_vec());
+            new_msg.extend(original[start..end].to_vec());
             // Add end color string
             new_msg.extend(colors::RESET_COLOR.as_bytes().to_vec());
             // Store the ending in case we have multiple matches so we can add the end later
-            last_end = capture.end();
+            last_end = end;
         }
         // Add on any extra chars and update the message String
-        new_msg.extend(clean_message[last_end..].to_vec());
+        new_msg.extend(original[last_end..].to_vec());
         String::from_utf8(new_msg).unwrap()
     }
 
@@ -426,27 +1183,35 @@ impl MainWindow {
         // Save the cursor position (i.e. if the user is editing text in the command line)
         queue!(stdout, cursor::SavePosition)?;
 
+        // Split view renders each buffer in its own pane
+        if self.split_active() {
+            return self.render_split_panes(&mut stdout);
+        }
+
         // Determine the start and end position of the render
         let (start, end) = self.determine_render_position();
+        self.update_seen_up_to(start, end);
+        let divider = self.new_message_divider(start, end);
+        let selection = selection::range(self);
+        let prefixed = self.gutter_width() + self.source_width() > 0;
 
         // If there are no messages in the buffer, tell the user
         // This will only ever hit once, because this method is only called if there are new
         // messages to render or a user action requires a full re-render
         if self.messages().is_empty() {
-            match self.input_type {
-                InputType::Parser => {
-                    self.write_to_command_line(NO_MESSAGE_IN_BUFFER_PARSER)?;
-                }
-                InputType::Regex => {}
-                _ => {
-                    self.write_to_command_line(NO_MESSAGE_IN_BUFFER_NORMAL)?;
-                }
+            if let Some(text) = self.empty_buffer_text() {
+                let text = text.to_owned();
+                self.write_to_command_line(&text)?;
             }
             self.config.was_empty = true;
             stdout.flush()?;
             return Ok(());
         }
 
+        // The tail follows new messages even if the scroll region does not move
+        self.render_tail(&mut stdout)?;
+        self.render_position(&mut stdout, end)?;
+
         // Don't do anything if nothing changed; start at index 0
         if !self.config.aggregation_enabled && self.config.previous_render == (max(0, start), end) {
             queue!(stdout, cursor::RestorePosition)?;
@@ -469,58 +1234,94 @@ impl MainWindow {
         // Since we are rendering if we got here, lock in the new render state
         self.config.previous_render = (max(0, start), end);
 
-        // Start the render from the last row
-        let mut current_row = self.config.last_row;
+        // Start the render from the last row above the tail
+        let mut current_row = self.config.last_row - self.tail_offset() as u16;
+
+        // Rows above this are reserved for the sticky header
+        let first_row = self.header_offset() as u16;
 
         // Cast to usize so we can reference this instead of casting every time we need
         let width = self.config.width as usize;
 
+        // Render the header first so any overflow is overwritten by the scroll region
+        if first_row > 0 {
+            let header = self.prepare_message(self.visible_message(0));
+            let header_length = self.length_finder.get_real_length(&header);
+            queue!(
+                stdout,
+                cursor::MoveTo(0, 0),
+                style::Print(&header),
+                style::Print(" ".repeat(width.saturating_sub(header_length)))
+            )?;
+        }
+
         // Render each message from bottom to top
         for index in (start..end).rev() {
-            // Get the next message from the message pointer
-            let mut message = self.get_message_at_index(index);
+            // The divider sits directly above the first new message
+            if divider == Some(index + 1) {
+                current_row = match current_row.checked_sub(1) {
+                    Some(value) if value >= first_row => value,
+                    _ => break,
+                };
+                queue!(
+                    stdout,
+                    cursor::MoveTo(0, current_row),
+                    style::Print(format!("{:─^1$}", " new messages ", width))
+                )?;
+            }
+
+            // Get the next message from the message pointer, trimmed unless raw mode is enabled
+            let original = self.get_message_at_index(index);
+            let mut message = self.prepare_message(original);
+
+            // Only the rendered text changes, so filtering still sees the original message
+            if self.config.highlight_changes && !self.config.strip_colors && index > 0 {
+                let previous = self.prepare_message(self.get_message_at_index(index - 1));
+                if let Some(changed) = highlight_changes(&previous, &message) {
+                    message = Cow::Owned(changed);
+                }
+            }
 
-            // Trim any spaces or newlines from the end of the message
-            message = message.trim_end();
+            // Mark messages that are new since the kept run
+            if let Some(diff) = self.active_diff() {
+                message = Cow::Owned(diff.gutter(original, &message));
+            }
 
             // Get some metadata we need to render the message
-            let message_length = self.length_finder.get_real_length(message);
-            let message_rows = max(1, ((message_length) + (width - 1)) / width);
+            let message_rows = self.message_rows(&message);
 
             // Update the current row, stop writing if there is no more space
             current_row = match current_row.checked_sub(max(1, message_rows as u16)) {
-                Some(value) => value,
-                None => break,
+                Some(value) if value >= first_row => value,
+                _ => break,
             };
 
-            // See method docs for note on why we need this padding
-            let message_padding_size = (width * message_rows) - message_length;
-            let padding = " ".repeat(message_padding_size);
+            // Selected messages are drawn in reverse video, including their padding
+            let is_selected = selection.is_some_and(|(first, last)| (first..last).contains(&index));
+            if is_selected {
+                queue!(stdout, style::SetAttribute(style::Attribute::Reverse))?;
+            }
 
-            if !(self.config.highlight_match && self.config.regex_pattern.is_some()) {
-                // Render message normally
-                queue!(
-                    stdout,
-                    cursor::MoveTo(0, current_row),
-                    style::Print(message),
-                    style::Print(padding)
-                )?;
-            } else {
+            if self.should_highlight() {
                 // Render message with highlight (additional allocation)
-                queue!(
-                    stdout,
-                    cursor::MoveTo(0, current_row),
-                    style::Print(self.highlight_match(message)),
-                    style::Print(padding)
-                )?;
+                message = Cow::Owned(self.highlight_match(&message));
+            }
+            if prefixed {
+                self.queue_prefixed_message(&mut stdout, current_row, &message, index)?;
+            } else {
+                self.queue_message(&mut stdout, current_row, &message)?;
+            }
+
+            if is_selected {
+                queue!(stdout, style::SetAttribute(style::Attribute::NoReverse))?;
             }
         }
 
         // Overwrite any new blank lines
         // We could iterate over (0..current_row), but we don't need to allocate clear_line
-        if current_row > 0 {
+        if current_row > first_row {
             let clear_line = " ".repeat(width);
-            (0..current_row).for_each(|row| {
+            (first_row..current_row).for_each(|row| {
                 // No `?` here because it is inside of a closure
                 queue!(stdout, cursor::MoveTo(0, row), style::Print(&clear_line),).unwrap()
             });
@@ -532,33 +1333,416 @@ impl MainWindow {
         Ok(())
     }
 
-    /// Force render
-    pub fn redraw(&mut self) -> Result<()> {
-        self.config.previous_render = (0, 0);
-        self.render_text_in_output()?;
-        Ok(())
-    }
-
-    /// Get the previous message pointer
-    pub fn previous_messages(&self) -> &Vec<String> {
-        match self.config.previous_stream_type {
-            StreamType::StdErr => &self.config.stderr_messages,
-            StreamType::StdOut => &self.config.stdout_messages,
-            StreamType::Auxiliary => &self.config.auxiliary_messages,
+    /// Text to show in the command line when the current buffer is empty, if any
+    fn empty_buffer_text(&self) -> Option<&str> {
+        match self.input_type {
+            InputType::Parser => Some(self.config.text.no_messages_parser()),
+            InputType::Regex => None,
+            _ => Some(self.config.text.no_messages()),
         }
     }
 
-    /// Get the current message pointer
-    pub fn messages(&self) -> &Vec<String> {
-        match self.config.stream_type {
-            StreamType::StdErr => &self.config.stderr_messages,
-            StreamType::StdOut => &self.config.stdout_messages,
-            StreamType::Auxiliary => &self.config.auxiliary_messages,
+    /// Draw the position of the bottom of the view on the border above the command line
+    ///
+    /// The border is redrawn first so a shorter position or a hidden one does not leave text behind.
+    fn render_position(&self, stdout: &mut Stdout, end: usize) -> Result<()> {
+        let width = self.config.width as usize;
+        queue!(
+            stdout,
+            cursor::MoveTo(1, self.config.last_row),
+            style::Print("─".repeat(width.saturating_sub(2)))
+        )?;
+        if self.config.show_position {
+            let text = format!(" {} ", position_text(end, self.number_of_messages()));
+            if text.len() + 2 < width {
+                queue!(
+                    stdout,
+                    cursor::MoveTo((width - 1 - text.len()) as u16, self.config.last_row),
+                    style::Print(text)
+                )?;
+            }
         }
+        Ok(())
     }
 
-    /// Move the cursor to the CLI window
-    pub fn go_to_cli(&mut self) -> Result<()> {
+    /// Render the newest messages of the buffer below a divider at the bottom of the output window
+    fn render_tail(&self, stdout: &mut Stdout) -> Result<()> {
+        let rows = self.tail_offset();
+        if rows == 0 {
+            return Ok(());
+        }
+        let width = self.config.width as usize;
+        let divider_row = self.config.last_row - rows as u16;
+        queue!(
+            stdout,
+            cursor::MoveTo(0, divider_row),
+            style::Print("─".repeat(width))
+        )?;
+
+        // Each message is cut to a single row so the strip never changes height
+        let messages: Vec<&str> = tail_range(self.number_of_messages(), TAIL_ROWS)
+            .map(|index| self.get_message_at_index(index))
+            .collect();
+        let first_row = self.config.last_row - messages.len() as u16;
+        for row in (divider_row + 1)..first_row {
+            queue!(
+                stdout,
+                cursor::MoveTo(0, row),
+                style::Print(" ".repeat(width))
+            )?;
+        }
+        for (offset, message) in messages.iter().enumerate() {
+            let message = truncate(
+                &self.config.color_replace_regex,
+                &self.prepare_message(message),
+                width,
+            );
+            let message_length = self.length_finder.get_real_length(&message);
+            queue!(
+                stdout,
+                cursor::MoveTo(0, first_row + offset as u16),
+                style::Print(&message),
+                style::Print(" ".repeat(width.saturating_sub(message_length)))
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Number of rows the status overlay can use, leaving a row for its divider
+    fn overlay_height(&self) -> usize {
+        (self.config.last_row as usize).saturating_sub(1)
+    }
+
+    /// Show the full status text over the output window
+    ///
+    /// Returns `false` if there is no status to show.
+    pub fn open_status_overlay(&mut self) -> Result<bool> {
+        match &self.config.current_status {
+            Some(status) => {
+                self.config.status_overlay =
+                    Some(StatusOverlay::new(status, self.config.width as usize));
+                self.render_status_overlay()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Send a key to the status overlay if it is open
+    ///
+    /// Up and down scroll a status too long for the window, any other key closes the overlay.
+    /// Returns `false` if the overlay is not open and the key should be handled normally.
+    pub fn handle_status_overlay(&mut self, key: KeyCode) -> Result<bool> {
+        let height = self.overlay_height();
+        let overlay = match self.config.status_overlay.as_mut() {
+            Some(overlay) => overlay,
+            None => return Ok(false),
+        };
+        match key {
+            KeyCode::Up => overlay.scroll_up(),
+            KeyCode::Down => overlay.scroll_down(height),
+            _ => {
+                self.config.status_overlay = None;
+                self.reset_output()?;
+                self.redraw()?;
+                return Ok(true);
+            }
+        }
+        self.render_status_overlay()?;
+        Ok(true)
+    }
+
+    /// Draw the status overlay at the bottom of the output window, below a divider row
+    pub fn render_status_overlay(&self) -> Result<()> {
+        let overlay = match &self.config.status_overlay {
+            Some(overlay) => overlay,
+            None => return Ok(()),
+        };
+        let mut stdout = stdout();
+        let width = self.config.width as usize;
+        let rows = overlay.visible(self.overlay_height());
+        let divider_row = self.config.last_row.saturating_sub(rows.len() as u16 + 1);
+        queue!(
+            stdout,
+            cursor::SavePosition,
+            cursor::MoveTo(0, divider_row),
+            style::Print(format!("{:─^1$}", " status ", width))
+        )?;
+        for (offset, row) in rows.iter().enumerate() {
+            queue!(
+                stdout,
+                cursor::MoveTo(0, divider_row + 1 + offset as u16),
+                style::Print(row),
+                style::Print(" ".repeat(width.saturating_sub(row.chars().count())))
+            )?;
+        }
+        queue!(stdout, cursor::RestorePosition)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Render stderr above stdout, separated by a divider row
+    fn render_split_panes(&mut self, stdout: &mut Stdout) -> Result<()> {
+        let (top, bottom) = split_panes(self.config.last_row);
+        self.render_pane(stdout, &top)?;
+        queue!(
+            stdout,
+            cursor::MoveTo(0, top.end_row()),
+            style::Print("─".repeat(self.config.width as usize))
+        )?;
+        self.render_pane(stdout, &bottom)?;
+
+        // Ensure the full window is rendered when leaving split view
+        self.config.previous_render = (0, 0);
+
+        queue!(stdout, cursor::RestorePosition)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Render the end of a pane's buffer from the bottom of the pane up
+    fn render_pane(&self, stdout: &mut Stdout, pane: &Pane) -> Result<()> {
+        let width = self.config.width as usize;
+        let messages = self.buffer(pane.stream_type);
+        let (start, end) = render_range(messages.len(), pane);
+        let mut current_row = pane.end_row();
+
+        for index in (start..end).rev() {
+            let message = self.prepare_message(&messages[index]);
+            let message_rows = self.message_rows(&message);
+
+            // Stop writing if there is no more space in the pane
+            current_row = match current_row.checked_sub(message_rows as u16) {
+                Some(value) if value >= pane.first_row => value,
+                _ => break,
+            };
+
+            self.queue_message(stdout, current_row, &message)?;
+        }
+
+        // Overwrite any blank lines left in the pane
+        let clear_line = " ".repeat(width);
+        for row in pane.first_row..current_row {
+            queue!(stdout, cursor::MoveTo(0, row), style::Print(&clear_line))?;
+        }
+        Ok(())
+    }
+
+    /// Force render
+    pub fn redraw(&mut self) -> Result<()> {
+        self.config.previous_render = (0, 0);
+        self.render_text_in_output()?;
+        Ok(())
+    }
+
+    /// Get the message pointer for a stream
+    fn buffer(&self, stream_type: StreamType) -> &Vec<String> {
+        match stream_type {
+            StreamType::StdErr => &self.config.stderr_messages,
+            StreamType::StdOut => &self.config.stdout_messages,
+            StreamType::Auxiliary => &self.config.auxiliary_messages,
+        }
+    }
+
+    /// Get the previous message pointer
+    pub fn previous_messages(&self) -> &Vec<String> {
+        self.buffer(self.config.previous_stream_type)
+    }
+
+    /// Show another stream, remembering the current one as the previous stream
+    pub fn switch_stream(&mut self, stream_type: StreamType) {
+        self.config.previous_stream_type = self.config.stream_type;
+        self.config.stream_type = stream_type;
+        // The new message marker, watermark, and freeze refer to the previous buffer
+        self.config.seen_up_to = None;
+        self.config.watermark = None;
+        if self.config.frozen_at.take().is_some() {
+            self.config.current_status = None;
+        }
+        self.reset_mutes();
+    }
+
+    /// Get the index of the stream each message in a buffer came from
+    fn sources(&self, stream_type: StreamType) -> &[usize] {
+        match stream_type {
+            StreamType::StdErr => &self.config.stderr_sources,
+            StreamType::StdOut => &self.config.stdout_sources,
+            StreamType::Auxiliary => &[],
+        }
+    }
+
+    /// Whether messages from muted streams are hidden from the current buffer
+    pub fn muting_active(&self) -> bool {
+        !self.config.muted_streams.is_empty()
+            && !matches!(self.config.stream_type, StreamType::Auxiliary)
+    }
+
+    /// Whether the message at `index` in the current buffer came from a muted stream
+    ///
+    /// Messages without a known stream, i.e. Logria's own messages, are never muted.
+    pub fn is_muted(&self, index: usize) -> bool {
+        self.sources(self.config.stream_type)
+            .get(index)
+            .is_some_and(|source| self.config.muted_streams.contains(source))
+    }
+
+    /// Hide or show the messages from the stream at `index`
+    ///
+    /// Returns `false` without changing anything if there is no such stream or it is already in that state.
+    pub fn set_muted(&mut self, index: usize, muted: bool) -> bool {
+        if index >= self.config.streams.len() {
+            return false;
+        }
+        let changed = if muted {
+            self.config.muted_streams.insert(index)
+        } else {
+            self.config.muted_streams.remove(&index)
+        };
+        if changed {
+            self.reset_mutes();
+            // Regex matches skip muted messages, so they are found again
+            self.config.matched_rows.clear();
+            self.config.last_index_regexed = 0;
+            self.config.did_switch = true;
+        }
+        changed
+    }
+
+    /// Find the messages in the current buffer that are not from a muted stream again
+    fn reset_mutes(&mut self) {
+        self.config.unmuted_rows.clear();
+        self.config.last_index_unmuted = 0;
+        self.process_mutes();
+    }
+
+    /// Record the index of each new message that is not from a muted stream
+    pub fn process_mutes(&mut self) {
+        if !self.muting_active() {
+            return;
+        }
+        let end = self.messages().len();
+        for index in self.config.last_index_unmuted..end {
+            if !self.is_muted(index) {
+                self.config.unmuted_rows.push(index);
+            }
+        }
+        self.config.last_index_unmuted = end;
+    }
+
+    /// Describe each stream with its index, for `:streams`
+    pub fn describe_streams(&self) -> Vec<String> {
+        self.config
+            .streams
+            .iter()
+            .enumerate()
+            .map(|(index, stream)| {
+                if self.config.muted_streams.contains(&index) {
+                    format!("{}: {} (muted)", index, stream.process_name)
+                } else {
+                    format!("{}: {}", index, stream.process_name)
+                }
+            })
+            .collect()
+    }
+
+    /// Get the current message pointer
+    pub fn messages(&self) -> &Vec<String> {
+        self.buffer(self.config.stream_type)
+    }
+
+    /// Build a summary of how much data each buffer is holding
+    ///
+    /// Sizes are approximate: the sum of the string lengths for message buffers
+    /// and the size of the stored indexes for matched rows.
+    pub fn diagnostics(&self) -> Vec<String> {
+        let buffer_line = |name: &str, count: usize, bytes: usize| {
+            format!(
+                "    {}: {} ({} bytes)",
+                name,
+                format_num!(",d", count as f64),
+                format_num!(",d", bytes as f64)
+            )
+        };
+        let buffer_bytes = |buffer: &Vec<String>| buffer.iter().map(|m| m.len()).sum();
+        vec![
+            String::from("Buffers"),
+            buffer_line(
+                "stderr messages",
+                self.config.stderr_messages.len(),
+                buffer_bytes(&self.config.stderr_messages),
+            ),
+            buffer_line(
+                "stdout messages",
+                self.config.stdout_messages.len(),
+                buffer_bytes(&self.config.stdout_messages),
+            ),
+            buffer_line(
+                "auxiliary messages",
+                self.config.auxiliary_messages.len(),
+                buffer_bytes(&self.config.auxiliary_messages),
+            ),
+            buffer_line(
+                "matched rows",
+                self.config.matched_rows.len(),
+                self.config.matched_rows.len() * size_of::<usize>(),
+            ),
+            String::from("App"),
+            format!("    streams: {}", self.config.streams.len()),
+            format!("    poll rate: {}ms", self.config.poll_rate),
+        ]
+    }
+
+    /// Build a summary of the modes, filters, and parsers that shape what is on screen
+    pub fn status_report(&self) -> Vec<String> {
+        // Commands are run from another mode, so report the mode the user will return to
+        let input_type = match self.input_type {
+            InputType::Command => self.previous_input_type,
+            input_type => input_type,
+        };
+        let regex = match &self.config.regex_pattern {
+            Some(pattern) => format!(
+                "/{}/ ({} matches)",
+                pattern.as_str(),
+                format_num!(",d", self.config.matched_rows.len() as f64)
+            ),
+            None => String::from("off"),
+        };
+        let parser = match self.config.parser_state {
+            ParserState::Disabled => String::from("off"),
+            ParserState::Full => match self.config.parser_indices.as_slice() {
+                [index] => format!("index {}", index),
+                indices => format!("indices {}", format_indices(indices)),
+            },
+            ref state => format!("{:?}", state),
+        };
+        vec![
+            String::from("Status"),
+            format!("    input: {:?}", input_type),
+            format!("    stream: {:?}", self.config.stream_type),
+            format!("    regex: {}", regex),
+            format!("    parser: {}", parser),
+            format!(
+                "    aggregation: {}",
+                if self.config.aggregation_enabled {
+                    "on"
+                } else {
+                    "off"
+                }
+            ),
+            format!("    scroll: {:?}", self.config.scroll_state),
+            format!(
+                "    frozen: {}",
+                match self.config.frozen_at {
+                    Some(frozen_at) => format!("at {} messages", frozen_at),
+                    None => String::from("off"),
+                }
+            ),
+            format!("    poll rate: {}ms", self.config.poll_rate),
+        ]
+    }
+
+    /// Move the cursor to the CLI window
+    pub fn go_to_cli(&mut self) -> Result<()> {
         let cli_position = self.config.height - 2;
         queue!(stdout(), cursor::MoveTo(1, cli_position))?;
         Ok(())
@@ -612,6 +1796,7 @@ impl MainWindow {
 
     /// Write text to the command line
     pub fn write_to_command_line(&mut self, content: &str) -> Result<()> {
+        self.trace("message", content);
         queue!(stdout(), cursor::SavePosition)?;
         // Remove what used to be in the command line
         self.reset_command_line()?;
@@ -655,9 +1840,76 @@ impl MainWindow {
         Ok(())
     }
 
+    /// Restore the view state saved in a workspace once its streams are open
+    pub fn apply_workspace(&mut self, workspace: &Workspace) -> Result<()> {
+        self.config.split_view = workspace.split_view;
+        if let Some(pattern) = &workspace.regex {
+            match Regex::new(pattern) {
+                Ok(regex) => {
+                    self.set_regex(regex);
+                    self.update_input_type(InputType::Regex)?;
+                }
+                Err(why) => {
+                    self.write_to_command_line(&format!("Invalid regex: /{}/ ({})", pattern, why))?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Start parsing with a session's saved parser once its streams are open
+    ///
+    /// The parser handler takes the loaded parser on its next tick. If the parser cannot be loaded,
+    /// i.e. if it was renamed, the app stays in normal mode and the error is shown.
+    pub fn attach_parser(&mut self, attached: &SessionParser) -> Result<()> {
+        Parser::verify_path();
+        match Parser::load(&format!("{}/{}", patterns(), attached.name)) {
+            Ok(mut parser) => {
+                parser.setup();
+                self.config.attached_parser = Some((attached.name.to_owned(), parser));
+                self.config.parser_indices = vec![attached.index];
+                self.config.parser_state = ParserState::Full;
+                self.config.last_index_processed = 0;
+                self.config.auxiliary_messages.clear();
+                self.config.previous_stream_type = self.config.stream_type;
+                self.config.stream_type = StreamType::Auxiliary;
+                self.config.did_switch = true;
+                self.update_input_type(InputType::Parser)?;
+            }
+            Err(why) => self.write_to_command_line(&why.to_string())?,
+        }
+        Ok(())
+    }
+
+    /// Filter the buffer with a regex; matches are rebuilt by the regex handler on the next tick
+    pub fn set_regex(&mut self, regex: Regex) {
+        self.config.current_status = Some(format!("Regex with pattern /{}/", regex.as_str()));
+        self.config.regex_pattern = Some(regex);
+        self.config.matched_rows.clear();
+        self.config.last_index_regexed = 0;
+        self.config.highlight_match = true;
+        self.config.did_switch = true;
+    }
+
+    /// Empty the auxiliary buffer and forget the function that generates it
+    ///
+    /// The buffer is left intact while a parser is active, as it holds the parsed output.
+    pub fn clear_auxiliary(&mut self) -> bool {
+        if self.config.parser_state != ParserState::Disabled {
+            return false;
+        }
+        self.config.auxiliary_messages.clear();
+        self.config.generate_auxiliary_messages = None;
+        true
+    }
+
     /// Set dimensions
     fn update_dimensions(&mut self) -> Result<()> {
         let (w, h) = size()?;
+        // Keep the last usable size until the terminal is large enough to draw in again
+        if !valid_size(w, h) {
+            return Ok(());
+        }
         self.config.height = h;
         self.config.width = w;
         self.config.last_row = self.config.height.checked_sub(3).unwrap_or(h);
@@ -667,11 +1919,102 @@ impl MainWindow {
 
     /// Set a new input type enum while preserving the old one in the history
     pub fn update_input_type(&mut self, input_type: InputType) -> Result<()> {
+        self.trace(
+            "mode",
+            &format!("{:?} -> {:?}", self.input_type, input_type),
+        );
         self.previous_input_type = self.input_type;
         self.input_type = input_type;
+        // Freezing only applies to the unfiltered buffer
+        if matches!(input_type, InputType::Regex | InputType::Parser)
+            && self.config.frozen_at.take().is_some()
+        {
+            self.config.current_status = None;
+        }
         Ok(())
     }
 
+    /// Record one of Logria's own actions to the trace file, if tracing is enabled
+    pub fn trace(&self, event: &str, detail: &str) {
+        if let Some(tracer) = &self.config.tracer {
+            tracer.record(event, detail);
+        }
+    }
+
+    /// Replace the streams Logria listens to
+    pub fn set_streams(&mut self, streams: Vec<InputStream>) {
+        for stream in &streams {
+            self.trace(
+                "stream",
+                &format!("started {} ({})", stream.process_name, stream._type),
+            );
+        }
+        self.config.streams = streams;
+        // Muted indices refer to the streams that were replaced
+        self.config.muted_streams.clear();
+        self.reset_mutes();
+    }
+
+    /// Read each file stream again from the start of its file, i.e. after it was rotated
+    ///
+    /// Files send their lines to stdout, so their lines are dropped from that buffer before they are
+    /// read again; other streams keep running and keep their messages. Returns the number of files
+    /// reloaded.
+    pub fn reload_files(&mut self) -> std::result::Result<usize, LogriaError> {
+        // Build every stream before replacing any, so a missing file leaves the streams as they were
+        let mut reloaded = vec![];
+        for (index, stream) in self.config.streams.iter().enumerate() {
+            if stream._type == "FileInput" {
+                let rebuilt = FileInput::build_with_options(
+                    stream.process_name.to_owned(),
+                    stream.source.to_owned(),
+                    &self.config.stream_options,
+                )?;
+                reloaded.push((index, rebuilt));
+            }
+        }
+        let count = reloaded.len();
+        if count == 0 {
+            return Ok(0);
+        }
+        let mut reloaded_sources = BTreeSet::new();
+        for (index, stream) in reloaded {
+            self.trace("stream", &format!("reloaded {}", stream.process_name));
+            // Stop following the file from where the old stream left off
+            *self.config.streams[index].should_die.lock().unwrap() = true;
+            self.config.streams[index] = stream;
+            reloaded_sources.insert(index);
+        }
+        let stdout_sources = &self.config.stdout_sources;
+        let mut position = 0;
+        self.config.stdout_messages.retain(|_| {
+            let keep = stdout_sources
+                .get(position)
+                .is_none_or(|source| !reloaded_sources.contains(source));
+            position += 1;
+            keep
+        });
+        self.config
+            .stdout_sources
+            .retain(|source| !reloaded_sources.contains(source));
+
+        // Indices into the old buffer no longer apply, so matches and parsed messages are rebuilt
+        self.reset_mutes();
+        self.config.matched_rows.clear();
+        self.config.last_index_regexed = 0;
+        self.config.last_index_processed = 0;
+        if self.config.parser_state == ParserState::Full {
+            self.config.auxiliary_messages.clear();
+        }
+        self.config.seen_up_to = None;
+        self.config.watermark = None;
+        self.config.frozen_at = None;
+        self.config.selection_start = None;
+        self.config.selection_end = None;
+        self.config.current_end = 0;
+        Ok(count)
+    }
+
     /// Determine a reasonable poll rate based on the speed of messages received
     fn handle_smart_poll_rate(&mut self, t_1: Duration, new_messages: u64) {
         if self.config.smart_poll_rate && !(self.input_type == InputType::Startup) {
@@ -686,22 +2029,93 @@ impl MainWindow {
         }
     }
 
+    /// Send a line of metrics to the metrics file if the interval has elapsed
+    fn handle_metrics(&mut self, new_messages: u64) {
+        if let Some(writer) = &mut self.config.metrics {
+            writer.record(new_messages);
+            if writer.should_write() {
+                let matched_messages = match self.config.regex_pattern {
+                    Some(_) => self.config.matched_rows.len(),
+                    None => 0,
+                };
+                writer.write(&MetricsSnapshot {
+                    timestamp: unix_timestamp(),
+                    lines_per_second: writer.lines_per_second(),
+                    stdout_messages: self.config.stdout_messages.len(),
+                    stderr_messages: self.config.stderr_messages.len(),
+                    matched_messages,
+                    poll_rate: self.config.poll_rate,
+                });
+            }
+        }
+    }
+
+    /// Exit the app if the inactivity timeout elapsed without messages or input
+    fn handle_inactivity(&mut self, had_activity: bool) -> Result<()> {
+        if let Some(timer) = &mut self.config.inactivity {
+            let now = Instant::now();
+            if had_activity {
+                timer.touch(now);
+            } else if timer.has_elapsed(now) {
+                self.quit()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stick to the end of the buffer again once there has been no input for the resume delay
+    ///
+    /// Returns `true` if the scroll state changed and the output needs to be rendered.
+    fn handle_resume_follow(&mut self, had_input: bool, now: Instant) -> bool {
+        if let Some(timer) = &mut self.config.resume_follow {
+            let following = matches!(self.config.scroll_state, ScrollState::Bottom)
+                || matches!(self.config.stream_type, StreamType::Auxiliary);
+            if had_input || following {
+                timer.touch(now);
+            } else if timer.has_elapsed(now) {
+                self.config.scroll_state = ScrollState::Bottom;
+                return true;
+            }
+        }
+        false
+    }
+
     /// Update poll rate of the main loop plus the child processes
     fn update_poll_rate(&mut self, new_poll_rate: u64) {
         self.config.poll_rate = new_poll_rate;
     }
 
+    /// Find the reason the app cannot run in a terminal, if there is one
+    fn environment_error(
+        &self,
+        stdin_tty: bool,
+        stdout_tty: bool,
+        terminal_size: Option<(u16, u16)>,
+    ) -> Option<&'static str> {
+        if !valid_tty(stdin_tty, stdout_tty, self.config.pipe) {
+            return Some(PIPE_INPUT_ERROR);
+        }
+
+        // A terminal that reports no size, i.e. a pty in CI, has nowhere to draw
+        if !terminal_size.is_some_and(|(w, h)| valid_size(w, h)) {
+            return Some(TERMINAL_TOO_SMALL_ERROR);
+        }
+        None
+    }
+
     fn validate_environment(&self) {
         // Ensure the tty is valid before doing any work
-        if !valid_tty() {
+        if let Some(error) =
+            self.environment_error(stdin().is_tty(), stdout().is_tty(), size().ok())
+        {
             /*
             Since we need to emit an error message, but the pipe to emit it through
             may be closed, we need to use a panic instead.
             This overrides the default panic handler to just emit a string, so it
             looks like a normal println
             */
-            panic::set_hook(Box::new(|_| {
-                println!("{}", PIPE_INPUT_ERROR);
+            panic::set_hook(Box::new(move |_| {
+                println!("{}", error);
             }));
 
             panic!();
@@ -713,25 +2127,7 @@ impl MainWindow {
         self.validate_environment();
 
         // Build the app
-        if let Some(c) = commands {
-            // Build streams from the command used to launch Logria
-            // If we cannot save to the disk, write to the command line and start without saving
-            let possible_streams = build_streams_from_input(&c, true);
-            match possible_streams {
-                Ok(streams) => self.config.streams = streams,
-                Err(why) => {
-                    self.write_to_command_line(&why.to_string())?;
-                    build_streams_from_input(&c, false).unwrap();
-                }
-            }
-
-            // Set to display stderr by default
-            self.config.previous_stream_type = StreamType::StdOut;
-            self.config.stream_type = StreamType::StdErr;
-
-            // Send input to normal handler
-            self.input_type = InputType::Normal;
-        }
+        self.open_initial_streams(commands)?;
 
         // Set UI Size
         self.update_dimensions()?;
@@ -748,34 +2144,229 @@ impl MainWindow {
         Ok(())
     }
 
+    /// Open the streams passed on the command line, or the saved session the app was launched with
+    ///
+    /// If there are neither, the app stays on the startup screen.
+    fn open_initial_streams(&mut self, commands: Option<Vec<String>>) -> Result<()> {
+        let mut workspace = None;
+        let mut attached = None;
+        let mut streams = match (commands, self.config.startup_session.take()) {
+            (Some(c), _) => {
+                self.config.session = Some(Session::new(&c, SessionType::Mixed));
+                // Build streams from the command used to launch Logria
+                // If we cannot save to the disk, write to the command line and start without saving
+                let possible_streams =
+                    build_streams_from_input(&c, true, &self.config.stream_options);
+                match possible_streams {
+                    Ok(streams) => streams,
+                    Err(why) => {
+                        self.write_to_command_line(&why.to_string())?;
+                        build_streams_from_input(&c, false, &self.config.stream_options).unwrap()
+                    }
+                }
+            }
+            (None, Some(mut session)) => {
+                workspace = session.workspace.take();
+                attached = session.parser.take();
+                let opened = session.clone();
+                match build_streams_from_session(session, &self.config.stream_options) {
+                    Ok(streams) => {
+                        self.config.session = Some(opened);
+                        streams
+                    }
+                    Err(why) => {
+                        // Fall back to the startup screen so another session can be chosen
+                        self.write_to_command_line(&why.to_string())?;
+                        return Ok(());
+                    }
+                }
+            }
+            (None, None) if self.config.pipe => vec![],
+            (None, None) => return Ok(()),
+        };
+        if self.config.pipe {
+            match StdinInput::build(String::from("stdin"), String::new()) {
+                Ok(stream) => streams.push(stream),
+                Err(why) => self.write_to_command_line(&why.to_string())?,
+            }
+        }
+        self.set_streams(streams);
+
+        // Set to display stderr by default, or stdout where piped messages are sent
+        if self.config.pipe {
+            self.config.previous_stream_type = StreamType::StdErr;
+            self.config.stream_type = StreamType::StdOut;
+        } else {
+            self.config.previous_stream_type = StreamType::StdOut;
+            self.config.stream_type = StreamType::StdErr;
+        }
+
+        // Send input to normal handler
+        self.input_type = InputType::Normal;
+        if let Some(workspace) = workspace {
+            self.apply_workspace(&workspace)?;
+        }
+        if let Some(attached) = attached {
+            self.attach_parser(&attached)?;
+        }
+        Ok(())
+    }
+
     /// Immediately exit the program
     pub fn quit(&mut self) -> Result<()> {
         execute!(stdout(), cursor::Show, Clear(ClearType::All))?;
         disable_raw_mode()?;
         for stream in &self.config.streams {
+            self.trace("stream", &format!("stopped {}", stream.process_name));
             *stream.should_die.lock().unwrap() = true;
         }
+        // Lines are written on another thread, so wait for them before exiting
+        if let Some(tracer) = self.config.tracer.take() {
+            tracer.finish();
+        }
         std::process::exit(0);
     }
 
     /// Update stderr and stdout buffers from every stream's queue
     fn receive_streams(&mut self) -> u64 {
         let mut total_messages = 0;
-        for stream in &self.config.streams {
+        for (source, stream) in self.config.streams.iter().enumerate() {
             // Read from streams until there is no more input
             // ? May lock if logs come in too fast
             while let Ok(data) = stream.stderr.try_recv() {
                 total_messages += 1;
-                self.config.stderr_messages.push(data);
+                let message = self.store_message(data);
+                self.config.stderr_messages.push(message);
+                self.config.stderr_sources.push(source);
             }
             while let Ok(data) = stream.stdout.try_recv() {
                 total_messages += 1;
-                self.config.stdout_messages.push(data);
+                let message = self.store_message(data);
+                self.config.stdout_messages.push(message);
+                self.config.stdout_sources.push(source);
             }
         }
+        self.enforce_buffer_limits();
+        self.process_mutes();
         total_messages
     }
 
+    /// Drop the oldest messages from the stderr and stdout buffers if they are over the limit
+    pub fn enforce_buffer_limits(&mut self) {
+        self.enforce_buffer_limit(StreamType::StdErr);
+        self.enforce_buffer_limit(StreamType::StdOut);
+    }
+
+    /// Drop the oldest messages from a stream's buffer once it holds more than `max_buffer_lines`
+    ///
+    /// Indices into the buffer are shifted so they still point at the same messages
+    fn enforce_buffer_limit(&mut self, stream_type: StreamType) {
+        let limit = match self.config.max_buffer_lines {
+            Some(limit) => limit,
+            None => return,
+        };
+        let (buffer, sources) = match stream_type {
+            StreamType::StdErr => (
+                &mut self.config.stderr_messages,
+                &mut self.config.stderr_sources,
+            ),
+            StreamType::StdOut => (
+                &mut self.config.stdout_messages,
+                &mut self.config.stdout_sources,
+            ),
+            StreamType::Auxiliary => return,
+        };
+        let dropped = buffer.len().saturating_sub(limit);
+        if dropped == 0 {
+            return;
+        }
+        buffer.drain(..dropped);
+        sources.drain(..min(dropped, sources.len()));
+
+        // The regex filters the stream being displayed
+        if self.config.stream_type == stream_type {
+            let dropped_matches = self
+                .config
+                .matched_rows
+                .partition_point(|&row| row < dropped);
+            self.config.matched_rows.drain(..dropped_matches);
+            self.config
+                .matched_rows
+                .iter_mut()
+                .for_each(|row| *row -= dropped);
+            self.config.last_index_regexed = self.config.last_index_regexed.saturating_sub(dropped);
+            let dropped_unmuted = self
+                .config
+                .unmuted_rows
+                .partition_point(|&row| row < dropped);
+            self.config.unmuted_rows.drain(..dropped_unmuted);
+            self.config
+                .unmuted_rows
+                .iter_mut()
+                .for_each(|row| *row -= dropped);
+            self.config.last_index_unmuted = self.config.last_index_unmuted.saturating_sub(dropped);
+            self.config.watermark = self
+                .config
+                .watermark
+                .map(|watermark| watermark.saturating_sub(dropped));
+
+            // Indices into the rendered messages shift by the number of rendered messages dropped
+            let removed_rows = match self.config.regex_pattern {
+                Some(_) => dropped_matches,
+                None if self.muting_active() => dropped_unmuted,
+                None => dropped,
+            };
+            self.config.seen_up_to = self
+                .config
+                .seen_up_to
+                .map(|seen| seen.saturating_sub(removed_rows));
+            self.config.frozen_at = self
+                .config
+                .frozen_at
+                .map(|frozen_at| frozen_at.saturating_sub(removed_rows));
+            // A selection whose messages were all dropped is cleared rather than moved to the first message
+            match (self.config.selection_start, self.config.selection_end) {
+                (Some(start), Some(end)) if start < removed_rows && end < removed_rows => {
+                    self.config.selection_start = None;
+                    self.config.selection_end = None;
+                }
+                (start, end) => {
+                    self.config.selection_start = start.map(|row| row.saturating_sub(removed_rows));
+                    self.config.selection_end = end.map(|row| row.saturating_sub(removed_rows));
+                }
+            }
+
+            // Keep a scrolled view on the same messages
+            if !matches!(self.config.scroll_state, ScrollState::Bottom) {
+                self.config.current_end = self.config.current_end.saturating_sub(removed_rows);
+            }
+        }
+
+        // Parsers read the stream that was displayed before the parsed messages
+        if self.config.stream_type == StreamType::Auxiliary
+            && self.config.previous_stream_type == stream_type
+        {
+            self.config.last_index_processed =
+                self.config.last_index_processed.saturating_sub(dropped);
+        }
+    }
+
+    /// Clean up a message from a stream before it is stored
+    fn store_message(&self, data: String) -> String {
+        // Cursor movement would corrupt the window, so it is removed before storing the message
+        let message = self.control_sequences.strip(data);
+        // Pathologically long messages are cut so they cannot bloat memory or rendering
+        match self.config.max_line_length {
+            Some(max) => elide(message, max),
+            None => message,
+        }
+    }
+
+    /// Whether a key event should be sent to the input handlers
+    fn is_dispatched(event: &KeyEvent) -> bool {
+        event.kind == KeyEventKind::Press
+    }
+
     /// Main app loop
     fn main(&mut self) -> Result<()> {
         // Exit event
@@ -794,9 +2385,11 @@ impl MainWindow {
         let mut parser_handler = ParserHandler::new();
         let mut startup_handler = StartupHandler::new();
 
-        // Setup startup messages
-        self.config.generate_auxiliary_messages = Some(StartupHandler::get_startup_text);
-        self.render_auxiliary_text()?;
+        // Setup startup messages, unless the streams were opened at launch
+        if self.input_type == InputType::Startup {
+            self.config.generate_auxiliary_messages = Some(StartupHandler::get_startup_text);
+            self.render_auxiliary_text()?;
+        }
 
         // Put the cursor in the command line
         self.go_to_cli()?;
@@ -817,31 +2410,42 @@ impl MainWindow {
             // Update streams and poll rate
             let num_new_messages = self.receive_streams();
             self.handle_smart_poll_rate(self.config.loop_time.elapsed(), num_new_messages);
+            self.handle_metrics(num_new_messages);
+            let mut had_input = false;
 
             if poll(Duration::from_millis(self.config.poll_rate))? {
                 match read()? {
                     Event::Key(input) => {
+                        // Some terminals also emit release events, which would double every key
+                        if !MainWindow::is_dispatched(&input) {
+                            continue;
+                        }
+                        had_input = true;
+
                         // Die on Ctrl-C
                         if input == exit_key {
                             self.quit()?;
                         }
 
+                        // An open status overlay takes keys until it is closed
+                        if self.handle_status_overlay(input.code)? {
+                            continue;
+                        }
+
                         // Otherwise, match input to action
                         match self.input_type {
-                            InputType::Normal => normal_handler.receive_input(self, input.code)?,
-                            InputType::Command => {
-                                command_handler.receive_input(self, input.code)?
-                            }
-                            InputType::Regex => regex_handler.receive_input(self, input.code)?,
-                            InputType::Parser => parser_handler.receive_input(self, input.code)?,
-                            InputType::Startup => {
-                                startup_handler.receive_input(self, input.code)?
-                            }
+                            InputType::Normal => normal_handler.receive_input(self, input)?,
+                            InputType::Command => command_handler.receive_input(self, input)?,
+                            InputType::Regex => regex_handler.receive_input(self, input)?,
+                            InputType::Parser => parser_handler.receive_input(self, input)?,
+                            InputType::Startup => startup_handler.receive_input(self, input)?,
                         }
                     }
                     Event::Mouse(_) => {} // Probably remove
                     Event::Resize(_, _) => {
                         self.update_dimensions()?;
+                        // The overlay was cut to the old width
+                        self.config.status_overlay = None;
                         self.redraw()?;
                     }
                     Event::FocusGained => {}
@@ -850,8 +2454,16 @@ impl MainWindow {
                 }
             }
 
-            // Process matches if we just switched or if there are new messages
-            if num_new_messages > 0 || self.config.did_switch {
+            // Exit unattended sessions once nothing has happened for long enough
+            self.handle_inactivity(num_new_messages > 0 || had_input)?;
+
+            // Follow new messages again if the user stopped scrolling
+            if self.handle_resume_follow(had_input, Instant::now()) && num_new_messages == 0 {
+                self.render_text_in_output()?;
+            }
+
+            // Process matches if we just switched, if there are new messages, or if the aggregation display changed
+            if num_new_messages > 0 || self.config.did_switch || self.config.rerender_aggregation {
                 // Process extension methods
                 match self.input_type {
                     InputType::Regex => {
@@ -875,18 +2487,91 @@ impl MainWindow {
                     }
                     _ => {}
                 }
+                // Outside of an aggregating parser there is no panel to rebuild
+                self.config.rerender_aggregation = false;
                 self.render_text_in_output()?;
+                self.render_status_overlay()?;
             }
         }
     }
 }
 
 #[cfg(test)]
-mod render_tests {
-    use crate::{communication::reader::MainWindow, ui::scroll::ScrollState};
+mod resume_follow_tests {
+    use std::time::{Duration, Instant};
 
-    #[test]
-    fn test_render_final_items() {
+    use crate::{
+        communication::{input::StreamType, reader::MainWindow},
+        ui::scroll::ScrollState,
+        util::inactivity::InactivityTimer,
+    };
+
+    fn window(now: Instant) -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        let mut timer = InactivityTimer::new(Duration::from_secs(5));
+        timer.touch(now);
+        logria.config.resume_follow = Some(timer);
+        logria
+    }
+
+    #[test]
+    fn test_resume_disabled() {
+        let start = Instant::now();
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = ScrollState::Free;
+        assert!(!logria.handle_resume_follow(false, start + Duration::from_secs(60)));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+    }
+
+    #[test]
+    fn test_resume_after_delay() {
+        let start = Instant::now();
+        let mut logria = window(start);
+        logria.config.scroll_state = ScrollState::Free;
+        assert!(!logria.handle_resume_follow(false, start + Duration::from_secs(4)));
+        assert!(logria.handle_resume_follow(false, start + Duration::from_secs(5)));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Bottom));
+    }
+
+    #[test]
+    fn test_resume_delayed_by_scrolling() {
+        let start = Instant::now();
+        let mut logria = window(start);
+        logria.config.scroll_state = ScrollState::Top;
+        assert!(!logria.handle_resume_follow(true, start + Duration::from_secs(4)));
+        assert!(!logria.handle_resume_follow(false, start + Duration::from_secs(8)));
+        assert!(logria.handle_resume_follow(false, start + Duration::from_secs(9)));
+    }
+
+    #[test]
+    fn test_resume_delay_starts_when_leaving_bottom() {
+        let start = Instant::now();
+        let mut logria = window(start);
+        assert!(!logria.handle_resume_follow(false, start + Duration::from_secs(60)));
+
+        // Leaving the bottom long after the timer started does not resume right away
+        logria.config.scroll_state = ScrollState::Free;
+        assert!(!logria.handle_resume_follow(true, start + Duration::from_secs(61)));
+        assert!(!logria.handle_resume_follow(false, start + Duration::from_secs(62)));
+    }
+
+    #[test]
+    fn test_resume_skips_auxiliary() {
+        let start = Instant::now();
+        let mut logria = window(start);
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.scroll_state = ScrollState::Top;
+        assert!(!logria.handle_resume_follow(false, start + Duration::from_secs(60)));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Top));
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use crate::{communication::reader::MainWindow, ui::scroll::ScrollState};
+
+    #[test]
+    fn test_render_final_items() {
         let mut logria = MainWindow::_new_dummy();
 
         // Set scroll state
@@ -941,6 +2626,65 @@ mod render_tests {
         assert_eq!(end, 80);
     }
 
+    #[test]
+    fn test_render_centered() {
+        let mut logria = MainWindow::_new_dummy();
+
+        // Set scroll state
+        logria.config.scroll_state = ScrollState::Centered;
+
+        // Set the centered message
+        logria.config.current_end = 50;
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 47);
+        assert_eq!(end, 54);
+        assert_eq!(logria.config.current_end, 50);
+    }
+
+    #[test]
+    fn test_render_centered_wrapped() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = ScrollState::Centered;
+
+        // The messages next to the centered message each take two rows
+        logria.config.stderr_messages[49] = "a".repeat(150);
+        logria.config.stderr_messages[51] = "b".repeat(150);
+
+        logria.config.current_end = 50;
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 48);
+        assert_eq!(end, 53);
+    }
+
+    #[test]
+    fn test_render_centered_wrapped_one_side() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = ScrollState::Centered;
+
+        // The message above takes three rows, so three short messages balance it below
+        logria.config.stderr_messages[49] = "a".repeat(250);
+
+        logria.config.current_end = 50;
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 49);
+        assert_eq!(end, 54);
+    }
+
+    #[test]
+    fn test_render_centered_near_edges() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = ScrollState::Centered;
+
+        // Without room above, the rows below are filled instead
+        logria.config.current_end = 1;
+        assert_eq!(logria.determine_render_position(), (0, 7));
+
+        // Past the last message, the last message is centered
+        logria.config.current_end = 150;
+        assert_eq!(logria.determine_render_position(), (93, 100));
+        assert_eq!(logria.config.current_end, 99);
+    }
+
     #[test]
     fn test_render_from_middle_early() {
         let mut logria = MainWindow::_new_dummy();
@@ -1012,92 +2756,1986 @@ mod render_tests {
 }
 
 #[cfg(test)]
-mod poll_rate_tests {
-    use crate::communication::{input::InputType, reader::MainWindow};
-    use std::time::Duration;
+mod highlight_tests {
+    use regex::bytes::Regex;
+
+    use crate::communication::reader::MainWindow;
 
     #[test]
-    fn test_no_poll_rate_change_when_disabled() {
+    fn test_highlight_plain() {
         let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("word").unwrap());
+        assert_eq!(logria.highlight_match("a word"), "a \x1b[35mword\x1b[0m");
+    }
 
-        // Disable smart polling
-        logria.config.smart_poll_rate = false;
+    #[test]
+    fn test_highlight_through_colors() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("INFO w").unwrap());
+        assert_eq!(
+            logria.highlight_match("\x1b[32mINFO\x1b[0m word"),
+            "\x1b[32m\x1b[35mINFO\x1b[0m w\x1b[0mord"
+        );
+    }
 
-        // Update the poll rate for 100ms
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 10);
+    #[test]
+    fn test_highlight_after_colors() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("word").unwrap());
+        assert_eq!(
+            logria.highlight_match("\x1b[32mINFO\x1b[0m word"),
+            "\x1b[32mINFO\x1b[0m \x1b[35mword\x1b[0m"
+        );
+    }
+}
 
-        assert_eq!(logria.config.poll_rate, 50);
+#[cfg(test)]
+mod diagnostics_tests {
+    use crate::communication::reader::MainWindow;
+
+    #[test]
+    fn test_diagnostics() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.matched_rows = (0..10).collect();
+        logria.config.auxiliary_messages = vec![String::from("four")];
+
+        assert_eq!(
+            logria.diagnostics(),
+            vec![
+                "Buffers",
+                "    stderr messages: 100 (190 bytes)",
+                "    stdout messages: 0 (0 bytes)",
+                "    auxiliary messages: 1 (4 bytes)",
+                "    matched rows: 10 (80 bytes)",
+                "App",
+                "    streams: 0",
+                "    poll rate: 50ms",
+            ]
+        );
     }
 
     #[test]
-    fn test_poll_rate_change_when_enabled_100ms_10messages() {
+    fn test_diagnostics_large_buffer() {
         let mut logria = MainWindow::_new_dummy();
-        logria.input_type = InputType::Normal;
+        logria.config.stdout_messages = (0..2000).map(|_| String::from("message")).collect();
 
-        // Test default value
-        assert_eq!(logria.config.poll_rate, 50);
+        assert_eq!(
+            logria.diagnostics()[2],
+            "    stdout messages: 2,000 (14,000 bytes)"
+        );
+    }
+}
 
-        // Update the poll rate
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 10);
+#[cfg(test)]
+mod run_diff_tests {
+    use crate::communication::{input::StreamType, reader::MainWindow};
 
-        assert_eq!(logria.config.poll_rate, 10);
+    #[test]
+    fn test_mark_run() {
+        let mut logria = MainWindow::_new_dummy();
+        assert_eq!(logria.mark_run(), 100);
+
+        let diff = logria.config.run_diff.as_ref().unwrap();
+        assert!(!diff.is_added("99"));
+        assert!(diff.is_added("100"));
     }
 
     #[test]
-    fn test_poll_rate_change_when_enabled_50ms_50messages() {
+    fn test_diff_inactive_until_toggled() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.mark_run();
+        assert!(logria.active_diff().is_none());
+
+        logria.config.run_diff.as_mut().unwrap().active = true;
+        assert!(logria.active_diff().is_some());
+    }
+
+    #[test]
+    fn test_diff_skips_auxiliary() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.mark_run();
+        logria.config.run_diff.as_mut().unwrap().active = true;
+        logria.config.stream_type = StreamType::Auxiliary;
+        assert!(logria.active_diff().is_none());
+    }
+}
+
+#[cfg(test)]
+mod mute_tests {
+    use std::{
+        sync::{
+            mpsc::{channel, Sender},
+            Arc, Mutex,
+        },
+        thread,
+    };
+
+    use crate::communication::{
+        input::{InputStream, InputType},
+        reader::MainWindow,
+    };
+
+    /// Build a window with a stream for each name that sends its messages to stderr
+    fn window(names: &[&str]) -> (MainWindow, Vec<Sender<String>>) {
         let mut logria = MainWindow::_new_dummy();
         logria.input_type = InputType::Normal;
+        logria.config.stderr_messages.clear();
+        let mut senders = vec![];
+        let streams = names
+            .iter()
+            .map(|name| {
+                let (_out_tx, out_rx) = channel();
+                let (err_tx, err_rx) = channel();
+                senders.push(err_tx);
+                InputStream {
+                    stdout: out_rx,
+                    stderr: err_rx,
+                    process_name: name.to_string(),
+                    source: name.to_string(),
+                    process: thread::Builder::new().spawn(|| {}),
+                    should_die: Arc::new(Mutex::new(false)),
+                    _type: String::from("CommandInput"),
+                }
+            })
+            .collect();
+        logria.set_streams(streams);
+        (logria, senders)
+    }
 
-        // Test default value
-        assert_eq!(logria.config.poll_rate, 50);
+    fn send(senders: &[Sender<String>], stream: usize, messages: &[&str]) {
+        for message in messages {
+            senders[stream].send(message.to_string()).unwrap();
+        }
+    }
 
-        // Update the poll rate
-        logria.handle_smart_poll_rate(Duration::new(0, 50000000), 5);
+    #[test]
+    fn test_mute_hides_stream() {
+        let (mut logria, senders) = window(&["a", "b"]);
+        send(&senders, 0, &["a0", "a1"]);
+        send(&senders, 1, &["b0", "b1"]);
+        logria.receive_streams();
+
+        assert!(logria.set_muted(1, true));
+        assert_eq!(logria.number_of_messages(), 2);
+        assert_eq!(logria.messages_in_range(0, 2), vec!["a0", "a1"]);
+        assert_eq!(logria.export_messages(), vec!["a0", "a1"]);
+    }
 
-        assert_eq!(logria.config.poll_rate, 10);
+    #[test]
+    fn test_mute_keeps_buffer_indices() {
+        let (mut logria, senders) = window(&["a", "b"]);
+        send(&senders, 0, &["a0"]);
+        send(&senders, 1, &["b0", "b1"]);
+        logria.receive_streams();
+        send(&senders, 0, &["a1"]);
+        logria.receive_streams();
+
+        logria.set_muted(1, true);
+        assert_eq!(logria.config.unmuted_rows, vec![0, 3]);
+        assert_eq!(logria.line_number(1), 3);
+        assert_eq!(logria.messages().len(), 4);
     }
 
     #[test]
-    fn test_poll_rate_change_when_enabled_idle() {
+    fn test_mute_filters_new_messages() {
+        let (mut logria, senders) = window(&["a", "b"]);
+        logria.set_muted(0, true);
+        send(&senders, 0, &["a0", "a1"]);
+        send(&senders, 1, &["b0"]);
+        logria.receive_streams();
+
+        assert_eq!(logria.messages_in_range(0, 10), vec!["b0"]);
+    }
+
+    #[test]
+    fn test_unmute_restores_stream() {
+        let (mut logria, senders) = window(&["a", "b"]);
+        send(&senders, 0, &["a0"]);
+        send(&senders, 1, &["b0"]);
+        logria.receive_streams();
+
+        logria.set_muted(0, true);
+        assert!(logria.set_muted(0, false));
+        assert!(!logria.muting_active());
+        assert_eq!(logria.messages_in_range(0, 10), vec!["a0", "b0"]);
+    }
+
+    #[test]
+    fn test_mute_with_buffer_limit() {
+        let (mut logria, senders) = window(&["a", "b"]);
+        logria.config.max_buffer_lines = Some(3);
+        logria.set_muted(1, true);
+        send(&senders, 0, &["a0", "a1"]);
+        send(&senders, 1, &["b0", "b1"]);
+        logria.receive_streams();
+
+        assert_eq!(logria.messages(), &vec!["a1", "b0", "b1"]);
+        assert_eq!(logria.config.unmuted_rows, vec![0]);
+        assert_eq!(logria.messages_in_range(0, 10), vec!["a1"]);
+    }
+
+    #[test]
+    fn test_cannot_mute_missing_stream() {
+        let (mut logria, _) = window(&["a"]);
+        assert!(!logria.set_muted(1, true));
+        assert!(logria.set_muted(0, true));
+        assert!(!logria.set_muted(0, true));
+    }
+
+    #[test]
+    fn test_describe_streams() {
+        let (mut logria, _) = window(&["a", "b"]);
+        logria.set_muted(1, true);
+        assert_eq!(logria.describe_streams(), vec!["0: a", "1: b (muted)"]);
+    }
+}
+
+#[cfg(test)]
+mod line_length_tests {
+    use std::{
+        sync::{mpsc::channel, Arc, Mutex},
+        thread,
+    };
+
+    use crate::communication::{input::InputStream, reader::MainWindow};
+
+    /// Build a window whose only stream has already sent `messages` to stdout
+    fn receive(max_line_length: Option<usize>, messages: &[String]) -> Vec<String> {
+        let (out_tx, out_rx) = channel();
+        let (_err_tx, err_rx) = channel();
+        for message in messages {
+            out_tx.send(message.to_owned()).unwrap();
+        }
         let mut logria = MainWindow::_new_dummy();
-        logria.input_type = InputType::Normal;
+        logria.config.max_line_length = max_line_length;
+        logria.config.streams = vec![InputStream {
+            stdout: out_rx,
+            stderr: err_rx,
+            process_name: String::from("test"),
+            source: String::from("test"),
+            process: thread::Builder::new().spawn(|| {}),
+            should_die: Arc::new(Mutex::new(false)),
+            _type: String::from("CommandInput"),
+        }];
+        logria.receive_streams();
+        logria.config.stdout_messages
+    }
 
-        // Test default value
-        assert_eq!(logria.config.poll_rate, 50);
+    #[test]
+    fn test_long_line_elided() {
+        let stored = receive(Some(10), &["x".repeat(5000)]);
+        assert_eq!(stored, vec!["xxxxxxxxxx… [5000 chars]"]);
+    }
 
-        // Update the poll rate
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
-        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+    #[test]
+    fn test_short_line_kept() {
+        let stored = receive(Some(10), &[String::from("short")]);
+        assert_eq!(stored, vec!["short"]);
+    }
 
-        assert_eq!(logria.config.poll_rate, 1000);
+    #[test]
+    fn test_no_limit_by_default() {
+        let stored = receive(None, &["x".repeat(5000)]);
+        assert_eq!(stored[0].len(), 5000);
+    }
+}
+
+#[cfg(test)]
+mod trace_tests {
+    use std::sync::mpsc::{channel, Receiver};
+
+    use crate::{
+        communication::{
+            input::{FileInput, Input, InputType},
+            reader::MainWindow,
+        },
+        util::trace::Tracer,
+    };
+
+    fn traced_window() -> (MainWindow, Receiver<String>) {
+        let (sender, receiver) = channel();
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.tracer = Some(Tracer::with_sender(sender));
+        (logria, receiver)
+    }
+
+    /// Get the traced lines without their timestamps
+    fn events(receiver: &Receiver<String>) -> Vec<String> {
+        receiver
+            .try_iter()
+            .map(|line| line.split_once(' ').unwrap().1.to_string())
+            .collect()
     }
 
     #[test]
-    fn test_poll_rate_change_when_enabled_idle_multiple() {
+    fn test_trace_actions() {
+        let (mut logria, receiver) = traced_window();
+        let stream = FileInput::build(String::from("README.md"), String::from("README.md"));
+        logria.set_streams(vec![stream.unwrap()]);
+        logria.update_input_type(InputType::Normal).unwrap();
+        logria.update_input_type(InputType::Regex).unwrap();
+        logria.write_to_command_line("Invalid regex: /(/").unwrap();
+
+        assert_eq!(
+            events(&receiver),
+            vec![
+                "stream started README.md (FileInput)",
+                "mode Startup -> Normal",
+                "mode Normal -> Regex",
+                "message Invalid regex: /(/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_trace_by_default() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.update_input_type(InputType::Normal).unwrap();
+        assert!(logria.config.tracer.is_none());
+    }
+}
+
+#[cfg(test)]
+mod status_overlay_tests {
+    use crossterm::event::KeyCode;
+
+    use crate::communication::reader::MainWindow;
+
+    const STATUS: &str = "Regex with pattern /(ERROR|FATAL).*(connection refused|timed out after \\d+ms|reset by peer)/ matching 12,345 of 1,000,000 messages";
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.width = 20;
+        logria.config.current_status = Some(STATUS.to_string());
+        logria
+    }
+
+    #[test]
+    fn test_overlay_shows_full_status() {
+        let mut logria = window();
+        assert!(logria.open_status_overlay().unwrap());
+        assert_eq!(
+            logria
+                .config
+                .status_overlay
+                .as_ref()
+                .unwrap()
+                .visible(100)
+                .concat(),
+            STATUS
+        );
+    }
+
+    #[test]
+    fn test_overlay_needs_status() {
+        let mut logria = window();
+        logria.config.current_status = None;
+        assert!(!logria.open_status_overlay().unwrap());
+        assert!(logria.config.status_overlay.is_none());
+    }
+
+    #[test]
+    fn test_overlay_scrolls() {
+        let mut logria = window();
+        logria.open_status_overlay().unwrap();
+        assert!(logria.handle_status_overlay(KeyCode::Down).unwrap());
+        let overlay = logria.config.status_overlay.as_ref().unwrap();
+        assert_eq!(overlay.visible(1)[0], STATUS[20..40]);
+    }
+
+    #[test]
+    fn test_overlay_closes_on_any_key() {
+        let mut logria = window();
+        logria.open_status_overlay().unwrap();
+        assert!(logria.handle_status_overlay(KeyCode::Char('q')).unwrap());
+        assert!(logria.config.status_overlay.is_none());
+        assert!(!logria.handle_status_overlay(KeyCode::Char('q')).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod status_tests {
+    use regex::bytes::Regex;
+
+    use crate::{
+        communication::{
+            handlers::parser::ParserState,
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        ui::scroll::ScrollState,
+    };
+
+    #[test]
+    fn test_status_default() {
         let mut logria = MainWindow::_new_dummy();
         logria.input_type = InputType::Normal;
+        assert_eq!(
+            logria.status_report(),
+            vec![
+                "Status",
+                "    input: Normal",
+                "    stream: StdErr",
+                "    regex: off",
+                "    parser: off",
+                "    aggregation: off",
+                "    scroll: Bottom",
+                "    frozen: off",
+                "    poll rate: 50ms",
+            ]
+        );
+    }
 
-        // Test default value
-        assert_eq!(logria.config.poll_rate, 50);
+    #[test]
+    fn test_status_configured() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Regex;
+        logria.config.stream_type = StreamType::StdOut;
+        logria.config.regex_pattern = Some(Regex::new("ERROR").unwrap());
+        logria.config.matched_rows = (0..1500).collect();
+        logria.config.parser_state = ParserState::Full;
+        logria.config.parser_indices = vec![2];
+        logria.config.aggregation_enabled = true;
+        logria.config.poll_rate = 120;
+        logria.config.scroll_state = ScrollState::Free;
 
-        // Update the poll rate
-        logria.handle_smart_poll_rate(Duration::new(0, 10000000), 1);
+        assert_eq!(
+            logria.status_report(),
+            vec![
+                "Status",
+                "    input: Regex",
+                "    stream: StdOut",
+                "    regex: /ERROR/ (1,500 matches)",
+                "    parser: index 2",
+                "    aggregation: on",
+                "    scroll: Free",
+                "    frozen: off",
+                "    poll rate: 120ms",
+            ]
+        );
+    }
 
-        assert_eq!(logria.config.poll_rate, 10);
+    #[test]
+    fn test_status_parser_indices() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.parser_state = ParserState::Full;
+        logria.config.parser_indices = vec![1, 3];
 
-        // Update the poll rate
-        logria.handle_smart_poll_rate(Duration::new(0, 10000000), 1);
+        assert_eq!(logria.status_report()[4], "    parser: indices 1, 3");
+    }
 
-        assert_eq!(logria.config.poll_rate, 10);
+    #[test]
+    fn test_status_parser_setup() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.parser_state = ParserState::NeedsIndex;
+        assert_eq!(logria.status_report()[4], "    parser: NeedsIndex");
+    }
+}
 
-        // Update the poll rate, don't go to 1000
-        logria.handle_smart_poll_rate(Duration::new(0, 10000000), 0);
+#[cfg(test)]
+mod dispatch_tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
 
-        assert_eq!(logria.config.poll_rate, 13);
+    use crate::communication::reader::MainWindow;
+
+    fn key_event(kind: KeyEventKind) -> KeyEvent {
+        KeyEvent {
+            code: KeyCode::Char('0'),
+            modifiers: KeyModifiers::NONE,
+            kind,
+            state: KeyEventState::NONE,
+        }
+    }
+
+    #[test]
+    fn test_press_dispatched() {
+        assert!(MainWindow::is_dispatched(&key_event(KeyEventKind::Press)));
+    }
+
+    #[test]
+    fn test_release_dropped() {
+        assert!(!MainWindow::is_dispatched(&key_event(
+            KeyEventKind::Release
+        )));
+    }
+
+    #[test]
+    fn test_repeat_dropped() {
+        assert!(!MainWindow::is_dispatched(&key_event(KeyEventKind::Repeat)));
+    }
+}
+
+#[cfg(test)]
+mod split_tests {
+    use crate::communication::{input::InputType, reader::MainWindow};
+
+    #[test]
+    fn test_split_inactive_by_default() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        assert!(!logria.split_active());
+    }
+
+    #[test]
+    fn test_split_active() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.split_view = true;
+        assert!(logria.split_active());
+    }
+
+    #[test]
+    fn test_split_not_active_with_regex() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Regex;
+        logria.config.split_view = true;
+        logria.config.regex_pattern = Some(regex::bytes::Regex::new("0").unwrap());
+        assert!(!logria.split_active());
+    }
+}
+
+#[cfg(test)]
+mod empty_buffer_tests {
+    use crate::{
+        communication::{input::InputType, reader::MainWindow},
+        constants::cli::messages::{NO_MESSAGE_IN_BUFFER_NORMAL, NO_MESSAGE_IN_BUFFER_PARSER},
+    };
+
+    #[test]
+    fn test_default_placeholder() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        assert_eq!(
+            logria.empty_buffer_text(),
+            Some(NO_MESSAGE_IN_BUFFER_NORMAL)
+        );
+
+        logria.input_type = InputType::Parser;
+        assert_eq!(
+            logria.empty_buffer_text(),
+            Some(NO_MESSAGE_IN_BUFFER_PARSER)
+        );
+    }
+
+    #[test]
+    fn test_overridden_placeholder() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.text.no_messages = Some(String::from("Nothing yet"));
+        logria.config.text.no_messages_parser = Some(String::from("Nothing parsed"));
+        assert_eq!(logria.empty_buffer_text(), Some("Nothing yet"));
+
+        logria.input_type = InputType::Parser;
+        assert_eq!(logria.empty_buffer_text(), Some("Nothing parsed"));
+    }
+
+    #[test]
+    fn test_no_placeholder_in_regex_mode() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Regex;
+        logria.config.text.no_messages = Some(String::from("Nothing yet"));
+        assert_eq!(logria.empty_buffer_text(), None);
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use crate::{
+        communication::{
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        ui::scroll::ScrollState,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn test_snapshot_captures_rendered_lines() {
+        let mut logria = window();
+        logria.take_snapshot();
+
+        let expected: Vec<String> = (93..100).map(|x| x.to_string()).collect();
+        assert_eq!(logria.config.snapshot.unwrap().messages, expected);
+    }
+
+    #[test]
+    fn test_snapshot_captures_matched_lines() {
+        let mut logria = window();
+        logria.config.regex_pattern = Some(regex::bytes::Regex::new("5").unwrap());
+        logria.config.matched_rows = vec![5, 15, 25, 50];
+        logria.take_snapshot();
+
+        assert_eq!(
+            logria.config.snapshot.unwrap().messages,
+            vec!["5", "15", "25", "50"]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_ignores_new_messages() {
+        let mut logria = window();
+        logria.take_snapshot();
+        logria.config.stderr_messages.push(String::from("new"));
+
+        assert!(logria.toggle_snapshot());
+        assert!(logria.viewing_snapshot());
+        assert_eq!(logria.messages().len(), 7);
+        assert_eq!(logria.messages().last().unwrap(), "99");
+    }
+
+    #[test]
+    fn test_snapshot_toggle_returns_to_live() {
+        let mut logria = window();
+        logria.take_snapshot();
+
+        assert!(logria.toggle_snapshot());
+        logria.config.scroll_state = ScrollState::Free;
+        logria.config.current_end = 3;
+
+        assert!(logria.toggle_snapshot());
+        assert!(!logria.viewing_snapshot());
+        assert!(matches!(logria.config.stream_type, StreamType::StdErr));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Bottom));
+
+        // The position in the snapshot is kept
+        assert!(logria.toggle_snapshot());
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.config.current_end, 3);
+    }
+
+    #[test]
+    fn test_snapshot_toggle_without_snapshot() {
+        let mut logria = window();
+        assert!(!logria.toggle_snapshot());
+        assert!(matches!(logria.config.stream_type, StreamType::StdErr));
+    }
+
+    #[test]
+    fn test_snapshot_toggle_from_other_auxiliary_view() {
+        let mut logria = window();
+        logria.take_snapshot();
+        logria.config.stream_type = StreamType::Auxiliary;
+        assert!(!logria.toggle_snapshot());
+        assert!(!logria.viewing_snapshot());
+    }
+}
+
+#[cfg(test)]
+mod divider_tests {
+    use crate::{
+        communication::{input::InputType, reader::MainWindow},
+        ui::scroll::ScrollState,
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.new_message_divider = true;
+        logria
+    }
+
+    fn receive(logria: &mut MainWindow, count: usize) {
+        (0..count).for_each(|_| logria.config.stderr_messages.push(String::from("new")));
+    }
+
+    fn render(logria: &mut MainWindow) -> Option<usize> {
+        let (start, end) = logria.determine_render_position();
+        logria.update_seen_up_to(start, end);
+        logria.new_message_divider(start, end)
+    }
+
+    #[test]
+    fn test_divider_disabled() {
+        let mut logria = window();
+        logria.config.new_message_divider = false;
+        logria.config.scroll_state = ScrollState::Top;
+        assert_eq!(render(&mut logria), None);
+        assert_eq!(logria.config.seen_up_to, None);
+    }
+
+    #[test]
+    fn test_divider_no_marker_at_bottom() {
+        let mut logria = window();
+        assert_eq!(render(&mut logria), None);
+        assert_eq!(logria.config.seen_up_to, None);
+    }
+
+    #[test]
+    fn test_divider_marks_when_scrolled_away() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        assert_eq!(render(&mut logria), None);
+        assert_eq!(logria.config.seen_up_to, Some(100));
+
+        // New messages do not move the marker
+        receive(&mut logria, 5);
+        render(&mut logria);
+        assert_eq!(logria.config.seen_up_to, Some(100));
+    }
+
+    #[test]
+    fn test_divider_shown_after_return() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        render(&mut logria);
+
+        receive(&mut logria, 3);
+        logria.config.scroll_state = ScrollState::Bottom;
+        assert_eq!(render(&mut logria), Some(100));
+        assert_eq!(logria.config.seen_up_to, Some(100));
+    }
+
+    #[test]
+    fn test_divider_cleared_when_off_screen() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        render(&mut logria);
+
+        receive(&mut logria, 3);
+        logria.config.scroll_state = ScrollState::Bottom;
+        assert_eq!(render(&mut logria), Some(100));
+
+        receive(&mut logria, 10);
+        assert_eq!(render(&mut logria), None);
+        assert_eq!(logria.config.seen_up_to, None);
+    }
+
+    #[test]
+    fn test_divider_cleared_without_new_messages() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        render(&mut logria);
+
+        logria.config.scroll_state = ScrollState::Bottom;
+        assert_eq!(render(&mut logria), None);
+        assert_eq!(logria.config.seen_up_to, None);
+    }
+}
+
+#[cfg(test)]
+mod tail_tests {
+    use crate::{
+        communication::{
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        ui::scroll::ScrollState,
+    };
+
+    #[test]
+    fn test_tail_inactive_by_default() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        assert_eq!(logria.tail_offset(), 0);
+    }
+
+    #[test]
+    fn test_tail_not_shown_in_auxiliary() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.tail_view = true;
+        logria.config.stream_type = StreamType::Auxiliary;
+        assert_eq!(logria.tail_offset(), 0);
+    }
+
+    #[test]
+    fn test_tail_not_shown_in_split_view() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.tail_view = true;
+        logria.config.split_view = true;
+        assert_eq!(logria.tail_offset(), 0);
+    }
+
+    #[test]
+    fn test_tail_excluded_from_top() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.tail_view = true;
+        logria.config.scroll_state = ScrollState::Top;
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 0);
+        assert_eq!(end, 4);
+    }
+
+    #[test]
+    fn test_tail_excluded_from_bottom() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.tail_view = true;
+        logria.config.scroll_state = ScrollState::Bottom;
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 96);
+        assert_eq!(end, 100);
+    }
+}
+
+#[cfg(test)]
+mod header_tests {
+    use crate::{
+        communication::{input::InputType, reader::MainWindow},
+        ui::scroll::ScrollState,
+    };
+
+    #[test]
+    fn test_header_inactive_by_default() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        assert_eq!(logria.header_offset(), 0);
+    }
+
+    #[test]
+    fn test_header_excluded_from_top() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.sticky_header = true;
+        logria.config.scroll_state = ScrollState::Top;
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 1);
+        assert_eq!(end, 7);
+    }
+
+    #[test]
+    fn test_header_excluded_from_bottom() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.sticky_header = true;
+        logria.config.scroll_state = ScrollState::Bottom;
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 94);
+        assert_eq!(end, 100);
+    }
+
+    #[test]
+    fn test_header_excluded_from_small_buffer() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.sticky_header = true;
+        logria.config.stderr_messages = (0..4).map(|x| x.to_string()).collect();
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 1);
+        assert_eq!(end, 4);
+    }
+
+    #[test]
+    fn test_header_not_pinned_with_regex() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Regex;
+        logria.config.sticky_header = true;
+        logria.config.regex_pattern = Some(regex::bytes::Regex::new("0").unwrap());
+        assert_eq!(logria.header_offset(), 0);
+    }
+}
+
+#[cfg(test)]
+mod wrap_tests {
+    use crate::{
+        communication::{input::InputType, reader::MainWindow},
+        ui::{scroll::ScrollState, wrap::WrapMode},
+    };
+
+    #[test]
+    fn test_hard_wrap_rows() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.width = 10;
+        assert_eq!(logria.message_rows("aaaaa aaaaa"), 2);
+    }
+
+    #[test]
+    fn test_word_wrap_rows() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.width = 10;
+        logria.config.wrap_mode = WrapMode::Word;
+        assert_eq!(logria.message_rows("aaaaaa aaaaaa aaaaaa"), 3);
+    }
+
+    #[test]
+    fn test_no_wrap_rows() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.width = 10;
+        logria.config.wrap_mode = WrapMode::Off;
+        assert_eq!(logria.message_rows("aaaaaa aaaaaa aaaaaa"), 1);
+    }
+
+    #[test]
+    fn test_hard_wrap_render_position_with_wide_message() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.width = 10;
+        logria.config.scroll_state = ScrollState::Top;
+        logria.config.stderr_messages = (0..20).map(|x| x.to_string()).collect();
+        logria.config.stderr_messages[1] = "a".repeat(25);
+
+        // The wide message takes three rows, pushing the later messages out of the window
+        assert_eq!(logria.determine_render_position(), (0, 5));
+    }
+
+    #[test]
+    fn test_no_wrap_render_position_with_wide_message() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.width = 10;
+        logria.config.wrap_mode = WrapMode::Off;
+        logria.config.scroll_state = ScrollState::Top;
+        logria.config.stderr_messages = (0..20).map(|x| x.to_string()).collect();
+        logria.config.stderr_messages[1] = "a".repeat(25);
+
+        // Every message takes exactly one row
+        assert_eq!(logria.determine_render_position(), (0, 7));
+    }
+
+    #[test]
+    fn test_word_wrap_render_position() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.width = 10;
+        logria.config.wrap_mode = WrapMode::Word;
+        logria.config.scroll_state = ScrollState::Top;
+        logria.config.stderr_messages = (0..20).map(|_| String::from("aaaaaa aaaaaa")).collect();
+
+        // Each message takes two rows, so only some fit in the window
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 0);
+        assert_eq!(end, 3);
+    }
+}
+
+#[cfg(test)]
+mod raw_tests {
+    use crate::communication::reader::MainWindow;
+
+    #[test]
+    fn test_trimmed_by_default() {
+        let logria = MainWindow::_new_dummy();
+        assert_eq!(logria.prepare_message("message   "), "message");
+    }
+
+    #[test]
+    fn test_raw_keeps_trailing_spaces() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.raw_messages = true;
+        assert_eq!(logria.prepare_message("message   "), "message   ");
+    }
+
+    #[test]
+    fn test_raw_escapes_control_chars() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.raw_messages = true;
+        assert_eq!(
+            logria.prepare_message("\x1b[32mmessage\t"),
+            "\\u{1b}[32mmessage\\t"
+        );
+    }
+
+    #[test]
+    fn test_trimmed_keeps_control_chars() {
+        let logria = MainWindow::_new_dummy();
+        assert_eq!(
+            logria.prepare_message("\x1b[32mmessage\t"),
+            "\x1b[32mmessage"
+        );
+    }
+}
+
+#[cfg(test)]
+mod safe_mode_tests {
+    use regex::bytes::Regex;
+
+    use crate::{
+        communication::reader::MainWindow,
+        constants::cli::poll_rate::{DEFAULT, SAMPLES},
+    };
+
+    #[test]
+    fn test_safe_mode_overrides_options() {
+        let mut logria = MainWindow::new(true, true, SAMPLES);
+        logria.config.poll_rate = 10;
+        logria.enable_safe_mode();
+
+        assert!(!logria.config.use_history);
+        assert!(!logria.config.smart_poll_rate);
+        assert!(logria.config.strip_colors);
+        assert_eq!(logria.config.poll_rate, DEFAULT);
+    }
+
+    #[test]
+    fn test_safe_mode_strips_colors() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.enable_safe_mode();
+        assert_eq!(
+            logria.prepare_message("\x1b[31mred\x1b[0m message   "),
+            "red message"
+        );
+    }
+
+    #[test]
+    fn test_safe_mode_skips_highlight() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.set_regex(Regex::new("1").unwrap());
+        assert!(logria.should_highlight());
+
+        logria.enable_safe_mode();
+        assert!(!logria.should_highlight());
+    }
+
+    #[test]
+    fn test_colors_kept_by_default() {
+        let logria = MainWindow::_new_dummy();
+        assert_eq!(
+            logria.prepare_message("\x1b[31mred\x1b[0m"),
+            "\x1b[31mred\x1b[0m"
+        );
+    }
+}
+
+#[cfg(test)]
+mod poll_rate_tests {
+    use crate::communication::{input::InputType, reader::MainWindow};
+    use std::time::Duration;
+
+    #[test]
+    fn test_no_poll_rate_change_when_disabled() {
+        let mut logria = MainWindow::_new_dummy();
+
+        // Disable smart polling
+        logria.config.smart_poll_rate = false;
+
+        // Update the poll rate for 100ms
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 10);
+
+        assert_eq!(logria.config.poll_rate, 50);
+    }
+
+    #[test]
+    fn test_poll_rate_change_when_enabled_100ms_10messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+
+        // Test default value
+        assert_eq!(logria.config.poll_rate, 50);
+
+        // Update the poll rate
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 10);
+
+        assert_eq!(logria.config.poll_rate, 10);
+    }
+
+    #[test]
+    fn test_poll_rate_change_when_enabled_50ms_50messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+
+        // Test default value
+        assert_eq!(logria.config.poll_rate, 50);
+
+        // Update the poll rate
+        logria.handle_smart_poll_rate(Duration::new(0, 50000000), 5);
+
+        assert_eq!(logria.config.poll_rate, 10);
+    }
+
+    #[test]
+    fn test_poll_rate_change_when_enabled_idle() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+
+        // Test default value
+        assert_eq!(logria.config.poll_rate, 50);
+
+        // Update the poll rate
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+        logria.handle_smart_poll_rate(Duration::new(0, 100000000), 0);
+
+        assert_eq!(logria.config.poll_rate, 1000);
+    }
+
+    #[test]
+    fn test_poll_rate_change_when_enabled_idle_multiple() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+
+        // Test default value
+        assert_eq!(logria.config.poll_rate, 50);
+
+        // Update the poll rate
+        logria.handle_smart_poll_rate(Duration::new(0, 10000000), 1);
+
+        assert_eq!(logria.config.poll_rate, 10);
+
+        // Update the poll rate
+        logria.handle_smart_poll_rate(Duration::new(0, 10000000), 1);
+
+        assert_eq!(logria.config.poll_rate, 10);
+
+        // Update the poll rate, don't go to 1000
+        logria.handle_smart_poll_rate(Duration::new(0, 10000000), 0);
+
+        assert_eq!(logria.config.poll_rate, 13);
+    }
+
+    #[test]
+    fn test_tracker_uses_configured_samples() {
+        let mut logria = MainWindow::new(true, true, 2);
+        logria.input_type = InputType::Normal;
+
+        for _ in 0..5 {
+            logria.handle_smart_poll_rate(Duration::new(0, 10000000), 1);
+        }
+
+        assert_eq!(logria.config.stream_options.poll_samples, 2);
+        assert_eq!(logria.config.message_speed_tracker.deque.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod timestamp_tests {
+    use regex::bytes::Regex;
+
+    use crate::{
+        communication::{handlers::parser::ParserState, input::InputType, reader::MainWindow},
+        ui::scroll::ScrollState,
+        util::{aggregators::date::DateParserType, timestamp::TimestampSearch},
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.stderr_messages = (0..20)
+            .map(|hour| format!("2021-03-19 {:02}:00:00 message {}", hour, hour))
+            .collect();
+        logria
+    }
+
+    #[test]
+    fn can_scroll_to_timestamp() {
+        let mut logria = window();
+        let search = TimestampSearch::new("05:00:00", None, &[]).unwrap();
+
+        assert!(logria.scroll_to_timestamp(&search));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+        assert_eq!(logria.determine_render_position(), (5, 12));
+    }
+
+    #[test]
+    fn can_scroll_to_timestamp_among_matches() {
+        let mut logria = window();
+        logria.config.regex_pattern = Some(Regex::new("message").unwrap());
+        logria.config.matched_rows = (1..20).step_by(2).collect();
+        let search = TimestampSearch::new("06:00:00", None, &[]).unwrap();
+
+        // The 07:00 message is the fourth match
+        assert!(logria.scroll_to_timestamp(&search));
+        assert_eq!(logria.config.current_end, 10);
+        assert_eq!(
+            logria.get_message_at_index(3),
+            "2021-03-19 07:00:00 message 7"
+        );
+    }
+
+    #[test]
+    fn can_scroll_to_timestamp_above_wrapped_messages() {
+        let mut logria = window();
+        logria.config.stderr_messages[6].push_str(&"a".repeat(250));
+        let search = TimestampSearch::new("05:00:00", None, &[]).unwrap();
+
+        // The wrapped message takes three rows, so fewer messages fit below the one found
+        assert!(logria.scroll_to_timestamp(&search));
+        assert_eq!(logria.config.current_end, 10);
+    }
+
+    #[test]
+    fn can_scroll_to_parsed_timestamp() {
+        let mut logria = window();
+        logria.input_type = InputType::Command;
+        logria.previous_input_type = InputType::Parser;
+        logria.config.parser_state = ParserState::Full;
+        logria.config.auxiliary_messages = (0..20)
+            .map(|day| format!("{:02}/03/2021", day + 1))
+            .collect();
+        let formats = [(String::from("[day]/[month]/[year]"), DateParserType::Date)];
+        let search = TimestampSearch::new("03/03/2021", None, &formats).unwrap();
+
+        assert!(logria.scroll_to_timestamp(&search));
+        assert_eq!(logria.config.current_end, 9);
+    }
+
+    #[test]
+    fn cannot_scroll_past_last_timestamp() {
+        let mut logria = window();
+        let search = TimestampSearch::new("2021-03-20", None, &[]).unwrap();
+
+        assert!(!logria.scroll_to_timestamp(&search));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Bottom));
+    }
+}
+
+#[cfg(test)]
+mod startup_bypass_tests {
+    use crate::{
+        communication::{
+            input::{InputType, StreamType},
+            reader::MainWindow,
+        },
+        extensions::session::{Session, SessionType, Workspace},
+    };
+
+    #[test]
+    fn shows_startup_without_streams() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.open_initial_streams(None).unwrap();
+        assert_eq!(logria.input_type, InputType::Startup);
+        assert!(logria.config.streams.is_empty());
+    }
+
+    #[test]
+    fn skips_startup_with_commands() {
+        let mut logria = MainWindow::_new_dummy();
+        logria
+            .open_initial_streams(Some(vec![String::from("README.md")]))
+            .unwrap();
+        assert_eq!(logria.input_type, InputType::Normal);
+        assert!(matches!(logria.config.stream_type, StreamType::StdErr));
+        assert_eq!(logria.config.streams.len(), 1);
+    }
+
+    #[test]
+    fn skips_startup_with_session() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.startup_session = Some(Session::new(
+            &[String::from("README.md"), String::from("Cargo.toml")],
+            SessionType::File,
+        ));
+        logria.open_initial_streams(None).unwrap();
+        assert_eq!(logria.input_type, InputType::Normal);
+        assert_eq!(logria.config.streams.len(), 2);
+        assert!(logria.config.startup_session.is_none());
+    }
+
+    #[test]
+    fn skips_startup_with_workspace() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.startup_session = Some(
+            Session::new(&[String::from("README.md")], SessionType::File).with_workspace(
+                Workspace {
+                    split_view: true,
+                    regex: Some(String::from("Logria")),
+                },
+            ),
+        );
+        logria.open_initial_streams(None).unwrap();
+        assert_eq!(logria.input_type, InputType::Regex);
+        assert!(logria.config.split_view);
+        assert_eq!(
+            logria
+                .config
+                .regex_pattern
+                .as_ref()
+                .map(|regex| regex.as_str()),
+            Some("Logria")
+        );
+        assert!(logria.config.session.unwrap().workspace.is_none());
+    }
+
+    #[test]
+    fn commands_take_precedence_over_session() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.startup_session = Some(Session::new(
+            &[String::from("README.md"), String::from("Cargo.toml")],
+            SessionType::File,
+        ));
+        logria
+            .open_initial_streams(Some(vec![String::from("README.md")]))
+            .unwrap();
+        assert_eq!(logria.input_type, InputType::Normal);
+        assert_eq!(logria.config.streams.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod line_number_tests {
+    use crate::{
+        communication::{input::InputType, reader::MainWindow},
+        ui::{scroll::ScrollState, wrap::WrapMode},
+    };
+    use regex::bytes::Regex;
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.show_line_numbers = true;
+        logria
+    }
+
+    #[test]
+    fn no_gutter_when_disabled() {
+        let mut logria = window();
+        logria.config.show_line_numbers = false;
+        assert_eq!(logria.gutter_width(), 0);
+        assert_eq!(logria.text_width(), 100);
+    }
+
+    #[test]
+    fn gutter_fits_largest_index() {
+        let logria = window();
+        // "99" plus a space
+        assert_eq!(logria.gutter_width(), 3);
+        assert_eq!(logria.text_width(), 97);
+    }
+
+    #[test]
+    fn gutter_fits_one_message() {
+        let mut logria = window();
+        logria.config.stderr_messages = vec![String::from("only")];
+        assert_eq!(logria.gutter_width(), 2);
+    }
+
+    #[test]
+    fn gutter_fits_largest_original_index() {
+        let mut logria = window();
+        logria.config.stderr_messages = (0..2000).map(|x| x.to_string()).collect();
+        logria.config.regex_pattern = Some(Regex::new("5").unwrap());
+        logria.config.matched_rows = vec![5, 1500];
+        assert_eq!(logria.line_number(1), 1500);
+        assert_eq!(logria.gutter_width(), 5);
+    }
+
+    #[test]
+    fn no_gutter_in_split_view() {
+        let mut logria = window();
+        logria.config.split_view = true;
+        assert_eq!(logria.gutter_width(), 0);
+    }
+
+    #[test]
+    fn wrap_accounts_for_gutter() {
+        let mut logria = window();
+        let message = "a".repeat(98);
+        assert_eq!(logria.message_rows(&message), 2);
+        logria.config.show_line_numbers = false;
+        assert_eq!(logria.message_rows(&message), 1);
+    }
+
+    #[test]
+    fn word_wrap_accounts_for_gutter() {
+        let mut logria = window();
+        logria.config.wrap_mode = WrapMode::Word;
+        let message = format!("{} {}", "a".repeat(60), "b".repeat(37));
+        assert_eq!(logria.message_rows(&message), 2);
+        logria.config.show_line_numbers = false;
+        assert_eq!(logria.message_rows(&message), 1);
+    }
+
+    #[test]
+    fn render_position_accounts_for_gutter() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        logria.config.stderr_messages = (0..20).map(|_| "a".repeat(100)).collect();
+
+        // Each message wraps onto a second row once the gutter takes up space
+        assert_eq!(logria.determine_render_position(), (0, 3));
+        logria.config.show_line_numbers = false;
+        assert_eq!(logria.determine_render_position(), (0, 7));
+    }
+}
+
+#[cfg(test)]
+mod source_tests {
+    use std::{
+        sync::{mpsc::channel, Arc, Mutex},
+        thread,
+    };
+
+    use crate::{
+        communication::{
+            input::{InputStream, InputType},
+            reader::MainWindow,
+        },
+        ui::scroll::ScrollState,
+    };
+    use regex::bytes::Regex;
+
+    /// Build a window with labels shown where each stream sent one message per entry in `messages`
+    fn window(names: &[&str], messages: &[&str]) -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.show_source = true;
+        logria.config.stderr_messages.clear();
+        let streams = names
+            .iter()
+            .map(|name| {
+                let (_out_tx, out_rx) = channel();
+                let (err_tx, err_rx) = channel();
+                for message in messages {
+                    err_tx.send(message.to_string()).unwrap();
+                }
+                InputStream {
+                    stdout: out_rx,
+                    stderr: err_rx,
+                    process_name: name.to_string(),
+                    source: name.to_string(),
+                    process: thread::Builder::new().spawn(|| {}),
+                    should_die: Arc::new(Mutex::new(false)),
+                    _type: String::from("CommandInput"),
+                }
+            })
+            .collect();
+        logria.set_streams(streams);
+        logria.receive_streams();
+        logria
+    }
+
+    #[test]
+    fn no_labels_when_disabled() {
+        let mut logria = window(&["a", "worker"], &["x"]);
+        logria.config.show_source = false;
+        assert_eq!(logria.source_width(), 0);
+        assert_eq!(logria.line_prefix(0, true), "");
+    }
+
+    #[test]
+    fn labels_fit_longest_name() {
+        let logria = window(&["a", "worker"], &["x"]);
+        // "[worker]" plus a space
+        assert_eq!(logria.source_width(), 9);
+        assert_eq!(logria.text_width(), 91);
+    }
+
+    #[test]
+    fn prefix_labels_first_row() {
+        let logria = window(&["a", "worker"], &["x"]);
+        assert_eq!(logria.line_prefix(0, true), "[a]      ");
+        assert_eq!(logria.line_prefix(1, true), "[worker] ");
+        assert_eq!(logria.line_prefix(1, false), " ".repeat(9));
+    }
+
+    #[test]
+    fn prefix_follows_line_number() {
+        let mut logria = window(&["a", "worker"], &["x"]);
+        logria.config.show_line_numbers = true;
+        assert_eq!(logria.line_prefix(1, true), "1 [worker] ");
+        assert_eq!(logria.text_width(), 89);
+    }
+
+    #[test]
+    fn prefix_labels_regex_matches() {
+        let mut logria = window(&["a", "worker"], &["x", "y"]);
+        logria.config.regex_pattern = Some(Regex::new("y").unwrap());
+        logria.config.matched_rows = vec![1, 3];
+        logria.config.highlight_match = true;
+        assert_eq!(logria.line_prefix(1, true), "[worker] ");
+        // The label is written separately, so highlighting only sees the message
+        assert_eq!(
+            logria.highlight_match(logria.get_message_at_index(1)),
+            "\x1b[35my\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn prefix_labels_unmuted_messages() {
+        let mut logria = window(&["a", "worker"], &["x"]);
+        logria.set_muted(0, true);
+        assert_eq!(logria.line_prefix(0, true), "[worker] ");
+    }
+
+    #[test]
+    fn no_labels_in_split_view() {
+        let mut logria = window(&["a", "worker"], &["x"]);
+        logria.config.split_view = true;
+        assert_eq!(logria.source_width(), 0);
+    }
+
+    #[test]
+    fn wrap_accounts_for_labels() {
+        let mut logria = window(&["a", "worker"], &["x"]);
+        let message = "a".repeat(95);
+        assert_eq!(logria.message_rows(&message), 2);
+        logria.config.show_source = false;
+        assert_eq!(logria.message_rows(&message), 1);
+    }
+
+    #[test]
+    fn render_position_accounts_for_labels() {
+        let messages: Vec<String> = (0..10).map(|_| "a".repeat(95)).collect();
+        let messages: Vec<&str> = messages.iter().map(String::as_str).collect();
+        let mut logria = window(&["a", "worker"], &messages);
+        logria.config.scroll_state = ScrollState::Top;
+
+        // Each message wraps onto a second row once the labels take up space
+        assert_eq!(logria.determine_render_position(), (0, 3));
+        logria.config.show_source = false;
+        assert_eq!(logria.determine_render_position(), (0, 7));
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use crate::communication::{
+        handlers::parser::ParserState, input::StreamType, reader::MainWindow,
+    };
+    use regex::bytes::Regex;
+
+    #[test]
+    fn exports_buffer() {
+        let logria = MainWindow::_new_dummy();
+        assert_eq!(
+            logria.export_messages(),
+            (0..100).map(|x| x.to_string()).collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn exports_regex_matches() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("5").unwrap());
+        logria.config.matched_rows = vec![5, 15, 50];
+        assert_eq!(logria.export_messages(), vec!["5", "15", "50"]);
+    }
+
+    #[test]
+    fn exports_parsed_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.previous_stream_type = StreamType::StdErr;
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.parser_state = ParserState::Full;
+        logria.config.auxiliary_messages = vec![String::from("a"), String::from("b")];
+        assert_eq!(logria.export_messages(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn exports_parsed_messages_over_regex() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("5").unwrap());
+        logria.config.matched_rows = vec![5];
+        logria.config.parser_state = ParserState::Full;
+        logria.config.auxiliary_messages = vec![String::from("a")];
+        assert_eq!(logria.export_messages(), vec!["a"]);
+    }
+
+    #[test]
+    fn exports_without_colors() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.stderr_messages = vec![String::from("\x1b[31mred\x1b[0m text")];
+        assert_eq!(logria.export_messages(), vec!["red text"]);
+    }
+}
+
+#[cfg(test)]
+mod buffer_limit_tests {
+    use crate::{
+        communication::{input::StreamType, reader::MainWindow},
+        ui::scroll::ScrollState,
+    };
+    use regex::bytes::Regex;
+
+    #[test]
+    fn keeps_buffer_without_limit() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.enforce_buffer_limits();
+        assert_eq!(logria.config.stderr_messages.len(), 100);
+    }
+
+    #[test]
+    fn drops_oldest_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.max_buffer_lines = Some(10);
+        logria.enforce_buffer_limits();
+        assert_eq!(
+            logria.config.stderr_messages,
+            (90..100).map(|x| x.to_string()).collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn shifts_regex_cursor() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("5").unwrap());
+        logria.config.matched_rows = vec![5, 15, 25, 50, 95];
+        logria.config.last_index_regexed = 100;
+        logria.config.max_buffer_lines = Some(60);
+        logria.enforce_buffer_limits();
+
+        assert_eq!(logria.config.matched_rows, vec![10, 55]);
+        assert_eq!(logria.config.last_index_regexed, 60);
+        // Matches still point at the same messages
+        assert_eq!(logria.messages()[logria.config.matched_rows[0]], "50");
+        assert_eq!(logria.messages()[logria.config.matched_rows[1]], "95");
+    }
+
+    #[test]
+    fn shifts_regex_cursor_past_start() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.matched_rows = vec![5];
+        logria.config.last_index_regexed = 20;
+        logria.config.max_buffer_lines = Some(10);
+        logria.enforce_buffer_limits();
+
+        assert!(logria.config.matched_rows.is_empty());
+        assert_eq!(logria.config.last_index_regexed, 0);
+    }
+
+    #[test]
+    fn shifts_parser_cursor() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.previous_stream_type = StreamType::StdErr;
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.last_index_processed = 100;
+        logria.config.max_buffer_lines = Some(40);
+        logria.enforce_buffer_limits();
+
+        assert_eq!(logria.config.last_index_processed, 40);
+        assert_eq!(logria.previous_messages().len(), 40);
+    }
+
+    #[test]
+    fn does_not_shift_cursors_for_other_stream() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.stdout_messages = (0..100).map(|x| x.to_string()).collect();
+        logria.config.matched_rows = vec![5, 50];
+        logria.config.last_index_regexed = 100;
+        logria.config.max_buffer_lines = Some(100);
+        logria.config.stdout_messages.push(String::from("new"));
+        logria.enforce_buffer_limits();
+
+        assert_eq!(logria.config.stdout_messages.len(), 100);
+        assert_eq!(logria.config.matched_rows, vec![5, 50]);
+        assert_eq!(logria.config.last_index_regexed, 100);
+    }
+
+    #[test]
+    fn keeps_scrolled_view_on_same_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = ScrollState::Free;
+        logria.config.current_end = 50;
+        logria.config.max_buffer_lines = Some(80);
+        logria.enforce_buffer_limits();
+
+        assert_eq!(logria.config.current_end, 30);
+        assert_eq!(logria.messages()[logria.config.current_end - 1], "49");
+    }
+
+    #[test]
+    fn shifts_new_message_divider() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.seen_up_to = Some(50);
+        logria.config.max_buffer_lines = Some(80);
+        logria.enforce_buffer_limits();
+
+        assert_eq!(logria.config.seen_up_to, Some(30));
+        assert_eq!(logria.messages()[30], "50");
+    }
+
+    #[test]
+    fn shifts_frozen_view_by_dropped_matches() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.regex_pattern = Some(Regex::new("5").unwrap());
+        logria.config.matched_rows = vec![5, 15, 25, 50, 95];
+        logria.config.frozen_at = Some(4);
+        logria.config.max_buffer_lines = Some(60);
+        logria.enforce_buffer_limits();
+
+        // Three matches were dropped, so the frozen view still ends before the match on "95"
+        assert_eq!(logria.config.frozen_at, Some(1));
+        assert_eq!(logria.number_of_messages(), 1);
+    }
+
+    #[test]
+    fn shifts_selection() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.selection_start = Some(40);
+        logria.config.selection_end = Some(10);
+        logria.config.max_buffer_lines = Some(80);
+        logria.enforce_buffer_limits();
+
+        // The part of the selection that was dropped is cut off
+        assert_eq!(logria.config.selection_start, Some(20));
+        assert_eq!(logria.config.selection_end, Some(0));
+        assert_eq!(logria.messages()[20], "40");
+    }
+
+    #[test]
+    fn clears_dropped_selection() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.selection_start = Some(5);
+        logria.config.selection_end = Some(10);
+        logria.config.max_buffer_lines = Some(80);
+        logria.enforce_buffer_limits();
+
+        assert!(logria.config.selection_start.is_none());
+        assert!(logria.config.selection_end.is_none());
+    }
+}
+
+#[cfg(test)]
+mod environment_tests {
+    use crate::{
+        communication::reader::MainWindow,
+        constants::cli::messages::{PIPE_INPUT_ERROR, TERMINAL_TOO_SMALL_ERROR},
+    };
+
+    #[test]
+    fn accepts_tty() {
+        let logria = MainWindow::_new_dummy();
+        assert!(logria
+            .environment_error(true, true, Some((80, 24)))
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_piped_stdin() {
+        let logria = MainWindow::_new_dummy();
+        assert_eq!(
+            logria.environment_error(false, true, Some((80, 24))),
+            Some(PIPE_INPUT_ERROR)
+        );
+    }
+
+    #[test]
+    fn accepts_piped_stdin_with_pipe() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.pipe = true;
+        assert!(logria
+            .environment_error(false, true, Some((80, 24)))
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_piped_stdout_with_pipe() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.pipe = true;
+        assert_eq!(
+            logria.environment_error(false, false, Some((80, 24))),
+            Some(PIPE_INPUT_ERROR)
+        );
+    }
+
+    #[test]
+    fn rejects_small_terminal() {
+        let logria = MainWindow::_new_dummy();
+        assert_eq!(
+            logria.environment_error(true, true, Some((0, 0))),
+            Some(TERMINAL_TOO_SMALL_ERROR)
+        );
+        assert_eq!(
+            logria.environment_error(true, true, None),
+            Some(TERMINAL_TOO_SMALL_ERROR)
+        );
+    }
+}
+
+#[cfg(test)]
+mod freeze_tests {
+    use crate::communication::{
+        input::{InputType, StreamType},
+        reader::MainWindow,
+    };
+
+    fn receive(logria: &mut MainWindow, count: usize) {
+        for _ in 0..count {
+            logria.config.stderr_messages.push(String::from("new"));
+        }
+    }
+
+    #[test]
+    fn test_freeze_stops_showing_new_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        assert_eq!(logria.toggle_freeze(), None);
+        receive(&mut logria, 5);
+        assert_eq!(logria.config.frozen_at, Some(100));
+        assert_eq!(logria.number_of_messages(), 100);
+        assert_eq!(logria.config.stderr_messages.len(), 105);
+    }
+
+    #[test]
+    fn test_resume_shows_new_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.toggle_freeze();
+        receive(&mut logria, 5);
+        assert_eq!(logria.toggle_freeze(), Some(5));
+        assert!(logria.config.frozen_at.is_none());
+        assert_eq!(logria.number_of_messages(), 105);
+    }
+
+    #[test]
+    fn test_resume_without_new_messages() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.toggle_freeze();
+        assert_eq!(logria.toggle_freeze(), Some(0));
+        assert_eq!(logria.toggle_freeze(), None);
+    }
+
+    #[test]
+    fn test_freeze_does_not_limit_auxiliary() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.config.frozen_at = Some(1);
+        logria.config.stream_type = StreamType::Auxiliary;
+        logria.config.auxiliary_messages = vec![String::from("a"), String::from("b")];
+        assert_eq!(logria.number_of_messages(), 2);
+    }
+
+    #[test]
+    fn test_regex_mode_resumes() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.toggle_freeze();
+        logria.update_input_type(InputType::Command).unwrap();
+        assert!(logria.config.frozen_at.is_some());
+        logria.update_input_type(InputType::Regex).unwrap();
+        assert!(logria.config.frozen_at.is_none());
+    }
+
+    #[test]
+    fn test_freeze_in_status() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria.toggle_freeze();
+        assert!(logria
+            .status_report()
+            .contains(&String::from("    frozen: at 100 messages")));
+    }
+}
+
+#[cfg(test)]
+mod reload_tests {
+    use std::{env::temp_dir, fs::write, thread, time::Duration};
+
+    use crate::communication::{
+        input::{CommandInput, FileInput, Input, InputType},
+        reader::MainWindow,
+    };
+
+    /// Receive messages until the file streams have sent every line
+    fn receive(logria: &mut MainWindow, expected: usize) {
+        for _ in 0..100 {
+            logria.receive_streams();
+            if logria.config.stdout_messages.len() >= expected {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_reload_repopulates_buffer() {
+        let path = temp_dir().join("logria_reload_repopulates");
+        let path = path.to_str().unwrap().to_string();
+        write(&path, "first\nsecond\n").unwrap();
+
+        let mut logria = MainWindow::_new_dummy();
+        logria.update_input_type(InputType::Normal).unwrap();
+        logria.set_streams(vec![
+            FileInput::build(String::from("log"), path.to_owned()).unwrap()
+        ]);
+        receive(&mut logria, 2);
+        assert_eq!(logria.config.stdout_messages, vec!["first", "second"]);
+
+        // The file was rotated
+        write(&path, "third\n").unwrap();
+        assert_eq!(logria.reload_files().unwrap(), 1);
+        assert!(logria.config.stdout_messages.is_empty());
+        receive(&mut logria, 1);
+
+        assert_eq!(logria.config.stdout_messages, vec!["third"]);
+        assert_eq!(logria.config.streams[0].source, path);
+    }
+
+    #[test]
+    fn test_reload_resets_processing_cursors() {
+        let path = temp_dir().join("logria_reload_resets");
+        let path = path.to_str().unwrap().to_string();
+        write(&path, "first\n").unwrap();
+
+        let mut logria = MainWindow::_new_dummy();
+        logria.set_streams(vec![FileInput::build(String::from("log"), path).unwrap()]);
+        logria.config.matched_rows = vec![0, 3];
+        logria.config.last_index_regexed = 4;
+        logria.config.last_index_processed = 4;
+        logria.config.watermark = Some(2);
+        logria.config.seen_up_to = Some(4);
+
+        logria.reload_files().unwrap();
+
+        assert!(logria.config.matched_rows.is_empty());
+        assert_eq!(logria.config.last_index_regexed, 0);
+        assert_eq!(logria.config.last_index_processed, 0);
+        assert!(logria.config.watermark.is_none());
+        assert!(logria.config.seen_up_to.is_none());
+    }
+
+    #[test]
+    fn test_reload_keeps_command_output() {
+        let path = temp_dir().join("logria_reload_keeps_commands");
+        let path = path.to_str().unwrap().to_string();
+        write(&path, "first\n").unwrap();
+
+        let mut logria = MainWindow::_new_dummy();
+        logria.set_streams(vec![
+            CommandInput::build(String::from("echo"), String::from("echo command")).unwrap(),
+            FileInput::build(String::from("log"), path.to_owned()).unwrap(),
+        ]);
+        receive(&mut logria, 2);
+        assert!(logria
+            .config
+            .stdout_messages
+            .contains(&String::from("command")));
+
+        write(&path, "second\n").unwrap();
+        assert_eq!(logria.reload_files().unwrap(), 1);
+        assert_eq!(logria.config.stdout_messages, vec!["command"]);
+        assert_eq!(logria.config.stdout_sources, vec![0]);
+        receive(&mut logria, 2);
+
+        assert_eq!(logria.config.stdout_messages, vec!["command", "second"]);
+    }
+
+    #[test]
+    fn test_reload_leaves_commands_untouched() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.set_streams(vec![CommandInput::build(
+            String::from("ls"),
+            String::from("ls"),
+        )
+        .unwrap()]);
+        logria.config.last_index_regexed = 4;
+
+        assert_eq!(logria.reload_files().unwrap(), 0);
+        assert_eq!(logria.config.last_index_regexed, 4);
+        assert_eq!(logria.config.stderr_messages.len(), 100);
+    }
+
+    #[test]
+    fn test_reload_missing_file_keeps_streams() {
+        let path = temp_dir().join("logria_reload_missing");
+        let path = path.to_str().unwrap().to_string();
+        write(&path, "first\n").unwrap();
+
+        let mut logria = MainWindow::_new_dummy();
+        logria.set_streams(vec![
+            FileInput::build(String::from("log"), path.to_owned()).unwrap()
+        ]);
+        receive(&mut logria, 1);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(logria.reload_files().is_err());
+        assert_eq!(logria.config.stdout_messages, vec!["first"]);
     }
 }
@@ -6,21 +6,37 @@ pub mod poll_rate {
     pub const SLOWEST: u64 = 1000;
     // Default rate, 500 hz
     pub const DEFAULT: u64 = 50;
+    // Number of recent poll rates averaged by smart polling
+    pub const SAMPLES: usize = 5;
+    // How often a followed file is checked for appended lines
+    pub const FOLLOW: u64 = 100;
+    // Checks in a row a followed file must be missing for to count as deleted rather than replaced
+    pub const DELETED_CHECKS: u32 = 10;
+    // How long a command run by `:pipe` can take before it is stopped
+    pub const PIPE_TIMEOUT: u64 = 5000;
 }
 
 pub mod patterns {
     pub const ANSI_COLOR_PATTERN: &str = r"(?-u)(\x9b|\x1b\[)[0-?]*[ -/]*[@-~]";
+    /// CSI sequences other than SGR, i.e. cursor movement and erasing, which end in any letter but `m`
+    pub const ANSI_CONTROL_PATTERN: &str = r"(\x{9b}|\x1b\[)[0-?]*[ -/]*[@-ln-~]";
 }
 
 pub mod colors {
     pub const RESET_COLOR: &str = "\x1b[0m";
     pub const HIGHLIGHT_COLOR: &str = "\x1b[35m";
+    pub const ADDED_COLOR: &str = "\x1b[32m";
+    pub const WARNING_COLOR: &str = "\x1b[33m";
+    pub const CRITICAL_COLOR: &str = "\x1b[31m";
+    pub const CHANGED_COLOR: &str = "\x1b[36m";
 }
 
 pub mod excludes {
     // Text to exclude from message history
     pub const HISTORY_EXCLUDES: [&str; 2] = [":history", ":history off"];
     pub const SESSION_FILE_EXCLUDES: [&str; 1] = [".DS_Store"];
+    // Commands skipped when finding the command for `:!!` to repeat
+    pub const REPEAT_EXCLUDES: [&str; 2] = ["!!", "repeat"];
 }
 
 pub mod cli_chars {
@@ -83,6 +99,26 @@ pub mod messages {
         "Disable variable polling rate based on incoming message rate";
     pub const DOCS_HELP: &str = "Prints documentation";
     pub const PATHS_HELP: &str = "Prints current configuration paths";
+    pub const METRICS_HELP: &str = "Periodically append app metrics to the file at this path";
+    pub const METRICS_INTERVAL_HELP: &str = "Milliseconds between metrics lines";
+    pub const EXIT_AFTER_HELP: &str = "Exit after this many minutes without new messages or input";
+    pub const POLL_SAMPLES_HELP: &str = "Number of recent poll rates averaged by smart polling";
+    pub const RESUME_AFTER_HELP: &str =
+        "Resume following new messages after this many seconds without scrolling";
+    pub const SAFE_HELP: &str =
+        "Disable history, smart polling, and colors to troubleshoot odd behavior";
+    pub const TRACE_HELP: &str =
+        "Append a timestamped log of Logria's own actions to the file at this path";
+    pub const MAX_LINE_LENGTH_HELP: &str =
+        "Cut messages longer than this many characters when they are received";
+    pub const GUNZIP_HELP: &str = "Decompress command output as a gzip stream";
+    pub const FOLLOW_HELP: &str = "Keep reading files as lines are appended, like tail -f";
+    pub const STOP_ON_DELETE_HELP: &str =
+        "Stop following a deleted file instead of waiting for it to be created again";
+    pub const PIPE_HELP: &str = "Read messages piped to stdin, ex: tail -f log.txt | logria --pipe";
+    pub const NO_STARTUP_HELP: &str =
+        "Skip the startup screen and open the saved session with this name";
+    pub const RESUME_HELP: &str = "Skip the startup screen and reopen the last session opened";
     pub const DOCS: &str = concat!(
         "CONTROLS:\n",
         "    +------+--------------------------------------------------+\n",
@@ -91,7 +127,19 @@ pub mod messages {
         "    |  :   | command mode                                     |\n",
         "    |  /   | regex search                                     |\n",
         "    |  h   | if regex active, toggle highlighting of matches  |\n",
+        "    |  ^   | if regex active, toggle matching only at start   |\n",
+        "    |  $   | if regex active, toggle matching only at end     |\n",
+        "    |  v   | if regex active, toggle showing non-matches only |\n",
+        "    | tab  | in regex mode, toggle ignoring case              |\n",
+        "    |  n   | if regex active, step to the next match          |\n",
+        "    |  N   | if regex active, step to the previous match      |\n",
         "    |  s   | swap reading `stderr` and `stdout`               |\n",
+        "    |  w   | toggle split view of `stderr` above `stdout`     |\n",
+        "    |  t   | toggle tail of newest messages at bottom         |\n",
+        "    |  c   | freeze the display, new messages are still read  |\n",
+        "    |  f   | toggle viewing the snapshot from `:snapshot`     |\n",
+        "    |  V   | select messages, extend the selection with ↑/↓   |\n",
+        "    |  y   | copy the selected messages to the snapshot       |\n",
         "    |  p   | activate parser                                  |\n",
         "    |  a   | toggle aggregation mode when parser is active    |\n",
         "    |  z   | deactivate parser                                |\n",
@@ -99,28 +147,98 @@ pub mod messages {
         "    |  ↓   | scroll buffer down one line                      |\n",
         "    |  →   | skip and stick to end of buffer                  |\n",
         "    |  ←   | skip and stick to beginning of buffer            |\n",
+        "    |  ^U  | scroll buffer up half of the window              |\n",
+        "    |  ^D  | scroll buffer down half of the window            |\n",
+        "    |  m   | keep the middle message centered, again to stop  |\n",
         "    +------+--------------------------------------------------+\n\n",
         "COMMANDS:\n",
         "    +-----------------+---------------------------------------+\n",
         "    | Key             | Command                               |\n",
         "    +=================+=======================================+\n",
         "    | :q              | exit Logria                           |\n",
+        "    | :!! or :repeat  | run the previous command again        |\n",
         "    | :poll #         | update poll rate to #, where # is an  |\n",
         "    |                 | integer (in milliseconds)             |\n",
         "    | :r #            | when launching logria or viewing      |\n",
         "    |                 | sessions, this will delete item #     |\n",
+        "    | :page #         | scroll # lines with pg up and pg down,|\n",
+        "    |                 | or a fraction of the window, i.e. 0.5 |\n",
         "    | :agg #          | set the limit for aggregation counters|\n",
         "    |                 | be top #, i.e. top 5 or top 1         |\n",
+        "    | :agg-export-md x| write the aggregation data to file x  |\n",
+        "    |                 | as a Markdown table                   |\n",
         "    | :history on     | enable command history disk cache     |\n",
         "    | :history off    | disable command history disk cache    |\n",
+        "    | :raw            | toggle showing messages exactly as    |\n",
+        "    |                 | received, without trimming            |\n",
+        "    | :header         | toggle pinning the first message above|\n",
+        "    |                 | the rest of the buffer                |\n",
+        "    | :divider        | toggle marking where new messages     |\n",
+        "    |                 | start after scrolling away            |\n",
+        "    | :save x         | write the messages on view, or those  |\n",
+        "    |                 | selected with V, to the file x        |\n",
+        "    | :snapshot       | freeze the messages on screen, view   |\n",
+        "    |                 | them with f                           |\n",
+        "    | :diff mark      | keep the buffer to compare a later run|\n",
+        "    |                 | against                               |\n",
+        "    | :diff           | toggle marking messages that are new  |\n",
+        "    |                 | since the kept run with +             |\n",
+        "    | :diff removed   | show kept messages that are missing   |\n",
+        "    |                 | from the buffer                       |\n",
+        "    | :since [regex]  | only show messages received from now  |\n",
+        "    |                 | on, optionally matching regex         |\n",
+        "    | :mem            | show buffer sizes and app diagnostics |\n",
+        "    | :limit #        | keep only the newest # messages of    |\n",
+        "    |                 | each stream, or :limit off            |\n",
+        "    | :reload         | read file streams again from the start|\n",
+        "    |                 | of each file, i.e. after rotation     |\n",
+        "    | :status         | show the active mode, stream, regex,  |\n",
+        "    |                 | parser, and poll rate                 |\n",
+        "    | :status full    | show a status too long for the command|\n",
+        "    |                 | line over the output window           |\n",
+        "    | :clear-aux      | empty the auxiliary buffer            |\n",
+        "    | :changes        | toggle coloring what changed since the|\n",
+        "    |                 | message above, i.e. counters          |\n",
+        "    | :position       | toggle showing the line at the bottom |\n",
+        "    |                 | of the view out of the buffer length  |\n",
+        "    | :numbers        | toggle showing the index of each      |\n",
+        "    |                 | message to its left                   |\n",
+        "    | :source         | toggle labeling each message with the |\n",
+        "    |                 | name of the stream it came from       |\n",
+        "    | :wrap           | cycle between hard wrap, word wrap,   |\n",
+        "    |                 | and no wrap for long messages         |\n",
+        "    | :filter save x  | save the active regex as filter x     |\n",
+        "    | :filter x       | apply the regex saved as filter x     |\n",
+        "    | :filters        | list saved filters                    |\n",
+        "    | :at t           | scroll to the first message at or     |\n",
+        "    |                 | after time t, i.e. :at 14:32:00       |\n",
+        "    | :g #            | scroll to message #, or the last      |\n",
+        "    |                 | message if # is past the end          |\n",
+        "    | :matchwrap      | toggle whether n and N wrap around    |\n",
+        "    |                 | past the last and first match         |\n",
+        "    | :context #      | show the match n and N move to # rows |\n",
+        "    |                 | from the top, or :context off         |\n",
+        "    | :pipe x         | run the buffer through command x, i.e.|\n",
+        "    |                 | :pipe sort                            |\n",
+        "    | :stream x       | show stream x: stderr, stdout, or aux |\n",
+        "    | :cd x           | start new commands in directory x, or |\n",
+        "    |                 | Logria's directory with :cd           |\n",
+        "    | :env KEY=VALUE  | set KEY for new commands, :env KEY to |\n",
+        "    |                 | unset it, or :env to list them        |\n",
+        "    | :streams        | list inputs with indices to mute them |\n",
+        "    | :mute #         | hide messages from input #, and       |\n",
+        "    |                 | :unmute # to show them again          |\n",
+        "    | :workspace x    | save the streams, split view, and     |\n",
+        "    |                 | regex as session x                    |\n",
         "    +-----------------+---------------------------------------|\n"
     );
     pub const PIPE_INPUT_ERROR: &str = concat!(
-        "Piping to Logria is not supported as it cannot\n",
-        "both listen to stdin as well as get user input\n",
-        "from your tty. Process substitution is also not\n",
-        "allowed, as Logria is unable to read from the\n",
-        "file descriptor created by the shell.\n",
+        "Piping to Logria requires the `--pipe` flag, so\n",
+        "Logria reads messages from stdin and user input\n",
+        "from your tty, i.e. `cmd | logria --pipe`.\n",
+        "Process substitution is not allowed, as Logria\n",
+        "is unable to read from the file descriptor\n",
+        "created by the shell.\n",
         "\n",
         "Piping from Logria is also not supported because\n",
         "the interface is fundamentally interactive and\n",
@@ -131,4 +249,9 @@ pub mod messages {
         "invoke Logria with `logria -e \"command\", or\n",
         "create a valid session file."
     );
+    pub const TERMINAL_TOO_SMALL_ERROR: &str = concat!(
+        "The terminal is too small to draw Logria in.\n",
+        "Resize it to at least 10 columns and 5 rows\n",
+        "and start Logria again."
+    );
 }
@@ -40,6 +40,24 @@ pub fn history_tape() -> String {
     root
 }
 
+pub fn filters() -> String {
+    let mut root = app_root();
+    root.push_str("/filters");
+    root
+}
+
+pub fn text() -> String {
+    let mut root = app_root();
+    root.push_str("/text");
+    root
+}
+
+pub fn last_session() -> String {
+    let mut root = app_root();
+    root.push_str("/last_session");
+    root
+}
+
 pub fn print_paths() {
     let mut result = String::new();
     result.push_str("Environment variables:\n");
@@ -65,7 +83,10 @@ pub fn print_paths() {
     result.push_str(&format!("Logria root: {}\n", app_root()));
     result.push_str(&format!("Patterns:    {}\n", patterns()));
     result.push_str(&format!("Sessions:    {}\n", sessions()));
-    result.push_str(&format!("History:     {}", history()));
+    result.push_str(&format!("History:     {}\n", history()));
+    result.push_str(&format!("Filters:     {}\n", filters()));
+    result.push_str(&format!("Resume:      {}\n", last_session()));
+    result.push_str(&format!("Text:        {}", text()));
     println!("{}", result);
 }
 
@@ -114,6 +135,30 @@ mod tests {
         assert_eq!(t, root)
     }
 
+    #[test]
+    fn test_filters() {
+        let t = directories::filters();
+        let mut root = config_dir().expect("").to_str().expect("").to_string();
+        root.push_str("/Logria/filters");
+        assert_eq!(t, root)
+    }
+
+    #[test]
+    fn test_text() {
+        let t = directories::text();
+        let mut root = config_dir().expect("").to_str().expect("").to_string();
+        root.push_str("/Logria/text");
+        assert_eq!(t, root)
+    }
+
+    #[test]
+    fn test_last_session() {
+        let t = directories::last_session();
+        let mut root = config_dir().expect("").to_str().expect("").to_string();
+        root.push_str("/Logria/last_session");
+        assert_eq!(t, root)
+    }
+
     #[test]
     fn test_print_paths() {
         // Ensure no weird crashes here
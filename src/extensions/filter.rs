@@ -0,0 +1,221 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::{create_dir_all, read_to_string, write},
+    path::Path,
+    result::Result,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::directories::app_root, util::error::LogriaError};
+
+/// A regex pattern as typed after `/`, with the anchor, exclude, and case toggles applied to it
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub pattern: String,
+    #[serde(default)]
+    pub anchor_start: bool,
+    #[serde(default)]
+    pub anchor_end: bool,
+    #[serde(default)]
+    pub invert: bool,
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+impl Filter {
+    /// Create a filter for a pattern with every toggle off
+    pub fn new(pattern: &str) -> Filter {
+        Filter {
+            pattern: pattern.to_owned(),
+            ..Filter::default()
+        }
+    }
+
+    /// The pattern to compile, wrapping the typed pattern in the active anchors and case flag
+    ///
+    /// Exclude mode is not part of the pattern; it decides whether matching messages are kept.
+    pub fn effective_pattern(&self) -> String {
+        let flags = if self.case_insensitive { "(?i)" } else { "" };
+        if !self.anchor_start && !self.anchor_end {
+            return format!("{}{}", flags, self.pattern);
+        }
+        format!(
+            "{}{}(?:{}){}",
+            flags,
+            if self.anchor_start { "^" } else { "" },
+            self.pattern,
+            if self.anchor_end { "$" } else { "" }
+        )
+    }
+}
+
+/// Regex filters saved under a name so they can be reapplied with `:filter <name>`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Filters {
+    pub patterns: BTreeMap<String, Filter>,
+}
+
+impl Filters {
+    /// Ensure the proper paths exist
+    pub fn verify_path() {
+        let root = app_root();
+        if !Path::new(&root).exists() {
+            create_dir_all(root).unwrap();
+        }
+    }
+
+    /// Create Filters struct from a filters file, or an empty one if the file does not exist yet
+    pub fn load(file_name: &str) -> Result<Filters, LogriaError> {
+        if !Path::new(file_name).exists() {
+            return Ok(Filters::default());
+        }
+        match read_to_string(file_name) {
+            Ok(json) => match serde_json::from_str(&json) {
+                Ok(filters) => Ok(filters),
+                Err(why) => Err(LogriaError::CannotRead(
+                    file_name.to_owned(),
+                    why.to_string(),
+                )),
+            },
+            Err(why) => Err(LogriaError::CannotRead(
+                file_name.to_owned(),
+                <dyn Error>::to_string(&why),
+            )),
+        }
+    }
+
+    /// Write the saved filters to a filters file
+    pub fn save(&self, file_name: &str) -> Result<(), LogriaError> {
+        let filters_json = serde_json::to_string_pretty(&self).unwrap();
+        match write(file_name, filters_json) {
+            Ok(_) => Ok(()),
+            Err(why) => Err(LogriaError::CannotWrite(
+                file_name.to_owned(),
+                <dyn Error>::to_string(&why),
+            )),
+        }
+    }
+
+    /// Save a filter under a name, replacing any filter already saved with that name
+    pub fn add(&mut self, name: &str, filter: Filter) -> Result<(), LogriaError> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) || name == "save" {
+            return Err(LogriaError::InvalidFilterName(name.to_owned()));
+        }
+        self.patterns.insert(name.to_owned(), filter);
+        Ok(())
+    }
+
+    /// Get the filter saved under a name
+    pub fn get(&self, name: &str) -> Option<&Filter> {
+        self.patterns.get(name.trim())
+    }
+
+    /// Get the names of all saved filters in sorted order
+    pub fn list(&self) -> Vec<String> {
+        self.patterns.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::remove_file};
+
+    use super::{Filter, Filters};
+
+    fn test_file(name: &str) -> String {
+        let path = temp_dir().join(name);
+        let _ = remove_file(&path);
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn can_load_missing_file() {
+        let file_name = test_file("logria_filters_missing");
+        let filters = Filters::load(&file_name).unwrap();
+        assert!(filters.list().is_empty());
+    }
+
+    #[test]
+    fn can_save_and_load() {
+        let file_name = test_file("logria_filters_save");
+        let mut filters = Filters::default();
+        filters.add("errors", Filter::new("ERROR|FATAL")).unwrap();
+        filters.save(&file_name).unwrap();
+
+        let loaded = Filters::load(&file_name).unwrap();
+        assert_eq!(loaded.get("errors"), Some(&Filter::new("ERROR|FATAL")));
+        remove_file(&file_name).unwrap();
+    }
+
+    #[test]
+    fn can_save_and_load_toggles() {
+        let file_name = test_file("logria_filters_toggles");
+        let filter = Filter {
+            anchor_start: true,
+            invert: true,
+            case_insensitive: true,
+            ..Filter::new("error")
+        };
+        let mut filters = Filters::default();
+        filters.add("errors", filter.clone()).unwrap();
+        filters.save(&file_name).unwrap();
+
+        let loaded = Filters::load(&file_name).unwrap();
+        assert_eq!(loaded.get("errors"), Some(&filter));
+        remove_file(&file_name).unwrap();
+    }
+
+    #[test]
+    fn can_build_effective_pattern() {
+        assert_eq!(Filter::new("A").effective_pattern(), "A");
+        let filter = Filter {
+            anchor_end: true,
+            case_insensitive: true,
+            ..Filter::new("A|B")
+        };
+        assert_eq!(filter.effective_pattern(), "(?i)(?:A|B)$");
+    }
+
+    #[test]
+    fn can_list() {
+        let mut filters = Filters::default();
+        filters.add("warnings", Filter::new("WARN")).unwrap();
+        filters.add("errors", Filter::new("ERROR")).unwrap();
+        assert_eq!(filters.list(), vec!["errors", "warnings"]);
+    }
+
+    #[test]
+    fn can_replace() {
+        let mut filters = Filters::default();
+        filters.add("errors", Filter::new("ERROR")).unwrap();
+        filters.add("errors", Filter::new("ERROR|FATAL")).unwrap();
+        assert_eq!(filters.list().len(), 1);
+        assert_eq!(filters.get("errors"), Some(&Filter::new("ERROR|FATAL")));
+    }
+
+    #[test]
+    fn cannot_get_missing_name() {
+        let filters = Filters::default();
+        assert!(filters.get("errors").is_none());
+    }
+
+    #[test]
+    fn cannot_add_invalid_name() {
+        let mut filters = Filters::default();
+        assert!(filters.add("", Filter::new("ERROR")).is_err());
+        assert!(filters.add("two words", Filter::new("ERROR")).is_err());
+        assert!(filters.add("save", Filter::new("ERROR")).is_err());
+        assert!(filters.list().is_empty());
+    }
+
+    #[test]
+    fn cannot_load_invalid_file() {
+        let file_name = test_file("logria_filters_invalid");
+        std::fs::write(&file_name, "not json").unwrap();
+        assert!(Filters::load(&file_name).is_err());
+        remove_file(&file_name).unwrap();
+    }
+}
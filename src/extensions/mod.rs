@@ -1,3 +1,5 @@
 pub mod parser;
 pub mod session;
 pub mod extension;
+pub mod filter;
+pub mod text;
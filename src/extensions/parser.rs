@@ -1,4 +1,5 @@
 use std::{
+    borrow::{Borrow, Cow},
     collections::HashMap,
     error::Error,
     fs::{create_dir_all, read_dir, read_to_string, remove_file, write},
@@ -8,27 +9,42 @@ use std::{
 
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{
     constants::directories::patterns,
     extensions::extension::ExtensionMethods,
     util::{
         aggregators::{
-            aggregator::{AggregationMethod, Aggregator},
+            aggregator::{
+                extract_number, AggregationMethod, Aggregator, DecimalSeparator, Thresholds,
+            },
             counter::Counter,
             date::{Date, DateParserType},
+            distinct::Distinct,
+            grouped::GroupedCounter,
             mean::Mean,
+            median::Median,
+            minmax::{Max, Min},
             none::NoneAg,
+            occurrences::Occurrences,
+            percentile::{Percentile, DEFAULT_SAMPLES},
+            quantiles::Quantiles,
+            sparkline::{Sparkline, DEFAULT_WINDOW},
             sum::Sum,
         },
         error::LogriaError,
     },
 };
 
+/// Placed between the parts of a message when several parts are rendered at once
+pub const FIELD_SEPARATOR: &str = " | ";
+
 #[derive(Eq, Hash, PartialEq, Serialize, Deserialize, Debug)]
 pub enum PatternType {
     Split,
     Regex,
+    Json,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -38,8 +54,19 @@ pub struct Parser {
     pub example: String,
     pub order: Vec<String>,
     pub aggregation_methods: HashMap<String, AggregationMethod>,
+    #[serde(default)]
+    pub decimal_separator: DecimalSeparator,
+    /// Count values that differ only in case together, i.e. `GET` and `get`
+    #[serde(default)]
+    pub ignore_case: bool,
+    /// Values above which a field's numeric results are colored in the aggregation panel
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub thresholds: HashMap<String, Thresholds>,
     #[serde(skip_serializing, skip_deserializing)]
     pub aggregator_map: HashMap<String, Box<dyn Aggregator>>,
+    /// The error from the most recent update of each aggregator that failed to update
+    #[serde(skip_serializing, skip_deserializing)]
+    pub aggregator_errors: HashMap<String, String>,
 }
 
 impl ExtensionMethods for Parser {
@@ -133,7 +160,11 @@ impl Parser {
             example,
             order,
             aggregation_methods,
+            decimal_separator: DecimalSeparator::default(),
+            ignore_case: false,
+            thresholds: HashMap::new(),
             aggregator_map: HashMap::new(),
+            aggregator_errors: HashMap::new(),
         }
     }
 
@@ -156,37 +187,105 @@ impl Parser {
             if let Some(method) = self.aggregation_methods.get(method_name) {
                 match method {
                     AggregationMethod::Mean => {
-                        self.aggregator_map
-                            .insert(method_name.to_string(), Box::new(Mean::new()));
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Mean::new(self.decimal_separator)),
+                        );
                     }
                     AggregationMethod::Mode => {
-                        self.aggregator_map
-                            .insert(method_name.to_string(), Box::new(Counter::new(Some(1))));
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Counter::new(Some(1)).ignore_case(self.ignore_case)),
+                        );
                     }
                     AggregationMethod::Sum => {
-                        self.aggregator_map
-                            .insert(method_name.to_string(), Box::new(Sum::new()));
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Sum::new(self.decimal_separator)),
+                        );
                     }
                     AggregationMethod::Count => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Counter::new(None).ignore_case(self.ignore_case)),
+                        );
+                    }
+                    AggregationMethod::Rare => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Counter::rare(None).ignore_case(self.ignore_case)),
+                        );
+                    }
+                    AggregationMethod::Distinct => {
                         self.aggregator_map
-                            .insert(method_name.to_string(), Box::new(Counter::new(None)));
+                            .insert(method_name.to_string(), Box::new(Distinct::new()));
                     }
                     AggregationMethod::Date(format) => {
                         self.aggregator_map.insert(
                             method_name.to_string(),
-                            Box::new(Date::new(format, DateParserType::Date)),
+                            Box::new(Date::new(format.candidates(), DateParserType::Date)),
                         );
                     }
                     AggregationMethod::Time(format) => {
                         self.aggregator_map.insert(
                             method_name.to_string(),
-                            Box::new(Date::new(format, DateParserType::Time)),
+                            Box::new(Date::new(format.candidates(), DateParserType::Time)),
                         );
                     }
                     AggregationMethod::DateTime(format) => {
                         self.aggregator_map.insert(
                             method_name.to_string(),
-                            Box::new(Date::new(format, DateParserType::DateTime)),
+                            Box::new(Date::new(format.candidates(), DateParserType::DateTime)),
+                        );
+                    }
+                    AggregationMethod::Sparkline => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Sparkline::new(self.decimal_separator, DEFAULT_WINDOW)),
+                        );
+                    }
+                    AggregationMethod::Quantiles => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Quantiles::new(self.decimal_separator)),
+                        );
+                    }
+                    AggregationMethod::Median => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Median::new(self.decimal_separator)),
+                        );
+                    }
+                    AggregationMethod::Percentile(percentile) => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Percentile::new(
+                                *percentile,
+                                self.decimal_separator,
+                                DEFAULT_SAMPLES,
+                            )),
+                        );
+                    }
+                    AggregationMethod::Min => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Min::new(self.decimal_separator)),
+                        );
+                    }
+                    AggregationMethod::Max => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(Max::new(self.decimal_separator)),
+                        );
+                    }
+                    AggregationMethod::Occurrences(pattern) => {
+                        self.aggregator_map
+                            .insert(method_name.to_string(), Box::new(Occurrences::new(pattern)));
+                    }
+                    AggregationMethod::GroupedCount(_) => {
+                        self.aggregator_map.insert(
+                            method_name.to_string(),
+                            Box::new(GroupedCounter::new().ignore_case(self.ignore_case)),
                         );
                     }
                     AggregationMethod::None => {
@@ -195,9 +294,34 @@ impl Parser {
                     }
                 };
             }
+            if let (Some(thresholds), Some(aggregator)) = (
+                self.thresholds.get(method_name),
+                self.aggregator_map.get_mut(method_name),
+            ) {
+                aggregator.set_thresholds(*thresholds);
+            }
         }
     }
 
+    /// Get the formats of the `Date`, `Time`, and `DateTime` fields, in field order
+    pub fn date_formats(&self) -> Vec<(String, DateParserType)> {
+        self.order
+            .iter()
+            .filter_map(|name| match self.aggregation_methods.get(name)? {
+                AggregationMethod::Date(format) => Some((format, DateParserType::Date)),
+                AggregationMethod::Time(format) => Some((format, DateParserType::Time)),
+                AggregationMethod::DateTime(format) => Some((format, DateParserType::DateTime)),
+                _ => None,
+            })
+            .flat_map(|(format, parser_type)| {
+                format
+                    .candidates()
+                    .into_iter()
+                    .map(move |format| (format, parser_type))
+            })
+            .collect()
+    }
+
     pub fn get_regex(&self) -> Result<Regex, LogriaError> {
         if self.pattern_type == PatternType::Regex {
             match Regex::new(&self.pattern) {
@@ -230,23 +354,298 @@ impl Parser {
                 }
             },
             PatternType::Split => {
-                self.example
-                    .split(&self.pattern)
-                    .collect::<Vec<&str>>()
+                // Split the same way as messages so the index picker matches what is parsed
+                self.split_parts(&self.example)
                     .iter()
                     .for_each(|value| example.push(value.to_string()));
             }
+            PatternType::Json => {
+                // The example must be valid even if the pattern names every field
+                if !matches!(
+                    serde_json::from_str::<Value>(&self.example),
+                    Ok(Value::Object(_))
+                ) {
+                    return Err(LogriaError::InvalidExampleJson(self.example.to_owned()));
+                }
+                example.extend(self.json_paths()?);
+            }
         };
 
-        // Validate the size of the generated text
-        if example.len() != self.aggregation_methods.len() {
+        // Validate the size of the generated text; grouped fields repeat a name in `order`
+        if example.len() != self.order.len() {
             return Err(LogriaError::InvalidExampleSplit(
                 example.len(),
-                self.aggregation_methods.len(),
+                self.order.len(),
             ));
         }
         Ok(example)
     }
+
+    /// Parse a batch of messages with the parser rules, returning the parts at `indices` for each
+    ///
+    /// Several parts are joined with [`FIELD_SEPARATOR`]; messages missing any of the parts are `None`.
+    pub fn apply(
+        &self,
+        messages: &[String],
+        indices: &[usize],
+    ) -> Result<Vec<Option<String>>, LogriaError> {
+        match self.pattern_type {
+            PatternType::Regex => {
+                // Compile the pattern once for the whole batch
+                let pattern = self.get_regex()?;
+                Ok(messages
+                    .iter()
+                    .map(|message| Parser::regex_part(&pattern, message, indices))
+                    .collect())
+            }
+            PatternType::Split => Ok(messages
+                .iter()
+                .map(|message| self.split_part(message, indices))
+                .collect()),
+            PatternType::Json => {
+                // Resolve the fields once for the whole batch
+                let paths = self.json_paths()?;
+                Ok(messages
+                    .iter()
+                    .map(|message| {
+                        let value = serde_json::from_str::<Value>(message).ok()?;
+                        Parser::join_parts(
+                            indices
+                                .iter()
+                                .map(|index| Parser::json_part(&value, paths.get(*index)?)),
+                        )
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Join the parts of a message, unless any part is missing
+    fn join_parts<S: Borrow<str>>(parts: impl Iterator<Item = Option<S>>) -> Option<String> {
+        parts
+            .collect::<Option<Vec<S>>>()
+            .filter(|parts| !parts.is_empty())
+            .map(|parts| parts.join(FIELD_SEPARATOR))
+    }
+
+    /// Get the capture groups at `indices` of a message
+    fn regex_part(pattern: &Regex, message: &str, indices: &[usize]) -> Option<String> {
+        let caps = pattern.captures(message)?;
+        // We add 1 here because the zeroth index of a Capture is the original message
+        Parser::join_parts(indices.iter().map(|index| {
+            caps.get(index.checked_add(1).unwrap_or(*index))
+                .map(|s| s.as_str())
+        }))
+    }
+
+    /// Split a message on the literal pattern, ignoring a trailing delimiter
+    fn split_parts<'a>(&self, message: &'a str) -> Vec<&'a str> {
+        message.split_terminator(self.pattern.as_str()).collect()
+    }
+
+    /// Get the split parts at `indices` of a message
+    fn split_part(&self, message: &str, indices: &[usize]) -> Option<String> {
+        let parts = self.split_parts(message);
+        Parser::join_parts(indices.iter().map(|index| parts.get(*index).copied()))
+    }
+
+    /// Get the dotted path of each field of a JSON parser, i.e. `response.status`
+    ///
+    /// Paths are separated by commas in the pattern; an empty pattern uses the top-level keys of the example.
+    fn json_paths(&self) -> Result<Vec<String>, LogriaError> {
+        if !self.pattern.trim().is_empty() {
+            return Ok(self
+                .pattern
+                .split(',')
+                .map(|path| path.trim().to_owned())
+                .collect());
+        }
+        match serde_json::from_str::<Value>(&self.example) {
+            Ok(Value::Object(fields)) => Ok(fields.keys().cloned().collect()),
+            _ => Err(LogriaError::InvalidExampleJson(self.example.to_owned())),
+        }
+    }
+
+    /// Get the value at a dotted path of a JSON message, where numeric keys index into arrays
+    fn json_part(value: &Value, path: &str) -> Option<String> {
+        let field = path.split('.').try_fold(value, |node, key| match node {
+            Value::Object(fields) => fields.get(key),
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })?;
+        // Strings are shown without their quotes, everything else as JSON
+        match field {
+            Value::String(text) => Some(text.to_owned()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// Split a message into all of its parsed parts
+    fn message_parts<'a>(&self, message: &'a str) -> Result<Vec<Cow<'a, str>>, LogriaError> {
+        match self.pattern_type {
+            PatternType::Regex => match self.get_regex()?.captures(message) {
+                Some(captures) => Ok(captures
+                    .iter()
+                    .skip(1)
+                    .flatten()
+                    .map(|f| Cow::Borrowed(f.as_str()))
+                    .collect()),
+                None => Err(LogriaError::CannotParseMessage(
+                    "regex did not match message!".to_string(),
+                )),
+            },
+            PatternType::Split => Ok(self
+                .split_parts(message)
+                .into_iter()
+                .map(Cow::Borrowed)
+                .collect()),
+            PatternType::Json => {
+                let value = serde_json::from_str::<Value>(message).map_err(|_| {
+                    LogriaError::CannotParseMessage("message is not JSON!".to_string())
+                })?;
+                self.json_paths()?
+                    .iter()
+                    .map(|path| {
+                        Parser::json_part(&value, path)
+                            .map(Cow::Owned)
+                            .ok_or_else(|| {
+                                LogriaError::CannotParseMessage(format!(
+                                    "no field {} in message!",
+                                    path
+                                ))
+                            })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Combine the parts of a message whose names repeat in `order`, keeping the order each name first appears in
+    ///
+    /// Grouped numeric parts are summed; if any part is not numeric, the parts are joined with a space.
+    fn grouped_parts(
+        &self,
+        message_parts: &[Cow<str>],
+    ) -> Result<Vec<(String, String)>, LogriaError> {
+        let mut groups: Vec<(String, Vec<&str>)> = vec![];
+        for (idx, part) in message_parts.iter().enumerate() {
+            match self.order.get(idx) {
+                Some(name) => match groups.iter_mut().find(|(group, _)| group == name) {
+                    Some((_, parts)) => parts.push(part.as_ref()),
+                    None => groups.push((name.to_owned(), vec![part.as_ref()])),
+                },
+                None => {
+                    return Err(LogriaError::CannotParseMessage(
+                        "number of aggregation methods not equal to number of matches!".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(name, parts)| (name, self.combine(&parts)))
+            .collect())
+    }
+
+    /// Merge the parts of a grouped field into a single value for its aggregator
+    fn combine(&self, parts: &[&str]) -> String {
+        if let [part] = parts {
+            return part.to_string();
+        }
+        let numbers: Option<Vec<f64>> = parts
+            .iter()
+            .map(|part| extract_number(part, self.decimal_separator))
+            .collect();
+        match numbers {
+            Some(numbers) => {
+                let total = numbers.iter().sum::<f64>().to_string();
+                // Aggregators parse the total with the same separator convention
+                match self.decimal_separator {
+                    DecimalSeparator::Period => total,
+                    DecimalSeparator::Comma => total.replace('.', ","),
+                }
+            }
+            None => parts.join(" "),
+        }
+    }
+
+    /// Update the aggregators with a single message
+    ///
+    /// If `render` is set, returns the name of each part followed by the `num_to_get` messages of its aggregator
+    /// and the error from its last update, if that update failed
+    pub fn aggregate(
+        &mut self,
+        message: &str,
+        num_to_get: &usize,
+        render: bool,
+    ) -> Result<Vec<String>, LogriaError> {
+        let message_parts = self.message_parts(message)?;
+        let grouped_parts = self.grouped_parts(&message_parts)?;
+
+        // If we got this far, allocate the return value
+        let mut aggregated_data = vec![];
+        for (item, part) in &grouped_parts {
+            if let Some(aggregator) = self.aggregator_map.get_mut(item) {
+                // Grouped counts also consume the value of the field they group by
+                let update = match self.aggregation_methods.get(item) {
+                    Some(AggregationMethod::GroupedCount(field)) => {
+                        match grouped_parts.iter().find(|(name, _)| name == field) {
+                            Some((_, group)) => aggregator.update_in_group(part, group),
+                            None => Err(LogriaError::CannotParseMessage(format!(
+                                "no field {} to group {} by",
+                                field, item
+                            ))),
+                        }
+                    }
+                    _ => aggregator.update(part),
+                };
+                // A field that fails to update should not stop the other fields from aggregating
+                match update {
+                    Ok(_) => self.aggregator_errors.remove(item),
+                    Err(why) => self
+                        .aggregator_errors
+                        .insert(item.to_owned(), why.to_string()),
+                };
+                if render {
+                    self.render_field(item, num_to_get, &mut aggregated_data);
+                }
+            } else {
+                return Err(LogriaError::InvalidParserState(format!(
+                    "aggregator missing for {}!",
+                    item
+                )));
+            }
+        }
+        Ok(aggregated_data)
+    }
+
+    /// Render the current state of every aggregator without updating them, i.e. when the number of items to show changes
+    pub fn render_aggregation(&self, num_to_get: &usize) -> Vec<String> {
+        let mut aggregated_data = vec![];
+        let mut rendered: Vec<&String> = vec![];
+        for item in &self.order {
+            // Fields that group several parts appear in the order more than once
+            if !rendered.contains(&item) {
+                rendered.push(item);
+                self.render_field(item, num_to_get, &mut aggregated_data);
+            }
+        }
+        aggregated_data
+    }
+
+    /// Add a field's name, its aggregator's messages, and the error from its last update to the rendered data
+    fn render_field(&self, item: &str, num_to_get: &usize, aggregated_data: &mut Vec<String>) {
+        if let Some(aggregator) = self.aggregator_map.get(item) {
+            // Name of aggregated part
+            aggregated_data.push(item.to_owned());
+            // Messages generated for that aggregator
+            aggregated_data.extend(aggregator.messages(num_to_get));
+            // Error from the last update, if any
+            if let Some(error) = self.aggregator_errors.get(item) {
+                aggregated_data.push(format!("    {}", error));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -259,6 +658,7 @@ mod parse_tests {
             extension::ExtensionMethods,
             parser::{AggregationMethod, Parser, PatternType},
         },
+        util::error::LogriaError,
     };
 
     #[test]
@@ -267,7 +667,7 @@ mod parse_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Level"), AggregationMethod::Count);
@@ -298,7 +698,7 @@ mod parse_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Level"), AggregationMethod::Count);
@@ -326,7 +726,7 @@ mod parse_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Level"), AggregationMethod::Count);
@@ -334,7 +734,7 @@ mod parse_tests {
         let mut map2 = HashMap::new();
         map2.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map2.insert(String::from("Method"), AggregationMethod::Count);
         map2.insert(String::from("Level"), AggregationMethod::Count);
@@ -380,9 +780,9 @@ mod parse_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("DateTime"),
-            AggregationMethod::DateTime(String::from(
-                "[year]-[month]-[day] [hour]:[minute]:[second]",
-            )),
+            AggregationMethod::DateTime(
+                String::from("[year]-[month]-[day] [hour]:[minute]:[second]").into(),
+            ),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Level"), AggregationMethod::Count);
@@ -390,9 +790,9 @@ mod parse_tests {
         let mut map2 = HashMap::new();
         map2.insert(
             String::from("DateTime"),
-            AggregationMethod::DateTime(String::from(
-                "[year]-[month]-[day] [hour]:[minute]:[second]",
-            )),
+            AggregationMethod::DateTime(
+                String::from("[year]-[month]-[day] [hour]:[minute]:[second]").into(),
+            ),
         );
         map2.insert(String::from("Method"), AggregationMethod::Count);
         map2.insert(String::from("Level"), AggregationMethod::Count);
@@ -458,12 +858,21 @@ mod parse_tests {
         assert!(regex.is_ok());
     }
 
+    #[test]
+    fn cannot_load_missing_file() {
+        let file_name = format!("{}/{}", patterns(), "Missing Parser Test");
+        assert!(matches!(
+            Parser::load(&file_name),
+            Err(LogriaError::CannotRead(..))
+        ));
+    }
+
     #[test]
     fn cannot_get_regex() {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Level"), AggregationMethod::Count);
@@ -528,7 +937,7 @@ mod parse_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Level"), AggregationMethod::Count);
@@ -561,34 +970,109 @@ mod parse_tests {
     }
 }
 
+#[cfg(test)]
+mod split_tests {
+    use crate::extensions::parser::{AggregationMethod, Parser, PatternType};
+
+    fn parser(pattern: &str, example: &str, fields: usize) -> Parser {
+        let order: Vec<String> = (0..fields).map(|field| field.to_string()).collect();
+        let map = order
+            .iter()
+            .map(|name| (name.to_owned(), AggregationMethod::Count))
+            .collect();
+        Parser::new(
+            pattern.to_string(),
+            PatternType::Split,
+            example.to_string(),
+            order,
+            map,
+        )
+    }
+
+    /// The index picker and the parsed messages must see the same parts
+    fn assert_agree(pattern: &str, example: &str, expected: &[&str]) {
+        let parser = parser(pattern, example, expected.len());
+        let picked = parser.get_example().unwrap();
+        let parsed: Vec<Option<String>> = (0..expected.len())
+            .map(|index| parser.split_part(example, &[index]))
+            .collect();
+        assert_eq!(picked, expected);
+        assert_eq!(parser.message_parts(example).unwrap(), expected);
+        assert_eq!(
+            parsed,
+            expected
+                .iter()
+                .map(|part| Some(part.to_string()))
+                .collect::<Vec<Option<String>>>()
+        );
+    }
+
+    #[test]
+    fn agree_on_pipe() {
+        assert_agree("|", "a|b|c", &["a", "b", "c"]);
+    }
+
+    #[test]
+    fn agree_on_spaced_pipe() {
+        assert_agree(" | ", "INFO | app | started", &["INFO", "app", "started"]);
+    }
+
+    #[test]
+    fn agree_on_period() {
+        assert_agree(".", "192.168.0.1", &["192", "168", "0", "1"]);
+    }
+
+    #[test]
+    fn agree_on_regex_special_characters() {
+        assert_agree(".*", "a.*b", &["a", "b"]);
+        assert_agree("[", "a[b", &["a", "b"]);
+    }
+
+    #[test]
+    fn agree_on_trailing_delimiter() {
+        assert_agree("|", "a|b|", &["a", "b"]);
+    }
+
+    #[test]
+    fn agree_on_multibyte_delimiter() {
+        assert_agree(" → ", "start → end", &["start", "end"]);
+    }
+}
+
 #[cfg(test)]
 mod aggregate_tests {
     use std::collections::HashMap;
 
-    use crate::extensions::parser::{AggregationMethod, Parser, PatternType};
+    use crate::{
+        constants::cli::colors::{RESET_COLOR, WARNING_COLOR},
+        extensions::parser::{AggregationMethod, DecimalSeparator, Parser, PatternType},
+        util::aggregators::aggregator::Thresholds,
+    };
 
     #[test]
     fn test_can_setup_multiple_aggregation_methods() {
         let mut map = HashMap::new();
         map.insert(
             String::from("Date"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         map.insert(String::from("Method"), AggregationMethod::Count);
         map.insert(String::from("Message"), AggregationMethod::Mode);
         map.insert(
             String::from("Level"),
-            AggregationMethod::Date("".to_string()),
+            AggregationMethod::Date("".to_string().into()),
         );
         map.insert(
             String::from("Level"),
             AggregationMethod::DateTime(
-                "[year]-[month]-[day] [hour]:[minute]:[second]".to_string(),
+                "[year]-[month]-[day] [hour]:[minute]:[second]"
+                    .to_string()
+                    .into(),
             ),
         );
         map.insert(
             String::from("Level"),
-            AggregationMethod::Time("[hour]:[minute]:[second]".to_string()),
+            AggregationMethod::Time("[hour]:[minute]:[second]".to_string().into()),
         );
         map.insert(String::from("Message"), AggregationMethod::Mean);
         map.insert(String::from("Message"), AggregationMethod::Sum);
@@ -613,7 +1097,7 @@ mod aggregate_tests {
         let mut map = HashMap::new();
         map.insert(
             String::from("1"),
-            AggregationMethod::Date(String::from("[year]-[month]-[day]")),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
         );
         let mut parser = Parser::new(
             String::from(" - "),
@@ -627,12 +1111,24 @@ mod aggregate_tests {
     }
 
     #[test]
-    fn test_can_setup_time() {
+    fn test_can_setup_quantiles() {
         let mut map = HashMap::new();
-        map.insert(
-            String::from("1"),
-            AggregationMethod::Time(String::from("[hour]:[minute]:[second]")),
+        map.insert(String::from("1"), AggregationMethod::Quantiles);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["1".to_string()],
+            map,
         );
+        parser.setup();
+        assert!(parser.aggregator_map.contains_key("1"));
+    }
+
+    #[test]
+    fn test_can_setup_median() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Median);
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -641,18 +1137,52 @@ mod aggregate_tests {
             map,
         );
         parser.setup();
-        assert!(parser.aggregator_map.get("1").is_some());
+        assert!(parser.aggregator_map.contains_key("1"));
     }
 
     #[test]
-    fn test_can_setup_datetime() {
+    fn test_can_count_distinct_split() {
         let mut map = HashMap::new();
-        map.insert(
-            String::from("1"),
-            AggregationMethod::DateTime(String::from(
-                "[year]-[month]-[day] [hour]:[minute]:[second]",
-            )),
+        map.insert(String::from("Method"), AggregationMethod::Distinct);
+        map.insert(String::from("Path"), AggregationMethod::None);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["Method".to_string(), "Path".to_string()],
+            map,
+        );
+        parser.setup();
+        for message in ["GET - /", "POST - /users", "GET  - /users"] {
+            parser.aggregate(message, &5, false).unwrap();
+        }
+        let result = parser.aggregate("GET - /", &5, true).unwrap();
+        assert_eq!(result[..3], ["Method", "    Distinct: 2", "    Total: 4"]);
+    }
+
+    #[test]
+    fn test_can_count_distinct_regex() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Status"), AggregationMethod::Distinct);
+        let mut parser = Parser::new(
+            String::from("status=(\\d+)"),
+            PatternType::Regex,
+            String::from("status=200"),
+            vec!["Status".to_string()],
+            map,
         );
+        parser.setup();
+        for message in ["status=200", "status=404", "status=200"] {
+            parser.aggregate(message, &5, false).unwrap();
+        }
+        let result = parser.aggregate("status=500", &5, true).unwrap();
+        assert_eq!(result, vec!["Status", "    Distinct: 3", "    Total: 4"]);
+    }
+
+    #[test]
+    fn test_can_setup_percentile() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Percentile(95.));
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -661,13 +1191,45 @@ mod aggregate_tests {
             map,
         );
         parser.setup();
-        assert!(parser.aggregator_map.get("1").is_some());
+        let result = parser.aggregate("12 ms", &5, true).unwrap();
+        assert_eq!(result, vec!["1", "    p95: 12.00", "    Count: 1"]);
     }
 
     #[test]
-    fn test_can_setup_count() {
+    fn test_can_setup_min_and_max() {
         let mut map = HashMap::new();
-        map.insert(String::from("1"), AggregationMethod::Count);
+        map.insert(String::from("Low"), AggregationMethod::Min);
+        map.insert(String::from("High"), AggregationMethod::Max);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["Low".to_string(), "High".to_string()],
+            map,
+        );
+        parser.setup();
+        parser.aggregate("4 - 4", &5, false).unwrap();
+        let result = parser.aggregate("-2 - 7", &5, true).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                "Low",
+                "    Min: -2.00",
+                "    Count: 2",
+                "High",
+                "    Max: 7.00",
+                "    Count: 2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_can_setup_occurrences() {
+        let mut map = HashMap::new();
+        map.insert(
+            String::from("1"),
+            AggregationMethod::Occurrences(String::from("retry")),
+        );
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -676,13 +1238,20 @@ mod aggregate_tests {
             map,
         );
         parser.setup();
-        assert!(parser.aggregator_map.get("1").is_some());
+        let result = parser.aggregate("retry retry", &5, true).unwrap();
+        assert_eq!(
+            result,
+            vec!["1", "    Total: 2", "    Per message: 2.00", "    Count: 1"]
+        );
     }
 
     #[test]
-    fn test_can_setup_mode() {
+    fn test_can_setup_time() {
         let mut map = HashMap::new();
-        map.insert(String::from("1"), AggregationMethod::Mode);
+        map.insert(
+            String::from("1"),
+            AggregationMethod::Time(String::from("[hour]:[minute]:[second]").into()),
+        );
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -695,9 +1264,14 @@ mod aggregate_tests {
     }
 
     #[test]
-    fn test_can_setup_mean() {
+    fn test_can_setup_datetime() {
         let mut map = HashMap::new();
-        map.insert(String::from("1"), AggregationMethod::Mean);
+        map.insert(
+            String::from("1"),
+            AggregationMethod::DateTime(
+                String::from("[year]-[month]-[day] [hour]:[minute]:[second]").into(),
+            ),
+        );
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -710,9 +1284,9 @@ mod aggregate_tests {
     }
 
     #[test]
-    fn test_can_setup_sum() {
+    fn test_can_setup_sparkline() {
         let mut map = HashMap::new();
-        map.insert(String::from("1"), AggregationMethod::Sum);
+        map.insert(String::from("1"), AggregationMethod::Sparkline);
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -721,13 +1295,13 @@ mod aggregate_tests {
             map,
         );
         parser.setup();
-        assert!(parser.aggregator_map.get("1").is_some());
+        assert!(parser.aggregator_map.contains_key("1"));
     }
 
     #[test]
-    fn test_can_setup_none() {
+    fn test_can_setup_count() {
         let mut map = HashMap::new();
-        map.insert(String::from("1"), AggregationMethod::None);
+        map.insert(String::from("1"), AggregationMethod::Count);
         let mut parser = Parser::new(
             String::from(" - "),
             PatternType::Split,
@@ -738,4 +1312,680 @@ mod aggregate_tests {
         parser.setup();
         assert!(parser.aggregator_map.get("1").is_some());
     }
+
+    #[test]
+    fn test_can_setup_mode() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Mode);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["1".to_string()],
+            map,
+        );
+        parser.setup();
+        assert!(parser.aggregator_map.get("1").is_some());
+    }
+
+    #[test]
+    fn test_can_setup_rare() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Rare);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["1".to_string()],
+            map,
+        );
+        parser.setup();
+        assert!(parser.aggregator_map.contains_key("1"));
+    }
+
+    #[test]
+    fn test_can_setup_mean() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Mean);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["1".to_string()],
+            map,
+        );
+        parser.setup();
+        assert!(parser.aggregator_map.get("1").is_some());
+    }
+
+    #[test]
+    fn test_can_setup_sum() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::Sum);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["1".to_string()],
+            map,
+        );
+        parser.setup();
+        assert!(parser.aggregator_map.get("1").is_some());
+    }
+
+    #[test]
+    fn test_can_setup_none() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::None);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from(""),
+            vec!["1".to_string()],
+            map,
+        );
+        parser.setup();
+        assert!(parser.aggregator_map.get("1").is_some());
+    }
+
+    #[test]
+    fn can_apply_split() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::None);
+        map.insert(String::from("2"), AggregationMethod::None);
+        let parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("a - b"),
+            vec!["1".to_string(), "2".to_string()],
+            map,
+        );
+        let messages = vec![
+            String::from("I - Am"),
+            String::from("A - Test"),
+            String::from("Unsplit"),
+        ];
+        assert_eq!(
+            parser.apply(&messages, &[1]).unwrap(),
+            vec![Some(String::from("Am")), Some(String::from("Test")), None]
+        );
+    }
+
+    #[test]
+    fn can_apply_regex() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::None);
+        let parser = Parser::new(
+            String::from("(\\d+)"),
+            PatternType::Regex,
+            String::from("1"),
+            vec!["1".to_string()],
+            map,
+        );
+        let messages = vec![
+            String::from("Log message part 65 test"),
+            String::from("No number"),
+            String::from("12"),
+        ];
+        assert_eq!(
+            parser.apply(&messages, &[0]).unwrap(),
+            vec![Some(String::from("65")), None, Some(String::from("12"))]
+        );
+    }
+
+    #[test]
+    fn can_apply_split_multiple_indices() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::None);
+        map.insert(String::from("2"), AggregationMethod::None);
+        map.insert(String::from("3"), AggregationMethod::None);
+        let parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("a - b - c"),
+            vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            map,
+        );
+        let messages = vec![String::from("I - Am - A"), String::from("Test - Message")];
+        assert_eq!(
+            parser.apply(&messages, &[2, 0]).unwrap(),
+            vec![Some(String::from("A | I")), None]
+        );
+    }
+
+    #[test]
+    fn can_apply_regex_multiple_indices() {
+        let mut map = HashMap::new();
+        map.insert(String::from("1"), AggregationMethod::None);
+        map.insert(String::from("2"), AggregationMethod::None);
+        let parser = Parser::new(
+            String::from("(\\w+)=(\\d+)"),
+            PatternType::Regex,
+            String::from("a=1"),
+            vec!["1".to_string(), "2".to_string()],
+            map,
+        );
+        let messages = vec![String::from("took=15 ms"), String::from("No number")];
+        assert_eq!(
+            parser.apply(&messages, &[0, 1]).unwrap(),
+            vec![Some(String::from("took | 15")), None]
+        );
+    }
+
+    #[test]
+    fn cannot_apply_bad_regex() {
+        let parser = Parser::new(
+            String::from("(\\d+"),
+            PatternType::Regex,
+            String::from("1"),
+            vec![],
+            HashMap::new(),
+        );
+        assert!(parser.apply(&[String::from("1")], &[0]).is_err());
+    }
+
+    #[test]
+    fn can_aggregate() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Level"), AggregationMethod::Count);
+        map.insert(String::from("Message"), AggregationMethod::None);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("INFO - message"),
+            vec!["Level".to_string(), "Message".to_string()],
+            map,
+        );
+        parser.setup();
+
+        assert!(parser.aggregate("INFO - a", &5, false).unwrap().is_empty());
+        let result = parser.aggregate("INFO - b", &5, true).unwrap();
+        assert_eq!(result[0], "Level");
+        assert!(result.contains(&String::from("Message")));
+    }
+
+    #[test]
+    fn cannot_aggregate_unbalanced() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Level"), AggregationMethod::Count);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("INFO"),
+            vec!["Level".to_string()],
+            map,
+        );
+        parser.setup();
+        assert!(parser.aggregate("INFO - a", &5, true).is_err());
+    }
+
+    #[test]
+    fn can_sum_grouped_fields() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Host"), AggregationMethod::Count);
+        map.insert(String::from("Bytes"), AggregationMethod::Sum);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("a - 1 - 2"),
+            vec!["Host".to_string(), "Bytes".to_string(), "Bytes".to_string()],
+            map,
+        );
+        parser.setup();
+
+        parser.aggregate("a - 10 - 5", &5, false).unwrap();
+        let result = parser.aggregate("b - 1,000 - 2.5", &5, true).unwrap();
+        assert_eq!(
+            result[result.len() - 2..],
+            vec!["Bytes".to_string(), "    Total: 1,018".to_string()]
+        );
+        assert_eq!(result.iter().filter(|item| *item == "Bytes").count(), 1);
+    }
+
+    #[test]
+    fn can_mean_grouped_fields() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Size"), AggregationMethod::Mean);
+        let mut parser = Parser::new(
+            String::from("(\\d+) (\\d+)"),
+            PatternType::Regex,
+            String::from("1 2"),
+            vec!["Size".to_string(), "Size".to_string()],
+            map,
+        );
+        parser.setup();
+
+        parser.aggregate("1 2", &5, false).unwrap();
+        let result = parser.aggregate("3 4", &5, true).unwrap();
+        assert_eq!(
+            result,
+            vec!["Size", "    Mean: 5.00", "    Count: 2", "    Total: 10"]
+        );
+    }
+
+    #[test]
+    fn can_sum_grouped_fields_comma_separator() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Size"), AggregationMethod::Sum);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("1 - 2"),
+            vec!["Size".to_string(), "Size".to_string()],
+            map,
+        );
+        parser.decimal_separator = DecimalSeparator::Comma;
+        parser.setup();
+
+        let result = parser.aggregate("1,5 - 2,25", &5, true).unwrap();
+        assert_eq!(result, vec!["Size", "    Total: 4"]);
+    }
+
+    #[test]
+    fn can_count_ignoring_case() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Method"), AggregationMethod::Count);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("GET"),
+            vec!["Method".to_string()],
+            map,
+        );
+        parser.ignore_case = true;
+        parser.setup();
+
+        parser.aggregate("GET", &5, false).unwrap();
+        let result = parser.aggregate("get", &5, true).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                "Method".to_string(),
+                format!("    get{}: 2 (100%)", RESET_COLOR)
+            ]
+        );
+    }
+
+    #[test]
+    fn can_count_grouped_by_field() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Endpoint"), AggregationMethod::None);
+        map.insert(
+            String::from("Status"),
+            AggregationMethod::GroupedCount(String::from("Endpoint")),
+        );
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("/users - 200"),
+            vec!["Endpoint".to_string(), "Status".to_string()],
+            map,
+        );
+        parser.setup();
+
+        parser.aggregate("/users - 200", &5, false).unwrap();
+        parser.aggregate("/orders - 500", &5, false).unwrap();
+        let result = parser.aggregate("/users - 404", &5, true).unwrap();
+        assert_eq!(
+            result[2..],
+            vec![
+                "Status".to_string(),
+                "    /users: 2".to_string(),
+                format!("        200{}: 1 (50%)", RESET_COLOR),
+                format!("        404{}: 1 (50%)", RESET_COLOR),
+                "    /orders: 1".to_string(),
+                format!("        500{}: 1 (100%)", RESET_COLOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_color_above_threshold() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Latency"), AggregationMethod::Mean);
+        map.insert(String::from("Bytes"), AggregationMethod::Sum);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("1 - 2"),
+            vec!["Latency".to_string(), "Bytes".to_string()],
+            map,
+        );
+        parser.thresholds.insert(
+            String::from("Latency"),
+            Thresholds {
+                warning: Some(100.),
+                critical: Some(500.),
+            },
+        );
+        parser.setup();
+
+        let result = parser.aggregate("250 - 2000", &5, true).unwrap();
+        assert_eq!(
+            result[1],
+            format!("    Mean: {}250.00{}", WARNING_COLOR, RESET_COLOR)
+        );
+        assert_eq!(result[5], "    Total: 2,000");
+    }
+
+    #[test]
+    fn cannot_count_grouped_by_missing_field() {
+        let mut map = HashMap::new();
+        map.insert(
+            String::from("Status"),
+            AggregationMethod::GroupedCount(String::from("Endpoint")),
+        );
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("200"),
+            vec!["Status".to_string()],
+            map,
+        );
+        parser.setup();
+
+        let result = parser.aggregate("200", &5, true).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                "Status",
+                "    Unable to parse message: no field Endpoint to group Status by"
+            ]
+        );
+    }
+
+    #[test]
+    fn can_join_grouped_text_fields() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Name"), AggregationMethod::Count);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("first - last"),
+            vec!["Name".to_string(), "Name".to_string()],
+            map,
+        );
+        parser.setup();
+
+        let result = parser.aggregate("Ada - Lovelace", &5, true).unwrap();
+        assert_eq!(result[0], "Name");
+        assert!(result[1].contains("Ada Lovelace"));
+    }
+
+    #[test]
+    fn can_get_example_with_grouped_fields() {
+        let mut map = HashMap::new();
+        map.insert(String::from("Host"), AggregationMethod::Count);
+        map.insert(String::from("Bytes"), AggregationMethod::Sum);
+        let parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("a - 1 - 2"),
+            vec!["Host".to_string(), "Bytes".to_string(), "Bytes".to_string()],
+            map,
+        );
+        assert_eq!(parser.get_example().unwrap(), vec!["a", "1", "2"]);
+    }
+
+    #[test]
+    fn can_aggregate_around_field_error() {
+        let mut map = HashMap::new();
+        map.insert(
+            String::from("Date"),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
+        );
+        map.insert(String::from("Size"), AggregationMethod::Mean);
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("2021-03-19 - 1"),
+            vec!["Date".to_string(), "Size".to_string()],
+            map,
+        );
+        parser.setup();
+
+        parser.aggregate("not a date - 2", &5, false).unwrap();
+        let result = parser.aggregate("03/19/2021 - 4", &5, true).unwrap();
+
+        assert_eq!(result[0], "Date");
+        assert!(result.contains(&String::from("    Failures: 2")));
+        let size = result.iter().position(|item| item == "Size").unwrap();
+        assert!(result[size - 1].starts_with("    Invalid format description"));
+        assert_eq!(
+            result[size..],
+            vec!["Size", "    Mean: 3.00", "    Count: 2", "    Total: 6"]
+        );
+    }
+
+    #[test]
+    fn clears_field_error_after_update() {
+        let mut map = HashMap::new();
+        map.insert(
+            String::from("Date"),
+            AggregationMethod::Date(String::from("[year]-[month]-[day]").into()),
+        );
+        let mut parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("2021-03-19"),
+            vec!["Date".to_string()],
+            map,
+        );
+        parser.setup();
+
+        parser.aggregate("not a date", &5, false).unwrap();
+        assert!(parser.aggregator_errors.contains_key("Date"));
+
+        let result = parser.aggregate("2021-03-19", &5, true).unwrap();
+        assert!(parser.aggregator_errors.is_empty());
+        assert_eq!(result.len(), 6);
+    }
+
+    #[test]
+    fn test_lists_date_formats_in_field_order() {
+        let mut map = HashMap::new();
+        map.insert(
+            String::from("Time"),
+            AggregationMethod::Time(String::from("[hour]:[minute]:[second]").into()),
+        );
+        map.insert(String::from("Method"), AggregationMethod::Count);
+        map.insert(
+            String::from("Date"),
+            AggregationMethod::Date(
+                vec![
+                    String::from("[year]-[month]-[day]"),
+                    String::from("[day]/[month]/[year]"),
+                ]
+                .into(),
+            ),
+        );
+        let parser = Parser::new(
+            String::from(" - "),
+            PatternType::Split,
+            String::from("2021-03-19 - 14:32:00 - GET"),
+            vec!["Date".to_string(), "Time".to_string(), "Method".to_string()],
+            map,
+        );
+
+        let formats: Vec<String> = parser
+            .date_formats()
+            .into_iter()
+            .map(|(format, _)| format)
+            .collect();
+        assert_eq!(
+            formats,
+            vec![
+                "[year]-[month]-[day]",
+                "[day]/[month]/[year]",
+                "[hour]:[minute]:[second]"
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        extensions::parser::{AggregationMethod, Parser, PatternType},
+        util::error::LogriaError,
+    };
+
+    const EXAMPLE: &str = r#"{"level": "info", "response": {"status": 200, "bytes": 512}}"#;
+
+    fn parser(pattern: &str, order: &[&str]) -> Parser {
+        let mut map = HashMap::new();
+        for name in order {
+            map.insert(name.to_string(), AggregationMethod::Count);
+        }
+        Parser::new(
+            String::from(pattern),
+            PatternType::Json,
+            String::from(EXAMPLE),
+            order.iter().map(|name| name.to_string()).collect(),
+            map,
+        )
+    }
+
+    #[test]
+    fn can_get_example_top_level_keys() {
+        let parser = parser("", &["Level", "Response"]);
+        assert_eq!(
+            parser.get_example().unwrap(),
+            vec![String::from("level"), String::from("response")]
+        );
+    }
+
+    #[test]
+    fn can_get_example_paths() {
+        let parser = parser("level, response.status", &["Level", "Status"]);
+        assert_eq!(
+            parser.get_example().unwrap(),
+            vec![String::from("level"), String::from("response.status")]
+        );
+    }
+
+    #[test]
+    fn cannot_get_example_not_json() {
+        let mut parser = parser("", &["Level"]);
+        parser.example = String::from("level=info");
+        assert!(matches!(
+            parser.get_example(),
+            Err(LogriaError::InvalidExampleJson(_))
+        ));
+    }
+
+    #[test]
+    fn can_apply_nested_path() {
+        let parser = parser("level, response.status", &["Level", "Status"]);
+        let messages = vec![
+            String::from(r#"{"level": "info", "response": {"status": 200}}"#),
+            String::from(r#"{"level": "warn", "response": {"status": 503}}"#),
+        ];
+        assert_eq!(
+            parser.apply(&messages, &[1]).unwrap(),
+            vec![Some(String::from("200")), Some(String::from("503"))]
+        );
+        assert_eq!(
+            parser.apply(&messages, &[0]).unwrap(),
+            vec![Some(String::from("info")), Some(String::from("warn"))]
+        );
+    }
+
+    #[test]
+    fn can_apply_array_index() {
+        let parser = parser("tags.1", &["Tag"]);
+        let messages = vec![String::from(r#"{"tags": ["a", "b"]}"#)];
+        assert_eq!(
+            parser.apply(&messages, &[0]).unwrap(),
+            vec![Some(String::from("b"))]
+        );
+    }
+
+    #[test]
+    fn can_apply_object_value() {
+        let parser = parser("", &["Level", "Response"]);
+        let messages = vec![String::from(EXAMPLE)];
+        assert_eq!(
+            parser.apply(&messages, &[1]).unwrap(),
+            vec![Some(String::from(r#"{"bytes":512,"status":200}"#))]
+        );
+    }
+
+    #[test]
+    fn skips_missing_key() {
+        let parser = parser("response.status", &["Status"]);
+        let messages = vec![
+            String::from(r#"{"response": {"bytes": 512}}"#),
+            String::from(r#"{"response": 200}"#),
+            String::from(r#"{"response": {"status": 404}}"#),
+        ];
+        assert_eq!(
+            parser.apply(&messages, &[0]).unwrap(),
+            vec![None, None, Some(String::from("404"))]
+        );
+    }
+
+    #[test]
+    fn skips_non_json_messages() {
+        let parser = parser("level", &["Level"]);
+        let messages = vec![
+            String::from("starting server"),
+            String::from(r#"{"level": "info"}"#),
+        ];
+        assert_eq!(
+            parser.apply(&messages, &[0]).unwrap(),
+            vec![None, Some(String::from("info"))]
+        );
+    }
+
+    #[test]
+    fn can_aggregate_nested_path() {
+        let mut parser = parser("level, response.status", &["Level", "Status"]);
+        parser.setup();
+        parser
+            .aggregate(
+                r#"{"level": "info", "response": {"status": 200}}"#,
+                &1,
+                false,
+            )
+            .unwrap();
+        let aggregated = parser
+            .aggregate(
+                r#"{"level": "info", "response": {"status": 200}}"#,
+                &1,
+                true,
+            )
+            .unwrap();
+        assert_eq!(
+            aggregated,
+            vec![
+                "Level",
+                "    info\u{1b}[0m: 2 (100%)",
+                "Status",
+                "    200\u{1b}[0m: 2 (100%)"
+            ]
+        );
+    }
+
+    #[test]
+    fn cannot_aggregate_missing_key() {
+        let mut parser = parser("level, response.status", &["Level", "Status"]);
+        parser.setup();
+        assert!(matches!(
+            parser.aggregate(r#"{"level": "info"}"#, &1, true),
+            Err(LogriaError::CannotParseMessage(_))
+        ));
+    }
+
+    #[test]
+    fn cannot_aggregate_non_json_message() {
+        let mut parser = parser("level", &["Level"]);
+        parser.setup();
+        assert!(matches!(
+            parser.aggregate("starting server", &1, true),
+            Err(LogriaError::CannotParseMessage(_))
+        ));
+    }
 }
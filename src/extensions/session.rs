@@ -9,22 +9,54 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    constants::{cli::excludes::SESSION_FILE_EXCLUDES, directories::sessions},
+    constants::{
+        cli::excludes::SESSION_FILE_EXCLUDES,
+        directories::{last_session, sessions},
+    },
     extensions::extension::ExtensionMethods,
     util::error::LogriaError,
 };
 
-#[derive(Eq, Hash, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Eq, Hash, PartialEq, Serialize, Deserialize, Debug, Clone, Copy)]
 pub enum SessionType {
     File,
     Command,
+    Url,
+    UnixSocket,
     Mixed,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// View state saved along with a session, restored once its streams are open
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// Render stderr and stdout in separate panes
+    #[serde(default)]
+    pub split_view: bool,
+    /// Pattern of the active regex filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+}
+
+/// Saved parser to start parsing with as soon as a session's streams are open
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SessionParser {
+    /// File name of the parser, as listed in parser mode
+    pub name: String,
+    /// Index of the parsed message to render
+    #[serde(default)]
+    pub index: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Session {
     pub commands: Vec<String>,
     pub stream_type: SessionType, // Cannot use `type` for the name as it is reserved
+    /// Saved view state, if the session was saved as a workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<Workspace>,
+    /// Parser to open the session with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parser: Option<SessionParser>,
 }
 
 impl ExtensionMethods for Session {
@@ -122,9 +154,17 @@ impl Session {
         Session {
             commands: commands.to_owned(),
             stream_type: session_type,
+            workspace: None,
+            parser: None,
         }
     }
 
+    /// Save view state along with the session's streams
+    pub fn with_workspace(mut self, workspace: Workspace) -> Session {
+        self.workspace = Some(workspace);
+        self
+    }
+
     /// Create Session struct from a session file
     pub fn load(file_name: &str) -> Result<Session, serde_json::error::Error> {
         // Read file
@@ -138,6 +178,45 @@ impl Session {
         };
         serde_json::from_str(&session_json)
     }
+
+    /// Load a saved session by the name shown on the startup screen
+    pub fn find(name: &str) -> Result<Session, LogriaError> {
+        let file_name = format!("{}/{}", sessions(), name);
+        if !Path::new(&file_name).is_file() {
+            return Err(LogriaError::CannotRead(
+                file_name,
+                String::from("no saved session with that name"),
+            ));
+        }
+        Session::load(&file_name).map_err(|why| LogriaError::CannotRead(file_name, why.to_string()))
+    }
+
+    /// Remember the name of the most recently opened session for `--resume`
+    pub fn record_last(name: &str) -> Result<(), LogriaError> {
+        Session::verify_path();
+        Session::write_last(&last_session(), name)
+    }
+
+    /// Load the most recently opened session, if it was recorded and still exists
+    pub fn last() -> Option<Session> {
+        Session::last_from(&last_session())
+    }
+
+    fn write_last(path: &str, name: &str) -> Result<(), LogriaError> {
+        write(path, name)
+            .map_err(|why| LogriaError::CannotWrite(path.to_owned(), <dyn Error>::to_string(&why)))
+    }
+
+    fn read_last(path: &str) -> Option<String> {
+        read_to_string(path)
+            .ok()
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+    }
+
+    fn last_from(path: &str) -> Option<Session> {
+        Session::read_last(path).and_then(|name| Session::find(&name).ok())
+    }
 }
 
 #[cfg(test)]
@@ -146,10 +225,10 @@ mod tests {
         constants::directories::sessions,
         extensions::{
             extension::ExtensionMethods,
-            session::{Session, SessionType},
+            session::{Session, SessionParser, SessionType, Workspace},
         },
     };
-    use std::path::Path;
+    use std::{env::temp_dir, fs::remove_file, path::Path};
 
     #[test]
     fn test_list_full() {
@@ -184,9 +263,116 @@ mod tests {
         let expected_session = Session {
             commands: vec![String::from("ls -la")],
             stream_type: SessionType::Command,
+            workspace: None,
+            parser: None,
         };
         assert_eq!(read_session.commands, expected_session.commands);
         assert_eq!(read_session.stream_type, expected_session.stream_type);
+        assert!(read_session.workspace.is_none());
+        assert!(read_session.parser.is_none());
+    }
+
+    #[test]
+    fn serialize_workspace() {
+        let workspace = Workspace {
+            split_view: true,
+            regex: Some(String::from("ERROR|WARN")),
+        };
+        let session = Session::new(&[String::from("README.md")], SessionType::File)
+            .with_workspace(workspace.clone());
+        session.save("ls -la workspace").unwrap();
+
+        let read_session = Session::find("ls -la workspace").unwrap();
+        assert_eq!(read_session.commands, vec![String::from("README.md")]);
+        assert_eq!(read_session.stream_type, SessionType::File);
+        assert_eq!(read_session.workspace, Some(workspace));
+    }
+
+    #[test]
+    fn deserialize_parser() {
+        let session: Session = serde_json::from_str(
+            r#"{"commands": ["ls"], "stream_type": "Command", "parser": {"name": "Hyphen Separated"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            session.parser,
+            Some(SessionParser {
+                name: String::from("Hyphen Separated"),
+                index: 0
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_partial_workspace() {
+        let session: Session = serde_json::from_str(
+            r#"{"commands": ["ls"], "stream_type": "Command", "workspace": {"regex": "ERROR"}}"#,
+        )
+        .unwrap();
+        let workspace = session.workspace.unwrap();
+        assert!(!workspace.split_view);
+        assert_eq!(workspace.regex, Some(String::from("ERROR")));
+    }
+
+    #[test]
+    fn can_find_session() {
+        let session = Session::new(&[String::from("ls -la")], SessionType::Command);
+        session.save("ls -la find").unwrap();
+        let found = Session::find("ls -la find").unwrap();
+        assert_eq!(found.commands, vec![String::from("ls -la")]);
+    }
+
+    #[test]
+    fn cannot_find_missing_session() {
+        assert!(Session::find("zzz missing session").is_err());
+    }
+
+    #[test]
+    fn can_write_and_read_last_session() {
+        let path = temp_dir().join("logria_last_session_round_trip");
+        let path = path.to_str().unwrap();
+        Session::write_last(path, "ls -la").unwrap();
+        assert_eq!(Session::read_last(path), Some(String::from("ls -la")));
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn cannot_read_missing_last_session() {
+        let path = temp_dir().join("logria_last_session_missing");
+        assert!(Session::read_last(path.to_str().unwrap()).is_none());
+    }
+
+    #[test]
+    fn cannot_read_empty_last_session() {
+        let path = temp_dir().join("logria_last_session_empty");
+        let path = path.to_str().unwrap();
+        Session::write_last(path, "\n").unwrap();
+        assert!(Session::read_last(path).is_none());
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn can_load_last_session() {
+        let session = Session::new(&[String::from("ls -la")], SessionType::Command);
+        session.save("ls -la resume").unwrap();
+        let path = temp_dir().join("logria_last_session_load");
+        let path = path.to_str().unwrap();
+        Session::write_last(path, "ls -la resume").unwrap();
+
+        let found = Session::last_from(path).unwrap();
+        assert_eq!(found.commands, vec![String::from("ls -la")]);
+        remove_file(path).unwrap();
+        remove_file(format!("{}/{}", sessions(), "ls -la resume")).unwrap();
+    }
+
+    #[test]
+    fn cannot_load_deleted_last_session() {
+        let path = temp_dir().join("logria_last_session_deleted");
+        let path = path.to_str().unwrap();
+        Session::write_last(path, "zzz missing session").unwrap();
+
+        assert!(Session::last_from(path).is_none());
+        remove_file(path).unwrap();
     }
 
     #[test]
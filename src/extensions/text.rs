@@ -0,0 +1,137 @@
+use std::{error::Error, fs::read_to_string, path::Path, result::Result};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::cli::messages::{
+        DOCS, NO_MESSAGE_IN_BUFFER_NORMAL, NO_MESSAGE_IN_BUFFER_PARSER, START_MESSAGE,
+    },
+    util::error::LogriaError,
+};
+
+/// Replacements for built in UI text, i.e. to localize or brand the app
+///
+/// Any text that is not set falls back to the built in text.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct TextOverrides {
+    /// Shown when the current buffer is empty
+    pub no_messages: Option<String>,
+    /// Shown when no messages match the current parser
+    pub no_messages_parser: Option<String>,
+    /// Shown above the saved sessions on the startup screen
+    pub startup: Option<Vec<String>>,
+    /// Printed by `--docs`
+    pub help: Option<String>,
+}
+
+impl TextOverrides {
+    /// Create TextOverrides struct from a text file, or an empty one if the file does not exist
+    pub fn load(file_name: &str) -> Result<TextOverrides, LogriaError> {
+        if !Path::new(file_name).exists() {
+            return Ok(TextOverrides::default());
+        }
+        match read_to_string(file_name) {
+            Ok(json) => match serde_json::from_str(&json) {
+                Ok(text) => Ok(text),
+                Err(why) => Err(LogriaError::CannotRead(
+                    file_name.to_owned(),
+                    why.to_string(),
+                )),
+            },
+            Err(why) => Err(LogriaError::CannotRead(
+                file_name.to_owned(),
+                <dyn Error>::to_string(&why),
+            )),
+        }
+    }
+
+    pub fn no_messages(&self) -> &str {
+        self.no_messages
+            .as_deref()
+            .unwrap_or(NO_MESSAGE_IN_BUFFER_NORMAL)
+    }
+
+    pub fn no_messages_parser(&self) -> &str {
+        self.no_messages_parser
+            .as_deref()
+            .unwrap_or(NO_MESSAGE_IN_BUFFER_PARSER)
+    }
+
+    pub fn startup(&self) -> Vec<String> {
+        match &self.startup {
+            Some(startup) => startup.to_owned(),
+            None => START_MESSAGE.iter().map(|line| line.to_string()).collect(),
+        }
+    }
+
+    pub fn help(&self) -> &str {
+        self.help.as_deref().unwrap_or(DOCS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::remove_file, fs::write};
+
+    use super::TextOverrides;
+    use crate::constants::cli::messages::{
+        DOCS, NO_MESSAGE_IN_BUFFER_NORMAL, NO_MESSAGE_IN_BUFFER_PARSER, START_MESSAGE,
+    };
+
+    fn test_file(name: &str) -> String {
+        let path = temp_dir().join(name);
+        let _ = remove_file(&path);
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn can_load_missing_file() {
+        let text = TextOverrides::load(&test_file("logria_text_missing")).unwrap();
+        assert_eq!(text.no_messages(), NO_MESSAGE_IN_BUFFER_NORMAL);
+        assert_eq!(text.no_messages_parser(), NO_MESSAGE_IN_BUFFER_PARSER);
+        assert_eq!(text.startup(), START_MESSAGE);
+        assert_eq!(text.help(), DOCS);
+    }
+
+    #[test]
+    fn can_load_partial_file() {
+        let file_name = test_file("logria_text_partial");
+        write(&file_name, r#"{"no_messages": "Rien à afficher"}"#).unwrap();
+
+        let text = TextOverrides::load(&file_name).unwrap();
+        assert_eq!(text.no_messages(), "Rien à afficher");
+        assert_eq!(text.no_messages_parser(), NO_MESSAGE_IN_BUFFER_PARSER);
+        assert_eq!(text.startup(), START_MESSAGE);
+        remove_file(&file_name).unwrap();
+    }
+
+    #[test]
+    fn can_load_startup() {
+        let file_name = test_file("logria_text_startup");
+        write(&file_name, r#"{"startup": ["Welcome to Acme Logs", " "]}"#).unwrap();
+
+        let text = TextOverrides::load(&file_name).unwrap();
+        assert_eq!(text.startup(), vec!["Welcome to Acme Logs", " "]);
+        remove_file(&file_name).unwrap();
+    }
+
+    #[test]
+    fn can_load_help() {
+        let file_name = test_file("logria_text_help");
+        write(&file_name, r#"{"help": "See the Acme Logs wiki"}"#).unwrap();
+
+        let text = TextOverrides::load(&file_name).unwrap();
+        assert_eq!(text.help(), "See the Acme Logs wiki");
+        assert_eq!(text.startup(), START_MESSAGE);
+        remove_file(&file_name).unwrap();
+    }
+
+    #[test]
+    fn cannot_load_invalid_file() {
+        let file_name = test_file("logria_text_invalid");
+        write(&file_name, "not json").unwrap();
+        assert!(TextOverrides::load(&file_name).is_err());
+        remove_file(&file_name).unwrap();
+    }
+}
@@ -8,27 +8,103 @@ mod extensions;
 mod ui;
 mod util;
 
-use communication::reader::MainWindow;
-use constants::{cli::messages::DOCS, directories::print_paths};
-use util::options::from_command_line;
+use communication::{input::DeletedFilePolicy, reader::MainWindow};
+use constants::{
+    cli::poll_rate::SAMPLES,
+    directories::{print_paths, text},
+};
+use extensions::{session::Session, text::TextOverrides};
+use std::{process::exit, time::Duration};
+
+use util::{
+    inactivity::InactivityTimer, metrics::MetricsWriter, options::from_command_line, trace::Tracer,
+};
 
 fn main() -> Result<()> {
     // Get options from command line
     let options = from_command_line();
     if options.get_flag("docs") {
-        println!("{}", DOCS);
+        match TextOverrides::load(&text()) {
+            Ok(overrides) => println!("{}", overrides.help()),
+            Err(why) => {
+                eprintln!("{}", why);
+                exit(1);
+            }
+        }
     } else if options.get_flag("paths") {
         print_paths();
     } else {
         let history = !options.get_flag("tape");
         let smart_poll_rate = !options.get_flag("mindless");
+        let poll_samples = options
+            .get_one::<u64>("poll_samples")
+            .map_or(SAMPLES, |samples| *samples as usize);
         let exec: Option<Vec<String>> = match options.try_get_one("exec") {
             Ok(cmd) => cmd.map(|text: &String| vec![text.to_string()]),
             Err(_) => None,
         };
 
         // Start app
-        let mut app = MainWindow::new(history, smart_poll_rate);
+        let mut app = MainWindow::new(history, smart_poll_rate, poll_samples);
+        if let Some(seconds) = options.get_one::<u64>("resume_after") {
+            app.config.resume_follow = Some(InactivityTimer::new(Duration::from_secs(*seconds)));
+        }
+        app.config.stream_options.gunzip = options.get_flag("gunzip");
+        app.config.stream_options.follow = options.get_flag("follow");
+        if options.get_flag("stop_on_delete") {
+            app.config.stream_options.deleted_file = DeletedFilePolicy::Stop;
+        }
+        app.config.pipe = options.get_flag("pipe");
+        app.config.max_line_length = options
+            .get_one::<u64>("max_line_length")
+            .map(|chars| *chars as usize);
+        // Passing streams with `-e` already skips the startup screen
+        if exec.is_none() {
+            if let Some(name) = options.get_one::<String>("no_startup") {
+                match Session::find(name) {
+                    Ok(session) => app.config.startup_session = Some(session),
+                    Err(why) => {
+                        eprintln!("{}", why);
+                        exit(1);
+                    }
+                }
+            } else if options.get_flag("resume") {
+                // Without a last session that still exists, show the startup screen as usual
+                app.config.startup_session = Session::last();
+            }
+        }
+        if options.get_flag("safe") {
+            app.enable_safe_mode();
+        }
+        match TextOverrides::load(&text()) {
+            Ok(overrides) => app.config.text = overrides,
+            Err(why) => {
+                eprintln!("{}", why);
+                exit(1);
+            }
+        }
+        if let Some(path) = options.get_one::<String>("metrics") {
+            let interval = *options.get_one::<u64>("metrics_interval").unwrap_or(&1000);
+            match MetricsWriter::new(path, interval) {
+                Ok(writer) => app.config.metrics = Some(writer),
+                Err(why) => {
+                    eprintln!("{}", why);
+                    exit(1);
+                }
+            }
+        }
+        if let Some(path) = options.get_one::<String>("trace") {
+            match Tracer::new(path) {
+                Ok(tracer) => app.config.tracer = Some(tracer),
+                Err(why) => {
+                    eprintln!("{}", why);
+                    exit(1);
+                }
+            }
+        }
+        if let Some(minutes) = options.get_one::<u64>("exit_after") {
+            app.config.inactivity = Some(InactivityTimer::new(Duration::from_secs(minutes * 60)));
+        }
         app.start(exec)?;
     }
     Ok(())
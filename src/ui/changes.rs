@@ -0,0 +1,123 @@
+use std::cmp::min;
+
+use crate::constants::cli::colors::{CHANGED_COLOR, RESET_COLOR};
+
+/// Whether a character continues a word or number, i.e. the `.` in `4.5`
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '.' || c == '_'
+}
+
+/// Find the byte index where a message starts to differ from the message before it
+///
+/// If the index falls inside a word, it is moved back to the start of the word, so `cpu: 45%`
+/// after `cpu: 47%` changes at `45%` rather than `5%`. Messages that are the same, that only drop
+/// text from the end, or that do not share a word with the previous message have no change to show.
+pub fn changed_suffix(previous: &str, current: &str) -> Option<usize> {
+    // Where the messages diverge, or the end of the shorter message if one starts the other
+    let mut split = min(previous.len(), current.len());
+    for ((index, a), b) in current.char_indices().zip(previous.chars()) {
+        if a != b {
+            split = index;
+            break;
+        }
+    }
+    if split == current.len() {
+        return None;
+    }
+    let inside_word = current[..split]
+        .chars()
+        .next_back()
+        .is_some_and(is_word_char)
+        && current[split..].chars().next().is_some_and(is_word_char);
+    if !inside_word {
+        return (split > 0).then_some(split);
+    }
+    let split = current[..split]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_word_char(*c))
+        .map_or(0, |(index, c)| index + c.len_utf8());
+    (split > 0).then_some(split)
+}
+
+/// Color the part of a message that changed since the message before it
+///
+/// Messages with their own color codes are left as they are, since the codes would be split.
+pub fn highlight_changes(previous: &str, current: &str) -> Option<String> {
+    if previous.contains('\x1b') || current.contains('\x1b') {
+        return None;
+    }
+    changed_suffix(previous, current).map(|split| {
+        format!(
+            "{}{}{}{}",
+            &current[..split],
+            CHANGED_COLOR,
+            &current[split..],
+            RESET_COLOR
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_suffix, highlight_changes};
+    use crate::constants::cli::colors::{CHANGED_COLOR, RESET_COLOR};
+
+    #[test]
+    fn can_find_changed_number() {
+        assert_eq!(changed_suffix("cpu: 47%", "cpu: 45%"), Some(5));
+    }
+
+    #[test]
+    fn can_find_changed_word() {
+        assert_eq!(
+            changed_suffix("worker 3 state=idle", "worker 3 state=busy"),
+            Some(15)
+        );
+    }
+
+    #[test]
+    fn can_find_changed_decimal() {
+        assert_eq!(changed_suffix("load 1.25 0.9", "load 1.75 0.9"), Some(5));
+    }
+
+    #[test]
+    fn can_find_appended_text() {
+        assert_eq!(changed_suffix("queue: 12", "queue: 12 (full)"), Some(9));
+    }
+
+    #[test]
+    fn can_find_change_after_multibyte_prefix() {
+        assert_eq!(changed_suffix("→ count 1", "→ count 2"), Some(10));
+    }
+
+    #[test]
+    fn cannot_find_change_in_same_message() {
+        assert_eq!(changed_suffix("cpu: 47%", "cpu: 47%"), None);
+    }
+
+    #[test]
+    fn cannot_find_change_in_shortened_message() {
+        assert_eq!(changed_suffix("queue: 12 (full)", "queue: 12"), None);
+    }
+
+    #[test]
+    fn cannot_find_change_without_shared_prefix() {
+        assert_eq!(changed_suffix("cpu: 47%", "mem: 47%"), None);
+        assert_eq!(changed_suffix("requests 100", "requests 101"), Some(9));
+        assert_eq!(changed_suffix("requests100", "requests101"), None);
+    }
+
+    #[test]
+    fn can_highlight_changes() {
+        assert_eq!(
+            highlight_changes("cpu: 47%", "cpu: 45%"),
+            Some(format!("cpu: {}45%{}", CHANGED_COLOR, RESET_COLOR))
+        );
+    }
+
+    #[test]
+    fn cannot_highlight_colored_message() {
+        assert_eq!(highlight_changes("cpu: 47%", "cpu: \x1b[31m45%"), None);
+    }
+}
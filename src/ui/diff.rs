@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use crate::constants::cli::colors::{ADDED_COLOR, RESET_COLOR};
+
+/// Messages kept from a previous run, compared as a set of lines against the live buffer
+pub struct RunDiff {
+    previous: Vec<String>,
+    /// The previous run's messages, for fast lookups while rendering
+    lines: HashSet<String>,
+    /// Whether added messages are marked in the output window
+    pub active: bool,
+}
+
+impl RunDiff {
+    pub fn new(previous: Vec<String>) -> RunDiff {
+        let lines = previous.iter().cloned().collect();
+        RunDiff {
+            previous,
+            lines,
+            active: false,
+        }
+    }
+
+    /// Whether a message did not appear anywhere in the previous run
+    pub fn is_added(&self, message: &str) -> bool {
+        !self.lines.contains(message)
+    }
+
+    /// Get the indexes of the messages in `current` that did not appear in the previous run
+    pub fn added(&self, current: &[String]) -> Vec<usize> {
+        current
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| self.is_added(message))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Get the messages from the previous run that no longer appear in `current`, in their original order
+    pub fn removed(&self, current: &[String]) -> Vec<String> {
+        let current: HashSet<&str> = current.iter().map(String::as_str).collect();
+        self.previous
+            .iter()
+            .filter(|message| !current.contains(message.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Prefix a message with a gutter that marks it if it is new in this run
+    pub fn gutter(&self, original: &str, message: &str) -> String {
+        if self.is_added(original) {
+            format!("{}+{} {}", ADDED_COLOR, RESET_COLOR, message)
+        } else {
+            format!("  {}", message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunDiff;
+
+    fn buffer(messages: &[&str]) -> Vec<String> {
+        messages.iter().map(|message| message.to_string()).collect()
+    }
+
+    #[test]
+    fn can_find_added() {
+        let diff = RunDiff::new(buffer(&["a", "b", "c"]));
+        assert_eq!(diff.added(&buffer(&["a", "x", "c", "y"])), vec![1, 3]);
+    }
+
+    #[test]
+    fn can_find_removed() {
+        let diff = RunDiff::new(buffer(&["a", "b", "c", "d"]));
+        assert_eq!(diff.removed(&buffer(&["d", "a"])), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn ignores_order_and_repeats() {
+        let diff = RunDiff::new(buffer(&["a", "b"]));
+        let current = buffer(&["b", "a", "a", "b"]);
+        assert!(diff.added(&current).is_empty());
+        assert!(diff.removed(&current).is_empty());
+    }
+
+    #[test]
+    fn can_diff_empty_runs() {
+        let diff = RunDiff::new(vec![]);
+        assert_eq!(diff.added(&buffer(&["a"])), vec![0]);
+        assert!(diff.removed(&buffer(&["a"])).is_empty());
+
+        let diff = RunDiff::new(buffer(&["a"]));
+        assert!(diff.added(&[]).is_empty());
+        assert_eq!(diff.removed(&[]), vec!["a"]);
+    }
+
+    #[test]
+    fn can_render_gutter() {
+        let diff = RunDiff::new(buffer(&["old"]));
+        assert_eq!(diff.gutter("new", "new"), "\x1b[32m+\x1b[0m new");
+        assert_eq!(diff.gutter("old", "old"), "  old");
+    }
+}
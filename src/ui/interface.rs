@@ -1,8 +1,13 @@
-use crossterm::{cursor, execute, queue, style, terminal, tty::IsTty, Result};
-use std::io::{stdin, stdout, Stdout, Write};
+use crossterm::{cursor, execute, queue, style, terminal, Result};
+use std::io::{stdout, Stdout, Write};
 
 use crate::communication::reader::MainWindow;
 
+/// Smallest number of columns the interface can be drawn in
+pub const MIN_WIDTH: u16 = 10;
+/// Smallest number of rows the interface can be drawn in, leaving room for the borders and command line
+pub const MIN_HEIGHT: u16 = 5;
+
 fn rect(stdout: &mut Stdout, start: u16, height: u16, width: u16) -> Result<()> {
     for y in start..height {
         for x in 0..width {
@@ -35,7 +40,36 @@ pub fn build(app: &mut MainWindow) -> Result<()> {
     Ok(())
 }
 
+/// Determine whether a terminal size is large enough to draw the interface in
+pub fn valid_size(width: u16, height: u16) -> bool {
+    width >= MIN_WIDTH && height >= MIN_HEIGHT
+}
+
 /// Ensure both stdin and stdout are controlled by the terminal emulator
-pub fn valid_tty() -> bool {
-    stdin().is_tty() && stdout().is_tty()
+///
+/// When `pipe` is set, stdin carries the piped messages instead, so only stdout needs to be a tty
+pub fn valid_tty(stdin_tty: bool, stdout_tty: bool, pipe: bool) -> bool {
+    stdout_tty && (stdin_tty || pipe)
+}
+
+#[cfg(test)]
+mod size_tests {
+    use super::{valid_size, MIN_HEIGHT, MIN_WIDTH};
+
+    #[test]
+    fn cannot_draw_in_zero_size() {
+        assert!(!valid_size(0, 0));
+    }
+
+    #[test]
+    fn cannot_draw_in_narrow_or_short_size() {
+        assert!(!valid_size(MIN_WIDTH - 1, 24));
+        assert!(!valid_size(80, MIN_HEIGHT - 1));
+    }
+
+    #[test]
+    fn can_draw_in_minimum_size() {
+        assert!(valid_size(MIN_WIDTH, MIN_HEIGHT));
+        assert!(valid_size(80, 24));
+    }
 }
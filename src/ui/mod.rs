@@ -1,2 +1,12 @@
+pub mod changes;
+pub mod diff;
 pub mod interface;
+pub mod overlay;
+pub mod position;
 pub mod scroll;
+pub mod selection;
+pub mod snapshot;
+pub mod source;
+pub mod split;
+pub mod tail;
+pub mod wrap;
@@ -0,0 +1,74 @@
+/// Status text too long for the command line, shown over the output window until a key is pressed
+pub struct StatusOverlay {
+    /// The status cut into rows the width of the window
+    rows: Vec<String>,
+    /// Index of the first row shown
+    offset: usize,
+}
+
+impl StatusOverlay {
+    pub fn new(status: &str, width: usize) -> StatusOverlay {
+        let chars: Vec<char> = status.chars().collect();
+        StatusOverlay {
+            rows: chars
+                .chunks(width.max(1))
+                .map(|row| row.iter().collect())
+                .collect(),
+            offset: 0,
+        }
+    }
+
+    /// Get the rows that fit in `height` rows, starting from the scroll position
+    pub fn visible(&self, height: usize) -> &[String] {
+        let end = (self.offset + height).min(self.rows.len());
+        &self.rows[self.offset..end]
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    /// Scroll down one row, stopping once the last row is visible in `height` rows
+    pub fn scroll_down(&mut self, height: usize) {
+        if self.offset + height < self.rows.len() {
+            self.offset += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatusOverlay;
+
+    #[test]
+    fn can_wrap_status() {
+        let overlay = StatusOverlay::new("abcdefghij", 4);
+        assert_eq!(overlay.visible(10), vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn keeps_full_status() {
+        let status = "Regex with pattern /ERROR|WARN.*(timeout|refused)/ ✓ 1,500 matches";
+        assert_eq!(StatusOverlay::new(status, 7).visible(100).concat(), status);
+    }
+
+    #[test]
+    fn can_scroll() {
+        let mut overlay = StatusOverlay::new("abcdefghij", 2);
+        overlay.scroll_down(2);
+        assert_eq!(overlay.visible(2), vec!["cd", "ef"]);
+        overlay.scroll_down(2);
+        overlay.scroll_down(2);
+        overlay.scroll_down(2);
+        assert_eq!(overlay.visible(2), vec!["gh", "ij"]);
+        overlay.scroll_up();
+        assert_eq!(overlay.visible(2), vec!["ef", "gh"]);
+    }
+
+    #[test]
+    fn cannot_scroll_above_top() {
+        let mut overlay = StatusOverlay::new("abcd", 2);
+        overlay.scroll_up();
+        assert_eq!(overlay.visible(1), vec!["ab"]);
+    }
+}
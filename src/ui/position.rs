@@ -0,0 +1,47 @@
+use std::cmp::min;
+
+use format_num::format_num;
+
+/// Describe where the bottom of the view is in the buffer, given the exclusive end of the rendered messages
+pub fn position_text(end: usize, total: usize) -> String {
+    format!(
+        "line {} / {}",
+        format_num!(",d", min(end, total) as f64),
+        format_num!(",d", total as f64)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::position::position_text;
+
+    #[test]
+    fn can_describe_top() {
+        assert_eq!(position_text(7, 100), "line 7 / 100");
+    }
+
+    #[test]
+    fn can_describe_middle() {
+        assert_eq!(position_text(50, 100), "line 50 / 100");
+    }
+
+    #[test]
+    fn can_describe_bottom() {
+        assert_eq!(position_text(100, 100), "line 100 / 100");
+    }
+
+    #[test]
+    fn can_describe_large_buffer() {
+        assert_eq!(position_text(12_345, 1_000_000), "line 12,345 / 1,000,000");
+    }
+
+    #[test]
+    fn can_describe_empty_buffer() {
+        assert_eq!(position_text(0, 0), "line 0 / 0");
+    }
+
+    #[test]
+    fn cannot_describe_past_end() {
+        assert_eq!(position_text(120, 100), "line 100 / 100");
+    }
+}
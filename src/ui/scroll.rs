@@ -1,15 +1,65 @@
 use std::cmp::{max, min};
 
-use crate::communication::reader::MainWindow;
+use crate::{communication::reader::MainWindow, util::error::LogriaError};
 
 #[derive(Debug)]
 pub enum ScrollState {
     Top,
     Free,
     Bottom,
+    /// Keep the message at `current_end` in the middle of the window
+    Centered,
+}
+
+/// How far `pg_up` and `pg_down` scroll
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageSize {
+    /// A fixed number of lines
+    Lines(u16),
+    /// A fraction of the output window, i.e. `0.5` for half a page
+    Fraction(f64),
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        PageSize::Fraction(1.)
+    }
+}
+
+impl PageSize {
+    /// Parse a number of lines, i.e. `10`, or a fraction of the window, i.e. `0.5`
+    pub fn parse(text: &str) -> Result<PageSize, LogriaError> {
+        let text = text.trim();
+        if let Ok(lines) = text.parse::<u16>() {
+            if lines > 0 {
+                return Ok(PageSize::Lines(lines));
+            }
+        } else if let Ok(fraction) = text.parse::<f64>() {
+            if fraction > 0. && fraction <= 1. {
+                return Ok(PageSize::Fraction(fraction));
+            }
+        }
+        Err(LogriaError::InvalidCommand(format!(
+            "page size must be a number of lines or a fraction of the window between 0 and 1, not {:?}",
+            text
+        )))
+    }
+
+    /// Number of lines to scroll for an output window with `last_row` rows, always at least one
+    pub fn lines(&self, last_row: u16) -> u16 {
+        match self {
+            PageSize::Lines(lines) => *lines,
+            PageSize::Fraction(fraction) => max(1, (last_row as f64 * fraction).round() as u16),
+        }
+    }
 }
 
 pub fn up(window: &mut MainWindow) {
+    // Move the centered message rather than leaving centered mode
+    if let ScrollState::Centered = window.config.scroll_state {
+        window.config.current_end = window.config.current_end.saturating_sub(1);
+        return;
+    }
     window.config.scroll_state = ScrollState::Free;
 
     // TODO: handle underflow
@@ -20,21 +70,39 @@ pub fn up(window: &mut MainWindow) {
 }
 
 pub fn down(window: &mut MainWindow) {
-    window.config.scroll_state = ScrollState::Free;
-
     // Get number of messages we can scroll
     let num_messages = window.number_of_messages();
 
+    // Move the centered message rather than leaving centered mode
+    if let ScrollState::Centered = window.config.scroll_state {
+        window.config.current_end = min(
+            num_messages.saturating_sub(1),
+            window.config.current_end + 1,
+        );
+        return;
+    }
+    window.config.scroll_state = ScrollState::Free;
+
     // No scrolling past the last message
     window.config.current_end = min(num_messages, window.config.current_end + 1);
 }
 
 pub fn pg_up(window: &mut MainWindow) {
-    (0..window.config.last_row).for_each(|_| up(window));
+    (0..window.config.page_size.lines(window.config.last_row)).for_each(|_| up(window));
 }
 
 pub fn pg_down(window: &mut MainWindow) {
-    (0..window.config.last_row).for_each(|_| down(window));
+    (0..window.config.page_size.lines(window.config.last_row)).for_each(|_| down(window));
+}
+
+/// Scroll up half of the output window, rounding down but always at least one line
+pub fn half_pg_up(window: &mut MainWindow) {
+    (0..max(1, window.config.last_row / 2)).for_each(|_| up(window));
+}
+
+/// Scroll down half of the output window, rounding down but always at least one line
+pub fn half_pg_down(window: &mut MainWindow) {
+    (0..max(1, window.config.last_row / 2)).for_each(|_| down(window));
 }
 
 pub fn bottom(window: &mut MainWindow) {
@@ -45,11 +113,26 @@ pub fn top(window: &mut MainWindow) {
     window.config.scroll_state = ScrollState::Top
 }
 
+/// Keep the message in the middle of the window there, or stop centering without moving the window
+pub fn center(window: &mut MainWindow) {
+    let (start, end) = window.determine_render_position();
+    match window.config.scroll_state {
+        ScrollState::Centered => {
+            window.config.scroll_state = ScrollState::Free;
+            window.config.current_end = end;
+        }
+        _ => {
+            window.config.scroll_state = ScrollState::Centered;
+            window.config.current_end = start + (end - start) / 2;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         communication::{input::InputType::Regex, reader::MainWindow},
-        ui::scroll,
+        ui::scroll::{self, PageSize},
     };
 
     #[test]
@@ -245,4 +328,162 @@ mod tests {
         assert_eq!(start, 0);
         assert_eq!(end, 6);
     }
+
+    #[test]
+    fn test_render_last_items_scroll_half_pgup() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.page_size = PageSize::Fraction(0.5);
+
+        // Set scroll state
+        logria.config.scroll_state = scroll::ScrollState::Bottom;
+
+        // Set existing status
+        logria.determine_render_position();
+
+        // Scroll action, half of the 7 row window rounds up to 4
+        scroll::pg_up(&mut logria);
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 89);
+        assert_eq!(end, 96);
+    }
+
+    #[test]
+    fn test_render_final_items_scroll_half_pg_up() {
+        let mut logria = MainWindow::_new_dummy();
+
+        // Set scroll state
+        logria.config.scroll_state = scroll::ScrollState::Bottom;
+
+        // Set existing status
+        logria.determine_render_position();
+
+        // Scroll action, half of the 7 row window rounds down to 3
+        scroll::half_pg_up(&mut logria);
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 90);
+        assert_eq!(end, 97);
+    }
+
+    #[test]
+    fn test_render_first_items_scroll_half_pg_down() {
+        let mut logria = MainWindow::_new_dummy();
+
+        // Set scroll state
+        logria.config.scroll_state = scroll::ScrollState::Top;
+
+        // Set existing status
+        logria.determine_render_position();
+
+        // Scroll action
+        scroll::half_pg_down(&mut logria);
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 3);
+        assert_eq!(end, 10);
+    }
+
+    #[test]
+    fn test_half_pg_ignores_page_size() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.page_size = PageSize::Lines(1);
+
+        // Set scroll state
+        logria.config.scroll_state = scroll::ScrollState::Bottom;
+
+        // Set existing status
+        logria.determine_render_position();
+
+        // Scroll action
+        scroll::half_pg_up(&mut logria);
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 90);
+        assert_eq!(end, 97);
+    }
+
+    #[test]
+    fn test_render_first_items_scroll_lines_pgdn() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.page_size = PageSize::Lines(3);
+
+        // Set scroll state
+        logria.config.scroll_state = scroll::ScrollState::Top;
+
+        // Set existing status
+        logria.determine_render_position();
+
+        // Scroll action
+        scroll::pg_down(&mut logria);
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!(start, 3);
+        assert_eq!(end, 10);
+    }
+
+    #[test]
+    fn test_center_keeps_window() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = scroll::ScrollState::Bottom;
+
+        // The message in the middle of the window is centered
+        scroll::center(&mut logria);
+        assert!(matches!(
+            logria.config.scroll_state,
+            scroll::ScrollState::Centered
+        ));
+        assert_eq!(logria.config.current_end, 96);
+        assert_eq!(logria.determine_render_position(), (93, 100));
+
+        // Centering again scrolls freely from the same window
+        scroll::center(&mut logria);
+        assert!(matches!(
+            logria.config.scroll_state,
+            scroll::ScrollState::Free
+        ));
+        assert_eq!(logria.determine_render_position(), (93, 100));
+    }
+
+    #[test]
+    fn test_scroll_centered() {
+        let mut logria = MainWindow::_new_dummy();
+        logria.config.scroll_state = scroll::ScrollState::Centered;
+        logria.config.current_end = 50;
+
+        // Scrolling moves the centered message
+        scroll::up(&mut logria);
+        assert_eq!(logria.determine_render_position(), (46, 53));
+        scroll::down(&mut logria);
+        scroll::down(&mut logria);
+        assert_eq!(logria.determine_render_position(), (48, 55));
+        assert!(matches!(
+            logria.config.scroll_state,
+            scroll::ScrollState::Centered
+        ));
+
+        // No scrolling past the last message
+        logria.config.current_end = 99;
+        scroll::down(&mut logria);
+        assert_eq!(logria.config.current_end, 99);
+    }
+
+    #[test]
+    fn test_page_size_lines() {
+        assert_eq!(PageSize::default().lines(40), 40);
+        assert_eq!(PageSize::Fraction(0.5).lines(40), 20);
+        assert_eq!(PageSize::Fraction(0.01).lines(40), 1);
+        assert_eq!(PageSize::Lines(10).lines(40), 10);
+    }
+
+    #[test]
+    fn test_parse_page_size() {
+        assert_eq!(PageSize::parse("10").unwrap(), PageSize::Lines(10));
+        assert_eq!(PageSize::parse(" 0.5 ").unwrap(), PageSize::Fraction(0.5));
+        assert_eq!(PageSize::parse("1.0").unwrap(), PageSize::Fraction(1.));
+        assert!(PageSize::parse("0").is_err());
+        assert!(PageSize::parse("1.5").is_err());
+        assert!(PageSize::parse("-0.5").is_err());
+        assert!(PageSize::parse("half").is_err());
+    }
 }
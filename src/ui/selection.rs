@@ -0,0 +1,154 @@
+use std::cmp::min;
+
+use crate::{communication::reader::MainWindow, ui::scroll::ScrollState};
+
+/// Start selecting from the last message on screen, freezing the view so new messages do not move it
+pub fn start(window: &mut MainWindow) {
+    let (_, end) = window.determine_render_position();
+    if end == 0 {
+        return;
+    }
+    window.config.selection_start = Some(end - 1);
+    window.config.selection_end = Some(end - 1);
+    window.config.current_end = end;
+    window.config.scroll_state = ScrollState::Free;
+}
+
+/// Stop selecting without acting on the selected messages
+pub fn clear(window: &mut MainWindow) {
+    window.config.selection_start = None;
+    window.config.selection_end = None;
+}
+
+/// Whether a selection is in progress
+pub fn is_active(window: &MainWindow) -> bool {
+    window.config.selection_start.is_some()
+}
+
+/// Get the start and exclusive end indexes of the selected messages, in the order they appear
+pub fn range(window: &MainWindow) -> Option<(usize, usize)> {
+    match (window.config.selection_start, window.config.selection_end) {
+        (Some(start), Some(end)) if start <= end => Some((start, end + 1)),
+        (Some(start), Some(end)) => Some((end, start + 1)),
+        _ => None,
+    }
+}
+
+/// Move the end of the selection up one message
+pub fn up(window: &mut MainWindow) {
+    if let Some(cursor) = window.config.selection_end {
+        window.config.selection_end = Some(cursor.saturating_sub(1));
+        follow_cursor(window);
+    }
+}
+
+/// Move the end of the selection down one message
+pub fn down(window: &mut MainWindow) {
+    if let Some(cursor) = window.config.selection_end {
+        let last = window.number_of_messages().saturating_sub(1);
+        window.config.selection_end = Some(min(cursor + 1, last));
+        follow_cursor(window);
+    }
+}
+
+/// Scroll just enough to keep the end of the selection on screen
+fn follow_cursor(window: &mut MainWindow) {
+    if let Some(cursor) = window.config.selection_end {
+        let (start, end) = window.determine_render_position();
+        if cursor < start {
+            window.config.current_end -= start - cursor;
+        } else if cursor >= end {
+            window.config.current_end = cursor + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        communication::{input::InputType, reader::MainWindow},
+        ui::{scroll::ScrollState, selection},
+    };
+
+    fn window() -> MainWindow {
+        let mut logria = MainWindow::_new_dummy();
+        logria.input_type = InputType::Normal;
+        logria
+    }
+
+    #[test]
+    fn can_start_at_last_message() {
+        let mut logria = window();
+        selection::start(&mut logria);
+        assert_eq!(selection::range(&logria), Some((99, 100)));
+        assert!(matches!(logria.config.scroll_state, ScrollState::Free));
+    }
+
+    #[test]
+    fn can_select_up() {
+        let mut logria = window();
+        selection::start(&mut logria);
+        selection::up(&mut logria);
+        selection::up(&mut logria);
+        assert_eq!(selection::range(&logria), Some((97, 100)));
+    }
+
+    #[test]
+    fn can_select_back_down_past_start() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        selection::start(&mut logria);
+        assert_eq!(selection::range(&logria), Some((6, 7)));
+
+        selection::up(&mut logria);
+        selection::down(&mut logria);
+        selection::down(&mut logria);
+        selection::down(&mut logria);
+        assert_eq!(selection::range(&logria), Some((6, 9)));
+    }
+
+    #[test]
+    fn cannot_select_past_last_message() {
+        let mut logria = window();
+        selection::start(&mut logria);
+        selection::down(&mut logria);
+        assert_eq!(selection::range(&logria), Some((99, 100)));
+    }
+
+    #[test]
+    fn cannot_select_before_first_message() {
+        let mut logria = window();
+        logria.config.scroll_state = ScrollState::Top;
+        selection::start(&mut logria);
+        (0..10).for_each(|_| selection::up(&mut logria));
+        assert_eq!(selection::range(&logria), Some((0, 7)));
+    }
+
+    #[test]
+    fn keeps_cursor_on_screen() {
+        let mut logria = window();
+        selection::start(&mut logria);
+        (0..10).for_each(|_| selection::up(&mut logria));
+        assert_eq!(selection::range(&logria), Some((89, 100)));
+
+        let (start, end) = logria.determine_render_position();
+        assert_eq!((start, end), (89, 96));
+    }
+
+    #[test]
+    fn can_clear() {
+        let mut logria = window();
+        selection::start(&mut logria);
+        selection::clear(&mut logria);
+        assert!(!selection::is_active(&logria));
+        assert_eq!(selection::range(&logria), None);
+    }
+
+    #[test]
+    fn cannot_start_without_messages() {
+        let mut logria = window();
+        logria.config.stream_type = crate::communication::input::StreamType::StdOut;
+        selection::start(&mut logria);
+        assert!(!selection::is_active(&logria));
+    }
+}
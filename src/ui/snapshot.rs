@@ -0,0 +1,23 @@
+use crate::ui::scroll::ScrollState;
+
+/// A frozen copy of the messages that were on screen, viewed in place of the live buffer
+pub struct Snapshot {
+    pub messages: Vec<String>,
+    /// Scroll position within the snapshot, kept while the live buffer is shown
+    pub scroll_state: ScrollState,
+    pub current_end: usize,
+    /// Whether the snapshot was the last view shown
+    pub active: bool,
+}
+
+impl Snapshot {
+    pub fn new(messages: Vec<String>) -> Snapshot {
+        let current_end = messages.len();
+        Snapshot {
+            messages,
+            scroll_state: ScrollState::Top,
+            current_end,
+            active: false,
+        }
+    }
+}
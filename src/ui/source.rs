@@ -0,0 +1,65 @@
+use std::cmp::min;
+
+/// Longest stream name shown in a source label, longer names are cut
+pub const SOURCE_NAME_LENGTH: usize = 12;
+
+/// Width of the labels for streams with these names, including the brackets and the space after them
+///
+/// Returns 0 if there are no streams.
+pub fn source_width<'a>(names: impl Iterator<Item = &'a str>) -> usize {
+    names
+        .map(|name| min(name.chars().count(), SOURCE_NAME_LENGTH))
+        .max()
+        .map_or(0, |length| length + 3)
+}
+
+/// Label a message with the name of the stream it came from, padded to `width`
+///
+/// Messages from an unknown stream get a blank label so they still line up.
+pub fn source_label(name: Option<&str>, width: usize) -> String {
+    match name {
+        Some(name) => {
+            let name: String = name.chars().take(SOURCE_NAME_LENGTH).collect();
+            format!("{:<1$}", format!("[{}]", name), width)
+        }
+        None => " ".repeat(width),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::source::{source_label, source_width};
+
+    #[test]
+    fn width_fits_longest_name() {
+        assert_eq!(source_width(["a", "worker"].into_iter()), 9);
+    }
+
+    #[test]
+    fn width_cuts_long_names() {
+        assert_eq!(source_width(["tail -f /var/log/syslog"].into_iter()), 15);
+    }
+
+    #[test]
+    fn no_width_without_streams() {
+        assert_eq!(source_width([].into_iter()), 0);
+    }
+
+    #[test]
+    fn label_is_padded() {
+        assert_eq!(source_label(Some("a"), 9), "[a]      ");
+    }
+
+    #[test]
+    fn label_cuts_long_names() {
+        assert_eq!(
+            source_label(Some("tail -f /var/log/syslog"), 15),
+            "[tail -f /var] "
+        );
+    }
+
+    #[test]
+    fn unknown_label_is_blank() {
+        assert_eq!(source_label(None, 4), "    ");
+    }
+}
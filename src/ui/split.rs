@@ -0,0 +1,96 @@
+use crate::communication::input::StreamType;
+
+/// A region of the output window that renders a single message buffer
+#[derive(Debug)]
+pub struct Pane {
+    /// The buffer rendered in this pane
+    pub stream_type: StreamType,
+    /// The first row of the window this pane may render to
+    pub first_row: u16,
+    /// The number of rows this pane may render to
+    pub height: u16,
+}
+
+impl Pane {
+    /// The row below the last row this pane may render to
+    pub fn end_row(&self) -> u16 {
+        self.first_row + self.height
+    }
+}
+
+/// Divide the output window into a stderr pane above a stdout pane, separated by a divider row
+pub fn split_panes(last_row: u16) -> (Pane, Pane) {
+    let available = last_row.saturating_sub(1);
+    let top_height = available / 2;
+    let top = Pane {
+        stream_type: StreamType::StdErr,
+        first_row: 0,
+        height: top_height,
+    };
+    let bottom = Pane {
+        stream_type: StreamType::StdOut,
+        first_row: top_height + 1,
+        height: available - top_height,
+    };
+    (top, bottom)
+}
+
+/// Determine the range of a buffer to render in a pane that follows the end of its buffer
+pub fn render_range(number_of_messages: usize, pane: &Pane) -> (usize, usize) {
+    (
+        number_of_messages.saturating_sub(pane.height as usize),
+        number_of_messages,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        communication::input::StreamType,
+        ui::split::{render_range, split_panes},
+    };
+
+    #[test]
+    fn can_split_even() {
+        let (top, bottom) = split_panes(7);
+        assert!(matches!(top.stream_type, StreamType::StdErr));
+        assert!(matches!(bottom.stream_type, StreamType::StdOut));
+        assert_eq!((top.first_row, top.height), (0, 3));
+        assert_eq!((bottom.first_row, bottom.height), (4, 3));
+        assert_eq!(bottom.end_row(), 7);
+    }
+
+    #[test]
+    fn can_split_odd() {
+        let (top, bottom) = split_panes(8);
+        assert_eq!((top.first_row, top.height), (0, 3));
+        assert_eq!((bottom.first_row, bottom.height), (4, 4));
+        assert_eq!(bottom.end_row(), 8);
+    }
+
+    #[test]
+    fn can_split_tiny() {
+        let (top, bottom) = split_panes(0);
+        assert_eq!(top.height, 0);
+        assert_eq!(bottom.height, 0);
+    }
+
+    #[test]
+    fn range_follows_end() {
+        let (top, bottom) = split_panes(7);
+        assert_eq!(render_range(100, &top), (97, 100));
+        assert_eq!(render_range(100, &bottom), (97, 100));
+    }
+
+    #[test]
+    fn range_fewer_messages_than_rows() {
+        let (_, bottom) = split_panes(20);
+        assert_eq!(render_range(4, &bottom), (0, 4));
+    }
+
+    #[test]
+    fn range_no_messages() {
+        let (top, _) = split_panes(7);
+        assert_eq!(render_range(0, &top), (0, 0));
+    }
+}
@@ -0,0 +1,34 @@
+use std::ops::Range;
+
+/// Number of messages shown in the tail strip
+pub const TAIL_ROWS: usize = 2;
+
+/// Get the indices of the newest `rows` of `length` messages, oldest first
+pub fn tail_range(length: usize, rows: usize) -> Range<usize> {
+    length.saturating_sub(rows)..length
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::tail::tail_range;
+
+    #[test]
+    fn can_get_newest() {
+        assert_eq!(tail_range(100, 2), 98..100);
+    }
+
+    #[test]
+    fn can_get_fewer_messages_than_rows() {
+        assert_eq!(tail_range(1, 2), 0..1);
+    }
+
+    #[test]
+    fn can_get_no_messages() {
+        assert!(tail_range(0, 2).is_empty());
+    }
+
+    #[test]
+    fn can_get_no_rows() {
+        assert!(tail_range(5, 0).is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+use std::cmp::max;
+
+use regex::bytes::Regex;
+
+/// How messages longer than the window width are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Break at exactly the window width
+    Hard,
+    /// Break at whitespace when possible
+    Word,
+    /// Truncate to a single row
+    Off,
+}
+
+impl WrapMode {
+    /// The mode `:wrap` switches to from this mode
+    pub fn next(self) -> WrapMode {
+        match self {
+            WrapMode::Hard => WrapMode::Word,
+            WrapMode::Word => WrapMode::Off,
+            WrapMode::Off => WrapMode::Hard,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WrapMode::Hard => "hard",
+            WrapMode::Word => "word",
+            WrapMode::Off => "off",
+        }
+    }
+}
+
+/// Split a message into color codes, which take no space, and single visible characters
+fn pieces<'a>(color_pattern: &Regex, message: &'a str) -> Vec<(&'a str, bool)> {
+    fn push_chars<'a>(text: &'a str, result: &mut Vec<(&'a str, bool)>) {
+        for (index, character) in text.char_indices() {
+            result.push((&text[index..index + character.len_utf8()], true));
+        }
+    }
+
+    let mut result = vec![];
+    let mut last_end = 0;
+    for color in color_pattern.find_iter(message.as_bytes()) {
+        push_chars(&message[last_end..color.start()], &mut result);
+        result.push((&message[color.start()..color.end()], false));
+        last_end = color.end();
+    }
+    push_chars(&message[last_end..], &mut result);
+    result
+}
+
+/// Break a message into rows no wider than `width`, preferring to break at whitespace
+///
+/// Color codes are never split, and words longer than `width` fall back to hard wrapping.
+pub fn word_wrap(color_pattern: &Regex, message: &str, width: usize) -> Vec<String> {
+    let width = max(width, 1);
+    let mut rows: Vec<String> = vec![];
+
+    // Pieces of the current row, the row's visible width, and the last whitespace we could break at
+    let mut row: Vec<(&str, bool)> = vec![];
+    let mut row_width = 0;
+    let mut last_break: Option<usize> = None;
+
+    for (piece, visible) in pieces(color_pattern, message) {
+        if !visible {
+            row.push((piece, visible));
+            continue;
+        }
+        let is_whitespace = piece.chars().all(char::is_whitespace);
+
+        if row_width == width {
+            if is_whitespace {
+                // Break here, dropping the whitespace
+                rows.push(row.drain(..).map(|(text, _)| text).collect());
+                row_width = 0;
+                last_break = None;
+                continue;
+            }
+            match last_break {
+                // Move the partial word after the last whitespace to the next row
+                Some(index) => {
+                    let tail: Vec<(&str, bool)> = row.drain(index..).skip(1).collect();
+                    rows.push(row.drain(..).map(|(text, _)| text).collect());
+                    row_width = tail.iter().filter(|(_, visible)| *visible).count();
+                    row = tail;
+                }
+                // No whitespace to break at, so break the word
+                None => {
+                    rows.push(row.drain(..).map(|(text, _)| text).collect());
+                    row_width = 0;
+                }
+            }
+            last_break = None;
+        }
+
+        if is_whitespace {
+            last_break = Some(row.len());
+        }
+        row.push((piece, visible));
+        row_width += 1;
+    }
+
+    if !row.is_empty() || rows.is_empty() {
+        rows.push(row.into_iter().map(|(text, _)| text).collect());
+    }
+    rows
+}
+
+/// Break a message into rows of exactly `width` visible characters, except for the last row
+///
+/// Color codes are never split and stay on the row of the character that follows them.
+pub fn hard_wrap(color_pattern: &Regex, message: &str, width: usize) -> Vec<String> {
+    let width = max(width, 1);
+    let mut rows: Vec<String> = vec![];
+    let mut row = String::new();
+    let mut row_width = 0;
+    for (piece, visible) in pieces(color_pattern, message) {
+        if visible {
+            if row_width == width {
+                rows.push(std::mem::take(&mut row));
+                row_width = 0;
+            }
+            row_width += 1;
+        }
+        row.push_str(piece);
+    }
+    rows.push(row);
+    rows
+}
+
+/// Cut a message down to `width` visible characters, keeping any color codes
+pub fn truncate(color_pattern: &Regex, message: &str, width: usize) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut row_width = 0;
+    for (piece, visible) in pieces(color_pattern, message) {
+        if visible {
+            if row_width == width {
+                continue;
+            }
+            row_width += 1;
+        }
+        result.push_str(piece);
+    }
+    result
+}
+
+/// Cut a message down to `width` visible characters, ending with `…` if any were cut
+pub fn truncate_with_ellipsis(color_pattern: &Regex, message: &str, width: usize) -> String {
+    let visible = pieces(color_pattern, message)
+        .iter()
+        .filter(|(_, visible)| *visible)
+        .count();
+    if visible <= width {
+        return message.to_owned();
+    }
+    let mut result = truncate(color_pattern, message, width.saturating_sub(1));
+    result.push('…');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::bytes::Regex;
+
+    use crate::{
+        constants::cli::patterns::ANSI_COLOR_PATTERN,
+        ui::wrap::{hard_wrap, truncate, truncate_with_ellipsis, word_wrap, WrapMode},
+    };
+
+    fn color_pattern() -> Regex {
+        Regex::new(ANSI_COLOR_PATTERN).unwrap()
+    }
+
+    #[test]
+    fn can_cycle_modes() {
+        assert_eq!(WrapMode::Hard.next(), WrapMode::Word);
+        assert_eq!(WrapMode::Word.next(), WrapMode::Off);
+        assert_eq!(WrapMode::Off.next(), WrapMode::Hard);
+    }
+
+    #[test]
+    fn word_wrap_fits() {
+        let rows = word_wrap(&color_pattern(), "hello world", 20);
+        assert_eq!(rows, vec!["hello world"]);
+    }
+
+    #[test]
+    fn word_wrap_empty() {
+        let rows = word_wrap(&color_pattern(), "", 20);
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn word_wrap_breaks_at_whitespace() {
+        let rows = word_wrap(&color_pattern(), "hello world foo", 8);
+        assert_eq!(rows, vec!["hello", "world", "foo"]);
+    }
+
+    #[test]
+    fn word_wrap_breaks_at_exact_width() {
+        let rows = word_wrap(&color_pattern(), "hello world foo", 11);
+        assert_eq!(rows, vec!["hello world", "foo"]);
+    }
+
+    #[test]
+    fn word_wrap_long_token() {
+        let rows = word_wrap(&color_pattern(), "abcdefghij xy", 4);
+        assert_eq!(rows, vec!["abcd", "efgh", "ij", "xy"]);
+    }
+
+    #[test]
+    fn word_wrap_keeps_color_codes() {
+        let rows = word_wrap(&color_pattern(), "\x1b[32mhello\x1b[0m world", 8);
+        assert_eq!(rows, vec!["\x1b[32mhello\x1b[0m", "world"]);
+    }
+
+    #[test]
+    fn word_wrap_does_not_count_color_codes() {
+        let rows = word_wrap(&color_pattern(), "\x1b[32mhello\x1b[0m", 5);
+        assert_eq!(rows, vec!["\x1b[32mhello\x1b[0m"]);
+    }
+
+    #[test]
+    fn word_wrap_wide_chars() {
+        let rows = word_wrap(&color_pattern(), "█四░ █四░", 4);
+        assert_eq!(rows, vec!["█四░", "█四░"]);
+    }
+
+    #[test]
+    fn can_hard_wrap() {
+        assert_eq!(
+            hard_wrap(&color_pattern(), "hello world", 4),
+            vec!["hell", "o wo", "rld"]
+        );
+    }
+
+    #[test]
+    fn can_hard_wrap_empty_message() {
+        assert_eq!(hard_wrap(&color_pattern(), "", 4), vec![""]);
+    }
+
+    #[test]
+    fn can_hard_wrap_keeping_color_codes() {
+        assert_eq!(
+            hard_wrap(&color_pattern(), "\x1b[32mhello\x1b[0m", 4),
+            vec!["\x1b[32mhell", "o\x1b[0m"]
+        );
+    }
+
+    #[test]
+    fn can_truncate() {
+        assert_eq!(truncate(&color_pattern(), "hello world", 5), "hello");
+    }
+
+    #[test]
+    fn can_truncate_keeping_color_codes() {
+        assert_eq!(
+            truncate(&color_pattern(), "\x1b[32mhello world\x1b[0m", 5),
+            "\x1b[32mhello\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn can_truncate_with_ellipsis() {
+        assert_eq!(
+            truncate_with_ellipsis(&color_pattern(), "hello world", 5),
+            "hell…"
+        );
+    }
+
+    #[test]
+    fn does_not_add_ellipsis_when_message_fits() {
+        assert_eq!(
+            truncate_with_ellipsis(&color_pattern(), "hello", 5),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn can_truncate_with_ellipsis_keeping_color_codes() {
+        assert_eq!(
+            truncate_with_ellipsis(&color_pattern(), "\x1b[32mhello world\x1b[0m", 5),
+            "\x1b[32mhell\x1b[0m…"
+        );
+    }
+}
@@ -1,21 +1,40 @@
-use crate::util::error::LogriaError;
+use crate::{
+    constants::cli::colors::{CRITICAL_COLOR, RESET_COLOR, WARNING_COLOR},
+    util::error::LogriaError,
+};
 use serde::{Deserialize, Serialize};
 
+/// Convention for the characters used as decimal and digit separators in numbers
+#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum DecimalSeparator {
+    /// `1,234.56`: `.` is the decimal mark and `,` separates digit groups
+    #[default]
+    Period,
+    /// `1.234,56`: `,` is the decimal mark and `.` separates digit groups
+    Comma,
+}
+
 /// Attempts to quickly extract a float from a string; may have weird effects
 /// if numbers are poorly formatted or are immediately next to each other.
-/// 
+///
 /// This function requires allocation because `parse::<f64>()` fails
 /// for strings that contain digit separators.
-/// 
+///
 /// Simply selecting the number range from `message` can fail for cases
 /// like  `"-83,234.34".parse::<f64>();`
-pub fn extract_number(message: &str) -> Option<f64> {
+pub fn extract_number(message: &str, separator: DecimalSeparator) -> Option<f64> {
     // Result float to parse
     let mut result = String::new();
 
     // If we have started compiling a float
     let mut in_float = false;
 
+    // Digit separators are dropped and the decimal mark is normalized to `.`
+    let (decimal, digit_separator) = match separator {
+        DecimalSeparator::Period => ('.', ','),
+        DecimalSeparator::Comma => (',', '.'),
+    };
+
     // For each char, check if it is a sign, digit, or digit separator
     // If it is, flip the float switch, and build the float string
     for (_, char) in message.char_indices() {
@@ -24,7 +43,9 @@ pub fn extract_number(message: &str) -> Option<f64> {
                 in_float = !in_float;
             }
             // Exclude digit separators; this is the part that requires allocation
-            if char != ',' {
+            if char == decimal {
+                result.push('.');
+            } else if char != digit_separator {
                 result.push(char);
             }
         } else if in_float {
@@ -34,123 +55,354 @@ pub fn extract_number(message: &str) -> Option<f64> {
     result.parse::<f64>().ok()
 }
 
+/// Values above which numeric aggregation results are colored, i.e. a mean latency that is too high
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Thresholds {
+    #[serde(default)]
+    pub warning: Option<f64>,
+    #[serde(default)]
+    pub critical: Option<f64>,
+}
+
+impl Thresholds {
+    /// Color `text`, the rendered form of `value`, for the highest threshold `value` is above
+    pub fn colorize(&self, value: f64, text: String) -> String {
+        let color = if self.critical.is_some_and(|critical| value > critical) {
+            CRITICAL_COLOR
+        } else if self.warning.is_some_and(|warning| value > warning) {
+            WARNING_COLOR
+        } else {
+            return text;
+        };
+        format!("{}{}{}", color, text, RESET_COLOR)
+    }
+}
+
 pub trait Aggregator {
     /// Insert an item into the aggregator, updating it's internal tracking data
     fn update(&mut self, message: &str) -> Result<(), LogriaError>;
+    /// Insert an item counted under `group`, the value of another field; most aggregators ignore the group
+    fn update_in_group(&mut self, message: &str, _group: &str) -> Result<(), LogriaError> {
+        self.update(message)
+    }
     /// Expensive function that generates messages to render
     fn messages(&self, n: &usize) -> Vec<String>;
+    /// Set the thresholds used to color rendered values; aggregators without a numeric result ignore them
+    fn set_thresholds(&mut self, _thresholds: Thresholds) {}
 }
 
+/// Format descriptions for a date aggregator, stored as a single string or a list of candidates
 #[derive(Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum DateFormats {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl DateFormats {
+    /// The format descriptions to try, in order
+    pub fn candidates(&self) -> Vec<String> {
+        match self {
+            DateFormats::Single(format) => vec![format.to_owned()],
+            DateFormats::Multiple(formats) => formats.to_owned(),
+        }
+    }
+}
+
+impl From<String> for DateFormats {
+    fn from(format: String) -> Self {
+        DateFormats::Single(format)
+    }
+}
+
+impl From<Vec<String>> for DateFormats {
+    fn from(formats: Vec<String>) -> Self {
+        DateFormats::Multiple(formats)
+    }
+}
+
+impl From<&str> for DateFormats {
+    fn from(format: &str) -> Self {
+        DateFormats::Single(format.to_owned())
+    }
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
 pub enum AggregationMethod {
     Mean,
     Mode, // Special case of Count, for most_common(1)
     Sum,
     Count,
-    Date(String),     // Format string provided by user
-    Time(String),     // Format string provided by user
-    DateTime(String), // Format string provided by user
+    Rare, // Count, reporting the least common items
+    Distinct,
+    Date(DateFormats),     // Format strings provided by user
+    Time(DateFormats),     // Format strings provided by user
+    DateTime(DateFormats), // Format strings provided by user
+    Sparkline,
+    GroupedCount(String), // Name of the field to group counts by
+    Quantiles,
+    Median,
+    Percentile(f64), // Percentile to report, between 0 and 100
+    Min,
+    Max,
+    Occurrences(String), // Pattern to count matches of within each message
     None,
 }
 
 #[cfg(test)]
 mod extract_tests {
-    use super::extract_number;
+    use super::{extract_number, DecimalSeparator};
 
     #[test]
     fn no_number() {
-        let result = extract_number("this is a test");
+        let result = extract_number("this is a test", DecimalSeparator::Period);
         assert!(result.is_none());
     }
 
     #[test]
     fn only_number() {
-        let result = extract_number("834234.34");
+        let result = extract_number("834234.34", DecimalSeparator::Period);
         assert!(result.unwrap() - 834234.34 == 0.);
     }
 
     #[test]
     fn only_number_comma() {
-        let result = extract_number("834,234.34");
+        let result = extract_number("834,234.34", DecimalSeparator::Period);
         assert!(result.unwrap() - 834234.34 == 0.);
     }
 
     #[test]
     fn only_number_multiple_commas() {
-        let result = extract_number("834,789,234.34");
+        let result = extract_number("834,789,234.34", DecimalSeparator::Period);
         assert!(result.unwrap() - 834789234.34 == 0.);
     }
 
     #[test]
     fn negative_number() {
-        let result = extract_number("test -83,234.34 this is");
+        let result = extract_number("test -83,234.34 this is", DecimalSeparator::Period);
         assert!(result.unwrap() + 83234.34 == 0.);
     }
 
     #[test]
     fn double_negative_number() {
-        let result = extract_number("test --83,234.34 this is");
+        let result = extract_number("test --83,234.34 this is", DecimalSeparator::Period);
         assert!(result.is_none());
     }
 
     #[test]
     fn trailing_negative_number() {
-        let result = extract_number("test 83,234.34-- this is");
+        let result = extract_number("test 83,234.34-- this is", DecimalSeparator::Period);
         assert!(result.is_none());
     }
 
     #[test]
     fn number_period_extra() {
-        let result = extract_number("this is a 123.123.123 test");
+        let result = extract_number("this is a 123.123.123 test", DecimalSeparator::Period);
         assert!(result.is_none());
     }
 
     #[test]
     fn number_trailing_comma() {
-        let result = extract_number("this is a 123.123,123 test");
+        let result = extract_number("this is a 123.123,123 test", DecimalSeparator::Period);
         // This is actually a bad edge case
         assert!(result.unwrap() - 123.123123 == 0.);
     }
 
+    #[test]
+    fn number_trailing_comma_comma_decimal() {
+        let result = extract_number("this is a 123.123,123 test", DecimalSeparator::Comma);
+        assert!(result.unwrap() - 123123.123 == 0.);
+    }
+
+    #[test]
+    fn only_number_comma_decimal() {
+        let result = extract_number("834234,34", DecimalSeparator::Comma);
+        assert!(result.unwrap() - 834234.34 == 0.);
+    }
+
+    #[test]
+    fn only_number_period_separator() {
+        let result = extract_number("1.234,56", DecimalSeparator::Comma);
+        assert!(result.unwrap() - 1234.56 == 0.);
+    }
+
+    #[test]
+    fn only_number_multiple_periods_separator() {
+        let result = extract_number("834.789.234,34", DecimalSeparator::Comma);
+        assert!(result.unwrap() - 834789234.34 == 0.);
+    }
+
+    #[test]
+    fn negative_number_comma_decimal() {
+        let result = extract_number("test -83.234,34 this is", DecimalSeparator::Comma);
+        assert!(result.unwrap() + 83234.34 == 0.);
+    }
+
+    #[test]
+    fn number_comma_extra_comma_decimal() {
+        let result = extract_number("this is a 123,123,123 test", DecimalSeparator::Comma);
+        assert!(result.is_none());
+    }
+
     #[test]
     fn number_trailing_decimal() {
-        let result = extract_number("this is a 123.123. test");
+        let result = extract_number("this is a 123.123. test", DecimalSeparator::Period);
         assert!(result.is_none());
     }
 
     #[test]
     fn one_number_end() {
-        let result = extract_number("this is a test 123.4");
+        let result = extract_number("this is a test 123.4", DecimalSeparator::Period);
         assert!(result.unwrap() - 123.4 == 0.);
     }
 
     #[test]
     fn one_number_middle() {
-        let result = extract_number("this is 123.46 a test");
+        let result = extract_number("this is 123.46 a test", DecimalSeparator::Period);
         assert!(result.unwrap() - 123.46 == 0.);
     }
 
     #[test]
     fn one_number_start() {
-        let result = extract_number("653.12 this is a test");
+        let result = extract_number("653.12 this is a test", DecimalSeparator::Period);
         assert!(result.unwrap() - 653.12 == 0.);
     }
 
     #[test]
     fn no_spaces() {
-        let result = extract_number("thisis983.12a test");
+        let result = extract_number("thisis983.12a test", DecimalSeparator::Period);
         assert!(result.unwrap() - 983.12 == 0.);
     }
 
     #[test]
     fn two_numbers_start_end() {
-        let result = extract_number("4.123 this is a test 123.4");
+        let result = extract_number("4.123 this is a test 123.4", DecimalSeparator::Period);
         assert!(result.unwrap() - 4.123 == 0.);
     }
 
     #[test]
     fn two_numbers_middle() {
-        let result = extract_number("this 1337 is 5543 a test");
+        let result = extract_number("this 1337 is 5543 a test", DecimalSeparator::Period);
         assert!(result.unwrap() - 1337. == 0.);
     }
 }
+
+#[cfg(test)]
+mod date_format_tests {
+    use super::{AggregationMethod, DateFormats};
+
+    #[test]
+    fn can_deserialize_single_format() {
+        let method: AggregationMethod =
+            serde_json::from_str(r#"{"Date": "[year]-[month]-[day]"}"#).unwrap();
+        assert_eq!(
+            method,
+            AggregationMethod::Date(DateFormats::Single(String::from("[year]-[month]-[day]")))
+        );
+    }
+
+    #[test]
+    fn can_deserialize_multiple_formats() {
+        let method: AggregationMethod =
+            serde_json::from_str(r#"{"Time": ["[hour]:[minute]", "[hour]:[minute]:[second]"]}"#)
+                .unwrap();
+        assert_eq!(
+            method,
+            AggregationMethod::Time(DateFormats::Multiple(vec![
+                String::from("[hour]:[minute]"),
+                String::from("[hour]:[minute]:[second]")
+            ]))
+        );
+    }
+
+    #[test]
+    fn can_deserialize_percentile() {
+        let method: AggregationMethod = serde_json::from_str(r#"{"Percentile": 95}"#).unwrap();
+        assert_eq!(method, AggregationMethod::Percentile(95.));
+    }
+
+    #[test]
+    fn single_format_serializes_as_string() {
+        let method = AggregationMethod::Date("[year]-[month]-[day]".into());
+        assert_eq!(
+            serde_json::to_string(&method).unwrap(),
+            r#"{"Date":"[year]-[month]-[day]"}"#
+        );
+    }
+
+    #[test]
+    fn can_get_candidates() {
+        let single = DateFormats::from("[year]");
+        let multiple = DateFormats::Multiple(vec![String::from("[year]"), String::from("[month]")]);
+        assert_eq!(single.candidates(), vec!["[year]"]);
+        assert_eq!(multiple.candidates(), vec!["[year]", "[month]"]);
+    }
+}
+
+#[cfg(test)]
+mod threshold_tests {
+    use super::Thresholds;
+    use crate::constants::cli::colors::{CRITICAL_COLOR, RESET_COLOR, WARNING_COLOR};
+
+    fn thresholds() -> Thresholds {
+        Thresholds {
+            warning: Some(100.),
+            critical: Some(500.),
+        }
+    }
+
+    fn colorize(thresholds: Thresholds, value: f64) -> String {
+        thresholds.colorize(value, value.to_string())
+    }
+
+    #[test]
+    fn can_leave_value_below_thresholds() {
+        assert_eq!(colorize(thresholds(), 50.), "50");
+    }
+
+    #[test]
+    fn can_leave_value_at_threshold() {
+        assert_eq!(colorize(thresholds(), 100.), "100");
+    }
+
+    #[test]
+    fn can_color_warning() {
+        assert_eq!(
+            colorize(thresholds(), 101.),
+            format!("{}101{}", WARNING_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn can_color_critical() {
+        assert_eq!(
+            colorize(thresholds(), 501.),
+            format!("{}501{}", CRITICAL_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn can_color_critical_without_warning() {
+        let thresholds = Thresholds {
+            warning: None,
+            critical: Some(500.),
+        };
+        assert_eq!(colorize(thresholds, 200.), "200");
+        assert_eq!(
+            colorize(thresholds, 600.),
+            format!("{}600{}", CRITICAL_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn can_leave_value_without_thresholds() {
+        assert_eq!(colorize(Thresholds::default(), 1e9), "1000000000");
+    }
+
+    #[test]
+    fn can_load_thresholds() {
+        let thresholds: Thresholds = serde_json::from_str(r#"{"warning": 100}"#).unwrap();
+        assert_eq!(thresholds.warning, Some(100.));
+        assert!(thresholds.critical.is_none());
+    }
+}
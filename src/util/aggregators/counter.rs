@@ -11,6 +11,9 @@ pub struct Counter {
     state: HashMap<String, u64>,
     order: HashMap<u64, BTreeSet<String>>,
     num_to_get: Option<usize>,
+    least_common: bool,
+    /// Count items that differ only in case together, under their lowercase form
+    ignore_case: bool,
 }
 
 impl Aggregator for Counter {
@@ -30,12 +33,15 @@ impl Aggregator for Counter {
         // Keep track of how many items we have added
         let mut total_added = 0;
 
-        // Get the keys sorted from highest to lowest
+        // Get the keys sorted from highest to lowest, or lowest to highest for the least common items
         let mut counts: Vec<u64> = self.order.keys().map(|f| f.to_owned()).collect();
         counts.sort_unstable();
+        if !self.least_common {
+            counts.reverse();
+        }
 
         // Get the value under each key
-        for count in counts.iter().rev() {
+        for count in &counts {
             let items = self.order.get(count).unwrap();
             for item in items {
                 let total = self.total() as f64;
@@ -62,6 +68,31 @@ impl Counter {
             state: HashMap::new(),
             order: HashMap::new(),
             num_to_get,
+            least_common: false,
+            ignore_case: false,
+        }
+    }
+
+    /// Create a counter that reports the least common items first
+    pub fn rare(num_to_get: Option<usize>) -> Counter {
+        Counter {
+            least_common: true,
+            ..Counter::new(num_to_get)
+        }
+    }
+
+    /// Set whether items that differ only in case are counted together
+    pub fn ignore_case(mut self, ignore_case: bool) -> Counter {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// Get the key an item is counted under
+    fn key(&self, item: &str) -> String {
+        if self.ignore_case {
+            item.to_lowercase()
+        } else {
+            item.to_owned()
         }
     }
 
@@ -105,6 +136,7 @@ impl Counter {
 
     /// Increment an item into the counter, creating if it does not exist
     fn increment(&mut self, item: &str) {
+        let item = &self.key(item);
         let old_count = self.state.get(item).unwrap_or(&0).to_owned();
         let new_count = old_count.checked_add(1).unwrap_or(old_count);
         self.state.insert(item.to_owned(), new_count);
@@ -113,6 +145,7 @@ impl Counter {
 
     /// Reduce an item from the counter, removing if it becomes 0
     fn decrement(&mut self, item: &str) {
+        let item = &self.key(item);
         let old_count = self.state.get(item).unwrap_or(&0).to_owned();
         let new_count = old_count.checked_sub(1);
         match new_count {
@@ -132,6 +165,7 @@ impl Counter {
 
     /// Remove an item from the counter completely
     fn delete(&mut self, item: &str) {
+        let item = &self.key(item);
         let count = self.state.get(item).unwrap().to_owned();
         self.purge_from_order(item, &count);
         self.purge_from_state(item);
@@ -284,6 +318,55 @@ mod behavior_tests {
     }
 }
 
+#[cfg(test)]
+mod case_tests {
+    use crate::util::aggregators::{aggregator::Aggregator, counter::Counter};
+    use std::collections::HashMap;
+
+    #[test]
+    fn can_ignore_case() {
+        let mut c: Counter = Counter::new(None).ignore_case(true);
+        c.increment("A");
+        c.increment("a");
+        c.increment("b");
+
+        let mut expected_count = HashMap::new();
+        expected_count.insert("a".to_string(), 2);
+        expected_count.insert("b".to_string(), 1);
+
+        assert_eq!(c.state, expected_count);
+        assert_eq!(c.order.get(&2).unwrap().len(), 1);
+        assert_eq!(c.messages(&1), vec!["    a\u{1b}[0m: 2 (67%)"]);
+    }
+
+    #[test]
+    fn can_keep_case() {
+        let mut c: Counter = Counter::new(None);
+        c.increment("A");
+        c.increment("a");
+
+        let mut expected_count = HashMap::new();
+        expected_count.insert("A".to_string(), 1);
+        expected_count.insert("a".to_string(), 1);
+
+        assert_eq!(c.state, expected_count);
+        assert!(!c.order.contains_key(&2));
+    }
+
+    #[test]
+    fn can_decrement_ignoring_case() {
+        let mut c: Counter = Counter::new(None).ignore_case(true);
+        c.increment("A");
+        c.increment("a");
+        c.decrement("A");
+        assert_eq!(c.state.get("a"), Some(&1));
+
+        c.delete("A");
+        assert!(c.state.is_empty());
+        assert!(c.order.is_empty());
+    }
+}
+
 #[cfg(test)]
 mod message_tests {
     use crate::util::aggregators::{aggregator::Aggregator, counter::Counter};
@@ -394,4 +477,41 @@ mod message_tests {
 
         assert_eq!(c.messages(&4), expected);
     }
+
+    #[test]
+    fn can_get_bottom_2() {
+        let mut c: Counter = Counter::rare(None);
+        c.increment(A);
+        c.increment(A);
+        c.increment(A);
+        c.increment(B);
+        c.increment(B);
+        c.increment(B);
+        c.increment(C);
+        c.increment(C);
+        c.increment(D);
+
+        let expected = vec![
+            String::from("    d\u{1b}[0m: 1 (11%)"),
+            String::from("    c\u{1b}[0m: 2 (22%)"),
+        ];
+
+        assert_eq!(c.messages(&2), expected);
+    }
+
+    #[test]
+    fn can_get_bottom_ties() {
+        let mut c: Counter = Counter::rare(None);
+        c.increment(A);
+        c.increment(A);
+        c.increment(B);
+        c.increment(C);
+
+        let expected = vec![
+            String::from("    b\u{1b}[0m: 1 (25%)"),
+            String::from("    c\u{1b}[0m: 1 (25%)"),
+        ];
+
+        assert_eq!(c.messages(&2), expected);
+    }
 }
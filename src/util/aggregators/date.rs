@@ -1,9 +1,13 @@
 use std::cmp::{max, min};
 
-use crate::util::{aggregators::aggregator::Aggregator, error::LogriaError};
+use crate::util::{
+    aggregators::aggregator::{Aggregator, DateFormats},
+    error::LogriaError,
+};
 use format_num::format_num;
 use time::{format_description::parse, Date as Dt, PrimitiveDateTime as DateTime, Time as Tm};
 
+#[derive(Clone, Copy)]
 pub enum DateParserType {
     Date,
     Time,
@@ -11,10 +15,11 @@ pub enum DateParserType {
 }
 
 pub struct Date {
-    format: String,
+    formats: Vec<String>,
     earliest: DateTime,
     latest: DateTime,
     count: i64,
+    failures: i64,
     rate: i64,
     unit: String,
     parser_type: DateParserType,
@@ -22,31 +27,15 @@ pub struct Date {
 
 impl Aggregator for Date {
     fn update(&mut self, message: &str) -> Result<(), LogriaError> {
-        match parse(&self.format) {
-            Ok(parser) => match self.parser_type {
-                DateParserType::Date => match Dt::parse(message, &parser) {
-                    Ok(date) => {
-                        self.upsert(DateTime::new(date, Tm::MIDNIGHT));
-                        Ok(())
-                    }
-                    Err(why) => Err(LogriaError::CannotParseDate(why.to_string())),
-                },
-                DateParserType::Time => match Tm::parse(message, &parser) {
-                    Ok(time) => {
-                        self.upsert(DateTime::new(Dt::MIN, time));
-                        Ok(())
-                    }
-                    Err(why) => Err(LogriaError::CannotParseDate(why.to_string())),
-                },
-                DateParserType::DateTime => match DateTime::parse(message, &parser) {
-                    Ok(date) => {
-                        self.upsert(date);
-                        Ok(())
-                    }
-                    Err(why) => Err(LogriaError::CannotParseDate(why.to_string())),
-                },
-            },
-            Err(why) => Err(LogriaError::CannotParseDate(why.to_string())),
+        match self.parse(message) {
+            Ok(date) => {
+                self.upsert(date);
+                Ok(())
+            }
+            Err(why) => {
+                self.failures += 1;
+                Err(why)
+            }
         }
     }
 
@@ -54,11 +43,17 @@ impl Aggregator for Date {
         let mut out_v = vec![
             format!(
                 "    Rate: {} {}",
-                format_num!(",.0f", self.rate as u32),
+                format_num!(",.0f", self.rate as f64),
                 self.unit
             ),
-            format!("    Count: {}", format_num!(",d", self.count as u32)),
+            format!("    Count: {}", format_num!(",d", self.count as f64)),
         ];
+        if self.failures > 0 {
+            out_v.push(format!(
+                "    Failures: {}",
+                format_num!(",d", self.failures as f64)
+            ));
+        }
         match self.parser_type {
             DateParserType::Date => {
                 out_v.push(format!("    Earliest: {}", self.earliest.date()));
@@ -73,38 +68,53 @@ impl Aggregator for Date {
                 out_v.push(format!("    Latest: {}", self.latest));
             }
         };
+        // Messages that all have the same timestamp have no span to show
+        if self.count > 0 && self.earliest != self.latest {
+            let (span, unit) = self.determine_span();
+            out_v.push(format!(
+                "    Span: {} {}{}",
+                format_num!(",d", span as f64),
+                unit,
+                if span == 1 { "" } else { "s" }
+            ));
+        }
         out_v
     }
 }
 
 impl Date {
-    pub fn new(format: &str, format_type: DateParserType) -> Self {
+    /// Create a date aggregator that accepts messages matching any of `formats`
+    pub fn new<T: Into<DateFormats>>(formats: T, format_type: DateParserType) -> Self {
+        let formats = formats.into().candidates();
         match format_type {
             // If we only care about the date, set the time to midnight
             DateParserType::Date => Date {
-                format: format.to_owned(),
+                formats,
                 earliest: DateTime::new(Dt::MAX, Tm::MIDNIGHT),
                 latest: DateTime::new(Dt::MIN, Tm::MIDNIGHT),
                 count: 0,
+                failures: 0,
                 rate: 0,
                 unit: String::from(""),
                 parser_type: DateParserType::Date,
             },
             // If we only care about the time, use the same date and the latest/earliest possible times
             DateParserType::Time => Date {
-                format: format.to_owned(),
+                formats,
                 earliest: DateTime::new(Dt::MIN, Tm::from_hms(23, 59, 59).unwrap()),
                 latest: DateTime::new(Dt::MIN, Tm::MIDNIGHT),
                 count: 0,
+                failures: 0,
                 rate: 0,
                 unit: String::from(""),
                 parser_type: DateParserType::Time,
             },
             DateParserType::DateTime => Date {
-                format: format.to_owned(),
+                formats,
                 earliest: DateTime::new(Dt::MAX, Tm::MIDNIGHT),
                 latest: DateTime::new(Dt::MIN, Tm::MIDNIGHT),
                 count: 0,
+                failures: 0,
                 rate: 0,
                 unit: String::from(""),
                 parser_type: DateParserType::DateTime,
@@ -112,6 +122,35 @@ impl Date {
         }
     }
 
+    /// Parse a message with the first of the candidate formats that matches it
+    pub fn parse(&self, message: &str) -> Result<DateTime, LogriaError> {
+        // Keep the last error in case none of the formats match
+        let mut error = String::from("no date formats provided");
+        for format in &self.formats {
+            match self.parse_with(format, message) {
+                Ok(date) => return Ok(date),
+                Err(why) => error = why,
+            }
+        }
+        Err(LogriaError::CannotParseDate(error))
+    }
+
+    /// Parse a message with a single format description
+    fn parse_with(&self, format: &str, message: &str) -> Result<DateTime, String> {
+        let parser = parse(format).map_err(|why| why.to_string())?;
+        match self.parser_type {
+            DateParserType::Date => Dt::parse(message, &parser)
+                .map(|date| DateTime::new(date, Tm::MIDNIGHT))
+                .map_err(|why| why.to_string()),
+            DateParserType::Time => Tm::parse(message, &parser)
+                .map(|time| DateTime::new(Dt::MIN, time))
+                .map_err(|why| why.to_string()),
+            DateParserType::DateTime => {
+                DateTime::parse(message, &parser).map_err(|why| why.to_string())
+            }
+        }
+    }
+
     fn upsert(&mut self, new_date: DateTime) {
         self.earliest = min(new_date, self.earliest);
         self.latest = max(new_date, self.latest);
@@ -121,8 +160,8 @@ impl Date {
         self.unit = rate_data.1;
     }
 
-    /// Determine the rate at which messages are received
-    fn determine_rate(&self) -> (i64, String) {
+    /// Determine the unit the rate is measured in and how many of them passed between the earliest and latest dates
+    fn determine_unit(&self) -> (i64, &'static str) {
         let difference = self.latest - self.earliest;
         let mut denominator = difference.whole_weeks();
         let mut unit = "week";
@@ -142,6 +181,34 @@ impl Date {
             denominator = difference.whole_seconds();
             unit = "second"
         }
+        (denominator, unit)
+    }
+
+    /// Determine how much time passed between the earliest and latest dates in the largest unit that fits
+    ///
+    /// The rate's unit depends on the number of messages, so it is not used here, i.e. 3 days is not `0 weeks`.
+    fn determine_span(&self) -> (i64, &'static str) {
+        let difference = self.latest - self.earliest;
+        [
+            (difference.whole_weeks(), "week"),
+            (difference.whole_days(), "day"),
+            (difference.whole_hours(), "hour"),
+            (difference.whole_minutes(), "minute"),
+        ]
+        .into_iter()
+        .find(|(span, _)| *span != 0)
+        .unwrap_or((difference.whole_seconds(), "second"))
+    }
+
+    /// Determine the rate at which messages are received
+    ///
+    /// Several messages with the same timestamp arrived in a single burst, so they are counted at once
+    /// instead of over a unit of time.
+    fn determine_rate(&self) -> (i64, String) {
+        if self.earliest == self.latest && self.count > 1 {
+            return (self.count, String::from("at once"));
+        }
+        let (denominator, unit) = self.determine_unit();
         let mut per_unit = String::from("per ");
         per_unit.push_str(unit);
         (
@@ -178,16 +245,17 @@ mod use_tests {
         d.update("01/04/2021").unwrap();
 
         let expected = Date {
-            format: "[month]/[day]/[year]".to_string(),
+            formats: vec!["[month]/[day]/[year]".to_string()],
             earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
             latest: DateTime::new(Dt::from_ordinal_date(2021, 4).unwrap(), Tm::MIDNIGHT),
             count: 4,
+            failures: 0,
             rate: 1,
             unit: String::from("per day"),
             parser_type: DateParserType::Date,
         };
 
-        assert_eq!(d.format, expected.format);
+        assert_eq!(d.formats, expected.formats);
         assert_eq!(d.earliest, expected.earliest);
         assert_eq!(d.latest, expected.latest);
         assert_eq!(d.count, expected.count);
@@ -204,16 +272,17 @@ mod use_tests {
         d.update("04:01:00").unwrap();
 
         let expected = Date {
-            format: "[hour]:[minute]:[second]".to_string(),
+            formats: vec!["[hour]:[minute]:[second]".to_string()],
             earliest: DateTime::new(Dt::MIN, Tm::from_hms(1, 1, 0).unwrap()),
             latest: DateTime::new(Dt::MIN, Tm::from_hms(4, 1, 0).unwrap()),
             count: 4,
+            failures: 0,
             rate: 1,
             unit: String::from("per hour"),
             parser_type: DateParserType::Time,
         };
 
-        assert_eq!(d.format, expected.format);
+        assert_eq!(d.formats, expected.formats);
         assert_eq!(d.earliest, expected.earliest);
         assert_eq!(d.latest, expected.latest);
         assert_eq!(d.count, expected.count);
@@ -234,7 +303,7 @@ mod use_tests {
         d.update("01/04/2021 04:01:00").unwrap();
 
         let expected = Date {
-            format: "[month]/[day]/[year] [hour]:[minute]:[second]".to_string(),
+            formats: vec!["[month]/[day]/[year] [hour]:[minute]:[second]".to_string()],
             earliest: DateTime::new(
                 Dt::from_ordinal_date(2021, 1).unwrap(),
                 Tm::from_hms(1, 1, 0).unwrap(),
@@ -244,18 +313,95 @@ mod use_tests {
                 Tm::from_hms(4, 1, 0).unwrap(),
             ),
             count: 4,
+            failures: 0,
             rate: 1,
             unit: String::from("per day"),
             parser_type: DateParserType::DateTime,
         };
 
-        assert_eq!(d.format, expected.format);
+        assert_eq!(d.formats, expected.formats);
         assert_eq!(d.earliest, expected.earliest);
         assert_eq!(d.latest, expected.latest);
         assert_eq!(d.count, expected.count);
         assert_eq!(d.unit, expected.unit);
         assert_eq!(d.rate, expected.rate);
     }
+
+    #[test]
+    fn can_update_date_multiple_formats() {
+        let mut d: Date = Date::new(
+            vec![
+                "[month]/[day]/[year]".to_string(),
+                "[year]-[month]-[day]".to_string(),
+            ],
+            DateParserType::Date,
+        );
+        d.update("01/01/2021").unwrap();
+        d.update("2021-01-02").unwrap();
+        d.update("01/03/2021").unwrap();
+        d.update("2021-01-04").unwrap();
+
+        assert_eq!(
+            d.earliest,
+            DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT)
+        );
+        assert_eq!(
+            d.latest,
+            DateTime::new(Dt::from_ordinal_date(2021, 4).unwrap(), Tm::MIDNIGHT)
+        );
+        assert_eq!(d.count, 4);
+        assert_eq!(d.failures, 0);
+    }
+
+    #[test]
+    fn can_update_date_time_multiple_formats() {
+        let mut d: Date = Date::new(
+            vec![
+                "[month]/[day]/[year] [hour]:[minute]:[second]".to_string(),
+                "[year]-[month]-[day]T[hour]:[minute]:[second]".to_string(),
+            ],
+            DateParserType::DateTime,
+        );
+        d.update("01/01/2021 01:01:00").unwrap();
+        d.update("2021-01-02T02:01:00").unwrap();
+        d.update("01/03/2021 03:01:00").unwrap();
+        d.update("2021-01-04T04:01:00").unwrap();
+
+        assert_eq!(d.count, 4);
+        assert_eq!(d.failures, 0);
+        assert_eq!(
+            d.latest,
+            DateTime::new(
+                Dt::from_ordinal_date(2021, 4).unwrap(),
+                Tm::from_hms(4, 1, 0).unwrap(),
+            )
+        );
+    }
+
+    #[test]
+    fn counts_failures() {
+        let mut d: Date = Date::new(
+            vec![
+                "[month]/[day]/[year]".to_string(),
+                "[year]-[month]-[day]".to_string(),
+            ],
+            DateParserType::Date,
+        );
+        d.update("01/01/2021").unwrap();
+        assert!(d.update("Jan 2 2021").is_err());
+        assert!(d.update("").is_err());
+
+        assert_eq!(d.count, 1);
+        assert_eq!(d.failures, 2);
+    }
+
+    #[test]
+    fn counts_failures_without_formats() {
+        let mut d: Date = Date::new(vec![], DateParserType::Date);
+        assert!(d.update("01/01/2021").is_err());
+        assert_eq!(d.count, 0);
+        assert_eq!(d.failures, 1);
+    }
 }
 
 #[cfg(test)]
@@ -278,6 +424,7 @@ mod message_tests {
             "    Count: 4".to_string(),
             "    Earliest: 2021-01-01".to_string(),
             "    Latest: 2021-01-04".to_string(),
+            "    Span: 3 days".to_string(),
         ];
         let messages = d.messages(&1);
 
@@ -297,6 +444,7 @@ mod message_tests {
             "    Count: 4".to_string(),
             "    Earliest: 1:01:00.0".to_string(),
             "    Latest: 4:01:00.0".to_string(),
+            "    Span: 3 hours".to_string(),
         ];
         let messages = d.messages(&1);
 
@@ -319,6 +467,117 @@ mod message_tests {
             "    Count: 4".to_string(),
             "    Earliest: 2021-01-01 1:01:00.0".to_string(),
             "    Latest: 2021-01-04 4:01:00.0".to_string(),
+            "    Span: 3 days".to_string(),
+        ];
+        let messages = d.messages(&1);
+
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn can_show_failures() {
+        let mut d: Date = Date::new(
+            vec![
+                "[month]/[day]/[year]".to_string(),
+                "[year]-[month]-[day]".to_string(),
+            ],
+            DateParserType::Date,
+        );
+        d.update("01/01/2021").unwrap();
+        d.update("2021-01-02").unwrap();
+        let _ = d.update("not a date");
+
+        let expected = vec![
+            "    Rate: 2 per day".to_string(),
+            "    Count: 2".to_string(),
+            "    Failures: 1".to_string(),
+            "    Earliest: 2021-01-01".to_string(),
+            "    Latest: 2021-01-02".to_string(),
+            "    Span: 1 day".to_string(),
+        ];
+        let messages = d.messages(&1);
+
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn can_show_failures_past_u32() {
+        let mut d: Date = Date::new("[month]/[day]/[year]", DateParserType::Date);
+        d.update("01/01/2021").unwrap();
+        d.failures = 5_000_000_000;
+
+        let messages = d.messages(&1);
+        assert_eq!(messages[2], "    Failures: 5,000,000,000");
+    }
+
+    #[test]
+    fn can_show_identical_timestamps() {
+        let mut d: Date = Date::new("[hour]:[minute]:[second]", DateParserType::Time);
+        d.update("01:01:00").unwrap();
+        d.update("01:01:00").unwrap();
+        d.update("01:01:00").unwrap();
+
+        let expected = vec![
+            "    Rate: 3 at once".to_string(),
+            "    Count: 3".to_string(),
+            "    Earliest: 1:01:00.0".to_string(),
+            "    Latest: 1:01:00.0".to_string(),
+        ];
+        let messages = d.messages(&1);
+
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn can_show_span_in_weeks() {
+        let mut d: Date = Date::new("[month]/[day]/[year]", DateParserType::Date);
+        d.update("01/01/2021").unwrap();
+        d.update("01/08/2021").unwrap();
+        d.update("01/15/2021").unwrap();
+        d.update("01/22/2021").unwrap();
+
+        let expected = vec![
+            "    Rate: 1 per week".to_string(),
+            "    Count: 4".to_string(),
+            "    Earliest: 2021-01-01".to_string(),
+            "    Latest: 2021-01-22".to_string(),
+            "    Span: 3 weeks".to_string(),
+        ];
+        let messages = d.messages(&1);
+
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn can_show_span_smaller_than_rate_unit() {
+        let mut d: Date = Date::new("[month]/[day]/[year]", DateParserType::Date);
+        d.update("01/01/2021").unwrap();
+        d.update("01/06/2021").unwrap();
+
+        let expected = vec![
+            "    Rate: 2 per week".to_string(),
+            "    Count: 2".to_string(),
+            "    Earliest: 2021-01-01".to_string(),
+            "    Latest: 2021-01-06".to_string(),
+            "    Span: 5 days".to_string(),
+        ];
+        let messages = d.messages(&1);
+
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn can_show_span_in_seconds() {
+        let mut d: Date = Date::new("[hour]:[minute]:[second]", DateParserType::Time);
+        d.update("01:01:00").unwrap();
+        d.update("01:01:30").unwrap();
+
+        let expected = vec![
+            "    Rate: 2 per minute".to_string(),
+            "    Count: 2".to_string(),
+            "    Earliest: 1:01:00.0".to_string(),
+            "    Latest: 1:01:30.0".to_string(),
+            "    Span: 30 seconds".to_string(),
         ];
         let messages = d.messages(&1);
 
@@ -334,10 +593,11 @@ mod rate_tests {
     #[test]
     fn weekly() {
         let d = Date {
-            format: "".to_string(),
+            formats: vec![],
             earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
             latest: DateTime::new(Dt::from_ordinal_date(2021, 15).unwrap(), Tm::MIDNIGHT),
             count: 10,
+            failures: 0,
             rate: 0,
             unit: String::from(""),
             parser_type: DateParserType::Date,
@@ -348,10 +608,11 @@ mod rate_tests {
     #[test]
     fn daily() {
         let d = Date {
-            format: "".to_string(),
+            formats: vec![],
             earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
             latest: DateTime::new(Dt::from_ordinal_date(2021, 15).unwrap(), Tm::MIDNIGHT),
             count: 15,
+            failures: 0,
             rate: 0,
             unit: String::from(""),
             parser_type: DateParserType::Date,
@@ -362,10 +623,11 @@ mod rate_tests {
     #[test]
     fn hourly() {
         let d = Date {
-            format: "".to_string(),
+            formats: vec![],
             earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
             latest: DateTime::new(Dt::from_ordinal_date(2021, 3).unwrap(), Tm::MIDNIGHT),
             count: 150,
+            failures: 0,
             rate: 0,
             unit: String::from(""),
             parser_type: DateParserType::Date,
@@ -376,10 +638,11 @@ mod rate_tests {
     #[test]
     fn minutely() {
         let d = Date {
-            format: "".to_string(),
+            formats: vec![],
             earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
             latest: DateTime::new(Dt::from_ordinal_date(2021, 2).unwrap(), Tm::MIDNIGHT),
             count: 1500,
+            failures: 0,
             rate: 0,
             unit: String::from(""),
             parser_type: DateParserType::Date,
@@ -390,10 +653,41 @@ mod rate_tests {
     #[test]
     fn secondly() {
         let d = Date {
-            format: "".to_string(),
+            formats: vec![],
             earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
             latest: DateTime::new(Dt::from_ordinal_date(2021, 2).unwrap(), Tm::MIDNIGHT),
             count: 100000,
+            failures: 0,
+            rate: 0,
+            unit: String::from(""),
+            parser_type: DateParserType::Date,
+        };
+        assert_eq!(d.determine_rate(), (1, "per second".to_string()))
+    }
+
+    #[test]
+    fn identical_timestamps() {
+        let d = Date {
+            formats: vec![],
+            earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
+            latest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
+            count: 25,
+            failures: 0,
+            rate: 0,
+            unit: String::from(""),
+            parser_type: DateParserType::Date,
+        };
+        assert_eq!(d.determine_rate(), (25, "at once".to_string()))
+    }
+
+    #[test]
+    fn single_timestamp() {
+        let d = Date {
+            formats: vec![],
+            earliest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
+            latest: DateTime::new(Dt::from_ordinal_date(2021, 1).unwrap(), Tm::MIDNIGHT),
+            count: 1,
+            failures: 0,
             rate: 0,
             unit: String::from(""),
             parser_type: DateParserType::Date,
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use crate::util::{aggregators::aggregator::Aggregator, error::LogriaError};
+use format_num::format_num;
+
+/// Counts how many different values a field has had
+pub struct Distinct {
+    values: HashSet<String>,
+    total: u64,
+}
+
+impl Aggregator for Distinct {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        // Values are compared without surrounding whitespace, the way counters display them
+        let value = message.trim();
+        if !self.values.contains(value) {
+            self.values.insert(value.to_owned());
+        }
+        self.total = self.total.saturating_add(1);
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        vec![
+            format!(
+                "    Distinct: {}",
+                format_num!(",d", self.values.len() as f64)
+            ),
+            format!("    Total: {}", format_num!(",d", self.total as f64)),
+        ]
+    }
+}
+
+impl Distinct {
+    pub fn new() -> Distinct {
+        Distinct {
+            values: HashSet::new(),
+            total: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod distinct_tests {
+    use crate::util::aggregators::{aggregator::Aggregator, distinct::Distinct};
+
+    #[test]
+    fn can_count_distinct() {
+        let mut distinct = Distinct::new();
+        for value in ["GET", "POST", "GET", "PUT"] {
+            distinct.update(value).unwrap();
+        }
+        assert_eq!(distinct.values.len(), 3);
+        assert_eq!(distinct.total, 4);
+    }
+
+    #[test]
+    fn repeated_values_are_stable() {
+        let mut distinct = Distinct::new();
+        for _ in 0..100 {
+            distinct.update("GET").unwrap();
+        }
+        assert_eq!(distinct.values.len(), 1);
+        assert_eq!(distinct.total, 100);
+    }
+
+    #[test]
+    fn trims_values() {
+        let mut distinct = Distinct::new();
+        distinct.update("GET").unwrap();
+        distinct.update(" GET ").unwrap();
+        distinct.update("GET\n").unwrap();
+        assert_eq!(distinct.values.len(), 1);
+    }
+
+    #[test]
+    fn display() {
+        let mut distinct = Distinct::new();
+        for value in ["a", "b", "a"] {
+            distinct.update(value).unwrap();
+        }
+        assert_eq!(
+            distinct.messages(&1),
+            vec!["    Distinct: 2".to_string(), "    Total: 3".to_string()]
+        );
+    }
+
+    #[test]
+    fn display_empty() {
+        let distinct = Distinct::new();
+        assert_eq!(
+            distinct.messages(&1),
+            vec!["    Distinct: 0".to_string(), "    Total: 0".to_string()]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use format_num::format_num;
+
+use crate::util::{
+    aggregators::{aggregator::Aggregator, counter::Counter},
+    error::LogriaError,
+};
+
+/// Counts values separately for each value of another field, i.e. status codes per endpoint
+pub struct GroupedCounter {
+    groups: HashMap<String, Counter>,
+    /// Number of values counted in each group
+    totals: HashMap<String, u64>,
+    /// Count values that differ only in case together in each group
+    ignore_case: bool,
+}
+
+impl Aggregator for GroupedCounter {
+    fn update(&mut self, _: &str) -> Result<(), LogriaError> {
+        Err(LogriaError::InvalidParserState(
+            "grouped counts need a field to group by!".to_string(),
+        ))
+    }
+
+    fn update_in_group(&mut self, message: &str, group: &str) -> Result<(), LogriaError> {
+        let group = group.trim();
+        self.groups
+            .entry(group.to_owned())
+            .or_insert_with(|| Counter::new(None).ignore_case(self.ignore_case))
+            .update(message)?;
+        *self.totals.entry(group.to_owned()).or_default() += 1;
+        Ok(())
+    }
+
+    /// Render the `n` largest groups, each followed by its `n` most common values
+    fn messages(&self, n: &usize) -> Vec<String> {
+        let mut groups: Vec<(&String, &u64)> = self.totals.iter().collect();
+        groups.sort_unstable_by(|(a_name, a_total), (b_name, b_total)| {
+            b_total.cmp(a_total).then(a_name.cmp(b_name))
+        });
+
+        let mut result = vec![];
+        for (group, total) in groups.into_iter().take(*n) {
+            result.push(format!(
+                "    {}: {}",
+                group,
+                format_num!(",d", *total as f64)
+            ));
+            if let Some(counter) = self.groups.get(group) {
+                result.extend(
+                    counter
+                        .messages(n)
+                        .into_iter()
+                        .map(|line| format!("    {}", line)),
+                );
+            }
+        }
+        result
+    }
+}
+
+impl GroupedCounter {
+    pub fn new() -> GroupedCounter {
+        GroupedCounter {
+            groups: HashMap::new(),
+            totals: HashMap::new(),
+            ignore_case: false,
+        }
+    }
+
+    /// Set whether values that differ only in case are counted together
+    pub fn ignore_case(mut self, ignore_case: bool) -> GroupedCounter {
+        self.ignore_case = ignore_case;
+        self
+    }
+}
+
+#[cfg(test)]
+mod grouped_tests {
+    use crate::{
+        constants::cli::colors::RESET_COLOR,
+        util::aggregators::{aggregator::Aggregator, grouped::GroupedCounter},
+    };
+
+    fn counter(lines: &[(&str, &str)]) -> GroupedCounter {
+        let mut counter = GroupedCounter::new();
+        for (endpoint, status) in lines {
+            counter.update_in_group(status, endpoint).unwrap();
+        }
+        counter
+    }
+
+    #[test]
+    fn can_count_per_group() {
+        let counter = counter(&[
+            ("/users", "200"),
+            ("/orders", "500"),
+            ("/users", "404"),
+            ("/users", "200"),
+            ("/orders", "200"),
+        ]);
+        assert_eq!(
+            counter.messages(&5),
+            vec![
+                "    /users: 3".to_string(),
+                format!("        200{}: 2 (67%)", RESET_COLOR),
+                format!("        404{}: 1 (33%)", RESET_COLOR),
+                "    /orders: 2".to_string(),
+                format!("        200{}: 1 (50%)", RESET_COLOR),
+                format!("        500{}: 1 (50%)", RESET_COLOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_limit_groups_and_values() {
+        let counter = counter(&[
+            ("/users", "200"),
+            ("/users", "200"),
+            ("/users", "404"),
+            ("/orders", "200"),
+        ]);
+        assert_eq!(
+            counter.messages(&1),
+            vec![
+                "    /users: 3".to_string(),
+                format!("        200{}: 2 (67%)", RESET_COLOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_count_per_group_ignoring_case() {
+        let mut counter = GroupedCounter::new().ignore_case(true);
+        counter.update_in_group("GET", "/users").unwrap();
+        counter.update_in_group("get", "/users").unwrap();
+        assert_eq!(
+            counter.messages(&5),
+            vec![
+                "    /users: 2".to_string(),
+                format!("        get{}: 2 (100%)", RESET_COLOR),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_render_empty() {
+        assert!(GroupedCounter::new().messages(&5).is_empty());
+    }
+
+    #[test]
+    fn cannot_update_without_group() {
+        let mut counter = GroupedCounter::new();
+        assert!(counter.update("200").is_err());
+        assert!(counter.messages(&5).is_empty());
+    }
+}
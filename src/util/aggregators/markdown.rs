@@ -0,0 +1,119 @@
+use regex::bytes::Regex;
+
+use crate::{constants::cli::patterns::ANSI_COLOR_PATTERN, util::sanitizers::ansi::CleanText};
+
+/// Header rows of an exported aggregation table
+const HEADER: &str = "| Field | Metric | Value |\n|--|--|--|\n";
+
+/// Remove color codes and escape characters that would break a table cell
+fn cell(color_pattern: &Regex, text: &str) -> String {
+    let clean = CleanText::new(color_pattern, text.trim().as_bytes());
+    String::from_utf8_lossy(&clean.text).replace('|', "\\|")
+}
+
+/// Format the aggregation panel as a Markdown table with one row per metric
+///
+/// Unindented lines in `panel` name a field and the indented lines below them are that field's metrics, written
+/// as `metric: value`. Lines without a metric name, like a sparkline, only fill the value column.
+pub fn markdown_table(panel: &[String]) -> String {
+    let color_pattern = Regex::new(ANSI_COLOR_PATTERN).unwrap();
+    let mut table = String::from(HEADER);
+    let mut field = String::new();
+    for line in panel {
+        if !line.starts_with(' ') {
+            field = cell(&color_pattern, line);
+            continue;
+        }
+        let (metric, value) = match line.rsplit_once(": ") {
+            Some((metric, value)) => (cell(&color_pattern, metric), cell(&color_pattern, value)),
+            None => (String::new(), cell(&color_pattern, line)),
+        };
+        table.push_str(&format!("| {} | {} | {} |\n", field, metric, value));
+    }
+    table
+}
+
+#[cfg(test)]
+mod markdown_tests {
+    use crate::util::aggregators::{
+        aggregator::{Aggregator, DecimalSeparator},
+        counter::Counter,
+        markdown::markdown_table,
+        mean::Mean,
+        sparkline::Sparkline,
+    };
+
+    fn panel(fields: Vec<(&str, Box<dyn Aggregator>)>) -> Vec<String> {
+        let mut panel = vec![];
+        for (name, aggregator) in fields {
+            panel.push(name.to_owned());
+            panel.extend(aggregator.messages(&5));
+        }
+        panel
+    }
+
+    #[test]
+    fn can_format_mean() {
+        let mut mean = Mean::new(DecimalSeparator::Period);
+        mean.update("1").unwrap();
+        mean.update("2").unwrap();
+        assert_eq!(
+            markdown_table(&panel(vec![("Latency", Box::new(mean))])),
+            concat!(
+                "| Field | Metric | Value |\n",
+                "|--|--|--|\n",
+                "| Latency | Mean | 1.50 |\n",
+                "| Latency | Count | 2 |\n",
+                "| Latency | Total | 3 |\n",
+            )
+        );
+    }
+
+    #[test]
+    fn can_format_counter() {
+        let mut counter = Counter::new(None);
+        counter.update("INFO").unwrap();
+        counter.update("INFO").unwrap();
+        counter.update("WARN").unwrap();
+        assert_eq!(
+            markdown_table(&panel(vec![("Level", Box::new(counter))])),
+            concat!(
+                "| Field | Metric | Value |\n",
+                "|--|--|--|\n",
+                "| Level | INFO | 2 (67%) |\n",
+                "| Level | WARN | 1 (33%) |\n",
+            )
+        );
+    }
+
+    #[test]
+    fn can_format_mixed_fields() {
+        let mut counter = Counter::new(None);
+        counter.update("a|b").unwrap();
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 2);
+        sparkline.update("1").unwrap();
+        sparkline.update("2").unwrap();
+        assert_eq!(
+            markdown_table(&panel(vec![
+                ("Path", Box::new(counter)),
+                ("Size", Box::new(sparkline))
+            ])),
+            concat!(
+                "| Field | Metric | Value |\n",
+                "|--|--|--|\n",
+                "| Path | a\\|b | 1 (100%) |\n",
+                "| Size |  | ▁█ |\n",
+                "| Size | Min | 1.00 |\n",
+                "| Size | Max | 2.00 |\n",
+            )
+        );
+    }
+
+    #[test]
+    fn can_format_empty_panel() {
+        assert_eq!(
+            markdown_table(&[]),
+            "| Field | Metric | Value |\n|--|--|--|\n"
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::util::{
-    aggregators::aggregator::{extract_number, Aggregator},
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator, Thresholds},
     error::LogriaError,
 };
 use format_num::format_num;
@@ -7,6 +7,8 @@ use format_num::format_num;
 pub struct Mean {
     count: f64,
     total: f64,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
 }
 
 /// Float implementation of Mean
@@ -36,23 +38,33 @@ impl Aggregator for Mean {
 
     fn messages(&self, _: &usize) -> Vec<String> {
         vec![
-            format!("    Mean: {:.2}", self.mean()),
+            format!(
+                "    Mean: {}",
+                self.thresholds
+                    .colorize(self.mean(), format!("{:.2}", self.mean()))
+            ),
             format!("    Count: {}", format_num!(",d", self.count)),
             format!("    Total: {}", format_num!(",d", self.total)),
         ]
     }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
 }
 
 impl Mean {
-    pub fn new() -> Mean {
+    pub fn new(separator: DecimalSeparator) -> Mean {
         Mean {
             count: 0.,
             total: 0.,
+            separator,
+            thresholds: Thresholds::default(),
         }
     }
 
     fn parse(&self, message: &str) -> Option<f64> {
-        extract_number(message)
+        extract_number(message, self.separator)
     }
 
     fn mean(&self) -> f64 {
@@ -66,11 +78,17 @@ impl Mean {
 
 #[cfg(test)]
 mod float_tests {
-    use crate::util::aggregators::{aggregator::Aggregator, mean::Mean};
+    use crate::{
+        constants::cli::colors::{CRITICAL_COLOR, RESET_COLOR, WARNING_COLOR},
+        util::aggregators::{
+            aggregator::{Aggregator, DecimalSeparator, Thresholds},
+            mean::Mean,
+        },
+    };
 
     #[test]
     fn mean() {
-        let mut mean: Mean = Mean::new();
+        let mut mean: Mean = Mean::new(DecimalSeparator::Period);
         mean.update("1_f64").unwrap();
         mean.update("2_f64").unwrap();
         mean.update("3_f64").unwrap();
@@ -82,7 +100,7 @@ mod float_tests {
 
     #[test]
     fn display() {
-        let mut mean: Mean = Mean::new();
+        let mut mean: Mean = Mean::new(DecimalSeparator::Period);
         mean.update("1_f64").unwrap();
         mean.update("2_f64").unwrap();
         mean.update("3_f64").unwrap();
@@ -97,9 +115,51 @@ mod float_tests {
         );
     }
 
+    #[test]
+    fn display_above_threshold() {
+        let mut mean: Mean = Mean::new(DecimalSeparator::Period);
+        mean.set_thresholds(Thresholds {
+            warning: Some(1.),
+            critical: Some(5.),
+        });
+        mean.update("2").unwrap();
+        mean.update("4").unwrap();
+        assert_eq!(
+            mean.messages(&1)[0],
+            format!("    Mean: {}3.00{}", WARNING_COLOR, RESET_COLOR)
+        );
+
+        mean.update("30").unwrap();
+        assert_eq!(
+            mean.messages(&1)[0],
+            format!("    Mean: {}12.00{}", CRITICAL_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn display_below_threshold() {
+        let mut mean: Mean = Mean::new(DecimalSeparator::Period);
+        mean.set_thresholds(Thresholds {
+            warning: Some(10.),
+            critical: None,
+        });
+        mean.update("2").unwrap();
+        assert_eq!(mean.messages(&1)[0], "    Mean: 2.00");
+    }
+
+    #[test]
+    fn mean_comma_decimal() {
+        let mut mean: Mean = Mean::new(DecimalSeparator::Comma);
+        mean.update("1.000,5 ms").unwrap();
+        mean.update("0,5 ms").unwrap();
+
+        assert!((mean.mean() - 500.5).abs() == 0_f64);
+        assert!((mean.count - 2_f64).abs() == 0_f64);
+    }
+
     #[test]
     fn empty_mean() {
-        let mean: Mean = Mean::new();
+        let mean: Mean = Mean::new(DecimalSeparator::Period);
 
         assert!(mean.mean() == 0_f64);
         assert!(mean.total == 0_f64);
@@ -108,7 +168,7 @@ mod float_tests {
 
     #[test]
     fn mean_overflow() {
-        let mut mean: Mean = Mean::new();
+        let mut mean: Mean = Mean::new(DecimalSeparator::Period);
         mean.update(&format!("{}test", f64::MAX - 1_f64)).unwrap();
         mean.update(&format!("{} test", f64::MAX - 1_f64)).unwrap();
 
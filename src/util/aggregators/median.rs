@@ -0,0 +1,228 @@
+use crate::util::{
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator, Thresholds},
+    error::LogriaError,
+};
+use format_num::format_num;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+/// A number ordered by `f64::total_cmp` so it can be kept in a heap
+#[derive(Clone, Copy, Debug)]
+struct Value(f64);
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+pub struct Median {
+    /// The smaller half of the numbers seen so far, largest first; holds the middle value for an odd count
+    lower: BinaryHeap<Value>,
+    /// The larger half of the numbers seen so far, smallest first
+    upper: BinaryHeap<Reverse<Value>>,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
+}
+
+/// Exact median of every number seen so far
+impl Aggregator for Median {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        // NaN cannot be ordered, so it is skipped like a message without a number
+        if let Some(number) = self.parse(message).filter(|number| !number.is_nan()) {
+            match self.lower.peek() {
+                Some(Value(largest)) if number > *largest => {
+                    self.upper.push(Reverse(Value(number)))
+                }
+                _ => self.lower.push(Value(number)),
+            }
+            // Keep the halves within one number of each other so the middle is at the top of the heaps
+            if self.lower.len() > self.upper.len() + 1 {
+                if let Some(value) = self.lower.pop() {
+                    self.upper.push(Reverse(value));
+                }
+            } else if self.upper.len() > self.lower.len() {
+                if let Some(Reverse(value)) = self.upper.pop() {
+                    self.lower.push(value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        let median = match self.median() {
+            Some(median) => self.thresholds.colorize(median, format!("{:.2}", median)),
+            None => "-".to_owned(),
+        };
+        vec![
+            format!("    Median: {}", median),
+            format!("    Count: {}", format_num!(",d", self.count() as f64)),
+        ]
+    }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
+}
+
+impl Median {
+    pub fn new(separator: DecimalSeparator) -> Median {
+        Median {
+            lower: BinaryHeap::new(),
+            upper: BinaryHeap::new(),
+            separator,
+            thresholds: Thresholds::default(),
+        }
+    }
+
+    fn parse(&self, message: &str) -> Option<f64> {
+        extract_number(message, self.separator)
+    }
+
+    /// Number of numbers seen so far
+    fn count(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    /// The middle value, or the mean of the two middle values for an even count
+    fn median(&self) -> Option<f64> {
+        match (self.lower.peek(), self.upper.peek()) {
+            (Some(Value(lower)), Some(Reverse(Value(upper))))
+                if self.lower.len() == self.upper.len() =>
+            {
+                Some((lower + upper) / 2.)
+            }
+            (Some(Value(lower)), _) => Some(*lower),
+            (None, _) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod float_tests {
+    use crate::{
+        constants::cli::colors::{RESET_COLOR, WARNING_COLOR},
+        util::aggregators::{
+            aggregator::{Aggregator, DecimalSeparator, Thresholds},
+            median::Median,
+        },
+    };
+
+    #[test]
+    fn median_odd() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        median.update("3_f64").unwrap();
+        median.update("1_f64").unwrap();
+        median.update("2_f64").unwrap();
+
+        assert_eq!(median.median(), Some(2.));
+        assert_eq!(median.count(), 3);
+    }
+
+    #[test]
+    fn median_even() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        median.update("4").unwrap();
+        median.update("1").unwrap();
+        median.update("3").unwrap();
+        median.update("2").unwrap();
+
+        assert_eq!(median.median(), Some(2.5));
+    }
+
+    #[test]
+    fn median_of_many() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        for value in (1..=1000).rev() {
+            median.update(&value.to_string()).unwrap();
+        }
+        assert_eq!(median.median(), Some(500.5));
+
+        median.update("1001").unwrap();
+        assert_eq!(median.median(), Some(501.));
+    }
+
+    #[test]
+    fn median_ignores_outliers() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        for value in ["10 ms", "12 ms", "11 ms", "9000 ms", "10 ms"] {
+            median.update(value).unwrap();
+        }
+
+        assert_eq!(median.median(), Some(11.));
+    }
+
+    #[test]
+    fn display() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        median.update("1_f64").unwrap();
+        median.update("2_f64").unwrap();
+        median.update("3_f64").unwrap();
+
+        assert_eq!(
+            median.messages(&1),
+            vec!["    Median: 2.00".to_string(), "    Count: 3".to_string()]
+        );
+    }
+
+    #[test]
+    fn display_above_threshold() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        median.set_thresholds(Thresholds {
+            warning: Some(1.),
+            critical: Some(5.),
+        });
+        median.update("2").unwrap();
+        assert_eq!(
+            median.messages(&1)[0],
+            format!("    Median: {}2.00{}", WARNING_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn median_comma_decimal() {
+        let mut median = Median::new(DecimalSeparator::Comma);
+        median.update("1.000,5 ms").unwrap();
+        median.update("0,5 ms").unwrap();
+
+        assert_eq!(median.median(), Some(500.5));
+    }
+
+    #[test]
+    fn skips_non_numeric_messages() {
+        let mut median = Median::new(DecimalSeparator::Period);
+        median.update("took 5 ms").unwrap();
+        median.update("timeout").unwrap();
+
+        assert_eq!(median.count(), 1);
+        assert_eq!(median.median(), Some(5.));
+    }
+
+    #[test]
+    fn empty_median() {
+        let median = Median::new(DecimalSeparator::Period);
+
+        assert!(median.median().is_none());
+        assert_eq!(
+            median.messages(&1),
+            vec!["    Median: -".to_string(), "    Count: 0".to_string()]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::util::{
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator, Thresholds},
+    error::LogriaError,
+};
+use format_num::format_num;
+
+/// Render the extreme value followed by the number of values seen
+fn render(label: &str, value: Option<f64>, count: u64, thresholds: &Thresholds) -> Vec<String> {
+    let value = match value {
+        Some(value) => thresholds.colorize(value, format!("{:.2}", value)),
+        None => "-".to_owned(),
+    };
+    vec![
+        format!("    {}: {}", label, value),
+        format!("    Count: {}", format_num!(",d", count as f64)),
+    ]
+}
+
+pub struct Min {
+    min: Option<f64>,
+    count: u64,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
+}
+
+/// Smallest number seen so far
+impl Aggregator for Min {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        // NaN cannot be compared, so it is skipped like a message without a number
+        if let Some(number) = extract_number(message, self.separator).filter(|n| !n.is_nan()) {
+            self.min = Some(self.min.map_or(number, |min| min.min(number)));
+            self.count = self.count.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        render("Min", self.min, self.count, &self.thresholds)
+    }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
+}
+
+impl Min {
+    pub fn new(separator: DecimalSeparator) -> Min {
+        Min {
+            min: None,
+            count: 0,
+            separator,
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+pub struct Max {
+    max: Option<f64>,
+    count: u64,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
+}
+
+/// Largest number seen so far
+impl Aggregator for Max {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        // NaN cannot be compared, so it is skipped like a message without a number
+        if let Some(number) = extract_number(message, self.separator).filter(|n| !n.is_nan()) {
+            self.max = Some(self.max.map_or(number, |max| max.max(number)));
+            self.count = self.count.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        render("Max", self.max, self.count, &self.thresholds)
+    }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
+}
+
+impl Max {
+    pub fn new(separator: DecimalSeparator) -> Max {
+        Max {
+            max: None,
+            count: 0,
+            separator,
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod minmax_tests {
+    use crate::{
+        constants::cli::colors::{RESET_COLOR, WARNING_COLOR},
+        util::aggregators::{
+            aggregator::{Aggregator, DecimalSeparator, Thresholds},
+            minmax::{Max, Min},
+        },
+    };
+
+    #[test]
+    fn min() {
+        let mut min = Min::new(DecimalSeparator::Period);
+        min.update("5 ms").unwrap();
+        min.update("2 ms").unwrap();
+        min.update("9 ms").unwrap();
+
+        assert_eq!(min.min, Some(2.));
+        assert_eq!(min.count, 3);
+    }
+
+    #[test]
+    fn max() {
+        let mut max = Max::new(DecimalSeparator::Period);
+        max.update("5 ms").unwrap();
+        max.update("9 ms").unwrap();
+        max.update("2 ms").unwrap();
+
+        assert_eq!(max.max, Some(9.));
+        assert_eq!(max.count, 3);
+    }
+
+    #[test]
+    fn min_negative() {
+        let mut min = Min::new(DecimalSeparator::Period);
+        min.update("-1").unwrap();
+        min.update("-10.5").unwrap();
+        min.update("3").unwrap();
+
+        assert_eq!(min.min, Some(-10.5));
+    }
+
+    #[test]
+    fn max_negative() {
+        let mut max = Max::new(DecimalSeparator::Period);
+        max.update("-10").unwrap();
+        max.update("-2.5").unwrap();
+
+        assert_eq!(max.max, Some(-2.5));
+    }
+
+    #[test]
+    fn skips_non_numeric_messages() {
+        let mut min = Min::new(DecimalSeparator::Period);
+        let mut max = Max::new(DecimalSeparator::Period);
+        for message in ["4", "timeout", "6"] {
+            min.update(message).unwrap();
+            max.update(message).unwrap();
+        }
+
+        assert_eq!(min.min, Some(4.));
+        assert_eq!(max.max, Some(6.));
+        assert_eq!(min.count, 2);
+        assert_eq!(max.count, 2);
+    }
+
+    #[test]
+    fn min_comma_decimal() {
+        let mut min = Min::new(DecimalSeparator::Comma);
+        min.update("1.000,5 ms").unwrap();
+        min.update("0,5 ms").unwrap();
+
+        assert_eq!(min.min, Some(0.5));
+    }
+
+    #[test]
+    fn display() {
+        let mut min = Min::new(DecimalSeparator::Period);
+        let mut max = Max::new(DecimalSeparator::Period);
+        for message in ["1", "-3", "2"] {
+            min.update(message).unwrap();
+            max.update(message).unwrap();
+        }
+
+        assert_eq!(
+            min.messages(&1),
+            vec!["    Min: -3.00".to_string(), "    Count: 3".to_string()]
+        );
+        assert_eq!(
+            max.messages(&1),
+            vec!["    Max: 2.00".to_string(), "    Count: 3".to_string()]
+        );
+    }
+
+    #[test]
+    fn display_above_threshold() {
+        let mut max = Max::new(DecimalSeparator::Period);
+        max.set_thresholds(Thresholds {
+            warning: Some(1.),
+            critical: Some(5.),
+        });
+        max.update("2").unwrap();
+        assert_eq!(
+            max.messages(&1)[0],
+            format!("    Max: {}2.00{}", WARNING_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn display_empty() {
+        let min = Min::new(DecimalSeparator::Period);
+        let max = Max::new(DecimalSeparator::Period);
+
+        assert!(min.min.is_none());
+        assert!(max.max.is_none());
+        assert_eq!(
+            min.messages(&1),
+            vec!["    Min: -".to_string(), "    Count: 0".to_string()]
+        );
+        assert_eq!(
+            max.messages(&1),
+            vec!["    Max: -".to_string(), "    Count: 0".to_string()]
+        );
+    }
+}
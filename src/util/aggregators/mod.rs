@@ -1,6 +1,15 @@
 pub mod aggregator;
 pub mod counter;
 pub mod date;
+pub mod distinct;
+pub mod grouped;
+pub mod markdown;
 pub mod mean;
+pub mod median;
+pub mod minmax;
 pub mod sum;
 pub mod none;
+pub mod occurrences;
+pub mod percentile;
+pub mod quantiles;
+pub mod sparkline;
@@ -0,0 +1,120 @@
+use regex::{Error, Regex};
+
+use crate::util::{aggregators::aggregator::Aggregator, error::LogriaError};
+use format_num::format_num;
+
+/// Counts every match of a pattern inside each message, rather than each message once
+pub struct Occurrences {
+    /// The compiled sub-pattern, or the error from compiling it
+    pattern: Result<Regex, Error>,
+    source: String,
+    matches: u64,
+    count: u64,
+}
+
+impl Aggregator for Occurrences {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        match &self.pattern {
+            Ok(pattern) => {
+                self.matches = self
+                    .matches
+                    .saturating_add(pattern.find_iter(message).count() as u64);
+                self.count = self.count.saturating_add(1);
+                Ok(())
+            }
+            Err(why) => Err(LogriaError::InvalidRegex(
+                why.to_owned(),
+                self.source.to_owned(),
+            )),
+        }
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        vec![
+            format!("    Total: {}", format_num!(",d", self.matches as f64)),
+            format!("    Per message: {:.2}", self.average()),
+            format!("    Count: {}", format_num!(",d", self.count as f64)),
+        ]
+    }
+}
+
+impl Occurrences {
+    pub fn new(pattern: &str) -> Occurrences {
+        Occurrences {
+            pattern: Regex::new(pattern),
+            source: pattern.to_owned(),
+            matches: 0,
+            count: 0,
+        }
+    }
+
+    fn average(&self) -> f64 {
+        if self.count == 0 {
+            0.
+        } else {
+            self.matches as f64 / self.count as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod occurrences_tests {
+    use crate::util::aggregators::{aggregator::Aggregator, occurrences::Occurrences};
+
+    #[test]
+    fn can_count_multiple_matches_per_message() {
+        let mut occurrences = Occurrences::new("retry");
+        occurrences.update("retry retry retry").unwrap();
+        occurrences.update("retry failed").unwrap();
+        occurrences.update("ok").unwrap();
+
+        assert_eq!(occurrences.matches, 4);
+        assert_eq!(occurrences.count, 3);
+    }
+
+    #[test]
+    fn can_count_pattern() {
+        let mut occurrences = Occurrences::new(r"id=\d+");
+        occurrences.update("id=1 id=2 id=x").unwrap();
+        occurrences.update("id=30,id=4").unwrap();
+
+        assert_eq!(occurrences.matches, 4);
+    }
+
+    #[test]
+    fn display() {
+        let mut occurrences = Occurrences::new("a");
+        occurrences.update("banana").unwrap();
+        occurrences.update("apple").unwrap();
+
+        assert_eq!(
+            occurrences.messages(&1),
+            vec![
+                "    Total: 4".to_string(),
+                "    Per message: 2.00".to_string(),
+                "    Count: 2".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn display_empty() {
+        let occurrences = Occurrences::new("a");
+
+        assert_eq!(
+            occurrences.messages(&1),
+            vec![
+                "    Total: 0".to_string(),
+                "    Per message: 0.00".to_string(),
+                "    Count: 0".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn cannot_count_invalid_pattern() {
+        let mut occurrences = Occurrences::new("(");
+        assert!(occurrences.update("(").is_err());
+        assert_eq!(occurrences.count, 0);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::util::{
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator, Thresholds},
+    error::LogriaError,
+};
+use format_num::format_num;
+
+/// Number of samples kept for each percentile, about 80KB of memory
+pub const DEFAULT_SAMPLES: usize = 10_000;
+
+/// Percentile of the numbers seen so far
+///
+/// Every number is kept until `capacity` samples are stored. After that, each new number replaces
+/// a random sample with decreasing probability (reservoir sampling), so memory stays bounded while
+/// the samples remain representative of every number seen, at the cost of an approximate result.
+pub struct Percentile {
+    /// Percentile to report, between 0 and 100
+    percentile: f64,
+    samples: Vec<f64>,
+    capacity: usize,
+    /// Count of every number seen, including those not kept as samples
+    seen: u64,
+    /// State of the pseudo random number generator used to pick samples to replace
+    state: u64,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
+}
+
+impl Aggregator for Percentile {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        if !(0. ..=100.).contains(&self.percentile) {
+            return Err(LogriaError::InvalidParserState(format!(
+                "percentile {} is not between 0 and 100",
+                self.percentile
+            )));
+        }
+        // NaN cannot be ordered, so it is skipped like a message without a number
+        if let Some(number) = extract_number(message, self.separator).filter(|n| !n.is_nan()) {
+            self.seen = self.seen.saturating_add(1);
+            if self.samples.len() < self.capacity {
+                self.samples.push(number);
+            } else {
+                let index = self.next_random() % self.seen;
+                if let Some(sample) = self.samples.get_mut(index as usize) {
+                    *sample = number;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        let value = match self.value() {
+            Some(value) => self.thresholds.colorize(value, format!("{:.2}", value)),
+            None => "-".to_owned(),
+        };
+        vec![
+            format!("    p{}: {}", self.percentile, value),
+            format!("    Count: {}", format_num!(",d", self.seen as f64)),
+        ]
+    }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
+}
+
+impl Percentile {
+    pub fn new(percentile: f64, separator: DecimalSeparator, capacity: usize) -> Percentile {
+        Percentile {
+            percentile,
+            samples: Vec::with_capacity(capacity.min(DEFAULT_SAMPLES)),
+            capacity,
+            seen: 0,
+            state: 0x2545_F491_4F6C_DD1D,
+            separator,
+            thresholds: Thresholds::default(),
+        }
+    }
+
+    /// Linear congruential generator; samples only need to be spread evenly, not unpredictably
+    fn next_random(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        self.state >> 11
+    }
+
+    /// Nearest rank percentile of the samples
+    fn value(&self) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.to_owned();
+        sorted.sort_by(f64::total_cmp);
+        let rank = (self.percentile / 100. * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+}
+
+#[cfg(test)]
+mod percentile_tests {
+    use crate::{
+        constants::cli::colors::{RESET_COLOR, WARNING_COLOR},
+        util::aggregators::{
+            aggregator::{Aggregator, DecimalSeparator, Thresholds},
+            percentile::{Percentile, DEFAULT_SAMPLES},
+        },
+    };
+
+    fn sequence(percentile: f64) -> Percentile {
+        let mut aggregator = Percentile::new(percentile, DecimalSeparator::Period, DEFAULT_SAMPLES);
+        for value in 1..=100 {
+            aggregator.update(&value.to_string()).unwrap();
+        }
+        aggregator
+    }
+
+    #[test]
+    fn p50() {
+        assert_eq!(sequence(50.).value(), Some(50.));
+    }
+
+    #[test]
+    fn p95() {
+        assert_eq!(sequence(95.).value(), Some(95.));
+    }
+
+    #[test]
+    fn p100() {
+        assert_eq!(sequence(100.).value(), Some(100.));
+    }
+
+    #[test]
+    fn p0() {
+        assert_eq!(sequence(0.).value(), Some(1.));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            sequence(99.5).messages(&1),
+            vec![
+                "    p99.5: 100.00".to_string(),
+                "    Count: 100".to_string()
+            ]
+        );
+        assert_eq!(
+            sequence(95.).messages(&1),
+            vec!["    p95: 95.00".to_string(), "    Count: 100".to_string()]
+        );
+    }
+
+    #[test]
+    fn display_above_threshold() {
+        let mut aggregator = sequence(95.);
+        aggregator.set_thresholds(Thresholds {
+            warning: Some(90.),
+            critical: Some(100.),
+        });
+        assert_eq!(
+            aggregator.messages(&1)[0],
+            format!("    p95: {}95.00{}", WARNING_COLOR, RESET_COLOR)
+        );
+    }
+
+    #[test]
+    fn display_empty() {
+        let aggregator = Percentile::new(95., DecimalSeparator::Period, DEFAULT_SAMPLES);
+        assert_eq!(
+            aggregator.messages(&1),
+            vec!["    p95: -".to_string(), "    Count: 0".to_string()]
+        );
+    }
+
+    #[test]
+    fn skips_non_numeric_messages() {
+        let mut aggregator = Percentile::new(50., DecimalSeparator::Period, DEFAULT_SAMPLES);
+        aggregator.update("took 5 ms").unwrap();
+        aggregator.update("timeout").unwrap();
+        assert_eq!(aggregator.seen, 1);
+        assert_eq!(aggregator.value(), Some(5.));
+    }
+
+    #[test]
+    fn caps_samples() {
+        let mut aggregator = Percentile::new(50., DecimalSeparator::Period, 100);
+        for value in 1..=10_000 {
+            aggregator.update(&value.to_string()).unwrap();
+        }
+        assert_eq!(aggregator.samples.len(), 100);
+        assert_eq!(aggregator.seen, 10_000);
+        // The reservoir is an even sample of every value, so the median stays near the middle
+        let median = aggregator.value().unwrap();
+        assert!((3_500. ..=6_500.).contains(&median), "{}", median);
+    }
+
+    #[test]
+    fn cannot_update_invalid_percentile() {
+        let mut aggregator = Percentile::new(150., DecimalSeparator::Period, DEFAULT_SAMPLES);
+        assert!(aggregator.update("1").is_err());
+        assert_eq!(aggregator.seen, 0);
+    }
+}
@@ -0,0 +1,321 @@
+use format_num::format_num;
+
+use crate::util::{
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator, Thresholds},
+    error::LogriaError,
+};
+
+/// Quantiles estimated by the `Quantiles` aggregator, with their labels
+const QUANTILES: [(&str, f64); 3] = [("p50", 0.5), ("p90", 0.9), ("p99", 0.99)];
+
+/// Number of markers tracked by each estimate
+const MARKERS: usize = 5;
+
+/// Streaming estimate of a single quantile using the P² algorithm
+///
+/// Tracks five markers whose heights approximate the minimum, the maximum, the quantile, and the
+/// quantiles halfway to either side, so memory does not grow with the number of values.
+struct Estimate {
+    quantile: f64,
+    /// Marker heights
+    heights: [f64; MARKERS],
+    /// Actual marker positions, starting at 1
+    positions: [f64; MARKERS],
+    /// Desired marker positions
+    desired: [f64; MARKERS],
+    /// Amount each desired position moves per value
+    increments: [f64; MARKERS],
+    /// Values seen before the markers are initialized
+    initial: Vec<f64>,
+}
+
+impl Estimate {
+    fn new(quantile: f64) -> Estimate {
+        Estimate {
+            quantile,
+            heights: [0.; MARKERS],
+            positions: [1., 2., 3., 4., 5.],
+            desired: [
+                1.,
+                1. + 2. * quantile,
+                1. + 4. * quantile,
+                3. + 2. * quantile,
+                5.,
+            ],
+            increments: [0., quantile / 2., quantile, (1. + quantile) / 2., 1.],
+            initial: Vec::with_capacity(MARKERS),
+        }
+    }
+
+    fn update(&mut self, value: f64) {
+        // The first values become the initial marker heights
+        if self.initial.len() < MARKERS {
+            self.initial.push(value);
+            if self.initial.len() == MARKERS {
+                self.initial.sort_by(f64::total_cmp);
+                self.heights.copy_from_slice(&self.initial);
+            }
+            return;
+        }
+
+        // Find the cell the value falls in, extending the extremes if needed
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[MARKERS - 1] {
+            self.heights[MARKERS - 1] = value;
+            MARKERS - 2
+        } else {
+            (0..MARKERS - 1)
+                .find(|&i| value < self.heights[i + 1])
+                .unwrap_or(MARKERS - 2)
+        };
+
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        // Move the middle markers toward their desired positions
+        for i in 1..MARKERS - 1 {
+            let offset = self.desired[i] - self.positions[i];
+            let room_right = self.positions[i + 1] - self.positions[i];
+            let room_left = self.positions[i - 1] - self.positions[i];
+            if (offset >= 1. && room_right > 1.) || (offset <= -1. && room_left < -1.) {
+                let step = offset.signum();
+                let height = self.parabolic(i, step);
+                self.heights[i] = if self.heights[i - 1] < height && height < self.heights[i + 1] {
+                    height
+                } else {
+                    self.linear(i, step)
+                };
+                self.positions[i] += step;
+            }
+        }
+    }
+
+    /// Piecewise parabolic prediction of a marker's height after moving it by `step`
+    fn parabolic(&self, i: usize, step: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + step / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + step) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - step) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    /// Linear prediction of a marker's height after moving it by `step`
+    fn linear(&self, i: usize, step: f64) -> f64 {
+        let j = if step > 0. { i + 1 } else { i - 1 };
+        self.heights[i]
+            + step * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    /// Get the current estimate, which is exact until the markers are initialized
+    fn value(&self) -> Option<f64> {
+        if self.initial.len() < MARKERS {
+            let mut values = self.initial.clone();
+            values.sort_by(f64::total_cmp);
+            let rank = (self.quantile * values.len() as f64).ceil() as usize;
+            return values.get(rank.saturating_sub(1)).copied();
+        }
+        Some(self.heights[MARKERS / 2])
+    }
+}
+
+/// Estimates the median, 90th, and 99th percentiles of a numeric field in constant memory
+pub struct Quantiles {
+    estimates: Vec<Estimate>,
+    count: u64,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
+}
+
+impl Aggregator for Quantiles {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        // Messages without a number are skipped so they do not skew the estimates
+        if let Some(number) = extract_number(message, self.separator) {
+            if number.is_finite() {
+                self.estimates
+                    .iter_mut()
+                    .for_each(|estimate| estimate.update(number));
+                self.count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        let mut result: Vec<String> = QUANTILES
+            .iter()
+            .zip(&self.estimates)
+            .map(|((label, _), estimate)| match estimate.value() {
+                Some(value) => format!(
+                    "    {}: {}",
+                    label,
+                    self.thresholds.colorize(value, format!("{:.2}", value))
+                ),
+                None => format!("    {}: -", label),
+            })
+            .collect();
+        result.push(format!(
+            "    Count: {}",
+            format_num!(",d", self.count as f64)
+        ));
+        result
+    }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
+    }
+}
+
+impl Quantiles {
+    pub fn new(separator: DecimalSeparator) -> Quantiles {
+        Quantiles {
+            estimates: QUANTILES
+                .iter()
+                .map(|(_, quantile)| Estimate::new(*quantile))
+                .collect(),
+            count: 0,
+            separator,
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod quantile_tests {
+    use crate::{
+        constants::cli::colors::{CRITICAL_COLOR, RESET_COLOR},
+        util::aggregators::{
+            aggregator::{Aggregator, DecimalSeparator, Thresholds},
+            quantiles::{Estimate, Quantiles},
+        },
+    };
+
+    /// Deterministic pseudo random numbers in [0, 1)
+    fn random(count: usize) -> Vec<f64> {
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        (0..count)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+
+    /// Exact nearest rank quantile
+    fn exact(values: &[f64], quantile: f64) -> f64 {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let rank = (quantile * sorted.len() as f64).ceil() as usize;
+        sorted[rank - 1]
+    }
+
+    fn assert_close(values: &[f64], quantile: f64, tolerance: f64) {
+        let mut estimate = Estimate::new(quantile);
+        values.iter().for_each(|value| estimate.update(*value));
+        let expected = exact(values, quantile);
+        let actual = estimate.value().unwrap();
+        assert!(
+            (actual - expected).abs() <= tolerance * expected.abs(),
+            "p{}: estimated {}, expected {}",
+            quantile * 100.,
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn can_estimate_uniform() {
+        let values: Vec<f64> = random(50_000).iter().map(|x| x * 1000.).collect();
+        for quantile in [0.5, 0.9, 0.99] {
+            assert_close(&values, quantile, 0.02);
+        }
+    }
+
+    #[test]
+    fn can_estimate_exponential() {
+        // Latencies tend to have a long tail, like an exponential distribution
+        let values: Vec<f64> = random(50_000)
+            .iter()
+            .map(|x| -(1. - x).ln() * 100.)
+            .collect();
+        for quantile in [0.5, 0.9, 0.99] {
+            assert_close(&values, quantile, 0.05);
+        }
+    }
+
+    #[test]
+    fn can_estimate_sorted_input() {
+        let values: Vec<f64> = (1..=10_000).map(f64::from).collect();
+        for quantile in [0.5, 0.9, 0.99] {
+            assert_close(&values, quantile, 0.02);
+        }
+    }
+
+    #[test]
+    fn can_estimate_few_values_exactly() {
+        let mut estimate = Estimate::new(0.5);
+        for value in [3., 1., 2.] {
+            estimate.update(value);
+        }
+        assert_eq!(estimate.value(), Some(2.));
+    }
+
+    #[test]
+    fn can_render() {
+        let mut quantiles = Quantiles::new(DecimalSeparator::Period);
+        for value in [
+            "took 4 ms",
+            "took 1 ms",
+            "timeout",
+            "took 2 ms",
+            "took 3 ms",
+        ] {
+            quantiles.update(value).unwrap();
+        }
+        assert_eq!(
+            quantiles.messages(&5),
+            vec![
+                "    p50: 2.00",
+                "    p90: 4.00",
+                "    p99: 4.00",
+                "    Count: 4"
+            ]
+        );
+    }
+
+    #[test]
+    fn can_render_above_threshold() {
+        let mut quantiles = Quantiles::new(DecimalSeparator::Period);
+        quantiles.set_thresholds(Thresholds {
+            warning: Some(2.),
+            critical: Some(3.),
+        });
+        for value in ["1", "2", "3", "4"] {
+            quantiles.update(value).unwrap();
+        }
+        assert_eq!(
+            quantiles.messages(&5),
+            vec![
+                "    p50: 2.00".to_string(),
+                format!("    p90: {}4.00{}", CRITICAL_COLOR, RESET_COLOR),
+                format!("    p99: {}4.00{}", CRITICAL_COLOR, RESET_COLOR),
+                "    Count: 4".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_render_no_data() {
+        let quantiles = Quantiles::new(DecimalSeparator::Period);
+        assert_eq!(
+            quantiles.messages(&5),
+            vec!["    p50: -", "    p90: -", "    p99: -", "    Count: 0"]
+        );
+    }
+}
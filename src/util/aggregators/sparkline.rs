@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use crate::util::{
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator},
+    error::LogriaError,
+};
+
+/// Number of recent values rendered by a sparkline
+pub const DEFAULT_WINDOW: usize = 40;
+
+/// Characters used to draw a sparkline, from lowest to highest
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub struct Sparkline {
+    values: VecDeque<f64>,
+    window: usize,
+    separator: DecimalSeparator,
+}
+
+impl Aggregator for Sparkline {
+    fn update(&mut self, message: &str) -> Result<(), LogriaError> {
+        // Messages without a number are skipped so they do not flatten the trend
+        if let Some(number) = extract_number(message, self.separator) {
+            if number.is_finite() {
+                if self.values.len() >= self.window {
+                    self.values.pop_front();
+                }
+                self.values.push_back(number);
+            }
+        }
+        Ok(())
+    }
+
+    fn messages(&self, _: &usize) -> Vec<String> {
+        match (self.min(), self.max()) {
+            (Some(min), Some(max)) => vec![
+                format!("    {}", self.render()),
+                format!("    Min: {:.2}", min),
+                format!("    Max: {:.2}", max),
+            ],
+            _ => vec![
+                format!("    {}", self.render()),
+                "    Min: -".to_owned(),
+                "    Max: -".to_owned(),
+            ],
+        }
+    }
+}
+
+impl Sparkline {
+    pub fn new(separator: DecimalSeparator, window: usize) -> Sparkline {
+        Sparkline {
+            values: VecDeque::with_capacity(window),
+            window,
+            separator,
+        }
+    }
+
+    fn min(&self) -> Option<f64> {
+        self.values.iter().copied().reduce(f64::min)
+    }
+
+    fn max(&self) -> Option<f64> {
+        self.values.iter().copied().reduce(f64::max)
+    }
+
+    /// Draw the values in the window, scaled between the smallest and largest value
+    ///
+    /// An empty window or a window where every value is the same renders as a flat line.
+    fn render(&self) -> String {
+        let (min, max) = match (self.min(), self.max()) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return LEVELS[0].to_string().repeat(self.window),
+        };
+        let range = max - min;
+        self.values
+            .iter()
+            .map(|value| {
+                if range == 0. {
+                    LEVELS[0]
+                } else {
+                    let level = ((value - min) / range * (LEVELS.len() - 1) as f64).round();
+                    LEVELS[level as usize]
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod sparkline_tests {
+    use crate::util::aggregators::{
+        aggregator::{Aggregator, DecimalSeparator},
+        sparkline::Sparkline,
+    };
+
+    #[test]
+    fn can_render_sequence() {
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 8);
+        for value in ["0", "1", "2", "3", "4", "5", "6", "7"] {
+            sparkline.update(value).unwrap();
+        }
+        assert_eq!(
+            sparkline.messages(&1),
+            vec![
+                "    ▁▂▃▄▅▆▇█".to_string(),
+                "    Min: 0.00".to_string(),
+                "    Max: 7.00".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_render_scaled_sequence() {
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 8);
+        for value in ["10", "80", "45", "10"] {
+            sparkline.update(value).unwrap();
+        }
+        assert_eq!(sparkline.render(), "▁█▅▁");
+    }
+
+    #[test]
+    fn can_render_flat_values() {
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 8);
+        for value in ["3", "3", "3"] {
+            sparkline.update(value).unwrap();
+        }
+        assert_eq!(sparkline.render(), "▁▁▁");
+    }
+
+    #[test]
+    fn can_render_no_data() {
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 4);
+        sparkline.update("no numbers here").unwrap();
+        assert_eq!(
+            sparkline.messages(&1),
+            vec![
+                "    ▁▁▁▁".to_string(),
+                "    Min: -".to_string(),
+                "    Max: -".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_recent_values() {
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 3);
+        for value in ["100", "1", "2", "3"] {
+            sparkline.update(value).unwrap();
+        }
+        assert_eq!(sparkline.render(), "▁▅█");
+        assert_eq!(sparkline.min(), Some(1.));
+        assert_eq!(sparkline.max(), Some(3.));
+    }
+
+    #[test]
+    fn skips_non_numeric_messages() {
+        let mut sparkline = Sparkline::new(DecimalSeparator::Period, 8);
+        sparkline.update("took 5 ms").unwrap();
+        sparkline.update("timeout").unwrap();
+        sparkline.update("took 1 ms").unwrap();
+        assert_eq!(sparkline.render(), "█▁");
+    }
+}
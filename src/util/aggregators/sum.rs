@@ -1,11 +1,13 @@
 use crate::util::{
-    aggregators::aggregator::{extract_number, Aggregator},
+    aggregators::aggregator::{extract_number, Aggregator, DecimalSeparator, Thresholds},
     error::LogriaError,
 };
 use format_num::format_num;
 
 pub struct Sum {
     total: f64,
+    separator: DecimalSeparator,
+    thresholds: Thresholds,
 }
 
 impl Aggregator for Sum {
@@ -19,27 +21,42 @@ impl Aggregator for Sum {
     }
 
     fn messages(&self, _: &usize) -> Vec<String> {
-        vec![format!("    Total: {}", format_num!(",d", self.total))]
+        vec![format!(
+            "    Total: {}",
+            self.thresholds
+                .colorize(self.total, format_num!(",d", self.total))
+        )]
+    }
+
+    fn set_thresholds(&mut self, thresholds: Thresholds) {
+        self.thresholds = thresholds;
     }
 }
 
 impl Sum {
-    pub fn new() -> Self {
-        Sum { total: 0. }
+    pub fn new(separator: DecimalSeparator) -> Self {
+        Sum {
+            total: 0.,
+            separator,
+            thresholds: Thresholds::default(),
+        }
     }
 
     fn parse(&self, message: &str) -> Option<f64> {
-        extract_number(message)
+        extract_number(message, self.separator)
     }
 }
 
 #[cfg(test)]
 mod float_tests {
-    use crate::util::aggregators::{aggregator::Aggregator, sum::Sum};
+    use crate::util::aggregators::{
+        aggregator::{Aggregator, DecimalSeparator},
+        sum::Sum,
+    };
 
     #[test]
     fn sum() {
-        let mut sum: Sum = Sum::new();
+        let mut sum: Sum = Sum::new(DecimalSeparator::Period);
         sum.update("1_f32").unwrap();
         sum.update("2_f32").unwrap();
         sum.update("3_f32").unwrap();
@@ -49,7 +66,7 @@ mod float_tests {
 
     #[test]
     fn messages() {
-        let mut sum: Sum = Sum::new();
+        let mut sum: Sum = Sum::new(DecimalSeparator::Period);
         sum.update("1_f32").unwrap();
         sum.update("2_f32").unwrap();
         sum.update("3_f32").unwrap();
@@ -57,16 +74,25 @@ mod float_tests {
         assert_eq!(sum.messages(&1), vec!["    Total: 6"]);
     }
 
+    #[test]
+    fn sum_comma_decimal() {
+        let mut sum: Sum = Sum::new(DecimalSeparator::Comma);
+        sum.update("1.000,5 ms").unwrap();
+        sum.update("2,25 ms").unwrap();
+
+        assert!(sum.total - 1002.75 == 0.);
+    }
+
     #[test]
     fn sum_empty() {
-        let mean: Sum = Sum::new();
+        let mean: Sum = Sum::new(DecimalSeparator::Period);
 
         assert!(mean.total - 0_f64 == 0_f64);
     }
 
     #[test]
     fn sum_overflow() {
-        let mut sum: Sum = Sum::new();
+        let mut sum: Sum = Sum::new(DecimalSeparator::Period);
         sum.update(&format!("{}test", f64::MAX)).unwrap();
         sum.update(&format!("{} test", f64::MAX)).unwrap();
 
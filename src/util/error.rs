@@ -7,6 +7,7 @@ pub enum LogriaError {
     WrongParserType,
     InvalidExampleRegex(String),
     InvalidExampleSplit(usize, usize),
+    InvalidExampleJson(String),
     CannotRead(String, String),
     CannotWrite(String, String),
     CannotRemove(String, String),
@@ -14,6 +15,8 @@ pub enum LogriaError {
     InvalidCommand(String),
     CannotParseMessage(String),
     InvalidParserState(String),
+    InvalidFilterName(String),
+    CannotPipe(String, String),
 }
 
 impl Display for LogriaError {
@@ -21,7 +24,7 @@ impl Display for LogriaError {
         match self {
             LogriaError::InvalidRegex(why, msg) => write!(fmt, "{}: {}", why, msg),
             LogriaError::WrongParserType => {
-                write!(fmt, "Cannot construct regex for a Split type parser")
+                write!(fmt, "Cannot construct regex for a non-Regex type parser")
             }
             LogriaError::InvalidExampleRegex(msg) => {
                 write!(fmt, "Invalid example: /{}/ has no captures", msg)
@@ -31,6 +34,9 @@ impl Display for LogriaError {
                 "Invalid example: {:?} matches for {:?} methods",
                 msg, count
             ),
+            LogriaError::InvalidExampleJson(example) => {
+                write!(fmt, "Invalid example: {:?} is not a JSON object", example)
+            }
             LogriaError::CannotRead(path, why) => write!(fmt, "Couldn't open {:?}: {}", path, why),
             LogriaError::CannotWrite(path, why) => {
                 write!(fmt, "Couldn't write {:?}: {}", path, why)
@@ -50,6 +56,12 @@ impl Display for LogriaError {
             LogriaError::InvalidParserState(msg) => {
                 write!(fmt, "Invalid parser state: {}", msg)
             }
+            LogriaError::InvalidFilterName(name) => {
+                write!(fmt, "Invalid filter name: {:?}", name)
+            }
+            LogriaError::CannotPipe(command, why) => {
+                write!(fmt, "Couldn't pipe through {:?}: {}", command, why)
+            }
         }
     }
 }
@@ -0,0 +1,120 @@
+use std::{
+    io::{Result, Write},
+    mem::take,
+};
+
+use flate2::write::MultiGzDecoder;
+
+/// Decompresses gzip data as it arrives and splits the result into lines
+pub struct GzipLines {
+    decoder: MultiGzDecoder<Vec<u8>>,
+    /// Decompressed bytes after the last newline
+    partial: Vec<u8>,
+}
+
+impl GzipLines {
+    pub fn new() -> GzipLines {
+        GzipLines {
+            decoder: MultiGzDecoder::new(vec![]),
+            partial: vec![],
+        }
+    }
+
+    /// Decompress a chunk of gzip data, returning the lines it completed
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<String>> {
+        self.decoder.write_all(chunk)?;
+        self.partial.extend(take(self.decoder.get_mut()));
+        Ok(self.take_lines())
+    }
+
+    /// End the stream, returning any remaining lines including one without a trailing newline
+    pub fn finish(&mut self) -> Result<Vec<String>> {
+        self.decoder.try_finish()?;
+        self.partial.extend(take(self.decoder.get_mut()));
+        let mut lines = self.take_lines();
+        if !self.partial.is_empty() {
+            lines.push(GzipLines::to_line(&take(&mut self.partial)));
+        }
+        Ok(lines)
+    }
+
+    /// Remove every complete line from the buffer of decompressed bytes
+    fn take_lines(&mut self) -> Vec<String> {
+        let end = match self.partial.iter().rposition(|&byte| byte == b'\n') {
+            Some(end) => end,
+            None => return vec![],
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        complete[..end]
+            .split(|&byte| byte == b'\n')
+            .map(GzipLines::to_line)
+            .collect()
+    }
+
+    fn to_line(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes.strip_suffix(b"\r").unwrap_or(bytes)).to_string()
+    }
+}
+
+impl Default for GzipLines {
+    fn default() -> Self {
+        GzipLines::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::GzipLines;
+
+    fn compress(text: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn decode(data: &[u8], chunk_size: usize) -> Vec<String> {
+        let mut lines = GzipLines::new();
+        let mut result = vec![];
+        for chunk in data.chunks(chunk_size) {
+            result.extend(lines.feed(chunk).unwrap());
+        }
+        result.extend(lines.finish().unwrap());
+        result
+    }
+
+    #[test]
+    fn can_decode_lines() {
+        let data = compress("first\nsecond\nthird\n");
+        assert_eq!(decode(&data, 1024), vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn can_decode_small_chunks() {
+        let text: String = (0..200).map(|x| format!("line {}\n", x)).collect();
+        let expected: Vec<String> = (0..200).map(|x| format!("line {}", x)).collect();
+        assert_eq!(decode(&compress(&text), 3), expected);
+    }
+
+    #[test]
+    fn can_decode_without_trailing_newline() {
+        let data = compress("first\r\nsecond");
+        assert_eq!(decode(&data, 1024), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn can_decode_concatenated_streams() {
+        let mut data = compress("first\n");
+        data.extend(compress("second\n"));
+        assert_eq!(decode(&data, 5), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn cannot_decode_plain_text() {
+        let mut lines = GzipLines::new();
+        assert!(lines.feed(b"not compressed\n").is_err());
+    }
+}
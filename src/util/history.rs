@@ -48,6 +48,15 @@ impl Tape {
         tape
     }
 
+    /// Construct a tape holding only `items`, without reading or writing the disk, for testing
+    pub fn _new_dummy(items: &[&str]) -> Tape {
+        Tape {
+            history_tape: items.iter().map(|item| String::from(*item)).collect(),
+            current_index: items.len().saturating_sub(1),
+            should_scroll_back: false,
+        }
+    }
+
     /// Read the history file from the disk to the current history buffer
     fn read_from_disk(&mut self) -> Result<(), LogriaError> {
         match OpenOptions::new().read(true).open(history_tape()) {
@@ -150,6 +159,15 @@ impl Tape {
     pub fn get_current_item(&self) -> String {
         self.history_tape[self.current_index].clone()
     }
+
+    /// Get the most recent item that is not one of `skip`
+    pub fn last_item_except(&self, skip: &[&str]) -> Option<String> {
+        self.history_tape
+            .iter()
+            .rev()
+            .find(|item| !skip.contains(&item.as_str()))
+            .cloned()
+    }
 }
 
 #[cfg(test)]
@@ -168,6 +186,18 @@ mod tests {
         assert_eq!(String::from("test"), tape.get_current_item());
     }
 
+    #[test]
+    fn can_find_last_item_except() {
+        let tape = Tape::_new_dummy(&["poll 50", "!!", "repeat"]);
+        assert_eq!(
+            tape.last_item_except(&["!!", "repeat"]),
+            Some(String::from("poll 50"))
+        );
+        assert!(Tape::_new_dummy(&["!!"])
+            .last_item_except(&["!!", "repeat"])
+            .is_none());
+    }
+
     #[test]
     fn scroll_back_n_good() {
         let mut tape = Tape::new();
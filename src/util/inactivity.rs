@@ -0,0 +1,68 @@
+use std::time::{Duration, Instant};
+
+/// Tracks the last time the app received new messages or user input
+#[derive(Debug)]
+pub struct InactivityTimer {
+    timeout: Duration,
+    last_activity: Instant,
+}
+
+impl InactivityTimer {
+    pub fn new(timeout: Duration) -> InactivityTimer {
+        InactivityTimer {
+            timeout,
+            last_activity: Instant::now(),
+        }
+    }
+
+    /// Record activity at `now`, restarting the timeout
+    pub fn touch(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    /// Whether the timeout has passed without any activity as of `now`
+    pub fn has_elapsed(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_activity) >= self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::InactivityTimer;
+
+    #[test]
+    fn not_elapsed_before_timeout() {
+        let start = Instant::now();
+        let mut timer = InactivityTimer::new(Duration::from_secs(60));
+        timer.touch(start);
+        assert!(!timer.has_elapsed(start + Duration::from_secs(59)));
+    }
+
+    #[test]
+    fn elapsed_at_timeout() {
+        let start = Instant::now();
+        let mut timer = InactivityTimer::new(Duration::from_secs(60));
+        timer.touch(start);
+        assert!(timer.has_elapsed(start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn activity_restarts_timeout() {
+        let start = Instant::now();
+        let mut timer = InactivityTimer::new(Duration::from_secs(60));
+        timer.touch(start);
+        timer.touch(start + Duration::from_secs(50));
+        assert!(!timer.has_elapsed(start + Duration::from_secs(100)));
+        assert!(timer.has_elapsed(start + Duration::from_secs(110)));
+    }
+
+    #[test]
+    fn not_elapsed_for_earlier_time() {
+        let start = Instant::now();
+        let mut timer = InactivityTimer::new(Duration::from_secs(60));
+        timer.touch(start + Duration::from_secs(10));
+        assert!(!timer.has_elapsed(start));
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::Write,
+    result::Result,
+    sync::mpsc::{channel, Sender},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::util::error::LogriaError;
+
+/// Point-in-time view of the app state written to the metrics file
+pub struct MetricsSnapshot {
+    pub timestamp: u64,
+    pub lines_per_second: f64,
+    pub stdout_messages: usize,
+    pub stderr_messages: usize,
+    pub matched_messages: usize,
+    pub poll_rate: u64,
+}
+
+impl MetricsSnapshot {
+    /// Render the snapshot as a single `key=value` line
+    pub fn format(&self) -> String {
+        format!(
+            "{} lines_per_second={:.2} stdout_messages={} stderr_messages={} matched_messages={} poll_rate={}",
+            self.timestamp,
+            self.lines_per_second,
+            self.stdout_messages,
+            self.stderr_messages,
+            self.matched_messages,
+            self.poll_rate
+        )
+    }
+}
+
+/// Appends metrics lines to a file from a background thread so the render loop never blocks on disk
+pub struct MetricsWriter {
+    sender: Sender<String>,
+    interval: Duration,
+    last_write: Instant,
+    messages_since_write: u64,
+}
+
+impl MetricsWriter {
+    pub fn new(path: &str, interval: u64) -> Result<MetricsWriter, LogriaError> {
+        let mut file = match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => file,
+            Err(why) => {
+                return Err(LogriaError::CannotWrite(
+                    path.to_owned(),
+                    <dyn Error>::to_string(&why),
+                ))
+            }
+        };
+
+        let (sender, receiver) = channel::<String>();
+        thread::Builder::new()
+            .name(String::from("MetricsWriter"))
+            .spawn(move || {
+                // The loop ends when the sender is dropped
+                for line in receiver {
+                    if writeln!(file, "{}", line).is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|why| LogriaError::CannotWrite(path.to_owned(), why.to_string()))?;
+
+        Ok(MetricsWriter {
+            sender,
+            interval: Duration::from_millis(interval),
+            last_write: Instant::now(),
+            messages_since_write: 0,
+        })
+    }
+
+    /// Track messages received since the last metrics line
+    pub fn record(&mut self, new_messages: u64) {
+        self.messages_since_write = self.messages_since_write.saturating_add(new_messages);
+    }
+
+    /// Whether enough time has passed to emit another metrics line
+    pub fn should_write(&self) -> bool {
+        self.last_write.elapsed() >= self.interval
+    }
+
+    /// Throughput since the last metrics line
+    pub fn lines_per_second(&self) -> f64 {
+        let elapsed = self.last_write.elapsed().as_secs_f64();
+        if elapsed == 0. {
+            0.
+        } else {
+            self.messages_since_write as f64 / elapsed
+        }
+    }
+
+    /// Send a snapshot to the writer thread and reset the interval
+    pub fn write(&mut self, snapshot: &MetricsSnapshot) {
+        // If the writer thread died there is nowhere to report the failure, so the line is dropped
+        let _ = self.sender.send(snapshot.format());
+        self.last_write = Instant::now();
+        self.messages_since_write = 0;
+    }
+}
+
+/// Seconds since the Unix epoch, used to timestamp metrics lines
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MetricsSnapshot, MetricsWriter};
+
+    #[test]
+    fn can_format() {
+        let snapshot = MetricsSnapshot {
+            timestamp: 1615000000,
+            lines_per_second: 12.5,
+            stdout_messages: 100,
+            stderr_messages: 25,
+            matched_messages: 10,
+            poll_rate: 50,
+        };
+        assert_eq!(
+            snapshot.format(),
+            "1615000000 lines_per_second=12.50 stdout_messages=100 stderr_messages=25 matched_messages=10 poll_rate=50"
+        );
+    }
+
+    #[test]
+    fn can_format_empty() {
+        let snapshot = MetricsSnapshot {
+            timestamp: 0,
+            lines_per_second: 0.,
+            stdout_messages: 0,
+            stderr_messages: 0,
+            matched_messages: 0,
+            poll_rate: 1000,
+        };
+        assert_eq!(
+            snapshot.format(),
+            "0 lines_per_second=0.00 stdout_messages=0 stderr_messages=0 matched_messages=0 poll_rate=1000"
+        );
+    }
+
+    #[test]
+    fn cannot_open_bad_path() {
+        assert!(MetricsWriter::new("/fake/path/metrics.log", 1000).is_err());
+    }
+}
@@ -6,3 +6,8 @@ pub mod types;
 pub mod poll;
 pub mod aggregators;
 pub mod credits;
+pub mod metrics;
+pub mod inactivity;
+pub mod timestamp;
+pub mod gzip;
+pub mod trace;
@@ -1,4 +1,4 @@
-use clap::{command, crate_version, Arg, ArgAction, ArgMatches};
+use clap::{command, crate_version, value_parser, Arg, ArgAction, ArgMatches};
 
 use crate::constants::app::NAME;
 use crate::constants::cli::messages;
@@ -46,5 +46,101 @@ pub fn from_command_line() -> ArgMatches {
                 .help(messages::EXEC_HELP)
                 .value_name("stream"),
         )
+        .arg(
+            Arg::new("metrics")
+                .long("metrics")
+                .help(messages::METRICS_HELP)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("metrics_interval")
+                .long("metrics-interval")
+                .help(messages::METRICS_INTERVAL_HELP)
+                .value_name("ms")
+                .value_parser(value_parser!(u64))
+                .default_value("1000"),
+        )
+        .arg(
+            Arg::new("exit_after")
+                .long("exit-after")
+                .help(messages::EXIT_AFTER_HELP)
+                .value_name("minutes")
+                .value_parser(value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("poll_samples")
+                .long("poll-samples")
+                .help(messages::POLL_SAMPLES_HELP)
+                .value_name("count")
+                .value_parser(value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("resume_after")
+                .long("resume-after")
+                .help(messages::RESUME_AFTER_HELP)
+                .value_name("seconds")
+                .value_parser(value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("safe")
+                .long("safe")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help(messages::SAFE_HELP),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .help(messages::TRACE_HELP)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("max_line_length")
+                .long("max-line-length")
+                .help(messages::MAX_LINE_LENGTH_HELP)
+                .value_name("chars")
+                .value_parser(value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("gunzip")
+                .long("gunzip")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help(messages::GUNZIP_HELP),
+        )
+        .arg(
+            Arg::new("follow")
+                .long("follow")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help(messages::FOLLOW_HELP),
+        )
+        .arg(
+            Arg::new("stop_on_delete")
+                .long("stop-on-delete")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help(messages::STOP_ON_DELETE_HELP),
+        )
+        .arg(
+            Arg::new("pipe")
+                .long("pipe")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help(messages::PIPE_HELP),
+        )
+        .arg(
+            Arg::new("no_startup")
+                .long("no-startup")
+                .help(messages::NO_STARTUP_HELP)
+                .value_name("session"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help(messages::RESUME_HELP),
+        )
         .get_matches()
 }
@@ -107,6 +107,15 @@ mod mean_track_tests {
         assert_eq!(tracker.size, 2);
     }
 
+    #[test]
+    fn can_create_custom_size() {
+        let mut tracker = RollingMean::new(10);
+        (1..=12).for_each(|item| tracker.update(item));
+        assert_eq!(tracker.max_size, 10);
+        assert_eq!(tracker.deque.len(), 10);
+        assert_eq!(tracker.size, 10);
+    }
+
     #[test]
     fn can_get_mean_full() {
         let mut tracker = RollingMean::new(5);
@@ -25,9 +25,209 @@ pub mod length {
     }
 }
 
+pub mod ansi {
+    use regex::bytes::Regex;
+
+    use crate::constants::cli::patterns::ANSI_CONTROL_PATTERN;
+
+    /// Removes escape sequences that move the cursor or erase the screen, keeping color codes
+    pub struct ControlSequences {
+        control_pattern: regex::Regex,
+    }
+
+    impl ControlSequences {
+        pub fn new() -> ControlSequences {
+            ControlSequences {
+                control_pattern: regex::Regex::new(ANSI_CONTROL_PATTERN).unwrap(),
+            }
+        }
+
+        /// Strip control sequences from a message, only allocating if there are any
+        pub fn strip(&self, message: String) -> String {
+            if self.control_pattern.is_match(&message) {
+                self.control_pattern.replace_all(&message, "").into_owned()
+            } else {
+                message
+            }
+        }
+    }
+
+    /// Message text with color codes removed, mapped back to the original message
+    pub struct CleanText {
+        pub text: Vec<u8>,
+        // Byte offset in the original message for each byte of `text`
+        offsets: Vec<usize>,
+        original_length: usize,
+    }
+
+    impl CleanText {
+        /// Remove the color codes matched by `color_pattern` from a message
+        pub fn new(color_pattern: &Regex, message: &[u8]) -> CleanText {
+            let mut text = Vec::with_capacity(message.len());
+            let mut offsets = Vec::with_capacity(message.len());
+            let mut last_end = 0;
+            for color in color_pattern.find_iter(message) {
+                text.extend_from_slice(&message[last_end..color.start()]);
+                offsets.extend(last_end..color.start());
+                last_end = color.end();
+            }
+            text.extend_from_slice(&message[last_end..]);
+            offsets.extend(last_end..message.len());
+            CleanText {
+                text,
+                offsets,
+                original_length: message.len(),
+            }
+        }
+
+        /// Map the start of a range in the clean text to the original message
+        pub fn original_start(&self, start: usize) -> usize {
+            self.offsets
+                .get(start)
+                .copied()
+                .unwrap_or(self.original_length)
+        }
+
+        /// Map the exclusive end of a range in the clean text to the original message
+        ///
+        /// Color codes directly after the range are not included.
+        pub fn original_end(&self, end: usize) -> usize {
+            match end.checked_sub(1) {
+                Some(last) => self
+                    .offsets
+                    .get(last)
+                    .map(|offset| offset + 1)
+                    .unwrap_or(self.original_length),
+                None => self.original_start(0),
+            }
+        }
+    }
+}
+
+pub mod control {
+    /// Escape control characters so a raw message cannot corrupt the terminal
+    pub fn escape_control_chars(content: &str) -> String {
+        let mut result = String::with_capacity(content.len());
+        for character in content.chars() {
+            if character.is_control() {
+                result.extend(character.escape_default());
+            } else {
+                result.push(character);
+            }
+        }
+        result
+    }
+}
+
+pub mod binary {
+    use std::str::from_utf8;
+
+    /// Largest share of non-text bytes allowed before content is considered binary
+    const MAX_NON_TEXT_RATIO: f64 = 0.3;
+
+    /// Whether a byte is unlikely to appear in text, allowing whitespace and ANSI escapes
+    fn is_non_text(byte: u8) -> bool {
+        matches!(byte, 0x00..=0x06 | 0x0e..=0x1a | 0x1c..=0x1f | 0x7f)
+    }
+
+    /// Guess whether a sample from the start of a file is binary
+    ///
+    /// Samples with null bytes are binary. Otherwise, a sample is binary if too many of its bytes are control
+    /// characters, counting bytes outside of ASCII as well when the sample is not valid UTF-8.
+    pub fn looks_binary(sample: &[u8]) -> bool {
+        if sample.is_empty() {
+            return false;
+        }
+        if sample.contains(&0) {
+            return true;
+        }
+        // A sample may end partway through a multibyte character
+        let is_utf8 = match from_utf8(sample) {
+            Ok(_) => true,
+            Err(why) => why.error_len().is_none(),
+        };
+        let non_text = sample
+            .iter()
+            .filter(|&&byte| is_non_text(byte) || (!is_utf8 && byte > 0x7f))
+            .count();
+        non_text as f64 / sample.len() as f64 > MAX_NON_TEXT_RATIO
+    }
+}
+
+pub mod elide {
+    use crate::constants::cli::colors::RESET_COLOR;
+
+    /// Cut a message to at most `max` characters, followed by a marker with its original length
+    ///
+    /// A color code cut short would swallow the marker, so the cut moves to before it.
+    pub fn elide(message: String, max: usize) -> String {
+        let cut = match message.char_indices().nth(max) {
+            Some((index, _)) => index,
+            None => return message,
+        };
+        let mut kept = &message[..cut];
+        if let Some(start) = kept.rfind('\x1b') {
+            let complete = kept[start..]
+                .chars()
+                .skip(2)
+                .any(|character| ('@'..='~').contains(&character));
+            if !complete {
+                kept = &kept[..start];
+            }
+        }
+        // Colors from the kept text should not bleed into the marker
+        let reset = if kept.contains('\x1b') {
+            RESET_COLOR
+        } else {
+            ""
+        };
+        format!("{}{}… [{} chars]", kept, reset, message.chars().count())
+    }
+}
+
+pub mod selection {
+    use std::num::ParseIntError;
+
+    /// Parse a numeric menu selection, ignoring surrounding whitespace and leading zeros
+    pub fn parse_selection(input: &str) -> Result<usize, ParseIntError> {
+        let trimmed = input.trim();
+        let digits = trimmed.trim_start_matches('0');
+        if digits.is_empty() && !trimmed.is_empty() {
+            return Ok(0);
+        }
+        digits.parse::<usize>()
+    }
+
+    /// Parse a list of menu selections separated by commas, where `a-b` selects `a` through `b`
+    ///
+    /// Selections keep the order they were entered in, i.e. `3,1` selects `3` before `1`.
+    pub fn parse_selections(input: &str) -> Result<Vec<usize>, ParseIntError> {
+        let mut selections = vec![];
+        for part in input.split(',').filter(|part| !part.trim().is_empty()) {
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    selections.extend(parse_selection(start)?..=parse_selection(end)?);
+                }
+                None => selections.push(parse_selection(part)?),
+            }
+        }
+        Ok(selections)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::length::LengthFinder;
+    use regex::bytes::Regex;
+
+    use super::{
+        ansi::{CleanText, ControlSequences},
+        binary::looks_binary,
+        control::escape_control_chars,
+        elide::elide,
+        length::LengthFinder,
+        selection::{parse_selection, parse_selections},
+    };
+    use crate::constants::cli::patterns::ANSI_COLOR_PATTERN;
 
     #[test]
     fn test_length_clean() {
@@ -50,4 +250,235 @@ mod tests {
         assert_eq!(l.get_real_length(content), 6);
         assert_eq!("\x1b[0m█四░\x1b[32m█四░", content);
     }
+
+    #[test]
+    fn test_escape_clean() {
+        assert_eq!(escape_control_chars("word  "), "word  ");
+    }
+
+    #[test]
+    fn test_escape_control_chars() {
+        let content = "\x1b[32mword\tword\r";
+        assert_eq!(escape_control_chars(content), "\\u{1b}[32mword\\tword\\r");
+    }
+
+    #[test]
+    fn test_clean_text_no_colors() {
+        let color_pattern = Regex::new(ANSI_COLOR_PATTERN).unwrap();
+        let clean = CleanText::new(&color_pattern, b"word");
+        assert_eq!(clean.text, b"word");
+        assert_eq!(clean.original_start(1), 1);
+        assert_eq!(clean.original_end(3), 3);
+    }
+
+    #[test]
+    fn test_clean_text_maps_through_colors() {
+        let color_pattern = Regex::new(ANSI_COLOR_PATTERN).unwrap();
+        let message = b"\x1b[32mINFO\x1b[0m word";
+        let clean = CleanText::new(&color_pattern, message);
+        assert_eq!(clean.text, b"INFO word");
+
+        // `INFO` is wrapped in color codes
+        assert_eq!(clean.original_start(0), 5);
+        assert_eq!(clean.original_end(4), 9);
+        assert_eq!(&message[5..9], b"INFO");
+
+        // `word` comes after the reset code
+        let start = clean.original_start(5);
+        let end = clean.original_end(9);
+        assert_eq!(&message[start..end], b"word");
+    }
+
+    #[test]
+    fn test_clean_text_match_across_colors() {
+        let color_pattern = Regex::new(ANSI_COLOR_PATTERN).unwrap();
+        let message = b"a\x1b[31mb\x1b[0mc";
+        let clean = CleanText::new(&color_pattern, message);
+        assert_eq!(clean.text, b"abc");
+        assert_eq!(clean.original_start(0), 0);
+        assert_eq!(clean.original_end(3), message.len());
+        assert_eq!(
+            &message[clean.original_start(1)..clean.original_end(2)],
+            b"b"
+        );
+    }
+
+    #[test]
+    fn test_clean_text_empty_range() {
+        let color_pattern = Regex::new(ANSI_COLOR_PATTERN).unwrap();
+        let message = b"\x1b[32mword";
+        let clean = CleanText::new(&color_pattern, message);
+        assert_eq!(clean.original_start(4), message.len());
+        assert_eq!(clean.original_end(0), 5);
+    }
+
+    #[test]
+    fn test_selection_zeros() {
+        assert_eq!(parse_selection("00"), Ok(0));
+    }
+
+    #[test]
+    fn test_selection_leading_zeros() {
+        assert_eq!(parse_selection("007"), Ok(7));
+    }
+
+    #[test]
+    fn test_selection_whitespace() {
+        assert_eq!(parse_selection(" 1 "), Ok(1));
+    }
+
+    #[test]
+    fn test_selection_empty() {
+        assert!(parse_selection("").is_err());
+        assert!(parse_selection("   ").is_err());
+    }
+
+    #[test]
+    fn test_selection_not_numeric() {
+        assert!(parse_selection("a").is_err());
+        assert!(parse_selection("0a").is_err());
+    }
+
+    #[test]
+    fn test_selections_single() {
+        assert_eq!(parse_selections("1"), Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_selections_list() {
+        assert_eq!(parse_selections("3, 1,"), Ok(vec![3, 1]));
+    }
+
+    #[test]
+    fn test_selections_range() {
+        assert_eq!(parse_selections("0,2-4"), Ok(vec![0, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_selections_invalid() {
+        assert!(parse_selections("1,a").is_err());
+        assert!(parse_selections("1-").is_err());
+    }
+
+    #[test]
+    fn test_binary_text() {
+        assert!(!looks_binary(
+            b"2021-03-19 09:14:26 INFO started\n\tat line 2\r\n"
+        ));
+    }
+
+    #[test]
+    fn test_binary_colored_text() {
+        assert!(!looks_binary(b"\x1b[31mERROR\x1b[0m failed\n"));
+    }
+
+    #[test]
+    fn test_binary_unicode_text() {
+        assert!(!looks_binary("température élevée ▁▂▃\n".as_bytes()));
+    }
+
+    #[test]
+    fn test_binary_truncated_unicode_text() {
+        let text = "élevée".as_bytes();
+        assert!(!looks_binary(&text[..text.len() - 1]));
+    }
+
+    #[test]
+    fn test_binary_empty() {
+        assert!(!looks_binary(b""));
+    }
+
+    #[test]
+    fn test_binary_null_bytes() {
+        assert!(looks_binary(b"ELF\x00\x01 mostly text otherwise"));
+    }
+
+    #[test]
+    fn test_binary_control_bytes() {
+        assert!(looks_binary(&[
+            0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x01, 0x03
+        ]));
+    }
+
+    #[test]
+    fn test_binary_invalid_utf8() {
+        assert!(looks_binary(&[
+            0xff, 0xd8, 0xff, 0xe0, 0x10, 0x4a, 0x46, 0x49
+        ]));
+    }
+
+    #[test]
+    fn test_control_keeps_plain_text() {
+        let c = ControlSequences::new();
+        assert_eq!(c.strip(String::from("plain message")), "plain message");
+    }
+
+    #[test]
+    fn test_control_keeps_colors() {
+        let c = ControlSequences::new();
+        assert_eq!(
+            c.strip(String::from("\x1b[31mERROR\x1b[0m failed")),
+            "\x1b[31mERROR\x1b[0m failed"
+        );
+    }
+
+    #[test]
+    fn test_control_strips_cursor_up() {
+        let c = ControlSequences::new();
+        assert_eq!(c.strip(String::from("\x1b[1Aprogress 50%")), "progress 50%");
+    }
+
+    #[test]
+    fn test_control_strips_erase_line() {
+        let c = ControlSequences::new();
+        assert_eq!(
+            c.strip(String::from("\x1b[2Kprogress 75%\x1b[K")),
+            "progress 75%"
+        );
+    }
+
+    #[test]
+    fn test_control_strips_mixed_with_colors() {
+        let c = ControlSequences::new();
+        assert_eq!(
+            c.strip(String::from(
+                "\x1b[1A\x1b[2K\x1b[32mok\x1b[0m \x1b[10;20Hdone\x1b[?25l"
+            )),
+            "\x1b[32mok\x1b[0m done"
+        );
+    }
+
+    #[test]
+    fn test_control_strips_c1_introducer() {
+        let c = ControlSequences::new();
+        assert_eq!(c.strip(String::from("\u{9b}2Jcleared")), "cleared");
+    }
+
+    #[test]
+    fn test_elide_short() {
+        assert_eq!(elide(String::from("short"), 5), "short");
+    }
+
+    #[test]
+    fn test_elide_long() {
+        assert_eq!(elide("a".repeat(20), 5), "aaaaa… [20 chars]");
+    }
+
+    #[test]
+    fn test_elide_multibyte() {
+        assert_eq!(elide(String::from("→→→→"), 2), "→→… [4 chars]");
+    }
+
+    #[test]
+    fn test_elide_color() {
+        assert_eq!(
+            elide(String::from("\x1b[32mgreen text\x1b[0m"), 10),
+            "\x1b[32mgreen\x1b[0m… [19 chars]"
+        );
+    }
+
+    #[test]
+    fn test_elide_inside_color_code() {
+        assert_eq!(elide(String::from("ab\x1b[32mgreen"), 5), "ab… [12 chars]");
+    }
 }
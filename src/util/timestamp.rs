@@ -0,0 +1,166 @@
+use time::PrimitiveDateTime as DateTime;
+
+use crate::util::{
+    aggregators::date::{Date, DateParserType},
+    error::LogriaError,
+};
+
+/// Formats tried, in order, when a timestamp is given without a format
+const DEFAULT_FORMATS: [(&str, DateParserType); 3] = [
+    (
+        "[year]-[month]-[day] [hour]:[minute]:[second]",
+        DateParserType::DateTime,
+    ),
+    ("[year]-[month]-[day]", DateParserType::Date),
+    ("[hour]:[minute]:[second]", DateParserType::Time),
+];
+
+/// Finds the first message with a timestamp at or after a bound
+pub struct TimestampSearch {
+    /// Parses timestamps with the format that parsed the bound
+    parser: Date,
+    bound: DateTime,
+    /// Number of whitespace separated words in a timestamp
+    width: usize,
+}
+
+impl TimestampSearch {
+    /// Build a search from timestamp text, using `format` if provided, then the formats of the active
+    /// parser's date fields, then a set of common formats
+    pub fn new(
+        target: &str,
+        format: Option<&str>,
+        parser_formats: &[(String, DateParserType)],
+    ) -> Result<TimestampSearch, LogriaError> {
+        let target = target.trim();
+        let candidates: Vec<(&str, DateParserType)> = match format {
+            Some(format) => vec![
+                (format, DateParserType::DateTime),
+                (format, DateParserType::Date),
+                (format, DateParserType::Time),
+            ],
+            None if !parser_formats.is_empty() => parser_formats
+                .iter()
+                .map(|(format, parser_type)| (format.as_str(), *parser_type))
+                .collect(),
+            None => DEFAULT_FORMATS.to_vec(),
+        };
+        for (format, parser_type) in candidates {
+            let parser = Date::new(format, parser_type);
+            if let Ok(bound) = parser.parse(target) {
+                return Ok(TimestampSearch {
+                    parser,
+                    bound,
+                    width: target.split_whitespace().count(),
+                });
+            }
+        }
+        Err(LogriaError::CannotParseDate(format!(
+            "{:?} does not match a known timestamp format",
+            target
+        )))
+    }
+
+    /// Find the first timestamp in a message, checking each run of `width` words
+    fn timestamp(&self, message: &str) -> Option<DateTime> {
+        let words: Vec<&str> = message
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+            .collect();
+        words
+            .windows(self.width)
+            .find_map(|window| self.parser.parse(&window.join(" ")).ok())
+    }
+
+    /// Get the index of the first message whose timestamp is at or after the bound, skipping messages without one
+    pub fn first_match<'a>(&self, messages: impl Iterator<Item = &'a str>) -> Option<usize> {
+        messages
+            .enumerate()
+            .find(|(_, message)| {
+                self.timestamp(message)
+                    .is_some_and(|timestamp| timestamp >= self.bound)
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimestampSearch;
+    use crate::util::aggregators::date::DateParserType;
+
+    fn messages() -> Vec<String> {
+        vec![
+            "starting up".to_string(),
+            "2021-03-19 09:14:26 INFO first".to_string(),
+            "2021-03-19 10:30:26 WARN second".to_string(),
+            "not a timestamp".to_string(),
+            "[2021-03-19 14:32:00] ERROR third".to_string(),
+            "2021-03-20 08:00:00 INFO fourth".to_string(),
+        ]
+    }
+
+    fn search(target: &str, format: Option<&str>) -> Option<usize> {
+        let messages = messages();
+        TimestampSearch::new(target, format, &[])
+            .unwrap()
+            .first_match(messages.iter().map(|message| message.as_str()))
+    }
+
+    #[test]
+    fn can_find_time() {
+        assert_eq!(search("10:00:00", None), Some(2));
+    }
+
+    #[test]
+    fn can_find_exact_time() {
+        assert_eq!(search("14:32:00", None), Some(4));
+    }
+
+    #[test]
+    fn can_find_date_time() {
+        assert_eq!(search("2021-03-19 11:00:00", None), Some(4));
+    }
+
+    #[test]
+    fn can_find_date() {
+        assert_eq!(search("2021-03-20", None), Some(5));
+    }
+
+    #[test]
+    fn can_find_with_format() {
+        let messages = ["2021-03-19 first", "19/03/2021 second"];
+        let found = TimestampSearch::new("19/03/2021", Some("[day]/[month]/[year]"), &[])
+            .unwrap()
+            .first_match(messages.into_iter());
+        assert_eq!(found, Some(1));
+    }
+
+    #[test]
+    fn can_find_with_parser_formats() {
+        let messages = ["19/03/2021 first", "20/03/2021 second"];
+        let formats = [(String::from("[day]/[month]/[year]"), DateParserType::Date)];
+        let found = TimestampSearch::new("20/03/2021", None, &formats)
+            .unwrap()
+            .first_match(messages.into_iter());
+        assert_eq!(found, Some(1));
+    }
+
+    #[test]
+    fn prefers_given_format_over_parser_formats() {
+        let formats = [(String::from("[year]"), DateParserType::Date)];
+        assert!(TimestampSearch::new("2021-03-19", None, &formats).is_err());
+        assert!(TimestampSearch::new("2021-03-19", Some("[year]-[month]-[day]"), &formats).is_ok());
+    }
+
+    #[test]
+    fn cannot_find_after_last() {
+        assert_eq!(search("2021-03-21", None), None);
+    }
+
+    #[test]
+    fn cannot_build_invalid_timestamp() {
+        assert!(TimestampSearch::new("yesterday", None, &[]).is_err());
+        assert!(TimestampSearch::new("14:32:00", Some("[year]"), &[]).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::Write,
+    result::Result,
+    sync::mpsc::{channel, Sender},
+    thread::{self, JoinHandle},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::util::error::LogriaError;
+
+/// Appends a timestamped line for each of Logria's own actions to a file, for debugging Logria itself
+pub struct Tracer {
+    sender: Sender<String>,
+    /// Thread writing the lines to the file, if there is one
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Tracer {
+    pub fn new(path: &str) -> Result<Tracer, LogriaError> {
+        let mut file = match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => file,
+            Err(why) => {
+                return Err(LogriaError::CannotWrite(
+                    path.to_owned(),
+                    <dyn Error>::to_string(&why),
+                ))
+            }
+        };
+
+        let (sender, receiver) = channel::<String>();
+        let writer = thread::Builder::new()
+            .name(String::from("Tracer"))
+            .spawn(move || {
+                // The loop ends when the sender is dropped
+                for line in receiver {
+                    if writeln!(file, "{}", line).is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|why| LogriaError::CannotWrite(path.to_owned(), why.to_string()))?;
+
+        Ok(Tracer {
+            sender,
+            writer: Some(writer),
+        })
+    }
+
+    /// Create a tracer that sends its lines to a channel instead of a file
+    #[cfg(test)]
+    pub fn with_sender(sender: Sender<String>) -> Tracer {
+        Tracer {
+            sender,
+            writer: None,
+        }
+    }
+
+    /// Stop tracing and wait until every recorded line is written, i.e. before the process exits
+    pub fn finish(self) {
+        // Dropping the sender ends the writer's loop once it has written the lines already sent
+        drop(self.sender);
+        if let Some(writer) = self.writer {
+            let _ = writer.join();
+        }
+    }
+
+    /// Record an event, i.e. `mode` with `Normal -> Regex`
+    pub fn record(&self, event: &str, detail: &str) {
+        // If the writer thread died there is nowhere to report the failure, so the line is dropped
+        let _ = self.sender.send(Tracer::format(millis(), event, detail));
+    }
+
+    /// Render an event as a single line
+    fn format(timestamp: u128, event: &str, detail: &str) -> String {
+        // Details can hold user input, which must not split the event across lines
+        format!("{} {} {}", timestamp, event, detail.replace('\n', "\\n"))
+    }
+}
+
+/// Milliseconds since the Unix epoch, used to timestamp trace lines
+fn millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env::temp_dir,
+        fs::{read_to_string, remove_file},
+        sync::mpsc::channel,
+    };
+
+    use super::Tracer;
+
+    #[test]
+    fn can_format() {
+        assert_eq!(
+            Tracer::format(1615000000000, "command", "poll 50"),
+            "1615000000000 command poll 50"
+        );
+    }
+
+    #[test]
+    fn can_format_multiline_detail() {
+        assert_eq!(
+            Tracer::format(0, "message", "first\nsecond"),
+            "0 message first\\nsecond"
+        );
+    }
+
+    #[test]
+    fn can_record() {
+        let (sender, receiver) = channel();
+        let tracer = Tracer::with_sender(sender);
+        tracer.record("mode", "Normal -> Regex");
+        let line = receiver.try_recv().unwrap();
+        assert!(line.ends_with(" mode Normal -> Regex"));
+    }
+
+    #[test]
+    fn finish_writes_recorded_lines() {
+        let path = temp_dir().join("logria_trace_finish_test.log");
+        let _ = remove_file(&path);
+        let tracer = Tracer::new(path.to_str().unwrap()).unwrap();
+        tracer.record("stream", "stopped test");
+        tracer.finish();
+
+        let written = read_to_string(&path).unwrap();
+        assert!(written.ends_with(" stream stopped test\n"));
+        remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn cannot_open_bad_path() {
+        assert!(Tracer::new("/fake/path/trace.log").is_err());
+    }
+}